@@ -13,6 +13,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         instruction::{AccountMeta, Instruction},
@@ -75,8 +76,7 @@ async fn main() -> Result<()> {
     let tuna_position_ata_a = get_associated_token_address(&tuna_spot_position, &mint_a);
     let tuna_position_ata_b = get_associated_token_address(&tuna_spot_position, &mint_b);
 
-    // CloseTunaSpotPosition discriminator from IDL
-    let discriminator: [u8; 8] = [4, 189, 171, 84, 110, 220, 10, 8];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("close_tuna_spot_position");
     let data = discriminator.to_vec();
 
     let instruction = Instruction {
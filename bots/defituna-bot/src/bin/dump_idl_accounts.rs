@@ -0,0 +1,74 @@
+/// Print the expected account metas and Anchor discriminator for a DeFiTuna instruction
+/// Run with: ./target/release/dump_idl_accounts open_tuna_spot_position
+///           ./target/release/dump_idl_accounts --list
+use anyhow::Result;
+use clap::Parser;
+use defituna_bot::defituna_client::anchor_discriminator;
+use defituna_bot::idl::{find_instruction, INSTRUCTIONS};
+
+#[derive(Parser, Debug)]
+#[command(name = "dump_idl_accounts")]
+#[command(about = "Dump expected accounts and discriminator for a DeFiTuna instruction", long_about = None)]
+struct Args {
+    /// Instruction name in snake_case (e.g. set_tuna_spot_position_limit_orders)
+    instruction: Option<String>,
+
+    /// List all known instructions
+    #[arg(short, long)]
+    list: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    if args.list || args.instruction.is_none() {
+        println!("Known instructions:");
+        for ix in INSTRUCTIONS {
+            println!("   {:<40} ({}, {} accounts)", ix.name, ix.program, ix.accounts.len());
+        }
+        return Ok(());
+    }
+
+    let name = args.instruction.unwrap();
+    let discriminator = anchor_discriminator(&name);
+
+    println!("📋 Instruction: {}", name);
+    println!("   Discriminator: {:?}", discriminator);
+    println!("   Discriminator (hex): {}", hex(&discriminator));
+
+    let Some(ix) = find_instruction(&name) else {
+        println!();
+        println!("⚠️  No account layout recorded for '{}' (discriminator computed from name only)", name);
+        println!("   Run with --list to see known instructions");
+        return Ok(());
+    };
+
+    println!("   Program: {}", ix.program);
+
+    if !ix.args.is_empty() {
+        println!();
+        println!("   Args (after discriminator):");
+        for arg in ix.args {
+            println!("      {}", arg);
+        }
+    }
+
+    println!();
+    println!("   Accounts:");
+    println!("      {:>3}  {:<8} {:<6} name", "#", "writable", "signer");
+    for (i, account) in ix.accounts.iter().enumerate() {
+        println!(
+            "      {:>3}  {:<8} {:<6} {}",
+            i,
+            if account.writable { "yes" } else { "no" },
+            if account.signer { "yes" } else { "no" },
+            account.name
+        );
+    }
+
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,111 @@
+/// List every DeFiTuna limit order held by a wallet (the executor's by default),
+/// or every order resting on the configured pool
+/// Run with: ./target/release/list_orders
+///           ./target/release/list_orders --owner <WALLET> --active
+///           ./target/release/list_orders --pool
+use anyhow::{Context, Result};
+use clap::Parser;
+use defituna_bot::config::BotConfig;
+use defituna_bot::defituna_client::PoolTicks;
+use defituna_bot::idl::ProgramIdl;
+use defituna_bot::solana_rpc_client::{fetch_limit_orders, fetch_owned_limit_orders, LimitOrderLayout};
+use dotenvy::dotenv;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use std::str::FromStr;
+
+#[derive(Parser, Debug)]
+#[command(name = "list_orders")]
+#[command(about = "List a wallet's DeFiTuna limit orders", long_about = None)]
+struct Args {
+    /// Wallet whose orders to list (default: EXECUTOR_KEYPAIR's)
+    #[arg(long, conflicts_with = "pool")]
+    owner: Option<String>,
+
+    /// List every order on the configured pool, whoever holds it
+    #[arg(long)]
+    pool: bool,
+
+    /// Hide orders that are fully filled
+    #[arg(long)]
+    active: bool,
+}
+
+fn main() -> Result<()> {
+    dotenv().ok();
+    let args = Args::parse();
+
+    let config = BotConfig::from_env()?;
+    let rpc_client = RpcClient::new_with_commitment(&config.rpc_url, config.commitment);
+    let program_id = Pubkey::from_str(&config.defituna_program_id).context("Invalid DefiTuna program ID")?;
+
+    let base_mint = Pubkey::from_str(&config.base_mint).context("Invalid base mint")?;
+    let quote_mint = Pubkey::from_str(&config.quote_mint).context("Invalid quote mint")?;
+    let (pool, _) = Pubkey::find_program_address(
+        &[b"fusion_pool", base_mint.as_ref(), quote_mint.as_ref()],
+        &program_id,
+    );
+    let layout = LimitOrderLayout::from_idl(&ProgramIdl::load(config.idl_path.as_deref())?)?;
+
+    // Prices only depend on the decimals, not the spacing
+    let ticks = PoolTicks {
+        base_decimals: config.base_decimals,
+        quote_decimals: config.quote_decimals,
+        ..PoolTicks::default()
+    };
+    let mut orders = if args.pool {
+        println!("📋 DeFiTuna limit orders on pool {}", pool);
+        fetch_limit_orders(&rpc_client, &program_id, &pool, &layout, &ticks)?
+    } else {
+        let owner = match &args.owner {
+            Some(owner) => Pubkey::from_str(owner).context("Invalid --owner")?,
+            None => {
+                let executor_keypair = bs58::decode(&config.executor_keypair)
+                    .into_vec()
+                    .context("Invalid executor private key")?;
+                Keypair::try_from(&executor_keypair[..])
+                    .context("Failed to parse executor keypair")?
+                    .pubkey()
+            }
+        };
+        println!("📋 DeFiTuna limit orders for {}", owner);
+        fetch_owned_limit_orders(&rpc_client, &program_id, &owner, &layout, &ticks)?
+    };
+    if args.active {
+        orders.retain(|order| order.is_active);
+    }
+
+    if orders.is_empty() {
+        println!("   None");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "   {:<44} {:<4} {:>14} {:>16} {:>16} {:>6}  status",
+        "order", "side", "price", "size", "filled", "fill%"
+    );
+    for order in &orders {
+        // Bids spend the quote token, asks the base token
+        let is_bid = order.is_bid;
+        let decimals = if is_bid { config.quote_decimals } else { config.base_decimals };
+        let units = 10f64.powi(decimals as i32);
+        println!(
+            "   {:<44} {:<4} {:>14.6} {:>16.6} {:>16.6} {:>5.1}%  {}",
+            order.pubkey,
+            if is_bid { "BID" } else { "ASK" },
+            order.price,
+            order.size as f64 / units,
+            order.filled as f64 / units,
+            order.filled as f64 / order.size.max(1) as f64 * 100.0,
+            if order.is_active { "active" } else { "filled" }
+        );
+    }
+
+    let active = orders.iter().filter(|order| order.is_active).count();
+    println!();
+    println!("   {} orders, {} active", orders.len(), active);
+
+    Ok(())
+}
@@ -13,6 +13,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         instruction::{AccountMeta, Instruction},
@@ -100,7 +101,7 @@ async fn main() -> Result<()> {
     // Discriminator: [87, 208, 173, 48, 231, 62, 210, 220]
     // Args: position_token (PoolToken), collateral_token (PoolToken)
     // PoolToken::A = 0, PoolToken::B = 1
-    let discriminator: [u8; 8] = [87, 208, 173, 48, 231, 62, 210, 220];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("open_tuna_spot_position");
     let mut data = discriminator.to_vec();
     data.push(0); // position_token = PoolToken::A (SOL)
     data.push(1); // collateral_token = PoolToken::B (USDC)
@@ -13,6 +13,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         instruction::{AccountMeta, Instruction},
@@ -88,7 +89,7 @@ async fn main() -> Result<()> {
 
     // OpenTunaSpotPosition instruction
     // Discriminator from IDL: [87, 208, 173, 48, 231, 62, 210, 220]
-    let discriminator: [u8; 8] = [87, 208, 173, 48, 231, 62, 210, 220];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("open_tuna_spot_position");
     let mut data = discriminator.to_vec();
     
     // Add args: position_token (PoolToken), collateral_token (PoolToken)
@@ -14,6 +14,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         commitment_config::CommitmentConfig,
@@ -201,8 +202,7 @@ async fn main() -> Result<()> {
     let token_program_a = spl_token::ID;
     let token_program_b = spl_token::ID;
     
-    // Discriminator: [133, 227, 147, 148, 220, 22, 59, 218]
-    let discriminator: [u8; 8] = [133, 227, 147, 148, 220, 22, 59, 218];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("modify_tuna_spot_position_orca");
     let mut data = discriminator.to_vec();
     
     // Args: decrease_percent (u32), collateral_amount (u64), borrow_amount (u64), required_swap_amount (u64), remaining_accounts_info
@@ -34,6 +34,7 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         instruction::{AccountMeta, Instruction},
@@ -103,7 +104,7 @@ async fn main() -> Result<()> {
     let upper_sqrt = args.upper_price.map(price_to_sqrt_price_x64).unwrap_or(u128::MAX);
 
     // Build instruction with discriminator from IDL
-    let discriminator: [u8; 8] = [10, 180, 19, 205, 169, 133, 52, 118];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("set_tuna_spot_position_limit_orders");
     let mut data = discriminator.to_vec();
     data.extend_from_slice(&lower_sqrt.to_le_bytes());
     data.extend_from_slice(&upper_sqrt.to_le_bytes());
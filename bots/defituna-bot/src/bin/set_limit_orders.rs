@@ -27,6 +27,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     use defituna_bot::config::BotConfig;
+    use defituna_bot::idl::Discriminators;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::{
         instruction::{AccountMeta, Instruction},
@@ -105,7 +106,7 @@ async fn main() -> Result<()> {
 
     // SetTunaSpotPositionLimitOrders instruction
     // Discriminator: [10, 180, 19, 205, 169, 133, 52, 118]
-    let discriminator: [u8; 8] = [10, 180, 19, 205, 169, 133, 52, 118];
+    let discriminator = Discriminators::load(config.idl_path.as_deref())?.get("set_tuna_spot_position_limit_orders");
     let mut data = discriminator.to_vec();
     
     // Add args: lower_limit_order_sqrt_price (u128), upper_limit_order_sqrt_price (u128)
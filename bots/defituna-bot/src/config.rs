@@ -1,5 +1,11 @@
 use anyhow::{Context, Result};
+use bot_common::parse_commitment;
+use solana_sdk::commitment_config::CommitmentConfig;
 use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::paper::ExecutionMode;
 
 #[derive(Debug, Clone)]
 pub struct BotConfig {
@@ -12,6 +18,11 @@ pub struct BotConfig {
     pub quote_token: String,
     pub base_mint: String,
     pub quote_mint: String,
+    /// Token decimals, for converting prices to pool ticks
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Overrides the pool's own tick spacing, which is read from the pool account when unset
+    pub tick_spacing: Option<u16>,
 
     // Strategy
     pub strategy_type: String,
@@ -21,25 +32,77 @@ pub struct BotConfig {
     pub spread_bps: u16,
     pub order_size: u64,
     pub max_position_size: u64,
+    pub min_ask_profit_bps: u16,
+    pub maker_fee_bps: u16,
+    /// Re-read the pool's fee tier this often; 0 reads it once at startup
+    pub pool_fee_refresh_secs: u64,
+    /// Cap orders at this fraction of the pool's same-side vault balance; 0 disables
+    pub max_order_depth_ratio: f64,
+    /// Force a flattening sell once a position is older than this; 0 disables
+    pub max_hold_minutes: u64,
+    /// Skip requoting until the mid moves this many bps from the last quote; 0 requotes every tick.
+    /// Either way, resting orders quoted at the old mid are cancelled before the requote.
+    pub requote_min_move_bps: u16,
+    /// Requote regardless of movement once the last quote is this old
+    pub requote_max_age_secs: u64,
+    /// How far inventory leans the quotes, in half-spreads at max_position_size; 0 disables
+    pub skew_factor: f64,
 
     // Arbitrage specific
+    /// Edge required on top of the round-trip fees below
     pub min_profit_bps: u16,
+    pub jupiter_swap_fee_bps: u16,
+    /// Self-hosted or paid Jupiter quote/swap API for market orders; public API when unset
+    pub jupiter_quote_url: Option<String>,
+    pub defituna_swap_fee_bps: u16,
+    /// Base plus priority fee per transaction, in lamports
+    pub network_fee_lamports: u64,
 
     // Risk management
     pub max_slippage_bps: u16,
+    /// Refuse market orders whose Jupiter quote moves the price more than this
+    /// many percent; 0 disables
+    pub max_price_impact_pct: f64,
     pub cooldown_minutes: u64,
     pub max_daily_trades: usize,
+    /// Skip trading while the subscription's latest account update or slot
+    /// notification is older than this; 0 disables
+    pub max_slot_age_secs: u64,
+    /// On shutdown, how long an in-progress trade may finish before the bot exits anyway
+    pub shutdown_timeout_secs: u64,
+    /// Cooldown, daily trade count and last slot, restored on restart
+    pub state_path: String,
+    /// Serves `/health` and `/status` on this port when set
+    pub health_port: Option<u16>,
+
+    // Execution
+    /// `live` sends transactions; `paper` fills against simulated balances
+    pub execution_mode: ExecutionMode,
+    /// Paper mode starting balances, in lamports and micro-USDC
+    pub paper_sol_balance: u64,
+    pub paper_usdc_balance: u64,
+    /// Slippage paper market orders fill at, against the order
+    pub paper_slippage_bps: u16,
 
     // Solana
     pub rpc_url: String,
+    /// Commitment RPC reads and transaction confirmation wait for
+    pub commitment: CommitmentConfig,
     pub executor_keypair: String,
 
     // DefiTuna
     pub defituna_program_id: String,
+    /// Anchor IDL JSON to read instruction discriminators from; computed from names when unset
+    pub idl_path: Option<String>,
+    /// Attach a `<memo_prefix>:<client_order_id>` memo to each order
+    pub include_memo: bool,
+    pub memo_prefix: String,
 
     // Strategy parameters
     pub lookback_minutes: usize,
     pub min_price_movement: f64,
+    /// Hard cap on stored price points, regardless of timestamps
+    pub max_retained_points: usize,
 }
 
 impl BotConfig {
@@ -53,6 +116,15 @@ impl BotConfig {
                 .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string())
         };
 
+        // Beyond 2 the ask at the cap would sit more than a half-spread below the mid
+        let skew_factor: f64 = env::var("SKEW_FACTOR")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid SKEW_FACTOR")?;
+        if !(0.0..=2.0).contains(&skew_factor) {
+            anyhow::bail!("SKEW_FACTOR must be in [0, 2], got {}", skew_factor);
+        }
+
         Ok(Self {
             rpc_ws_url: env::var("RPC_WS_URL")
                 .unwrap_or_else(|_| "wss://api.devnet.solana.com".to_string()),
@@ -67,6 +139,20 @@ impl BotConfig {
                 .unwrap_or_else(|_| "So11111111111111111111111111111111111111112".to_string()),
             quote_mint: env::var("QUOTE_MINT")
                 .unwrap_or_else(|_| "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
+            base_decimals: env::var("BASE_DECIMALS")
+                .unwrap_or_else(|_| "9".to_string())
+                .parse()
+                .context("Invalid BASE_DECIMALS")?,
+            quote_decimals: env::var("QUOTE_DECIMALS")
+                .unwrap_or_else(|_| "6".to_string())
+                .parse()
+                .context("Invalid QUOTE_DECIMALS")?,
+            tick_spacing: env::var("TICK_SPACING")
+                .ok()
+                .map(|s| s.parse())
+                .transpose()
+                .context("Invalid TICK_SPACING")?
+                .filter(|&spacing: &u16| spacing > 0),
 
             strategy_type: env::var("STRATEGY").unwrap_or_else(|_| "market_maker".to_string()),
             trade_amount: env::var("TRADE_AMOUNT_USDC")
@@ -89,16 +175,63 @@ impl BotConfig {
                 .parse::<u64>()
                 .context("Invalid MAX_POSITION_SIZE")?
                 * 1_000_000,
+            min_ask_profit_bps: env::var("MIN_ASK_PROFIT_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid MIN_ASK_PROFIT_BPS")?,
+            maker_fee_bps: env::var("MAKER_FEE_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid MAKER_FEE_BPS")?,
+            pool_fee_refresh_secs: env::var("POOL_FEE_REFRESH_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .context("Invalid POOL_FEE_REFRESH_SECS")?,
+            max_order_depth_ratio: env::var("MAX_ORDER_DEPTH_RATIO")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid MAX_ORDER_DEPTH_RATIO")?,
+            max_hold_minutes: env::var("MAX_HOLD_MINUTES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid MAX_HOLD_MINUTES")?,
+            requote_min_move_bps: env::var("REQUOTE_MIN_MOVE_BPS")
+                .or_else(|_| env::var("REQUOTE_THRESHOLD_BPS"))
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid REQUOTE_MIN_MOVE_BPS")?,
+            requote_max_age_secs: env::var("REQUOTE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("Invalid REQUOTE_MAX_AGE_SECS")?,
+            skew_factor,
 
             min_profit_bps: env::var("MIN_PROFIT_BPS")
                 .unwrap_or_else(|_| "20".to_string())
                 .parse()
                 .context("Invalid MIN_PROFIT_BPS")?,
+            jupiter_swap_fee_bps: env::var("JUPITER_SWAP_FEE_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid JUPITER_SWAP_FEE_BPS")?,
+            jupiter_quote_url: env::var("JUPITER_QUOTE_URL").ok().filter(|u| !u.trim().is_empty()),
+            defituna_swap_fee_bps: env::var("DEFITUNA_SWAP_FEE_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("Invalid DEFITUNA_SWAP_FEE_BPS")?,
+            network_fee_lamports: env::var("NETWORK_FEE_LAMPORTS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .context("Invalid NETWORK_FEE_LAMPORTS")?,
 
             max_slippage_bps: env::var("MAX_SLIPPAGE_BPS")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()
                 .context("Invalid MAX_SLIPPAGE_BPS")?,
+            max_price_impact_pct: env::var("MAX_PRICE_IMPACT_PCT")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .context("Invalid MAX_PRICE_IMPACT_PCT")?,
             cooldown_minutes: env::var("COOLDOWN_MINUTES")
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
@@ -107,15 +240,58 @@ impl BotConfig {
                 .unwrap_or_else(|_| "100".to_string())
                 .parse()
                 .context("Invalid MAX_DAILY_TRADES")?,
+            max_slot_age_secs: env::var("MAX_SLOT_AGE_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("Invalid MAX_SLOT_AGE_SECS")?,
+            shutdown_timeout_secs: env::var("SHUTDOWN_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("Invalid SHUTDOWN_TIMEOUT_SECS")?,
+            state_path: env::var("STATE_PATH").unwrap_or_else(|_| "defituna_state.json".to_string()),
+            health_port: env::var("HEALTH_PORT")
+                .ok()
+                .map(|p| p.parse())
+                .transpose()
+                .context("Invalid HEALTH_PORT")?,
+
+            execution_mode: env::var("EXECUTION_MODE")
+                .unwrap_or_else(|_| "live".to_string())
+                .parse()?,
+            paper_sol_balance: (env::var("PAPER_SOL_BALANCE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse::<f64>()
+                .context("Invalid PAPER_SOL_BALANCE")?
+                * 1_000_000_000.0) as u64,
+            paper_usdc_balance: (env::var("PAPER_USDC_BALANCE")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse::<f64>()
+                .context("Invalid PAPER_USDC_BALANCE")?
+                * 1_000_000.0) as u64,
+            paper_slippage_bps: env::var("PAPER_SLIPPAGE_BPS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("Invalid PAPER_SLIPPAGE_BPS")?,
 
             rpc_url,
-            executor_keypair: env::var("EXECUTOR_KEYPAIR")
-                .or_else(|_| env::var("EXECUTOR_PRIVATE_KEY"))
-                .or_else(|_| env::var("PRIVATE_KEY"))
-                .context("EXECUTOR_KEYPAIR, EXECUTOR_PRIVATE_KEY, or PRIVATE_KEY not set")?,
+            commitment: parse_commitment(
+                &env::var("COMMITMENT").unwrap_or_else(|_| "confirmed".to_string()),
+            )?,
+            executor_keypair: executor_keypair_base58(
+                &env::var("EXECUTOR_KEYPAIR")
+                    .or_else(|_| env::var("EXECUTOR_PRIVATE_KEY"))
+                    .or_else(|_| env::var("PRIVATE_KEY"))
+                    .context("EXECUTOR_KEYPAIR, EXECUTOR_PRIVATE_KEY, or PRIVATE_KEY not set")?,
+            )?,
 
             defituna_program_id: env::var("DEFITUNA_PROGRAM_ID")
                 .unwrap_or_else(|_| "tuna4uSQZncNeeiAMKbstuxA9CUkHH6HmC64wgmnogD".to_string()),
+            idl_path: env::var("IDL_PATH").ok().filter(|p| !p.trim().is_empty()),
+            include_memo: env::var("INCLUDE_MEMO")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("Invalid INCLUDE_MEMO")?,
+            memo_prefix: env::var("MEMO_PREFIX").unwrap_or_else(|_| "defituna-bot".to_string()),
 
             lookback_minutes: env::var("LOOKBACK_MINUTES")
                 .unwrap_or_else(|_| "60".to_string())
@@ -125,6 +301,66 @@ impl BotConfig {
                 .unwrap_or_else(|_| "0.02".to_string())
                 .parse()
                 .context("Invalid MIN_PRICE_MOVEMENT")?,
+            max_retained_points: env::var("MAX_RETAINED_PRICE_POINTS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .context("Invalid MAX_RETAINED_PRICE_POINTS")?,
         })
     }
 }
+
+/// The executor key as base58, which is what the bot and binaries decode. The
+/// variable may hold the base58 key itself or the path of a JSON keypair file
+/// (`solana-keygen` format: an array of 64 bytes).
+fn executor_keypair_base58(value: &str) -> Result<String> {
+    let value = value.trim();
+    let path = Path::new(value);
+    if path.is_file() {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read executor keypair file {}", path.display()))?;
+        let bytes: Vec<u8> = serde_json::from_str(&contents).with_context(|| {
+            format!("Executor keypair {} is a file but not a JSON byte array keypair", path.display())
+        })?;
+        if bytes.len() != 64 {
+            anyhow::bail!(
+                "Executor keypair file {} holds {} bytes, expected 64",
+                path.display(),
+                bytes.len()
+            );
+        }
+        return Ok(bs58::encode(bytes).into_string());
+    }
+
+    let bytes = bs58::decode(value)
+        .into_vec()
+        .context("Executor keypair is not an existing file, and not a valid base58 private key")?;
+    if bytes.len() != 64 {
+        anyhow::bail!("Executor keypair decoded as base58 to {} bytes, expected 64", bytes.len());
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executor_keypair_from_base58_or_json_file() {
+        let bytes: Vec<u8> = (0..64).collect();
+        let base58 = bs58::encode(&bytes).into_string();
+        assert_eq!(executor_keypair_base58(&base58).unwrap(), base58);
+
+        let path = env::temp_dir().join(format!("defituna-keypair-{}.json", std::process::id()));
+        fs::write(&path, serde_json::to_string(&bytes).unwrap()).unwrap();
+        assert_eq!(executor_keypair_base58(path.to_str().unwrap()).unwrap(), base58);
+
+        // A file that isn't a keypair is reported as such, not as bad base58
+        fs::write(&path, "not json").unwrap();
+        let err = executor_keypair_base58(path.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("not a JSON byte array"));
+        fs::remove_file(&path).unwrap();
+
+        let err = executor_keypair_base58("./missing-keypair.json").unwrap_err();
+        assert!(err.to_string().contains("not an existing file"));
+    }
+}
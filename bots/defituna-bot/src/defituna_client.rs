@@ -1,48 +1,114 @@
 use anyhow::{Context, Result};
+use base64::Engine;
+use jupiter_client::{check_quote_route, JupiterClient};
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_client::{RpcClient, SerializableTransaction};
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    instruction::{AccountMeta, CompiledInstruction, Instruction},
+    message::VersionedMessage,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     system_program,
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
     compute_budget::ComputeBudgetInstruction,
 };
-use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use std::collections::HashSet;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 use crate::config::BotConfig;
+use crate::idl::ProgramIdl;
+use crate::solana_rpc_client::{decode_limit_order, fetch_owned_limit_orders, LimitOrderLayout, LimitOrderUpdate};
 
 // DeFiTuna FusionAMM constants
-const TICK_SPACING: i32 = 64; // Standard tick spacing for SOL/USDC pools
-const SPL_TOKEN_2022_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const DEFAULT_TICK_SPACING: i32 = 64; // Standard tick spacing for SOL/USDC pools
+const TICKS_PER_ARRAY: i32 = 88;
+pub(crate) const SPL_TOKEN_2022_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
 const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+/// Per-request RPC timeout
+pub(crate) const RPC_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a sent transaction may take to reach the client's commitment
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// FusionPool account: discriminator, bump, version, token mints and vaults,
+// tick spacing (u16) and its seed, the fee rate (u16), three more u16 fee
+// rates, liquidity (u128), the Q64.64 sqrt price (u128) and the current tick
+// index (i32), little-endian
+const FUSION_POOL_MINT_A_OFFSET: usize = 11;
+const FUSION_POOL_MINT_B_OFFSET: usize = 43;
+const FUSION_POOL_VAULT_A_OFFSET: usize = 75;
+const FUSION_POOL_VAULT_B_OFFSET: usize = 107;
+const FUSION_POOL_TICK_SPACING_OFFSET: usize = 139;
+const FUSION_POOL_FEE_RATE_OFFSET: usize = 143;
+const FUSION_POOL_SQRT_PRICE_OFFSET: usize = 167;
+const FUSION_POOL_TICK_CURRENT_INDEX_OFFSET: usize = 183;
 
 // FusionAMM NFT metadata update authority (from SDK)
 const FP_NFT_UPDATE_AUTH: &str = "GEyKMXn6zp7VN1JcXQJmBKnWcSm3sPZFLzTL2V2ub5K7";
 
+/// A FusionPool account as read from chain; token A is the base, B the quote
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pool {
     pub address: Pubkey,
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
-    pub base_reserve: u64,
-    pub quote_reserve: u64,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// sqrt(raw price) as a Q64.64 fixed-point number
+    pub sqrt_price: u128,
+    /// Tick the price is currently in
+    pub tick_current_index: i32,
+}
+
+/// A limit order `place_limit_order` left resting on chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedLimitOrder {
+    pub signature: String,
+    /// Order account, what `cancel_order` takes
+    pub order: Pubkey,
+}
+
+/// Outcome of `cancel_order`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancelledOrder {
+    pub signature: String,
+    /// Unfilled input returned to the owner, in base units
+    pub remaining: u64,
 }
 
 pub struct DefiTunaClient {
-    rpc_client: RpcClient,
+    /// Blocking client; shared so sends can run on the blocking thread pool
+    rpc_client: Arc<RpcClient>,
     program_id: Pubkey,
     base_mint: Pubkey,
     quote_mint: Pubkey,
     executor_keypair: Keypair,
+    /// Prefix for the memo attached to each trade (orders, cancels, swaps); `None` disables memos
+    memo_prefix: Option<String>,
+    order_seq: AtomicU64,
+    /// FusionAMM IDL (IDL_PATH): discriminators, instruction accounts, account layouts
+    idl: ProgramIdl,
+    order_layout: LimitOrderLayout,
+    /// Limit order accounts placed by this process and not cancelled since
+    placed_orders: Mutex<HashSet<Pubkey>>,
+    /// Routes market orders
+    jupiter: JupiterClient,
+    /// MAX_PRICE_IMPACT_PCT; `None` quotes any impact
+    max_price_impact_pct: Option<f64>,
+    ticks: PoolTicks,
 }
 
 impl DefiTunaClient {
     pub fn new(config: &BotConfig) -> Result<Self> {
-        let rpc_client = RpcClient::new(&config.rpc_url);
+        let rpc_client = Arc::new(RpcClient::new_with_timeout_and_commitment(&config.rpc_url, RPC_TIMEOUT, config.commitment));
         let program_id = Pubkey::from_str(&config.defituna_program_id)
             .context("Invalid DefiTuna program ID")?;
         let base_mint = Pubkey::from_str(&config.base_mint)
@@ -55,61 +121,163 @@ impl DefiTunaClient {
             .context("Invalid executor private key")?;
         let executor_keypair = Keypair::try_from(&executor_keypair[..])
             .context("Failed to parse executor keypair")?;
+        let idl = ProgramIdl::load(config.idl_path.as_deref())?;
+        let order_layout = LimitOrderLayout::from_idl(&idl)?;
+        let mut jupiter = JupiterClient::builder();
+        if let Some(url) = &config.jupiter_quote_url {
+            jupiter = jupiter.quote_url(url);
+        }
+
+        let spacing = config.tick_spacing.unwrap_or(DEFAULT_TICK_SPACING as u16);
 
         info!(
             "Initialized DefiTuna client: program={}, pair={}/{}, executor={}",
             program_id, base_mint, quote_mint, executor_keypair.pubkey()
         );
 
-        Ok(Self {
+        let mut client = Self {
             rpc_client,
             program_id,
             base_mint,
             quote_mint,
             executor_keypair,
-        })
+            memo_prefix: config.include_memo.then(|| config.memo_prefix.clone()),
+            order_seq: AtomicU64::new(0),
+            idl,
+            order_layout,
+            placed_orders: Mutex::new(HashSet::new()),
+            jupiter: jupiter.build(),
+            max_price_impact_pct: (config.max_price_impact_pct > 0.0).then_some(config.max_price_impact_pct),
+            ticks: PoolTicks::new(spacing, config.base_decimals, config.quote_decimals),
+        };
+
+        // TICK_SPACING overrides the pool's own, which is read when it can be
+        if config.tick_spacing.is_none() {
+            match client.read_pool_tick_spacing() {
+                Ok(spacing) => client.ticks.spacing = spacing as i32,
+                Err(e) => warn!("⚠️  Pool tick spacing unavailable, using {}: {}", spacing, e),
+            }
+        }
+        info!("   Tick spacing: {}", client.ticks.spacing);
+
+        Ok(client)
     }
 
-    pub async fn get_pool(&self) -> Result<Pool> {
-        // Derive pool PDA from token mints
-        let (pool_pda, _bump) = Pubkey::find_program_address(
+    /// Tick math for the traded pool
+    pub fn ticks(&self) -> PoolTicks {
+        self.ticks
+    }
+
+    /// Where limit order accounts keep their fields, per the IDL
+    pub fn order_layout(&self) -> &LimitOrderLayout {
+        &self.order_layout
+    }
+
+    /// Whether `order` was placed or adopted by this client (and not cancelled since)
+    pub fn owns_order(&self, order: &Pubkey) -> bool {
+        self.placed_orders.lock().unwrap().contains(order)
+    }
+
+    /// The limit orders on the traded pool whose NFTs the executor wallet
+    /// holds, adopted as this client's own
+    pub async fn discover_orders(&self) -> Result<Vec<LimitOrderUpdate>> {
+        let pool = self.pool_address().to_string();
+        let (program_id, owner) = (self.program_id, self.executor_keypair.pubkey());
+        let (layout, ticks) = (self.order_layout.clone(), self.ticks);
+        let mut orders = self
+            .spawn_rpc(move |rpc| fetch_owned_limit_orders(rpc, &program_id, &owner, &layout, &ticks))
+            .await??;
+        orders.retain(|order| order.pool == pool);
+        let mut placed = self.placed_orders.lock().unwrap();
+        for order in &orders {
+            placed.insert(Pubkey::from_str(&order.pubkey)?);
+        }
+        Ok(orders)
+    }
+
+    /// Client order id used to match on-chain memos to the bot's logs
+    fn next_client_order_id(&self) -> String {
+        let seq = self.order_seq.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{}", chrono::Utc::now().timestamp_millis(), seq)
+    }
+
+    /// Memo for the next trade's transaction; `None` when memos are off
+    fn next_memo(&self) -> Option<String> {
+        let memo = trade_memo(self.memo_prefix.as_ref()?, &self.next_client_order_id());
+        info!("   Memo: {}", memo);
+        Some(memo)
+    }
+
+    /// Pool PDA, derived from the token mints
+    pub fn pool_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
             &[
                 b"fusion_pool",
                 self.base_mint.as_ref(),
                 self.quote_mint.as_ref(),
             ],
             &self.program_id,
-        );
-        
-        info!("   Derived pool PDA: {}", pool_pda);
-        
-        Ok(Pool {
-            address: pool_pda,
-            base_mint: self.base_mint,
-            quote_mint: self.quote_mint,
-            base_reserve: 1_000_000_000,
-            quote_reserve: 100_000_000_000,
-        })
+        )
+        .0
+    }
+
+    /// The traded pool, decoded from its account
+    pub async fn get_pool(&self) -> Result<Pool> {
+        let address = self.pool_address();
+        let data = self
+            .spawn_rpc(move |rpc| {
+                rpc.get_account_data(&address)
+                    .with_context(|| format!("Failed to fetch pool account {}", address))
+            })
+            .await??;
+        let pool = decode_pool(address, &data)?;
+        if pool.base_mint != self.base_mint || pool.quote_mint != self.quote_mint {
+            anyhow::bail!(
+                "Pool {} trades {}/{}, expected {}/{}",
+                address, pool.base_mint, pool.quote_mint, self.base_mint, self.quote_mint
+            );
+        }
+        Ok(pool)
+    }
+
+    /// Maker fee charged by the pool, in bps, read from the pool account
+    pub async fn get_pool_fee_bps(&self) -> Result<f64> {
+        let address = self.pool_address();
+        let data = self
+            .spawn_rpc(move |rpc| rpc.get_account_data(&address).context("Failed to fetch pool account"))
+            .await??;
+        Ok(decode_pool_fee_rate(&data)? as f64 / 100.0)
     }
 
+    fn read_pool_tick_spacing(&self) -> Result<u16> {
+        let data = self
+            .rpc_client
+            .get_account_data(&self.pool_address())
+            .context("Failed to fetch pool account")?;
+        decode_pool_tick_spacing(&data)
+    }
+
+    /// Pool price from its sqrt price, in quote per base token
     pub async fn get_spot_price(&self) -> Result<f64> {
         let pool = self.get_pool().await?;
-        let price = (pool.quote_reserve as f64 / 1_000_000.0) / (pool.base_reserve as f64 / 1_000_000_000.0);
-        Ok(price)
+        if pool.sqrt_price == 0 {
+            anyhow::bail!("Pool {} has no price yet", pool.address);
+        }
+        Ok(self.ticks.sqrt_price_to_price(pool.sqrt_price))
     }
 
-    pub async fn place_limit_order(&self, is_bid: bool, price: f64, size: u64) -> Result<String> {
+    pub async fn place_limit_order(&self, is_bid: bool, price: f64, size: u64) -> Result<PlacedLimitOrder> {
         let order_type = if is_bid { "BID" } else { "ASK" };
-        let sol_amount = size as f64 / 1_000_000_000.0;
-        let usdc_value = sol_amount * price;
+        // `size` is quote, like every order size; an ask deposits its base equivalent
+        let deposit = if is_bid { size } else { self.ticks.quote_to_base(size, self.ticks.round_price_to_tick(price)) };
+        let usdc_value = size as f64 / 10f64.powi(self.ticks.quote_decimals as i32);
         
         // Get pool PDA
-        let pool = self.get_pool().await?;
-        let pool_address = pool.address;
+        let pool_address = self.pool_address();
         
         info!("� Placing ON-CHAIN {} limit order on DeFiTuna FusionAMM", order_type);
         info!("   Price: ${:.4}", price);
-        info!("   Size: {:.4} SOL (${:.2} USDC value)", sol_amount, usdc_value);
+        info!("   Size: ${:.2} USDC value (deposit {} of the input token)", usdc_value, deposit);
         info!("   Pool: {}", pool_address);
         info!("   Wallet: {}", self.executor_keypair.pubkey());
         
@@ -118,35 +286,27 @@ impl DefiTunaClient {
         info!("   Order NFT Mint: {}", limit_order_mint.pubkey());
         
         // Step 2: Derive limit order PDA (the actual order account)
-        let (limit_order_pda, _bump) = Pubkey::find_program_address(
-            &[b"limit_order", limit_order_mint.pubkey().as_ref()],
-            &self.program_id,
-        );
+        let limit_order_pda = self.limit_order_address(&limit_order_mint.pubkey());
         info!("   Order PDA: {}", limit_order_pda);
         
         // Step 3: Convert price to tick index (concentrated liquidity uses ticks)
-        let tick_index = self.price_to_tick_index(price, is_bid);
-        let initializable_tick = self.get_initializable_tick(tick_index);
+        let tick_index = self.ticks.price_to_tick_index(price);
+        let initializable_tick = self.ticks.initializable_tick(tick_index);
         info!("   Tick index: {} (initializable: {})", tick_index, initializable_tick);
+        info!(
+            "   Expected fill: ${:.4} (requested ${:.4})",
+            self.ticks.tick_index_to_price(initializable_tick),
+            price
+        );
         
         // Step 4: Calculate tick array address
-        let tick_array_start = (initializable_tick / (TICK_SPACING * 88)) * (TICK_SPACING * 88);
-        let (tick_array_pda, _) = Pubkey::find_program_address(
-            &[
-                b"tick_array",
-                pool_address.as_ref(),
-                &tick_array_start.to_le_bytes(),
-            ],
-            &self.program_id,
-        );
+        let (tick_array_pda, tick_array_start) = self.tick_array_address(&pool_address, initializable_tick);
         info!("   Tick array: {} (start: {})", tick_array_pda, tick_array_start);
         
         // Step 5: Get token accounts
         let token_2022 = Pubkey::from_str(SPL_TOKEN_2022_ID)?;
-        let limit_order_token_account = get_associated_token_address(
-            &self.executor_keypair.pubkey(),
-            &limit_order_mint.pubkey(),
-        );
+        let limit_order_token_account =
+            self.limit_order_token_account(&self.executor_keypair.pubkey(), &limit_order_mint.pubkey())?;
         
         let input_mint = if is_bid { self.quote_mint } else { self.base_mint };
         let user_token_account = get_associated_token_address(
@@ -155,14 +315,7 @@ impl DefiTunaClient {
         );
         
         // Step 6: Derive token vault PDA
-        let (token_vault, _) = Pubkey::find_program_address(
-            &[
-                b"token_vault",
-                pool_address.as_ref(),
-                input_mint.as_ref(),
-            ],
-            &self.program_id,
-        );
+        let token_vault = self.token_vault_address(&pool_address, &input_mint);
         
         info!("   Input token: {}", input_mint);
         info!("   User ATA: {}", user_token_account);
@@ -171,14 +324,19 @@ impl DefiTunaClient {
         // Build instructions
         let mut instructions = Vec::new();
         
+        // Optional memo for on-chain attribution
+        if let Some(memo) = self.next_memo() {
+            instructions.push(memo_instruction(&memo, &self.executor_keypair.pubkey()));
+        }
+        
         // Add compute budget to avoid running out
         instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(400_000));
         
         // Instruction 1: OpenLimitOrder (creates the order account)
         let open_order_data = self.build_open_limit_order_data(initializable_tick, is_bid)?;
-        instructions.push(Instruction {
-            program_id: self.program_id,
-            accounts: vec![
+        instructions.push(self.program_instruction(
+            "open_limit_order",
+            vec![
                 AccountMeta::new(self.executor_keypair.pubkey(), true),  // funder
                 AccountMeta::new_readonly(self.executor_keypair.pubkey(), false),  // owner
                 AccountMeta::new(limit_order_pda, false),  // limit_order
@@ -190,14 +348,14 @@ impl DefiTunaClient {
                 AccountMeta::new_readonly(system_program::ID, false),  // system_program
                 AccountMeta::new_readonly(Pubkey::from_str(FP_NFT_UPDATE_AUTH)?, false),  // metadata_update_auth
             ],
-            data: open_order_data,
-        });
+            open_order_data,
+        )?);
         
         // Instruction 2: IncreaseLimitOrder (deposits tokens)
-        let increase_order_data = self.build_increase_limit_order_data(size)?;
-        instructions.push(Instruction {
-            program_id: self.program_id,
-            accounts: vec![
+        let increase_order_data = self.build_increase_limit_order_data(deposit)?;
+        instructions.push(self.program_instruction(
+            "increase_limit_order",
+            vec![
                 AccountMeta::new(self.executor_keypair.pubkey(), true),  // limit_order_authority
                 AccountMeta::new(pool_address, false),  // fusion_pool
                 AccountMeta::new(limit_order_pda, false),  // limit_order
@@ -209,8 +367,8 @@ impl DefiTunaClient {
                 AccountMeta::new_readonly(spl_token::ID, false),  // token_program
                 AccountMeta::new_readonly(Pubkey::from_str(MEMO_PROGRAM_ID)?, false),  // memo_program
             ],
-            data: increase_order_data,
-        });
+            increase_order_data,
+        )?);
         
         // Sign and send transaction
         info!("📤 Sending transaction with {} instructions...", instructions.len());
@@ -223,7 +381,7 @@ impl DefiTunaClient {
             recent_blockhash,
         );
         
-        let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
+        let signature = self.send_and_confirm(&transaction).await?;
         let sig = signature.to_string();
         
         info!("✅ ON-CHAIN limit order placed successfully!");
@@ -231,28 +389,42 @@ impl DefiTunaClient {
         info!("🔗 Explorer: https://explorer.solana.com/tx/{}?cluster=devnet", sig);
         info!("💎 Order NFT: {}", limit_order_mint.pubkey());
         info!("📋 Order Account: {}", limit_order_pda);
+
+        self.placed_orders.lock().unwrap().insert(limit_order_pda);
         
-        Ok(sig)
+        Ok(PlacedLimitOrder { signature: sig, order: limit_order_pda })
     }
-    
-    fn price_to_tick_index(&self, price: f64, _is_bid: bool) -> i32 {
-        // Convert price to tick using: tick = log(price) / log(1.0001)
-        // For SOL/USDC with 9 and 6 decimals
-        let adjusted_price = price / 1000.0; // Adjust for decimal difference
-        let tick = (adjusted_price.ln() / 1.0001_f64.ln()) as i32;
-        tick
+
+    /// A FusionAMM instruction, refused if its accounts don't match the IDL's
+    fn program_instruction(&self, name: &str, accounts: Vec<AccountMeta>, data: Vec<u8>) -> Result<Instruction> {
+        self.idl.check_accounts(name, &accounts)?;
+        Ok(Instruction { program_id: self.program_id, accounts, data })
     }
-    
-    fn get_initializable_tick(&self, tick: i32) -> i32 {
-        // Round to nearest initializable tick based on spacing
-        let remainder = tick % TICK_SPACING;
-        if remainder == 0 {
-            tick
-        } else if remainder > 0 {
-            tick - remainder
-        } else {
-            tick - (TICK_SPACING + remainder)
-        }
+
+    /// The owner's token-2022 account holding an order NFT
+    fn limit_order_token_account(&self, owner: &Pubkey, limit_order_mint: &Pubkey) -> Result<Pubkey> {
+        let token_2022 = Pubkey::from_str(SPL_TOKEN_2022_ID)?;
+        Ok(get_associated_token_address_with_program_id(owner, limit_order_mint, &token_2022))
+    }
+
+    /// Limit order account for an order NFT mint
+    fn limit_order_address(&self, limit_order_mint: &Pubkey) -> Pubkey {
+        limit_order_address(&self.program_id, limit_order_mint)
+    }
+
+    /// Tick array holding `tick_index`, and the array's start tick
+    fn tick_array_address(&self, pool: &Pubkey, tick_index: i32) -> (Pubkey, i32) {
+        let start = self.ticks.tick_array_start(tick_index);
+        let (address, _) = Pubkey::find_program_address(
+            &[b"tick_array", pool.as_ref(), &start.to_le_bytes()],
+            &self.program_id,
+        );
+        (address, start)
+    }
+
+    /// Pool vault for one of its tokens
+    fn token_vault_address(&self, pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"token_vault", pool.as_ref(), mint.as_ref()], &self.program_id).0
     }
     
     fn build_open_limit_order_data(&self, tick_index: i32, a_to_b: bool) -> Result<Vec<u8>> {
@@ -264,8 +436,8 @@ impl DefiTunaClient {
         
         let mut data = Vec::new();
         
-        // Calculate discriminator using anchor's method: first 8 bytes of sha256("global:open_limit_order")
-        let discriminator = anchor_discriminator("open_limit_order");
+        // From IDL_PATH, else anchor's method: first 8 bytes of sha256("global:open_limit_order")
+        let discriminator = self.idl.discriminator("open_limit_order");
         data.extend_from_slice(&discriminator);
         
         // tick_index
@@ -288,7 +460,7 @@ impl DefiTunaClient {
         
         let mut data = Vec::new();
         
-        let discriminator = anchor_discriminator("increase_limit_order");
+        let discriminator = self.idl.discriminator("increase_limit_order");
         data.extend_from_slice(&discriminator);
         
         // amount
@@ -300,42 +472,370 @@ impl DefiTunaClient {
         Ok(data)
     }
 
+    /// Swap at market through Jupiter: a buy spends `amount` of the quote token
+    /// on the base token, a sell spends `amount` of the base token. Returns the
+    /// confirmed signature.
     pub async fn execute_market_order(&self, is_buy: bool, amount: u64, max_slippage_bps: u16) -> Result<String> {
-        warn!("🚧 PLACEHOLDER: Market orders not yet implemented");
-        info!("💱 Market Order Parameters: is_buy={}, amount={}, max_slippage={}bps ({:.2}%)", 
-            is_buy, amount, max_slippage_bps, max_slippage_bps as f64 / 100.0);
-        
-        let price = self.get_spot_price().await.unwrap_or(100.0);
-        let max_price_deviation = price * (max_slippage_bps as f64 / 10000.0);
-        
-        if is_buy {
-            let usdc = amount as f64 / 1_000_000.0;
-            let max_price = price + max_price_deviation;
-            info!("📝 Would BUY ${:.2} USDC @ ${:.2}/SOL (max price: ${:.2})", usdc, price, max_price);
-            info!("   Expected SOL received: {:.4} SOL", usdc / price);
+        let (input_mint, output_mint) = if is_buy {
+            (self.quote_mint, self.base_mint)
         } else {
-            let sol = amount as f64 / 1_000_000_000.0;
-            let min_price = price - max_price_deviation;
-            info!("📝 Would SELL {:.4} SOL @ ${:.2}/SOL (min price: ${:.2})", sol, price, min_price);
-            info!("   Expected USDC received: ${:.2}", sol * price);
+            (self.base_mint, self.quote_mint)
+        };
+        info!(
+            "💱 Market {}: {} of {} via Jupiter (max slippage {}bps)",
+            if is_buy { "BUY" } else { "SELL" },
+            amount,
+            input_mint,
+            max_slippage_bps
+        );
+
+        let quote = self
+            .jupiter
+            .get_quote(&input_mint.to_string(), &output_mint.to_string(), amount, max_slippage_bps)
+            .await
+            .context("Failed to get Jupiter quote")?;
+        check_quote_route(&quote, self.max_price_impact_pct).context("Refusing market order")?;
+
+        let swap = self
+            .jupiter
+            .get_swap_transaction(&quote, &self.executor_keypair.pubkey().to_string(), true, None)
+            .await
+            .context("Failed to get Jupiter swap transaction")?;
+        let transaction_bytes = base64::engine::general_purpose::STANDARD
+            .decode(&swap.swap_transaction)
+            .context("Failed to decode swap transaction")?;
+        let mut transaction: VersionedTransaction =
+            bincode::deserialize(&transaction_bytes).context("Failed to deserialize swap transaction")?;
+        if let Some(memo) = self.next_memo() {
+            append_memo(&mut transaction.message, &memo)?;
         }
-        
-        let sig = format!("PLACEHOLDER_MARKET_{}_{}", if is_buy { "BUY" } else { "SELL" }, chrono::Utc::now().timestamp());
-        info!("✅ Simulated transaction: {}", sig);
-        info!("🔗 View on explorer: https://explorer.solana.com/tx/{}?cluster=devnet", sig);
-        Ok(sig)
+
+        let blockhash = self
+            .spawn_rpc(|rpc| rpc.get_latest_blockhash().context("Failed to get latest blockhash"))
+            .await??;
+        transaction.message.set_recent_blockhash(blockhash);
+        let transaction = VersionedTransaction::try_new(transaction.message, &[&self.executor_keypair])
+            .context("Failed to sign swap transaction")?;
+
+        let signature = self
+            .send_and_confirm(&transaction)
+            .await
+            .context("Failed to send swap transaction")?;
+        info!(
+            "✅ Market order filled: {} in, {} out (quoted) | {}",
+            quote.in_amount, quote.out_amount, signature
+        );
+        Ok(signature.to_string())
+    }
+
+    /// Send `transaction` and poll until it reaches the client's commitment.
+    /// The blocking RPC calls run on tokio's blocking pool and the wait between
+    /// polls is an async sleep, so the runtime keeps serving other tasks. Gives
+    /// up after `CONFIRM_TIMEOUT`; the transaction may still land after that.
+    async fn send_and_confirm<T>(&self, transaction: &T) -> Result<Signature>
+    where
+        T: SerializableTransaction + Clone + Send + 'static,
+    {
+        let transaction = transaction.clone();
+        let signature = self
+            .spawn_rpc(move |rpc| rpc.send_transaction(&transaction).context("Failed to send transaction"))
+            .await??;
+        let deadline = Instant::now() + CONFIRM_TIMEOUT;
+        loop {
+            match self
+                .spawn_rpc(move |rpc| {
+                    rpc.get_signature_status_with_commitment(&signature, rpc.commitment())
+                        .context("Failed to check transaction status")
+                })
+                .await??
+            {
+                Some(Ok(())) => return Ok(signature),
+                Some(Err(err)) => anyhow::bail!("Transaction {} failed: {}", signature, err),
+                None if Instant::now() >= deadline => anyhow::bail!(
+                    "Transaction {} not confirmed within {}s",
+                    signature,
+                    CONFIRM_TIMEOUT.as_secs()
+                ),
+                None => tokio::time::sleep(CONFIRM_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    /// Run a blocking RPC call on tokio's blocking pool
+    async fn spawn_rpc<R, F>(&self, call: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&RpcClient) -> R + Send + 'static,
+    {
+        let rpc_client = self.rpc_client.clone();
+        tokio::task::spawn_blocking(move || call(&rpc_client))
+            .await
+            .context("RPC task panicked")
+    }
+
+    /// Withdraw what's left of a limit order (unfilled input and filled output)
+    /// and close it. Everything the instructions need is read from the order
+    /// account; the order is only ours to close while we hold its NFT.
+    pub async fn cancel_order(&self, order_id: Pubkey) -> Result<CancelledOrder> {
+        let data = self
+            .spawn_rpc(move |rpc| rpc.get_account_data(&order_id).context("Failed to fetch limit order account"))
+            .await??;
+        let order = decode_limit_order(&data, &self.order_layout, &self.ticks)?;
+        let pool = self.pool_address();
+        if order.pool != pool.to_string() {
+            anyhow::bail!("Limit order {} rests on pool {}, not {}", order_id, order.pool, pool);
+        }
+        let mint = Pubkey::from_str(&order.mint).context("Invalid limit order mint")?;
+        if self.limit_order_address(&mint) != order_id {
+            anyhow::bail!("{} isn't the limit order of its NFT {}", order_id, mint);
+        }
+
+        let owner = self.executor_keypair.pubkey();
+        let nft_account = self.limit_order_token_account(&owner, &mint)?;
+        let held = self
+            .spawn_rpc(move |rpc| {
+                rpc.get_token_account_balance(&nft_account).with_context(|| {
+                    format!("Limit order {} isn't held by {}: no account for its NFT", order_id, owner)
+                })
+            })
+            .await??;
+        if held.amount != "1" {
+            anyhow::bail!("Limit order {} isn't held by {}: its NFT was transferred", order_id, owner);
+        }
+
+        // Decreasing by the whole order withdraws whatever it holds, filled or not
+        let remaining = order.size.saturating_sub(order.filled);
+        info!("🗑️  Cancelling limit order {} ({} of {} unfilled)", order_id, remaining, order.size);
+        let mut instructions = self.cancel_limit_order_instructions(&pool, &mint, order.tick_index, order.size)?;
+        if let Some(memo) = self.next_memo() {
+            instructions.insert(0, memo_instruction(&memo, &owner));
+        }
+
+        let recent_blockhash = self
+            .spawn_rpc(|rpc| rpc.get_latest_blockhash().context("Failed to get latest blockhash"))
+            .await??;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.executor_keypair.pubkey()),
+            &[&self.executor_keypair],
+            recent_blockhash,
+        );
+        let signature = self
+            .send_and_confirm(&transaction)
+            .await
+            .context("Failed to cancel limit order")?;
+        self.placed_orders.lock().unwrap().remove(&order_id);
+
+        let sig = signature.to_string();
+        info!("✅ Limit order {} cancelled: {}", order_id, sig);
+        Ok(CancelledOrder { signature: sig, remaining })
+    }
+
+    /// DecreaseLimitOrder by `amount` (returning both tokens to the owner's
+    /// accounts), then CloseLimitOrder, which burns the order NFT
+    fn cancel_limit_order_instructions(
+        &self,
+        pool: &Pubkey,
+        limit_order_mint: &Pubkey,
+        tick_index: i32,
+        amount: u64,
+    ) -> Result<Vec<Instruction>> {
+        let owner = self.executor_keypair.pubkey();
+        let limit_order_pda = self.limit_order_address(limit_order_mint);
+        let limit_order_token_account = self.limit_order_token_account(&owner, limit_order_mint)?;
+        let (tick_array_pda, _) = self.tick_array_address(pool, tick_index);
+        let token_2022 = Pubkey::from_str(SPL_TOKEN_2022_ID)?;
+
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(400_000)];
+
+        instructions.push(self.program_instruction(
+            "decrease_limit_order",
+            vec![
+                AccountMeta::new(owner, true),  // limit_order_authority
+                AccountMeta::new(*pool, false),  // fusion_pool
+                AccountMeta::new(limit_order_pda, false),  // limit_order
+                AccountMeta::new_readonly(limit_order_token_account, false),  // limit_order_token_account
+                AccountMeta::new_readonly(self.base_mint, false),  // token_mint_a
+                AccountMeta::new_readonly(self.quote_mint, false),  // token_mint_b
+                AccountMeta::new(get_associated_token_address(&owner, &self.base_mint), false),  // token_owner_account_a
+                AccountMeta::new(get_associated_token_address(&owner, &self.quote_mint), false),  // token_owner_account_b
+                AccountMeta::new(self.token_vault_address(pool, &self.base_mint), false),  // token_vault_a
+                AccountMeta::new(self.token_vault_address(pool, &self.quote_mint), false),  // token_vault_b
+                AccountMeta::new(tick_array_pda, false),  // tick_array
+                AccountMeta::new_readonly(spl_token::ID, false),  // token_program_a
+                AccountMeta::new_readonly(spl_token::ID, false),  // token_program_b
+                AccountMeta::new_readonly(Pubkey::from_str(MEMO_PROGRAM_ID)?, false),  // memo_program
+            ],
+            self.build_decrease_limit_order_data(amount)?,
+        )?);
+
+        instructions.push(self.program_instruction(
+            "close_limit_order",
+            vec![
+                AccountMeta::new(owner, true),  // limit_order_authority
+                AccountMeta::new(owner, false),  // receiver (of the closed accounts' rent)
+                AccountMeta::new(limit_order_pda, false),  // limit_order
+                AccountMeta::new(*limit_order_mint, false),  // limit_order_mint
+                AccountMeta::new(limit_order_token_account, false),  // limit_order_token_account
+                AccountMeta::new_readonly(token_2022, false),  // token2022_program
+            ],
+            self.idl.discriminator("close_limit_order").to_vec(),
+        )?);
+
+        Ok(instructions)
+    }
+
+    fn build_decrease_limit_order_data(&self, amount: u64) -> Result<Vec<u8>> {
+        // DecreaseLimitOrder instruction format, as IncreaseLimitOrder:
+        // [0-8]: discriminator
+        // [8-16]: amount (u64)
+        // [16]: remaining_accounts_info option (None = 0)
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.idl.discriminator("decrease_limit_order"));
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(0); // None
+
+        Ok(data)
+    }
+}
+
+/// Tick math for one pool: its tick spacing, and the token decimals that turn a
+/// human price (quote per base token) into the raw price ticks are based on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolTicks {
+    pub spacing: i32,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+}
+
+impl Default for PoolTicks {
+    /// SOL/USDC on a standard pool
+    fn default() -> Self {
+        Self { spacing: DEFAULT_TICK_SPACING, base_decimals: 9, quote_decimals: 6 }
+    }
+}
+
+impl PoolTicks {
+    pub fn new(spacing: u16, base_decimals: u8, quote_decimals: u8) -> Self {
+        Self { spacing: spacing as i32, base_decimals, quote_decimals }
+    }
+
+    /// Raw price (quote base units per base base unit) over human price
+    fn decimal_adjustment(&self) -> f64 {
+        10f64.powi(self.quote_decimals as i32 - self.base_decimals as i32)
+    }
+
+    /// Tick at or below `price`: tick = floor(log(raw price) / log(1.0001))
+    pub fn price_to_tick_index(&self, price: f64) -> i32 {
+        let raw_price = price * self.decimal_adjustment();
+        (raw_price.ln() / 1.0001_f64.ln()).floor() as i32
+    }
+
+    /// Round a tick down to the nearest initializable tick based on spacing
+    pub fn initializable_tick(&self, tick: i32) -> i32 {
+        tick - tick.rem_euclid(self.spacing)
+    }
+
+    /// Inverse of `price_to_tick_index`
+    pub fn tick_index_to_price(&self, tick: i32) -> f64 {
+        1.0001_f64.powi(tick) / self.decimal_adjustment()
+    }
+
+    /// Human price from a pool's Q64.64 sqrt price
+    pub fn sqrt_price_to_price(&self, sqrt_price: u128) -> f64 {
+        let sqrt = sqrt_price as f64 / 2f64.powi(64);
+        sqrt * sqrt / self.decimal_adjustment()
     }
 
-    pub async fn cancel_order(&self, order_id: Pubkey) -> Result<String> {
-        warn!("🚧 PLACEHOLDER: Cancel not yet implemented");
-        info!("📝 Would cancel: {}", order_id);
-        Ok(format!("PLACEHOLDER_CANCEL_{}", chrono::Utc::now().timestamp()))
+    /// Price a limit order actually rests at once rounded to an initializable tick
+    pub fn round_price_to_tick(&self, price: f64) -> f64 {
+        self.tick_index_to_price(self.initializable_tick(self.price_to_tick_index(price)))
+    }
+
+    /// Base-token base units `amount` quote base units buy at `price`
+    pub fn quote_to_base(&self, amount: u64, price: f64) -> u64 {
+        (amount as f64 / price / self.decimal_adjustment()).round() as u64
+    }
+
+    /// Value of `amount` base-token base units at `price`, in quote base units
+    pub fn base_to_quote(&self, amount: u64, price: f64) -> u64 {
+        (amount as f64 * price * self.decimal_adjustment()).round() as u64
+    }
+
+    /// First tick of the tick array (88 initializable ticks) holding `tick`
+    pub fn tick_array_start(&self, tick: i32) -> i32 {
+        let ticks_per_array = self.spacing * TICKS_PER_ARRAY;
+        tick.div_euclid(ticks_per_array) * ticks_per_array
+    }
+}
+
+/// Memo text for an order: `<prefix>:<client_order_id>`
+pub fn trade_memo(prefix: &str, client_order_id: &str) -> String {
+    format!("{}:{}", prefix, client_order_id)
+}
+
+/// Append a memo instruction to an already compiled (e.g. Jupiter-built)
+/// message, signed by its fee payer. A new memo program key goes last among
+/// the static keys, so lookup-table account indexes move up by one.
+pub fn append_memo(message: &mut VersionedMessage, memo: &str) -> Result<()> {
+    let memo_program = Pubkey::from_str(MEMO_PROGRAM_ID).expect("valid memo program id");
+    let (header, account_keys, instructions, lookups) = match message {
+        VersionedMessage::Legacy(message) => {
+            (&mut message.header, &mut message.account_keys, &mut message.instructions, 0)
+        }
+        VersionedMessage::V0(message) => {
+            let lookups = message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum();
+            (&mut message.header, &mut message.account_keys, &mut message.instructions, lookups)
+        }
+    };
+
+    let program_id_index = match account_keys.iter().position(|key| *key == memo_program) {
+        Some(index) => index,
+        None => {
+            if account_keys.len() + lookups >= 256 {
+                anyhow::bail!(
+                    "No room for the memo program among the message's {} accounts",
+                    account_keys.len() + lookups
+                );
+            }
+            let static_keys = account_keys.len() as u8;
+            for instruction in instructions.iter_mut() {
+                for index in instruction.accounts.iter_mut().chain([&mut instruction.program_id_index]) {
+                    if *index >= static_keys {
+                        *index += 1;
+                    }
+                }
+            }
+            account_keys.push(memo_program);
+            header.num_readonly_unsigned_accounts += 1;
+            account_keys.len() - 1
+        }
+    };
+    instructions.push(CompiledInstruction {
+        program_id_index: program_id_index as u8,
+        accounts: vec![0],
+        data: memo.as_bytes().to_vec(),
+    });
+    Ok(())
+}
+
+/// SPL Memo instruction carrying `memo`, signed by `signer`
+pub fn memo_instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: Pubkey::from_str(MEMO_PROGRAM_ID).expect("valid memo program id"),
+        accounts: vec![AccountMeta::new_readonly(*signer, true)],
+        data: memo.as_bytes().to_vec(),
     }
 }
 
 /// Calculate Anchor instruction discriminator
 /// Anchor uses: discriminator = first 8 bytes of sha256("global:<instruction_name>")
-fn anchor_discriminator(name: &str) -> [u8; 8] {
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
     use sha2::{Sha256, Digest};
     let mut hasher = Sha256::new();
     hasher.update(format!("global:{}", name));
@@ -344,3 +844,412 @@ fn anchor_discriminator(name: &str) -> [u8; 8] {
     discriminator.copy_from_slice(&result[..8]);
     discriminator
 }
+
+/// Anchor account discriminator: first 8 bytes of sha256("account:<AccountName>")
+pub fn anchor_account_discriminator(name: &str) -> [u8; 8] {
+    use sha2::{Sha256, Digest};
+    let result = Sha256::digest(format!("account:{}", name));
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&result[..8]);
+    discriminator
+}
+
+/// Fee rate of a FusionPool account, in hundredths of a bp (3000 = 0.30%)
+pub fn decode_pool_fee_rate(data: &[u8]) -> Result<u16> {
+    decode_pool_u16(data, FUSION_POOL_FEE_RATE_OFFSET)
+}
+
+/// Tick spacing of a FusionPool account
+pub fn decode_pool_tick_spacing(data: &[u8]) -> Result<u16> {
+    let spacing = decode_pool_u16(data, FUSION_POOL_TICK_SPACING_OFFSET)?;
+    if spacing == 0 {
+        anyhow::bail!("Pool has a tick spacing of 0");
+    }
+    Ok(spacing)
+}
+
+/// Mints, vaults and price of the FusionPool account at `address`
+pub fn decode_pool(address: Pubkey, data: &[u8]) -> Result<Pool> {
+    check_pool_data(data, FUSION_POOL_TICK_CURRENT_INDEX_OFFSET + 4)?;
+    let pubkey_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).expect("32 bytes");
+    let mut sqrt_price = [0u8; 16];
+    sqrt_price.copy_from_slice(&data[FUSION_POOL_SQRT_PRICE_OFFSET..FUSION_POOL_SQRT_PRICE_OFFSET + 16]);
+    Ok(Pool {
+        address,
+        base_mint: pubkey_at(FUSION_POOL_MINT_A_OFFSET),
+        quote_mint: pubkey_at(FUSION_POOL_MINT_B_OFFSET),
+        base_vault: pubkey_at(FUSION_POOL_VAULT_A_OFFSET),
+        quote_vault: pubkey_at(FUSION_POOL_VAULT_B_OFFSET),
+        sqrt_price: u128::from_le_bytes(sqrt_price),
+        tick_current_index: i32::from_le_bytes(
+            data[FUSION_POOL_TICK_CURRENT_INDEX_OFFSET..FUSION_POOL_TICK_CURRENT_INDEX_OFFSET + 4]
+                .try_into()
+                .expect("4 bytes"),
+        ),
+    })
+}
+
+fn decode_pool_u16(data: &[u8], offset: usize) -> Result<u16> {
+    check_pool_data(data, FUSION_POOL_FEE_RATE_OFFSET + 2)?;
+    Ok(u16::from_le_bytes([data[offset], data[offset + 1]]))
+}
+
+fn check_pool_data(data: &[u8], min_len: usize) -> Result<()> {
+    if data.len() < min_len {
+        anyhow::bail!("Account data too short for a pool: {} bytes", data.len());
+    }
+    if data[..8] != anchor_account_discriminator("FusionPool") {
+        anyhow::bail!("Not a FusionPool account");
+    }
+    Ok(())
+}
+
+/// Limit order account for an order NFT mint
+pub fn limit_order_address(program_id: &Pubkey, limit_order_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"limit_order", limit_order_mint.as_ref()], program_id).0
+}
+
+/// FusionPool account data for tests: the mints, sqrt price and current tick set
+#[cfg(test)]
+pub(crate) fn pool_account_data(mint_a: &Pubkey, mint_b: &Pubkey, sqrt_price: u128, tick_current_index: i32) -> Vec<u8> {
+    let mut data = anchor_account_discriminator("FusionPool").to_vec();
+    data.resize(FUSION_POOL_TICK_CURRENT_INDEX_OFFSET + 64, 0);
+    data[FUSION_POOL_MINT_A_OFFSET..FUSION_POOL_MINT_A_OFFSET + 32].copy_from_slice(mint_a.as_ref());
+    data[FUSION_POOL_MINT_B_OFFSET..FUSION_POOL_MINT_B_OFFSET + 32].copy_from_slice(mint_b.as_ref());
+    data[FUSION_POOL_SQRT_PRICE_OFFSET..FUSION_POOL_SQRT_PRICE_OFFSET + 16].copy_from_slice(&sqrt_price.to_le_bytes());
+    data[FUSION_POOL_TICK_CURRENT_INDEX_OFFSET..FUSION_POOL_TICK_CURRENT_INDEX_OFFSET + 4]
+        .copy_from_slice(&tick_current_index.to_le_bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::FUSION_IDL;
+    use crate::solana_rpc_client::order_account_data;
+    use solana_client::rpc_client::Mocks;
+    use solana_client::rpc_request::RpcRequest;
+    use std::collections::HashMap;
+
+    fn test_client() -> DefiTunaClient {
+        DefiTunaClient {
+            rpc_client: Arc::new(RpcClient::new_mock("fails".to_string())),
+            program_id: Pubkey::new_unique(),
+            base_mint: Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
+            quote_mint: Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap(),
+            executor_keypair: Keypair::new(),
+            memo_prefix: None,
+            order_seq: AtomicU64::new(0),
+            idl: ProgramIdl::default(),
+            order_layout: LimitOrderLayout::default(),
+            placed_orders: Mutex::new(HashSet::new()),
+            jupiter: JupiterClient::new(),
+            max_price_impact_pct: None,
+            ticks: PoolTicks::default(),
+        }
+    }
+
+    #[test]
+    fn test_cancel_limit_order_instructions() {
+        let mut client = test_client();
+        client.idl = ProgramIdl::from_idl_json(FUSION_IDL).unwrap();
+        let owner = client.executor_keypair.pubkey();
+        let pool = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let limit_order = client.limit_order_address(&mint);
+
+        let instructions = client.cancel_limit_order_instructions(&pool, &mint, 5056, 1_500_000_000).unwrap();
+        let [_, decrease, close] = &instructions[..] else {
+            panic!("expected compute budget, decrease and close, got {} instructions", instructions.len());
+        };
+
+        assert_eq!(decrease.data[..8], [7; 8]);
+        assert_eq!(decrease.program_id, client.program_id);
+        assert_eq!(decrease.accounts[0].pubkey, owner);
+        assert_eq!(decrease.accounts[1].pubkey, pool);
+        assert_eq!(decrease.accounts[2].pubkey, limit_order);
+        // The order NFT is a token-2022 mint
+        let token_2022 = Pubkey::from_str(SPL_TOKEN_2022_ID).unwrap();
+        let nft_account = get_associated_token_address_with_program_id(&owner, &mint, &token_2022);
+        assert_eq!(decrease.accounts[3].pubkey, nft_account);
+        // The order rests in the same tick array it was placed into
+        assert_eq!(decrease.accounts[10].pubkey, client.tick_array_address(&pool, 5056).0);
+        assert_eq!(decrease.data[8..16], 1_500_000_000u64.to_le_bytes());
+        assert_eq!(decrease.data[16], 0);
+
+        assert_eq!(close.data, [9; 8]);
+        assert_eq!(close.accounts[2].pubkey, limit_order);
+        assert_eq!(close.accounts[3].pubkey, mint);
+        assert_eq!(close.accounts[4].pubkey, nft_account);
+
+        // Built against an IDL that disagrees, nothing is sent
+        client.idl = ProgramIdl::from_idl_json(&FUSION_IDL.replace(r#"{ "name": "memo_program" }"#, r#"{ "name": "memo_program" }, { "name": "extra" }"#)).unwrap();
+        let err = client.cancel_limit_order_instructions(&pool, &mint, 5056, 1).unwrap_err();
+        assert!(err.to_string().contains("decrease_limit_order takes 15 accounts"), "{}", err);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cancel_reads_the_order_from_chain() {
+        // A client on a mock RPC serving one order account and its NFT balance
+        let cancel = |order_pool: Option<Pubkey>, nft_amount: &str| {
+            let mut client = test_client();
+            let mint = Pubkey::new_unique();
+            let order = client.limit_order_address(&mint);
+            let pool = order_pool.unwrap_or_else(|| client.pool_address());
+            let data = order_account_data(&pool, &mint, 5056, 2_000_000_000, true);
+            let mut mocks = Mocks::new();
+            mocks.insert(
+                RpcRequest::GetAccountInfo,
+                serde_json::json!({ "context": { "slot": 1 }, "value": {
+                    "lamports": 2_039_280,
+                    "space": data.len(),
+                    "data": [base64::engine::general_purpose::STANDARD.encode(&data), "base64"],
+                    "owner": client.program_id.to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                } }),
+            );
+            mocks.insert(
+                RpcRequest::GetTokenAccountBalance,
+                serde_json::json!({ "context": { "slot": 1 }, "value": {
+                    "amount": nft_amount, "decimals": 0, "uiAmount": null, "uiAmountString": nft_amount,
+                } }),
+            );
+            client.rpc_client = Arc::new(RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks));
+            async move { client.cancel_order(order).await }
+        };
+
+        // Nothing in memory about the order: it's closed from what the chain says
+        let cancelled = cancel(None, "1").await.unwrap();
+        assert_eq!(cancelled.remaining, 2_000_000_000);
+        assert!(!cancelled.signature.is_empty());
+
+        let err = cancel(None, "0").await.unwrap_err();
+        assert!(err.to_string().contains("isn't held by"), "{}", err);
+        let err = cancel(Some(Pubkey::new_unique()), "1").await.unwrap_err();
+        assert!(err.to_string().contains("rests on pool"), "{}", err);
+
+        let err = test_client().cancel_order(Pubkey::new_unique()).await.unwrap_err();
+        assert!(err.to_string().contains("Failed to fetch limit order account"), "{}", err);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_market_order_swaps_through_jupiter() {
+        // Quotes and swaps from a local Jupiter stand-in, recording the mints asked for
+        let mut client = test_client();
+        let payer = client.executor_keypair.pubkey();
+        let quoted: Arc<Mutex<Vec<(String, String, String)>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded = quoted.clone();
+        let app = axum::Router::new()
+            .route(
+                "/quote",
+                axum::routing::get(move |axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>| {
+                    let recorded = recorded.clone();
+                    async move {
+                        recorded.lock().unwrap().push((
+                            params["inputMint"].clone(),
+                            params["outputMint"].clone(),
+                            params["slippageBps"].clone(),
+                        ));
+                        axum::Json(serde_json::json!({
+                            "inputMint": params["inputMint"],
+                            "inAmount": params["amount"],
+                            "outputMint": params["outputMint"],
+                            "outAmount": "1000",
+                            "otherAmountThreshold": "990",
+                            "swapMode": "ExactIn",
+                            "slippageBps": 50,
+                            "platformFee": null,
+                            "priceImpactPct": "0",
+                            "routePlan": [{
+                                "swapInfo": {
+                                    "ammKey": Pubkey::new_unique().to_string(),
+                                    "label": "Orca",
+                                    "inputMint": params["inputMint"],
+                                    "outputMint": params["outputMint"],
+                                    "inAmount": params["amount"],
+                                    "outAmount": "1000",
+                                    "feeAmount": "0",
+                                    "feeMint": params["inputMint"],
+                                },
+                                "percent": 100,
+                            }],
+                        }))
+                    }
+                }),
+            )
+            .route(
+                "/swap",
+                axum::routing::post(move || async move {
+                    let noop = memo_instruction("swap", &payer);
+                    let unsigned = VersionedTransaction::from(Transaction::new_unsigned(
+                        solana_sdk::message::Message::new(&[noop], Some(&payer)),
+                    ));
+                    axum::Json(serde_json::json!({
+                        "swapTransaction": base64::engine::general_purpose::STANDARD
+                            .encode(bincode::serialize(&unsigned).unwrap()),
+                        "lastValidBlockHeight": 100,
+                    }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        client.jupiter = JupiterClient::with_base_url(&format!("http://{}", addr));
+        client.rpc_client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+
+        // Buys spend the quote token, sells the base token; both land a real signature
+        let bought = client.execute_market_order(true, 10_000_000, 75).await.unwrap();
+        let sold = client.execute_market_order(false, 50_000_000, 75).await.unwrap();
+        for signature in [&bought, &sold] {
+            assert!(solana_sdk::signature::Signature::from_str(signature).is_ok(), "{}", signature);
+        }
+
+        let (base, quote) = (client.base_mint.to_string(), client.quote_mint.to_string());
+        assert_eq!(
+            *quoted.lock().unwrap(),
+            vec![
+                (quote.clone(), base.clone(), "75".to_string()),
+                (base, quote, "75".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_pool_fee_rate() {
+        let mut data = anchor_account_discriminator("FusionPool").to_vec();
+        data.resize(FUSION_POOL_FEE_RATE_OFFSET + 64, 0);
+        data[FUSION_POOL_FEE_RATE_OFFSET..FUSION_POOL_FEE_RATE_OFFSET + 2].copy_from_slice(&3000u16.to_le_bytes());
+        assert_eq!(decode_pool_fee_rate(&data).unwrap(), 3000);
+
+        data[..8].copy_from_slice(&anchor_account_discriminator("LimitOrder"));
+        assert!(decode_pool_fee_rate(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_pool_tick_spacing() {
+        let mut data = anchor_account_discriminator("FusionPool").to_vec();
+        data.resize(FUSION_POOL_FEE_RATE_OFFSET + 64, 0);
+        data[FUSION_POOL_TICK_SPACING_OFFSET..FUSION_POOL_TICK_SPACING_OFFSET + 2].copy_from_slice(&8u16.to_le_bytes());
+        data[FUSION_POOL_FEE_RATE_OFFSET..FUSION_POOL_FEE_RATE_OFFSET + 2].copy_from_slice(&3000u16.to_le_bytes());
+        assert_eq!(decode_pool_tick_spacing(&data).unwrap(), 8);
+
+        data[FUSION_POOL_TICK_SPACING_OFFSET..FUSION_POOL_TICK_SPACING_OFFSET + 2].copy_from_slice(&[0, 0]);
+        assert!(decode_pool_tick_spacing(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_pool() {
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // sqrt(150 USDC/SOL in raw units: 150e6 / 1e9) in Q64.64
+        let sqrt_price = (0.15f64.sqrt() * 2f64.powi(64)) as u128;
+        let mut data = pool_account_data(&mint_a, &mint_b, sqrt_price, -18_972);
+        let (vault_a, vault_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        data[FUSION_POOL_VAULT_A_OFFSET..FUSION_POOL_VAULT_A_OFFSET + 32].copy_from_slice(vault_a.as_ref());
+        data[FUSION_POOL_VAULT_B_OFFSET..FUSION_POOL_VAULT_B_OFFSET + 32].copy_from_slice(vault_b.as_ref());
+
+        let address = Pubkey::new_unique();
+        let pool = decode_pool(address, &data).unwrap();
+        assert_eq!(pool.address, address);
+        assert_eq!((pool.base_mint, pool.quote_mint), (mint_a, mint_b));
+        assert_eq!((pool.base_vault, pool.quote_vault), (vault_a, vault_b));
+        assert!((PoolTicks::default().sqrt_price_to_price(pool.sqrt_price) - 150.0).abs() < 1e-6);
+        assert_eq!(pool.tick_current_index, -18_972);
+
+        assert!(decode_pool(address, &data[..FUSION_POOL_SQRT_PRICE_OFFSET]).is_err());
+        data[..8].copy_from_slice(&anchor_account_discriminator("LimitOrder"));
+        assert!(decode_pool(address, &data).is_err());
+    }
+
+    #[test]
+    fn test_price_tick_round_trip() {
+        // SOL/USDC, a 6/6-decimal stable pair and a 5/9-decimal memecoin pair (BONK/SOL-like)
+        let pools = [
+            (PoolTicks::default(), [0.5, 23.7, 151.25, 4_200.0]),
+            (PoolTicks::new(1, 6, 6), [0.9987, 1.0, 1.0012, 2.5]),
+            (PoolTicks::new(128, 5, 9), [1.7e-8, 2.3e-7, 0.0001, 0.05]),
+        ];
+        for (ticks, prices) in pools {
+            for price in prices {
+                let tick = ticks.price_to_tick_index(price);
+                // The tick is the one at or below the price, one basis point wide
+                assert!(ticks.tick_index_to_price(tick) <= price * (1.0 + 1e-12), "{:?} {}", ticks, price);
+                assert!(ticks.tick_index_to_price(tick + 1) > price, "{:?} {}", ticks, price);
+
+                // Rounding to an initializable tick loses less than one spacing
+                let rounded = ticks.round_price_to_tick(price);
+                let one_spacing = 1.0001_f64.powi(ticks.spacing);
+                assert!(rounded <= price * (1.0 + 1e-12), "{:?} {}", ticks, price);
+                assert!(rounded * one_spacing > price, "{:?} {}", ticks, price);
+                assert_eq!(ticks.initializable_tick(tick).rem_euclid(ticks.spacing), 0);
+            }
+        }
+
+        // SOL/USDC needs the 10^(6-9) adjustment: ~$150 sits well below tick 0
+        assert!(PoolTicks::default().price_to_tick_index(150.0) < -18_000);
+    }
+
+    #[test]
+    fn test_tick_array_start() {
+        let ticks = PoolTicks::default();
+        assert_eq!(ticks.tick_array_start(5056), 0);
+        assert_eq!(ticks.tick_array_start(64 * 88), 64 * 88);
+        // Negative ticks belong to the array starting at or below them
+        assert_eq!(ticks.tick_array_start(-1), -64 * 88);
+        assert_eq!(ticks.tick_array_start(-18_944), -4 * 64 * 88);
+    }
+
+    #[test]
+    fn test_memo_instruction() {
+        let signer = Pubkey::new_unique();
+        let memo = trade_memo("defituna-bot", "1700000000000-7");
+        let ix = memo_instruction(&memo, &signer);
+
+        assert_eq!(ix.program_id.to_string(), MEMO_PROGRAM_ID);
+        assert_eq!(ix.accounts, vec![AccountMeta::new_readonly(signer, true)]);
+        assert_eq!(String::from_utf8(ix.data).unwrap(), "defituna-bot:1700000000000-7");
+    }
+
+    #[test]
+    fn test_append_memo_to_swap_message() {
+        use solana_sdk::message::{v0, MessageHeader};
+
+        // Payer, a writable account, the swap program, then two accounts from a lookup table
+        let payer = Pubkey::new_unique();
+        let swap_program = Pubkey::new_unique();
+        let mut message = VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, Pubkey::new_unique(), swap_program],
+            recent_blockhash: Default::default(),
+            instructions: vec![CompiledInstruction { program_id_index: 2, accounts: vec![0, 1, 3, 4], data: vec![7] }],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        });
+
+        append_memo(&mut message, "defituna-bot:1700000000000-7").unwrap();
+
+        let VersionedMessage::V0(message) = message else { unreachable!() };
+        assert_eq!(message.account_keys.len(), 4);
+        assert_eq!(message.account_keys[3].to_string(), MEMO_PROGRAM_ID);
+        assert_eq!(message.header.num_readonly_unsigned_accounts, 2);
+        // The swap's lookup-table accounts moved past the new static key
+        assert_eq!(message.instructions[0].accounts, vec![0, 1, 4, 5]);
+        let memo = &message.instructions[1];
+        assert_eq!((memo.program_id_index, memo.accounts.clone()), (3, vec![0]));
+        assert_eq!(memo.data, b"defituna-bot:1700000000000-7");
+
+        // A legacy message already carrying the memo program reuses its key
+        let noop = memo_instruction("swap", &payer);
+        let mut legacy = VersionedMessage::Legacy(solana_sdk::message::Message::new(&[noop], Some(&payer)));
+        append_memo(&mut legacy, "defituna-bot:1700000000000-8").unwrap();
+        assert_eq!(legacy.static_account_keys().len(), 2);
+        assert_eq!(legacy.instructions().len(), 2);
+    }
+}
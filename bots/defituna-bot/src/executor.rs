@@ -1,24 +1,48 @@
 use anyhow::{Context, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    program_pack::Pack,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
 };
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use tracing::{info, warn};
 
 use crate::config::BotConfig;
-use crate::defituna_client::DefiTunaClient;
-use crate::strategies::TradeSignal;
+use crate::defituna_client::{CancelledOrder, DefiTunaClient, PoolTicks, RPC_TIMEOUT};
+use crate::paper::{ExecutionMode, PaperBroker};
+use crate::solana_rpc_client::{LimitOrderLayout, LimitOrderUpdate};
+use crate::strategies::{BookDepth, TradeSignal};
+
+/// What executing a signal produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeReceipt {
+    pub signature: String,
+    /// Limit order left resting: its account on chain, or its paper order id
+    pub order_id: Option<String>,
+}
+
+impl TradeReceipt {
+    fn filled(signature: String) -> Self {
+        Self { signature, order_id: None }
+    }
+}
 
 pub struct TradeExecutor {
-    rpc_client: RpcClient,
+    rpc_client: Arc<RpcClient>,
     executor_keypair: Keypair,
     defituna_client: DefiTunaClient,
+    /// Set in paper mode: orders fill here instead of on-chain
+    paper: Option<PaperBroker>,
+    /// Last `on_market_price`, which market sells are sized in base at
+    market_price: Mutex<Option<f64>>,
 }
 
 impl TradeExecutor {
     pub async fn new(config: &BotConfig, defituna_client: DefiTunaClient) -> Result<Self> {
-        let rpc_client = RpcClient::new(&config.rpc_url);
+        let rpc_client = Arc::new(RpcClient::new_with_timeout_and_commitment(&config.rpc_url, RPC_TIMEOUT, config.commitment));
 
         let executor_keypair = bs58::decode(&config.executor_keypair)
             .into_vec()
@@ -31,10 +55,23 @@ impl TradeExecutor {
             executor_keypair.pubkey()
         );
 
+        let paper = (config.execution_mode == ExecutionMode::Paper).then(|| {
+            info!(
+                "📝 Paper trading: {} SOL, {} USDC, {}bps slippage",
+                config.paper_sol_balance as f64 / 1_000_000_000.0,
+                config.paper_usdc_balance as f64 / 1_000_000.0,
+                config.paper_slippage_bps
+            );
+            PaperBroker::new(config.paper_sol_balance, config.paper_usdc_balance, config.paper_slippage_bps)
+                .with_ticks(defituna_client.ticks())
+        });
+
         Ok(Self {
             rpc_client,
             executor_keypair,
             defituna_client,
+            paper,
+            market_price: Mutex::new(None),
         })
     }
 
@@ -42,57 +79,254 @@ impl TradeExecutor {
         &self,
         signal: &TradeSignal,
         config: &BotConfig,
-    ) -> Result<String> {
+    ) -> Result<TradeReceipt> {
+        if let Some(paper) = &self.paper {
+            return self.execute_paper_trade(paper, signal, config).await;
+        }
+
         match signal {
             TradeSignal::Buy { amount, reason } => {
                 info!("Executing BUY: {} | Reason: {}", amount, reason);
                 self.defituna_client
                     .execute_market_order(true, *amount, config.max_slippage_bps)
                     .await
+                    .map(TradeReceipt::filled)
             }
             TradeSignal::Sell { amount, reason } => {
                 info!("Executing SELL: {} | Reason: {}", amount, reason);
                 self.defituna_client
-                    .execute_market_order(false, *amount, config.max_slippage_bps)
+                    .execute_market_order(false, self.sell_amount_in_base(*amount)?, config.max_slippage_bps)
                     .await
+                    .map(TradeReceipt::filled)
             }
             TradeSignal::PlaceBid { price, size } => {
                 info!("Placing BID: price=${:.4}, size={}", price, size);
-                self.defituna_client
-                    .place_limit_order(true, *price, *size)
-                    .await
+                self.place_limit_order(true, *price, *size).await
             }
             TradeSignal::PlaceAsk { price, size } => {
                 info!("Placing ASK: price=${:.4}, size={}", price, size);
-                self.defituna_client
-                    .place_limit_order(false, *price, *size)
-                    .await
+                self.place_limit_order(false, *price, *size).await
             }
-            TradeSignal::Hold => {
+            TradeSignal::CancelOrder { .. } => Err(anyhow::anyhow!("CancelOrder goes through cancel_order")),
+            TradeSignal::Hold { .. } => {
                 warn!("Received HOLD signal, but execute_trade was called");
                 Err(anyhow::anyhow!("Cannot execute HOLD signal"))
             }
         }
     }
 
+    /// Sells are sized in quote like every signal, but spend the base token:
+    /// convert at the last market price
+    fn sell_amount_in_base(&self, amount: u64) -> Result<u64> {
+        let price = self
+            .market_price
+            .lock()
+            .unwrap()
+            .context("No market price yet to size the sell in base")?;
+        Ok(self.pool_ticks().quote_to_base(amount, price))
+    }
+
+    async fn place_limit_order(&self, is_bid: bool, price: f64, size: u64) -> Result<TradeReceipt> {
+        let placed = self.defituna_client.place_limit_order(is_bid, price, size).await?;
+        Ok(TradeReceipt {
+            signature: placed.signature,
+            order_id: Some(placed.order.to_string()),
+        })
+    }
+
+    async fn execute_paper_trade(
+        &self,
+        paper: &PaperBroker,
+        signal: &TradeSignal,
+        config: &BotConfig,
+    ) -> Result<TradeReceipt> {
+        let resting = |order_id: String| TradeReceipt { signature: order_id.clone(), order_id: Some(order_id) };
+        match signal {
+            // Market orders fill around the last `on_market_price`
+            TradeSignal::Buy { amount, .. } => paper
+                .execute_market_order(true, *amount, config.max_slippage_bps)
+                .await
+                .map(TradeReceipt::filled),
+            TradeSignal::Sell { amount, .. } => paper
+                .execute_market_order(false, self.sell_amount_in_base(*amount)?, config.max_slippage_bps)
+                .await
+                .map(TradeReceipt::filled),
+            TradeSignal::PlaceBid { price, size } => paper.place_limit_order(true, *price, *size).await.map(resting),
+            TradeSignal::PlaceAsk { price, size } => paper.place_limit_order(false, *price, *size).await.map(resting),
+            TradeSignal::CancelOrder { .. } => Err(anyhow::anyhow!("CancelOrder goes through cancel_order")),
+            TradeSignal::Hold { .. } => Err(anyhow::anyhow!("Cannot execute HOLD signal")),
+        }
+    }
+
+    /// Cancel a resting limit order by its account address
+    pub async fn cancel_order(&self, order_pubkey: &str) -> Result<CancelledOrder> {
+        if let Some(paper) = &self.paper {
+            return paper.cancel_order(order_pubkey).await;
+        }
+        let order_id = Pubkey::from_str(order_pubkey).context("Invalid limit order address")?;
+        self.defituna_client.cancel_order(order_id).await
+    }
+
+    /// Adopt the limit orders the wallet already holds on the pool (e.g. placed
+    /// before a restart), so their fills are handled like fresh ones. Paper
+    /// trading holds no on-chain orders.
+    pub async fn discover_orders(&self) -> Result<Vec<LimitOrderUpdate>> {
+        if self.paper.is_some() {
+            return Ok(Vec::new());
+        }
+        self.defituna_client.discover_orders().await
+    }
+
+    /// Balance of the executor's associated token account for `mint`, in base units
     pub async fn get_balance(&self, mint: &Pubkey) -> Result<u64> {
-        info!("💰 Fetching balance for mint: {}", mint);
-        
-        // TODO: Implement token balance fetching
-        // Steps:
-        // 1. Derive associated token account (ATA)
-        // 2. Fetch account data from RPC
-        // 3. Deserialize TokenAccount struct
-        // 4. Return balance amount
-        
-        info!("   Wallet: {}", self.executor_keypair.pubkey());
-        info!("   Token Mint: {}", mint);
-        info!("📊 Balance: 0 (placeholder - implement ATA lookup)");
-        
-        Ok(0)
+        let (owner, mint) = (self.executor_keypair.pubkey(), *mint);
+        let balance = self.spawn_rpc(move |rpc| token_balance(rpc, &owner, &mint)).await??;
+        info!("💰 Balance of {}: {}", mint, balance);
+        Ok(balance)
+    }
+
+    /// Same-side depth from the pool's vault balances, valued at `price`
+    pub async fn get_book_depth(&self, price: f64) -> Result<BookDepth> {
+        let pool = self.defituna_client.get_pool().await?;
+        let (base_vault, quote_vault) = (pool.base_vault, pool.quote_vault);
+        let (base_reserve, quote_reserve) = self
+            .spawn_rpc(move |rpc| -> Result<(u64, u64)> {
+                Ok((vault_balance(rpc, &base_vault)?, vault_balance(rpc, &quote_vault)?))
+            })
+            .await??;
+        Ok(BookDepth::from_reserves(base_reserve, quote_reserve, price, &self.pool_ticks()))
+    }
+
+    /// Tick math of the pool limit orders rest on
+    pub fn pool_ticks(&self) -> PoolTicks {
+        self.defituna_client.ticks()
+    }
+
+    /// The DeFiTuna pool limit orders rest on
+    pub fn pool_address(&self) -> Pubkey {
+        self.defituna_client.pool_address()
+    }
+
+    /// Where limit order accounts keep their fields
+    pub fn order_layout(&self) -> LimitOrderLayout {
+        self.defituna_client.order_layout().clone()
+    }
+
+    /// Whether the limit order `order` (an account, or a paper order id) is one this bot placed
+    pub fn owns_order(&self, order: &str) -> bool {
+        if let Some(paper) = &self.paper {
+            return paper.owns_order(order);
+        }
+        Pubkey::from_str(order).is_ok_and(|order| self.defituna_client.owns_order(&order))
+    }
+
+    /// Feed the market price to paper trading, returning the resting paper
+    /// orders it filled. Live orders fill on chain and arrive through the feed.
+    /// Market sells are sized at this price in both modes.
+    pub fn on_market_price(&self, price: f64) -> Vec<LimitOrderUpdate> {
+        *self.market_price.lock().unwrap() = Some(price);
+        match &self.paper {
+            Some(paper) => paper.on_market_price(price),
+            None => Vec::new(),
+        }
+    }
+
+    /// The pool's maker fee tier, in bps
+    pub async fn get_pool_fee_bps(&self) -> Result<f64> {
+        self.defituna_client.get_pool_fee_bps().await
+    }
+
+    /// Spot price of the DeFiTuna pool
+    pub async fn get_spot_price(&self) -> Result<f64> {
+        self.defituna_client.get_spot_price().await
     }
 
     pub fn pubkey(&self) -> Pubkey {
         self.executor_keypair.pubkey()
     }
+
+    /// Run a blocking RPC call on tokio's blocking pool
+    async fn spawn_rpc<R, F>(&self, call: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&RpcClient) -> R + Send + 'static,
+    {
+        let rpc_client = self.rpc_client.clone();
+        tokio::task::spawn_blocking(move || call(&rpc_client))
+            .await
+            .context("RPC task panicked")
+    }
+}
+
+/// SPL token balance of `owner`'s associated token account for `mint`;
+/// 0 when the account hasn't been created yet
+fn token_balance(rpc_client: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+    let ata = get_associated_token_address(owner, mint);
+    let account = rpc_client
+        .get_account_with_commitment(&ata, rpc_client.commitment())
+        .with_context(|| format!("Failed to fetch token account {}", ata))?
+        .value;
+
+    let Some(account) = account else {
+        return Ok(0);
+    };
+    let token_account = spl_token::state::Account::unpack(&account.data)
+        .with_context(|| format!("Invalid token account {}", ata))?;
+    Ok(token_account.amount)
+}
+
+/// Token balance of a pool vault, in base units
+fn vault_balance(rpc_client: &RpcClient, vault: &Pubkey) -> Result<u64> {
+    rpc_client
+        .get_token_account_balance(vault)
+        .with_context(|| format!("Failed to fetch vault balance {}", vault))?
+        .amount
+        .parse()
+        .with_context(|| format!("Invalid vault balance {}", vault))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_client::{rpc_client::Mocks, rpc_request::RpcRequest};
+
+    fn mock_rpc(account_info: serde_json::Value) -> RpcClient {
+        let mut mocks = Mocks::new();
+        mocks.insert(RpcRequest::GetAccountInfo, account_info);
+        RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+    }
+
+    #[test]
+    fn test_token_balance_reads_ata_amount() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let token_account = spl_token::state::Account {
+            mint,
+            owner,
+            amount: 1_234_567,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        token_account.pack_into_slice(&mut data);
+
+        let rpc_client = mock_rpc(json!({
+            "context": { "slot": 1 },
+            "value": {
+                "data": [bs58::encode(&data).into_string(), "base58"],
+                "executable": false,
+                "lamports": 2_039_280,
+                "owner": spl_token::id().to_string(),
+                "rentEpoch": 0,
+                "space": spl_token::state::Account::LEN,
+            },
+        }));
+        assert_eq!(token_balance(&rpc_client, &owner, &mint).unwrap(), 1_234_567);
+
+        // No ATA yet: zero, not an error
+        let rpc_client = mock_rpc(json!({ "context": { "slot": 1 }, "value": null }));
+        assert_eq!(token_balance(&rpc_client, &owner, &mint).unwrap(), 0);
+    }
 }
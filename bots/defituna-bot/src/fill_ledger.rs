@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use crate::solana_rpc_client::LimitOrderUpdate;
+
+/// How much of each order's fill has been booked, so repeated updates for an
+/// order book only what filled since
+#[derive(Debug, Default)]
+pub struct FillLedger {
+    booked: HashMap<String, u64>,
+}
+
+impl FillLedger {
+    /// Input filled on `order` since it was last seen
+    pub fn new_fill(&mut self, order: &LimitOrderUpdate) -> u64 {
+        let booked = self.booked.entry(order.pubkey.clone()).or_insert(0);
+        let new_fill = order.filled.saturating_sub(*booked);
+        *booked = (*booked).max(order.filled);
+        new_fill
+    }
+
+    /// Stop tracking an order that is gone
+    pub fn forget(&mut self, order_id: &str) {
+        self.booked.remove(order_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(size: u64, filled: u64) -> LimitOrderUpdate {
+        LimitOrderUpdate {
+            pubkey: "order".to_string(),
+            mint: "mint".to_string(),
+            pool: "pool".to_string(),
+            tick_index: -18_944,
+            is_bid: true,
+            price: 150.0,
+            size,
+            filled,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_fill_ledger_books_each_fill_once() {
+        let mut ledger = FillLedger::default();
+        assert_eq!(ledger.new_fill(&order(100, 0)), 0);
+        assert_eq!(ledger.new_fill(&order(100, 40)), 40);
+        assert_eq!(ledger.new_fill(&order(100, 40)), 0);
+        assert_eq!(ledger.new_fill(&order(100, 100)), 60);
+        assert_eq!(ledger.new_fill(&order(100, 100)), 0);
+        ledger.forget("order");
+        assert_eq!(ledger.new_fill(&order(100, 100)), 100);
+    }
+}
@@ -0,0 +1,554 @@
+//! Expected account layouts for the DeFiTuna / FusionAMM instructions this crate builds by hand.
+//! Used by the `dump_idl_accounts` binary to cross-check against the on-chain program.
+//! At runtime, `ProgramIdl` reads instruction accounts and account layouts from the
+//! program's published IDL instead (`IDL_PATH`).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_sdk::instruction::AccountMeta;
+use std::collections::HashMap;
+
+use crate::defituna_client::{anchor_account_discriminator, anchor_discriminator};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AccountSpec {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct InstructionSpec {
+    pub name: &'static str,
+    pub program: &'static str,
+    pub args: &'static [&'static str],
+    pub accounts: &'static [AccountSpec],
+}
+
+impl InstructionSpec {
+    pub fn discriminator(&self) -> [u8; 8] {
+        anchor_discriminator(self.name)
+    }
+}
+
+const fn signer(name: &'static str) -> AccountSpec {
+    AccountSpec { name, writable: true, signer: true }
+}
+
+const fn signer_readonly(name: &'static str) -> AccountSpec {
+    AccountSpec { name, writable: false, signer: true }
+}
+
+const fn writable(name: &'static str) -> AccountSpec {
+    AccountSpec { name, writable: true, signer: false }
+}
+
+const fn readonly(name: &'static str) -> AccountSpec {
+    AccountSpec { name, writable: false, signer: false }
+}
+
+pub const INSTRUCTIONS: &[InstructionSpec] = &[
+    InstructionSpec {
+        name: "open_tuna_spot_position",
+        program: "DeFiTuna",
+        args: &["position_token: PoolToken (u8)", "collateral_token: PoolToken (u8)"],
+        accounts: &[
+            signer("authority"),
+            readonly("mint_a"),
+            readonly("mint_b"),
+            readonly("token_program_a"),
+            readonly("token_program_b"),
+            writable("tuna_position"),
+            writable("tuna_position_ata_a"),
+            writable("tuna_position_ata_b"),
+            readonly("pool"),
+            readonly("system_program"),
+            readonly("associated_token_program"),
+        ],
+    },
+    InstructionSpec {
+        name: "close_tuna_spot_position",
+        program: "DeFiTuna",
+        args: &[],
+        accounts: &[
+            signer("authority"),
+            readonly("mint_a"),
+            readonly("mint_b"),
+            readonly("token_program_a"),
+            readonly("token_program_b"),
+            writable("tuna_position"),
+            writable("tuna_position_ata_a"),
+            writable("tuna_position_ata_b"),
+        ],
+    },
+    InstructionSpec {
+        name: "set_tuna_spot_position_limit_orders",
+        program: "DeFiTuna",
+        args: &[
+            "lower_limit_order_sqrt_price: u128",
+            "upper_limit_order_sqrt_price: u128",
+        ],
+        accounts: &[signer_readonly("authority"), writable("tuna_position")],
+    },
+    InstructionSpec {
+        name: "modify_tuna_spot_position_orca",
+        program: "DeFiTuna",
+        args: &[
+            "decrease_percent: u32",
+            "collateral_amount: u64",
+            "borrow_amount: u64",
+            "required_swap_amount: u64",
+            "remaining_accounts_info: RemainingAccountsInfo",
+        ],
+        accounts: &[
+            signer("authority"),
+            readonly("tuna_config"),
+            readonly("mint_a"),
+            readonly("mint_b"),
+            readonly("token_program_a"),
+            readonly("token_program_b"),
+            writable("market"),
+            writable("vault_a"),
+            writable("vault_b"),
+            writable("vault_a_ata"),
+            writable("vault_b_ata"),
+            writable("tuna_position"),
+            writable("tuna_position_ata_a"),
+            writable("tuna_position_ata_b"),
+            writable("tuna_position_owner_ata_a"),
+            writable("tuna_position_owner_ata_b"),
+            writable("fee_recipient_ata_a"),
+            writable("fee_recipient_ata_b"),
+            readonly("pyth_oracle_price_feed_a"),
+            readonly("pyth_oracle_price_feed_b"),
+            readonly("whirlpool_program"),
+            writable("whirlpool"),
+            readonly("memo_program"),
+            readonly("system_program"),
+        ],
+    },
+    InstructionSpec {
+        name: "open_limit_order",
+        program: "FusionAMM",
+        args: &["tick_index: i32", "a_to_b: bool", "with_token_metadata_extension: bool"],
+        accounts: &[
+            signer("funder"),
+            readonly("owner"),
+            writable("limit_order"),
+            signer("limit_order_mint"),
+            writable("limit_order_token_account"),
+            readonly("fusion_pool"),
+            readonly("associated_token_program"),
+            readonly("token2022_program"),
+            readonly("system_program"),
+            readonly("metadata_update_auth"),
+        ],
+    },
+    InstructionSpec {
+        name: "increase_limit_order",
+        program: "FusionAMM",
+        args: &["amount: u64", "remaining_accounts_info: Option<RemainingAccountsInfo>"],
+        accounts: &[
+            signer("limit_order_authority"),
+            writable("fusion_pool"),
+            writable("limit_order"),
+            readonly("limit_order_token_account"),
+            readonly("token_mint"),
+            writable("token_owner_account"),
+            writable("token_vault"),
+            writable("tick_array"),
+            readonly("token_program"),
+            readonly("memo_program"),
+        ],
+    },
+];
+
+pub fn find_instruction(name: &str) -> Option<&'static InstructionSpec> {
+    INSTRUCTIONS.iter().find(|ix| ix.name == name)
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlFile {
+    instructions: Vec<IdlInstruction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlInstruction {
+    name: String,
+    /// Present in Anchor 0.30+ IDLs; older ones only carry the (camelCase) name
+    #[serde(default)]
+    discriminator: Option<[u8; 8]>,
+}
+
+/// Instruction discriminators read from an Anchor IDL JSON (`IDL_PATH`), so a
+/// program upgrade only needs a new IDL. Instructions the IDL doesn't list fall
+/// back to `anchor_discriminator` of the name.
+#[derive(Debug, Clone, Default)]
+pub struct Discriminators {
+    from_idl: HashMap<String, [u8; 8]>,
+}
+
+impl Discriminators {
+    pub fn from_idl_json(json: &str) -> Result<Self> {
+        let idl: IdlFile = serde_json::from_str(json).context("Invalid IDL JSON")?;
+        let from_idl = idl
+            .instructions
+            .into_iter()
+            .map(|ix| {
+                let name = snake_case(&ix.name);
+                let discriminator = ix.discriminator.unwrap_or_else(|| anchor_discriminator(&name));
+                (name, discriminator)
+            })
+            .collect();
+        Ok(Self { from_idl })
+    }
+
+    /// Discriminators from the IDL at `path`, or computed from names when unset
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read IDL {}", path))?;
+        Self::from_idl_json(&json).with_context(|| format!("Failed to load IDL {}", path))
+    }
+
+    /// Discriminator for the snake_case instruction `name`
+    pub fn get(&self, name: &str) -> [u8; 8] {
+        self.from_idl
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| anchor_discriminator(name))
+    }
+}
+
+/// What the bot reads from the program's Anchor IDL (`IDL_PATH`): instruction
+/// discriminators and account lists, and the field layout of account types.
+/// Without an IDL, discriminators are computed from names and nothing else is known.
+#[derive(Debug, Clone, Default)]
+pub struct ProgramIdl {
+    pub discriminators: Discriminators,
+    /// Instruction accounts in order, by snake_case instruction name
+    instruction_accounts: HashMap<String, Vec<IdlAccountMeta>>,
+    /// Account discriminators the IDL lists, by account type name
+    account_discriminators: HashMap<String, [u8; 8]>,
+    /// Fixed-size leading fields of each struct type, by type name
+    layouts: HashMap<String, Vec<IdlField>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IdlAccountMeta {
+    name: String,
+    writable: bool,
+    signer: bool,
+}
+
+/// A field of an account type, at its byte offset in the account data
+/// (after the 8-byte discriminator)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdlField {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl ProgramIdl {
+    pub fn from_idl_json(json: &str) -> Result<Self> {
+        let discriminators = Discriminators::from_idl_json(json)?;
+        let idl: serde_json::Value = serde_json::from_str(json).context("Invalid IDL JSON")?;
+
+        let mut instruction_accounts = HashMap::new();
+        for ix in idl["instructions"].as_array().into_iter().flatten() {
+            let (Some(name), Some(accounts)) = (ix["name"].as_str(), ix["accounts"].as_array()) else {
+                continue;
+            };
+            let mut metas = Vec::new();
+            flatten_accounts(accounts, &mut metas);
+            instruction_accounts.insert(snake_case(name), metas);
+        }
+
+        // Anchor 0.30+ lists account discriminators under `accounts` and the
+        // layouts under `types`; older IDLs put the layout in `accounts` itself
+        let mut account_discriminators = HashMap::new();
+        let mut layouts = HashMap::new();
+        let accounts = idl["accounts"].as_array().into_iter().flatten();
+        for def in accounts.chain(idl["types"].as_array().into_iter().flatten()) {
+            let Some(name) = def["name"].as_str() else {
+                continue;
+            };
+            if let Ok(discriminator) = serde_json::from_value::<[u8; 8]>(def["discriminator"].clone()) {
+                account_discriminators.insert(name.to_string(), discriminator);
+            }
+            if let Some(fields) = def["type"]["fields"].as_array() {
+                layouts.insert(name.to_string(), struct_layout(fields));
+            }
+        }
+
+        Ok(Self { discriminators, instruction_accounts, account_discriminators, layouts })
+    }
+
+    /// The IDL at `path`, or nothing but computed discriminators when unset
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read IDL {}", path))?;
+        Self::from_idl_json(&json).with_context(|| format!("Failed to load IDL {}", path))
+    }
+
+    /// Discriminator for the snake_case instruction `name`
+    pub fn discriminator(&self, name: &str) -> [u8; 8] {
+        self.discriminators.get(name)
+    }
+
+    /// Discriminator of the account type `name` (e.g. `LimitOrder`)
+    pub fn account_discriminator(&self, name: &str) -> [u8; 8] {
+        self.account_discriminators
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| anchor_account_discriminator(name))
+    }
+
+    /// Leading fixed-size fields of the account type `name`, if the IDL defines it
+    pub fn account_layout(&self, name: &str) -> Option<&[IdlField]> {
+        self.layouts.get(name).map(Vec::as_slice)
+    }
+
+    /// Refuse an instruction whose accounts don't line up with the IDL's: a
+    /// different count, signer, or a writable account passed read-only.
+    /// Instructions the IDL doesn't define (or no IDL) pass unchecked.
+    pub fn check_accounts(&self, name: &str, accounts: &[AccountMeta]) -> Result<()> {
+        let Some(expected) = self.instruction_accounts.get(name) else {
+            return Ok(());
+        };
+        if expected.len() != accounts.len() {
+            anyhow::bail!("{} takes {} accounts in the IDL, built with {}", name, expected.len(), accounts.len());
+        }
+        for (meta, spec) in accounts.iter().zip(expected) {
+            if meta.is_signer != spec.signer || (spec.writable && !meta.is_writable) {
+                anyhow::bail!(
+                    "{}.{} is {}signer/{} in the IDL, built as {}signer/{}",
+                    name,
+                    spec.name,
+                    if spec.signer { "" } else { "non-" },
+                    if spec.writable { "writable" } else { "read-only" },
+                    if meta.is_signer { "" } else { "non-" },
+                    if meta.is_writable { "writable" } else { "read-only" },
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Instruction accounts, with composite account groups expanded in place.
+/// Anchor 0.30+ writes `writable`/`signer`, older IDLs `isMut`/`isSigner`.
+fn flatten_accounts(accounts: &[serde_json::Value], out: &mut Vec<IdlAccountMeta>) {
+    for account in accounts {
+        if let Some(nested) = account["accounts"].as_array() {
+            flatten_accounts(nested, out);
+            continue;
+        }
+        let flag = |new: &str, legacy: &str| account[new].as_bool().or(account[legacy].as_bool()).unwrap_or(false);
+        out.push(IdlAccountMeta {
+            name: snake_case(account["name"].as_str().unwrap_or_default()),
+            writable: flag("writable", "isMut"),
+            signer: flag("signer", "isSigner"),
+        });
+    }
+}
+
+/// Offsets of a struct's fields up to the first one without a fixed size
+/// (strings, vecs, options, defined types)
+fn struct_layout(fields: &[serde_json::Value]) -> Vec<IdlField> {
+    let mut layout = Vec::new();
+    let mut offset = 8;
+    for field in fields {
+        let Some(size) = fixed_size(&field["type"]) else {
+            break;
+        };
+        layout.push(IdlField {
+            name: snake_case(field["name"].as_str().unwrap_or_default()),
+            offset,
+            size,
+        });
+        offset += size;
+    }
+    layout
+}
+
+fn fixed_size(ty: &serde_json::Value) -> Option<usize> {
+    if let Some(array) = ty["array"].as_array() {
+        return Some(fixed_size(array.first()?)? * array.get(1)?.as_u64()? as usize);
+    }
+    Some(match ty.as_str()? {
+        "u8" | "i8" | "bool" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" => 4,
+        "u64" | "i64" | "f64" => 8,
+        "u128" | "i128" => 16,
+        "pubkey" | "publicKey" => 32,
+        _ => return None,
+    })
+}
+
+/// `openLimitOrder` -> `open_limit_order`; snake_case names pass through
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if !out.is_empty() {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// An Anchor 0.30 IDL excerpt in the shape the FusionAMM IDL is published in
+#[cfg(test)]
+pub(crate) const FUSION_IDL: &str = r#"{
+    "instructions": [{
+        "name": "decrease_limit_order",
+        "discriminator": [7, 7, 7, 7, 7, 7, 7, 7],
+        "accounts": [
+            { "name": "limit_order_authority", "signer": true },
+            { "name": "fusion_pool", "writable": true },
+            { "name": "limit_order", "writable": true },
+            { "name": "limit_order_token_account" },
+            { "name": "token_mint_a" },
+            { "name": "token_mint_b" },
+            { "name": "token_owner_account_a", "writable": true },
+            { "name": "token_owner_account_b", "writable": true },
+            { "name": "token_vault_a", "writable": true },
+            { "name": "token_vault_b", "writable": true },
+            { "name": "tick_array", "writable": true },
+            { "name": "token_program_a" },
+            { "name": "token_program_b" },
+            { "name": "memo_program" }
+        ],
+        "args": [{ "name": "amount", "type": "u64" }, { "name": "remaining_accounts_info", "type": { "option": { "defined": { "name": "RemainingAccountsInfo" } } } }]
+    }, {
+        "name": "close_limit_order",
+        "discriminator": [9, 9, 9, 9, 9, 9, 9, 9],
+        "accounts": [
+            { "name": "limit_order_authority", "signer": true },
+            { "name": "receiver", "writable": true },
+            { "name": "limit_order", "writable": true },
+            { "name": "limit_order_mint", "writable": true },
+            { "name": "limit_order_token_account", "writable": true },
+            { "name": "token2022_program" }
+        ],
+        "args": []
+    }],
+    "accounts": [{ "name": "LimitOrder", "discriminator": [1, 1, 2, 3, 5, 8, 13, 21] }],
+    "types": [{
+        "name": "LimitOrder",
+        "type": {
+            "kind": "struct",
+            "fields": [
+                { "name": "version", "type": "u16" },
+                { "name": "fusion_pool", "type": "pubkey" },
+                { "name": "limit_order_mint", "type": "pubkey" },
+                { "name": "tick_index", "type": "i32" },
+                { "name": "amount", "type": "u64" },
+                { "name": "a_to_b", "type": "bool" },
+                { "name": "reserved", "type": { "array": ["u8", 64] } },
+                { "name": "label", "type": "string" },
+                { "name": "after_label", "type": "u8" }
+            ]
+        }
+    }]
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_discriminators() {
+        // Values hardcoded in the open_spot_position / set_limit_orders binaries
+        let open = find_instruction("open_tuna_spot_position").unwrap();
+        assert_eq!(open.discriminator(), [87, 208, 173, 48, 231, 62, 210, 220]);
+
+        let set_limits = find_instruction("set_tuna_spot_position_limit_orders").unwrap();
+        assert_eq!(set_limits.discriminator(), [10, 180, 19, 205, 169, 133, 52, 118]);
+    }
+
+    #[test]
+    fn test_discriminators_from_idl() {
+        // An upgraded program's discriminator, a legacy camelCase entry, and one the IDL omits
+        let idl = r#"{
+            "instructions": [
+                { "name": "open_tuna_spot_position", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8], "accounts": [], "args": [] },
+                { "name": "setTunaSpotPositionLimitOrders", "accounts": [], "args": [] }
+            ]
+        }"#;
+        let discriminators = Discriminators::from_idl_json(idl).unwrap();
+
+        assert_eq!(discriminators.get("open_tuna_spot_position"), [1, 2, 3, 4, 5, 6, 7, 8]);
+        // Values hardcoded in the set_limit_orders / close_position binaries
+        assert_eq!(
+            discriminators.get("set_tuna_spot_position_limit_orders"),
+            [10, 180, 19, 205, 169, 133, 52, 118]
+        );
+        assert_eq!(discriminators.get("close_tuna_spot_position"), [4, 189, 171, 84, 110, 220, 10, 8]);
+
+        // No IDL configured: everything is computed from names
+        let computed = Discriminators::load(None).unwrap();
+        assert_eq!(computed.get("open_tuna_spot_position"), [87, 208, 173, 48, 231, 62, 210, 220]);
+        assert_eq!(computed.get("modify_tuna_spot_position_orca"), [133, 227, 147, 148, 220, 22, 59, 218]);
+
+        assert!(Discriminators::from_idl_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_program_idl_layouts() {
+        let idl = ProgramIdl::from_idl_json(FUSION_IDL).unwrap();
+        assert_eq!(idl.discriminator("close_limit_order"), [9; 8]);
+        assert_eq!(idl.account_discriminator("LimitOrder"), [1, 1, 2, 3, 5, 8, 13, 21]);
+        assert_eq!(idl.account_discriminator("FusionPool"), anchor_account_discriminator("FusionPool"));
+
+        // Offsets count the discriminator; the string ends the fixed-size prefix
+        let layout = idl.account_layout("LimitOrder").unwrap();
+        let field = |name: &str| layout.iter().find(|f| f.name == name).map(|f| (f.offset, f.size));
+        assert_eq!(field("fusion_pool"), Some((10, 32)));
+        assert_eq!(field("tick_index"), Some((74, 4)));
+        assert_eq!(field("a_to_b"), Some((86, 1)));
+        assert_eq!(field("reserved"), Some((87, 64)));
+        assert_eq!(field("after_label"), None);
+        assert!(idl.account_layout("FusionPool").is_none());
+    }
+
+    #[test]
+    fn test_check_accounts_against_idl() {
+        let idl = ProgramIdl::from_idl_json(FUSION_IDL).unwrap();
+        let key = solana_sdk::pubkey::Pubkey::new_unique;
+        let mut accounts = vec![
+            AccountMeta::new(key(), true),
+            AccountMeta::new(key(), false),
+            AccountMeta::new(key(), false),
+            AccountMeta::new(key(), false),
+            AccountMeta::new(key(), false),
+            AccountMeta::new_readonly(key(), false),
+        ];
+        // Writable where the IDL only needs read-only is fine
+        idl.check_accounts("close_limit_order", &accounts).unwrap();
+
+        accounts[2] = AccountMeta::new_readonly(accounts[2].pubkey, false);
+        let err = idl.check_accounts("close_limit_order", &accounts).unwrap_err();
+        assert!(err.to_string().contains("close_limit_order.limit_order"), "{}", err);
+        assert!(idl.check_accounts("close_limit_order", &accounts[..5]).is_err());
+
+        // Nothing to check against
+        idl.check_accounts("open_limit_order", &accounts[..1]).unwrap();
+        ProgramIdl::default().check_accounts("close_limit_order", &accounts).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_instruction() {
+        assert!(find_instruction("not_an_instruction").is_none());
+    }
+}
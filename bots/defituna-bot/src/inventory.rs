@@ -0,0 +1,108 @@
+/// Running inventory and average-cost basis for the traded base token.
+/// Sizes are quote base units, like every order size; the base token held is
+/// tracked alongside so the position can be valued and sold at any price.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    /// Quote spent on the open position
+    cost_basis: f64,
+    /// Base token held, as quote base units per unit of price
+    base: f64,
+    opened_at: Option<i64>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a buy of `size` quote base units at `price` (quote per base)
+    pub fn record_buy(&mut self, size: u64, price: f64, timestamp: i64) {
+        if self.base == 0.0 {
+            self.opened_at = Some(timestamp);
+        }
+        self.cost_basis += size as f64;
+        self.base += size as f64 / price;
+    }
+
+    /// Remove a sell of `size` quote base units at `price`; cost basis shrinks
+    /// pro rata to the base sold so the average entry is unchanged
+    pub fn record_sell(&mut self, size: u64, price: f64) {
+        if self.base == 0.0 {
+            return;
+        }
+        let sold = (size as f64 / price).min(self.base);
+        // Float dust left after selling everything still counts as flat
+        if self.base - sold <= self.base * 1e-9 {
+            *self = Self::default();
+            return;
+        }
+        self.cost_basis -= self.cost_basis * sold / self.base;
+        self.base -= sold;
+    }
+
+    /// Quote spent on the open position, in quote base units
+    pub fn position(&self) -> u64 {
+        self.cost_basis.round() as u64
+    }
+
+    /// What the base held is worth at `price`, in quote base units, rounded
+    /// down so selling it never asks for more base than is held
+    pub fn value_at(&self, price: f64) -> u64 {
+        (self.base * price).floor() as u64
+    }
+
+    /// Average entry price of the open position, if any
+    pub fn avg_entry_price(&self) -> Option<f64> {
+        if self.base == 0.0 {
+            return None;
+        }
+        Some(self.cost_basis / self.base)
+    }
+
+    /// Unix timestamp when the position went from flat to long
+    pub fn opened_at(&self) -> Option<i64> {
+        self.opened_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_cost_basis() {
+        let mut inventory = Inventory::new();
+        // 1_000 at 10 buys 100 base, 1_000 at 20 another 50
+        inventory.record_buy(1_000, 10.0, 1);
+        inventory.record_buy(1_000, 20.0, 2);
+        assert_eq!(inventory.position(), 2_000);
+        assert_eq!(inventory.value_at(20.0), 3_000);
+        assert!((inventory.avg_entry_price().unwrap() - 2_000.0 / 150.0).abs() < 1e-9);
+        assert_eq!(inventory.opened_at(), Some(1));
+
+        // 500 at 10 sells 50 base: a third of the position
+        inventory.record_sell(500, 10.0);
+        assert_eq!(inventory.value_at(10.0), 1_000);
+        assert!((inventory.avg_entry_price().unwrap() - 2_000.0 / 150.0).abs() < 1e-9);
+
+        inventory.record_sell(50_000, 10.0);
+        assert_eq!(inventory.position(), 0);
+        assert_eq!(inventory.avg_entry_price(), None);
+        assert_eq!(inventory.opened_at(), None);
+    }
+
+    #[test]
+    fn test_value_follows_price() {
+        let mut inventory = Inventory::new();
+        inventory.record_buy(1_500, 100.0, 1);
+
+        // Cost basis stays put while the base held is worth less
+        assert_eq!(inventory.position(), 1_500);
+        assert_eq!(inventory.value_at(80.0), 1_200);
+
+        // Selling its value at the lower price flattens the position
+        inventory.record_sell(1_200, 80.0);
+        assert_eq!(inventory.position(), 0);
+        assert_eq!(inventory.opened_at(), None);
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use solana_client::nonblocking::pubsub_client::PubsubClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
@@ -8,11 +9,16 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{info, warn, debug};
 
+use crate::defituna_client::PoolTicks;
+use crate::solana_rpc_client::{decode_limit_order, LimitOrderLayout, LimitOrderUpdate};
+
 #[derive(Debug, Clone)]
 pub struct SlotData {
     pub slot: u64,
     pub swaps: Option<Vec<SwapData>>,
     pub limit_orders: Option<Vec<LimitOrderUpdate>>,
+    /// When the stream last delivered an update
+    pub observed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,18 +29,6 @@ pub struct SwapData {
     pub output_amount: u64,
 }
 
-#[derive(Debug, Clone)]
-pub struct LimitOrderUpdate {
-    pub pubkey: String,
-    pub owner: String,
-    pub input_mint: String,
-    pub output_mint: String,
-    pub price: f64,
-    pub size: u64,
-    pub filled: u64,
-    pub is_active: bool,
-}
-
 impl SwapData {
     pub fn is_relevant_pair(&self, base_mint: &str, quote_mint: &str) -> bool {
         (self.input_mint == base_mint && self.output_mint == quote_mint)
@@ -50,18 +44,18 @@ impl SwapData {
 
 pub struct SolanaRpcClient {
     latest_data: Arc<Mutex<Option<SlotData>>>,
-    latest_slot: Arc<Mutex<u64>>,
+    latest_slot: Arc<Mutex<(u64, DateTime<Utc>)>>,
     defituna_program: String,
 }
 
 impl SolanaRpcClient {
-    pub async fn new(ws_endpoint: &str, defituna_program: &str) -> Result<Self> {
+    pub async fn new(ws_endpoint: &str, defituna_program: &str, layout: LimitOrderLayout, ticks: PoolTicks) -> Result<Self> {
         info!("📡 Connecting to Solana RPC WebSocket for DeFiTuna limit order monitoring...");
         info!("   Endpoint: {}", ws_endpoint);
         info!("   Program: {}", defituna_program);
 
         let latest_data: Arc<Mutex<Option<SlotData>>> = Arc::new(Mutex::new(None));
-        let latest_slot = Arc::new(Mutex::new(0));
+        let latest_slot = Arc::new(Mutex::new((0, Utc::now())));
         
         let data_clone = latest_data.clone();
         let slot_clone = latest_slot.clone();
@@ -81,7 +75,7 @@ impl SolanaRpcClient {
                             match update_oneof {
                                 helius_laserstream::grpc::subscribe_update::UpdateOneof::Account(account_update) => {
                                     let slot = account_update.slot;
-                                    *slot_clone.lock().await = slot;
+                                    *slot_clone.lock().await = (slot, Utc::now());
                                     
                                     if let Some(account_info) = &account_update.account {
                                         debug!("📦 Limit order account update in slot {}: pubkey={:?}, owner={:?}, lamports={}", 
@@ -90,22 +84,21 @@ impl SolanaRpcClient {
                                             bs58::encode(&account_info.owner).into_string(),
                                             account_info.lamports);
                                         
-                                        // TODO: Decode limit order account data
-                                        // Parse the account data to extract limit order details
-                                        // For now, create placeholder
-                                        let limit_order = LimitOrderUpdate {
-                                            pubkey: bs58::encode(&account_info.pubkey).into_string(),
-                                            owner: bs58::encode(&account_info.owner).into_string(),
-                                            input_mint: String::new(), // TODO: decode from account data
-                                            output_mint: String::new(), // TODO: decode from account data
-                                            price: 0.0,
-                                            size: 0,
-                                            filled: 0,
-                                            is_active: true,
+                                        // Other program accounts are skipped
+                                        let limit_order = match decode_limit_order(&account_info.data, &layout, &ticks) {
+                                            Ok(order) => LimitOrderUpdate {
+                                                pubkey: bs58::encode(&account_info.pubkey).into_string(),
+                                                ..order
+                                            },
+                                            Err(e) => {
+                                                debug!("Skipping account update in slot {}: {}", slot, e);
+                                                continue;
+                                            }
                                         };
                                         
                                         let mut data = data_clone.lock().await;
                                         if let Some(slot_data) = data.as_mut() {
+                                            slot_data.observed_at = Utc::now();
                                             if let Some(ref mut orders) = slot_data.limit_orders {
                                                 orders.push(limit_order);
                                             } else {
@@ -116,13 +109,14 @@ impl SolanaRpcClient {
                                                 slot,
                                                 swaps: None,
                                                 limit_orders: Some(vec![limit_order]),
+                                                observed_at: Utc::now(),
                                             });
                                         }
                                     }
                                 }
                                 helius_laserstream::grpc::subscribe_update::UpdateOneof::Transaction(tx_update) => {
                                     let slot = tx_update.slot;
-                                    *slot_clone.lock().await = slot;
+                                    *slot_clone.lock().await = (slot, Utc::now());
                                     
                                     if let Some(tx) = &tx_update.transaction {
                                         debug!("📊 DeFiTuna transaction in slot {}: sig={:?}", 
@@ -134,6 +128,7 @@ impl SolanaRpcClient {
                                             slot,
                                             swaps: Some(vec![]), // TODO: Parse actual swap data from tx
                                             limit_orders: None,
+                                            observed_at: Utc::now(),
                                         };
                                         
                                         *data_clone.lock().await = Some(slot_data);
@@ -141,7 +136,7 @@ impl SolanaRpcClient {
                                 }
                                 helius_laserstream::grpc::subscribe_update::UpdateOneof::Slot(slot_update) => {
                                     debug!("Slot: {}", slot_update.slot);
-                                    *slot_clone.lock().await = slot_update.slot;
+                                    *slot_clone.lock().await = (slot_update.slot, Utc::now());
                                 }
                                 _ => {}
                             }
@@ -168,11 +163,12 @@ impl SolanaRpcClient {
         }
         
         // Return empty data if LaserStream hasn't received anything yet
-        let slot = *self.latest_slot.lock().await;
+        let (slot, observed_at) = *self.latest_slot.lock().await;
         Ok(SlotData {
             slot,
             swaps: None,
             limit_orders: None,
+            observed_at,
         })
     }
 }
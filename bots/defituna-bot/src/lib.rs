@@ -1,6 +1,13 @@
 pub mod config;
 pub mod defituna_client;
 pub mod executor;
+pub mod fill_ledger;
+pub mod idl;
+pub mod inventory;
+pub mod paper;
+pub mod pnl;
 pub mod solana_rpc_client;
+pub mod price_source;
 pub mod price_tracker;
+pub mod status;
 pub mod strategies;
@@ -1,22 +1,39 @@
 use anyhow::Result;
 use dotenv::dotenv;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+use bot_common::state::PersistedState;
+use bot_common::ShutdownWatchdog;
+
 mod config;
 mod defituna_client;
 mod executor;
+mod fill_ledger;
+mod idl;
+mod inventory;
+mod paper;
+mod pnl;
 mod solana_rpc_client;
 
-use solana_rpc_client::SolanaRpcClient;
+use solana_rpc_client::{ConnectionEvent, LimitOrderUpdate, SolanaRpcClient};
+mod price_source;
 mod price_tracker;
+mod status;
 mod strategies;
 
 use config::BotConfig;
 use defituna_client::DefiTunaClient;
 use executor::TradeExecutor;
+use fill_ledger::FillLedger;
+use pnl::PnlTracker;
+use price_source::ArbitragePrices;
 use price_tracker::PriceTracker;
-use strategies::create_strategy;
+use status::{BotStatus, SharedStatus};
+use strategies::{create_strategy, Fill, TradeSignal};
 
 /// Bot state to track cooldowns and processed slots
 struct BotState {
@@ -24,6 +41,11 @@ struct BotState {
     last_slot: Option<u64>,
     daily_trade_count: usize,
     day_start: chrono::DateTime<chrono::Utc>,
+    /// Cleared while the RPC subscription is down so no trade uses stale data
+    feed_connected: bool,
+    /// Fills booked from the bot's resting orders, per order
+    order_fills: FillLedger,
+    pnl: PnlTracker,
 }
 
 impl BotState {
@@ -33,9 +55,38 @@ impl BotState {
             last_slot: None,
             daily_trade_count: 0,
             day_start: chrono::Utc::now(),
+            feed_connected: false,
+            order_fills: FillLedger::default(),
+            pnl: PnlTracker::new(),
         }
     }
 
+    /// Carry over the cooldown, daily count and last slot of a previous run
+    fn restore(&mut self, persisted: PersistedState) {
+        self.cooldown_until = persisted.cooldown_until;
+        self.daily_trade_count = persisted.daily_trade_count;
+        self.day_start = persisted.day_start;
+        self.last_slot = persisted.last_slot;
+    }
+
+    fn persisted(&self) -> PersistedState {
+        PersistedState {
+            cooldown_until: self.cooldown_until,
+            daily_trade_count: self.daily_trade_count,
+            day_start: self.day_start,
+            last_slot: self.last_slot,
+        }
+    }
+
+    fn on_connection_event(&mut self, event: ConnectionEvent) {
+        match event {
+            ConnectionEvent::Subscribed => info!("▶️  Feed subscribed, trading enabled"),
+            ConnectionEvent::Disconnected => warn!("⏸️  Feed disconnected, pausing trading"),
+            ConnectionEvent::Resubscribed => info!("▶️  Feed resubscribed, resuming trading"),
+        }
+        self.feed_connected = event != ConnectionEvent::Disconnected;
+    }
+
     fn is_in_cooldown(&self) -> bool {
         self.cooldown_until
             .map_or(false, |until| chrono::Utc::now() < until)
@@ -63,6 +114,17 @@ impl BotState {
     fn can_trade(&mut self, max_daily_trades: usize) -> bool {
         let now = chrono::Utc::now();
         if now.signed_duration_since(self.day_start).num_hours() >= 24 {
+            let summary = self.pnl.roll_day();
+            info!(
+                "📒 Daily PnL: {} trades, {} fills, volume {:.2}, realized {:+.4} (total {:+.4}), position {}",
+                self.daily_trade_count,
+                summary.fills,
+                summary.volume,
+                summary.realized_pnl,
+                self.pnl.realized_pnl(),
+                summary.position
+            );
+
             // Reset daily counter
             self.daily_trade_count = 0;
             self.day_start = now;
@@ -77,8 +139,8 @@ impl BotState {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
     dotenv().ok();
+    let _telemetry = bot_common::telemetry::init("defituna-bot")?;
 
     info!("🐟 Starting DefiTuna Trading Bot");
 
@@ -88,58 +150,230 @@ async fn main() -> Result<()> {
         config.strategy_type, config.base_token, config.quote_token
     );
 
-    // Initialize components
+    // Initialize components; the order feed prices ticks the way the client reads the pool
+    let defituna_client = DefiTunaClient::new(&config)?;
+    let executor = TradeExecutor::new(&config, defituna_client).await?;
+    // Orders left resting by an earlier run are still ours: we hold their NFTs
+    match executor.discover_orders().await {
+        Ok(orders) if !orders.is_empty() => {
+            let active = orders.iter().filter(|order| order.is_active).count();
+            info!("📋 Holding {} limit orders on the pool ({} active)", orders.len(), active);
+        }
+        Ok(_) => {}
+        Err(e) => warn!("⚠️  Failed to look up limit orders held by the wallet: {:#}", e),
+    }
     let rpc_client = SolanaRpcClient::new(
         &config.rpc_ws_url,
         &config.defituna_program_id,
+        &executor.pool_address(),
+        executor.order_layout(),
+        executor.pool_ticks(),
     ).await?;
-    let mut price_tracker = PriceTracker::new(config.lookback_minutes);
-    let strategy = create_strategy(&config)?;
-    let defituna_client = DefiTunaClient::new(&config)?;
-    let executor = TradeExecutor::new(&config, defituna_client).await?;
+    let mut price_tracker = PriceTracker::new(config.lookback_minutes)
+        .with_max_retained_points(config.max_retained_points);
+    let arbitrage_prices = config
+        .strategy_type
+        .eq_ignore_ascii_case("arbitrage")
+        .then(|| ArbitragePrices::new(&config));
+    let mut strategy = create_strategy(&config, arbitrage_prices.as_ref())?;
+    strategy.on_pool_ticks(executor.pool_ticks());
+    let mut pool_fee_due = Some(Instant::now());
 
+    let mut feed_events = rpc_client.take_events();
+    let state_path = Path::new(&config.state_path);
     let mut state = BotState::new();
+    if let Some(persisted) = PersistedState::load(state_path)? {
+        info!(
+            "📂 Restored state from {}: {} trades today, cooldown until {:?}",
+            state_path.display(),
+            persisted.daily_trade_count,
+            persisted.cooldown_until
+        );
+        state.restore(persisted);
+    }
+    let mut saved = (state.persisted(), chrono::Utc::now());
     let poll_interval = Duration::from_secs(config.poll_interval_seconds);
 
+    let status: SharedStatus = Arc::new(RwLock::new(BotStatus::default()));
+    if let Some(port) = config.health_port {
+        let status = status.clone();
+        tokio::spawn(async move {
+            if let Err(e) = status::serve(port, status).await {
+                error!("Status server failed: {}", e);
+            }
+        });
+    }
+
     info!("✅ Bot is running! Monitoring DefiTuna markets...");
     info!("⚙️  Configuration:");
     info!("   Strategy: {}", config.strategy_type);
+    info!("   Execution: {:?}", config.execution_mode);
     info!("   Pair: {}/{}", config.base_token, config.quote_token);
     info!("   Poll interval: {}s", config.poll_interval_seconds);
     info!("   Max slippage: {}bps ({:.2}%)", config.max_slippage_bps, config.max_slippage_bps as f64 / 100.0);
     info!("   Cooldown: {} minutes", config.cooldown_minutes);
     info!("   Max daily trades: {}", config.max_daily_trades);
+    info!("   Max order/depth ratio: {:.2}", config.max_order_depth_ratio);
     info!("🔄 Starting main event loop...");
 
     let mut loop_count = 0;
-    // Main event loop
+    // Main event loop; Ctrl-C/SIGTERM lets the current tick finish, bounded by SHUTDOWN_TIMEOUT_SECS
+    let shutdown = bot_common::shutdown_signal();
+    tokio::pin!(shutdown);
+
     loop {
         loop_count += 1;
         if loop_count % 10 == 1 {
             info!("🔄 Event loop iteration #{}", loop_count);
         }
-        
-        if let Err(e) = process_slot_update(
-            &rpc_client,
-            &mut price_tracker,
-            &strategy,
-            &executor,
-            &config,
-            &mut state,
-        )
-        .await
-        {
+
+        if let Some(events) = feed_events.as_mut() {
+            while let Ok(event) = events.try_recv() {
+                state.on_connection_event(event);
+            }
+        }
+        publish_state(&status, &state, &price_tracker).await;
+        if !state.feed_connected {
+            if !bot_common::sleep_or_shutdown(poll_interval, &mut shutdown).await {
+                break;
+            }
+            continue;
+        }
+
+        if pool_fee_due.is_some_and(|due| Instant::now() >= due) {
+            pool_fee_due = refresh_pool_fee(&executor, &mut strategy, &config, poll_interval).await;
+        }
+
+        // The tick borrows the bot state; release it before publishing
+        let result = {
+            let tick = process_slot_update(
+                &rpc_client,
+                &mut price_tracker,
+                &mut strategy,
+                arbitrage_prices.as_ref(),
+                &executor,
+                &config,
+                &mut state,
+            );
+            tokio::pin!(tick);
+
+            tokio::select! {
+                result = &mut tick => result,
+                _ = &mut shutdown => {
+                    info!(
+                        "🛑 Shutdown requested, letting the current tick finish (max {}s)...",
+                        config.shutdown_timeout_secs
+                    );
+                    // On its own thread, so a tick stuck in a blocking RPC call can't hold the exit up
+                    let watchdog = ShutdownWatchdog::arm(Duration::from_secs(config.shutdown_timeout_secs), || {
+                        warn!("   Tick abandoned: a sent order may still land on chain");
+                        std::process::exit(1)
+                    });
+                    let result = tick.await;
+                    watchdog.disarm();
+                    if let Err(e) = result {
+                        warn!("⚠️  Error processing slot update: {}", e);
+                    }
+                    break;
+                }
+            }
+        };
+        if let Err(e) = result {
             warn!("⚠️  Error processing slot update: {}", e);
         }
+        publish_state(&status, &state, &price_tracker).await;
+        save_state_on_change(&state, state_path, &mut saved);
+
+        if !bot_common::sleep_or_shutdown(poll_interval, &mut shutdown).await {
+            info!("🛑 Shutdown requested");
+            break;
+        }
+    }
+
+    save_state(state.persisted(), state_path, &mut saved);
+    log_shutdown_summary(&state, &price_tracker);
+    info!("👋 Bot stopped");
+    Ok(())
+}
+
+/// Hand the strategy the pool's fee tier so fills book at net prices;
+/// MAKER_FEE_BPS stands in until it's read. Returns when to read it next:
+/// after POOL_FEE_REFRESH_SECS, the next tick after a failure, or never.
+async fn refresh_pool_fee(
+    executor: &TradeExecutor,
+    strategy: &mut Box<dyn strategies::Strategy>,
+    config: &BotConfig,
+    poll_interval: Duration,
+) -> Option<Instant> {
+    match executor.get_pool_fee_bps().await {
+        Ok(fee_bps) => {
+            info!("💸 Pool fee tier: {:.2}bps", fee_bps);
+            strategy.on_pool_fee(fee_bps);
+            (config.pool_fee_refresh_secs > 0)
+                .then(|| Instant::now() + Duration::from_secs(config.pool_fee_refresh_secs))
+        }
+        Err(e) => {
+            warn!("⚠️  Pool fee tier unavailable, using MAKER_FEE_BPS: {}", e);
+            Some(Instant::now() + poll_interval)
+        }
+    }
+}
+
+/// Publish the slot, price, trade count and cooldown to the `/status` snapshot
+async fn publish_state(status: &SharedStatus, state: &BotState, price_tracker: &PriceTracker) {
+    let mut status = status.write().await;
+    status.last_slot = state.last_slot;
+    status.current_price = price_tracker.current_price();
+    status.daily_trade_count = state.daily_trade_count;
+    status.in_cooldown = state.is_in_cooldown();
+    status.cooldown_until = state.cooldown_until;
+    status.feed_connected = state.feed_connected;
+}
+
+/// Save `state` when it changed since `saved`, a new last slot alone at
+/// most every `SLOT_SAVE_INTERVAL_SECS`
+fn save_state_on_change(state: &BotState, path: &Path, saved: &mut (PersistedState, chrono::DateTime<chrono::Utc>)) {
+    let persisted = state.persisted();
+    if persisted.save_due(&saved.0, saved.1, chrono::Utc::now()) {
+        save_state(persisted, path, saved);
+    }
+}
 
-        tokio::time::sleep(poll_interval).await;
+fn save_state(persisted: PersistedState, path: &Path, saved: &mut (PersistedState, chrono::DateTime<chrono::Utc>)) {
+    match persisted.save(path) {
+        Ok(()) => *saved = (persisted, chrono::Utc::now()),
+        Err(e) => warn!("⚠️  Failed to save bot state: {:#}", e),
     }
 }
 
+/// What the session did, logged once the main loop exits
+fn log_shutdown_summary(state: &BotState, price_tracker: &PriceTracker) {
+    let summary = state.pnl.daily_summary();
+    let unrealized = price_tracker
+        .current_price()
+        .map(|price| state.pnl.unrealized_pnl(price))
+        .unwrap_or_default();
+    info!(
+        "📒 Shutdown summary: {} trades today, {} fills, volume {:.2}, realized {:+.4} (total {:+.4}), unrealized {:+.4}, position {}",
+        state.daily_trade_count,
+        summary.fills,
+        summary.volume,
+        summary.realized_pnl,
+        state.pnl.realized_pnl(),
+        unrealized,
+        summary.position
+    );
+    if let Some(slot) = state.last_slot {
+        info!("   Last processed slot: {}", slot);
+    }
+}
+
+#[tracing::instrument(skip_all)]
 async fn process_slot_update(
     rpc_client: &SolanaRpcClient,
     price_tracker: &mut PriceTracker,
-    strategy: &Box<dyn strategies::Strategy>,
+    strategy: &mut Box<dyn strategies::Strategy>,
+    arbitrage_prices: Option<&ArbitragePrices>,
     executor: &TradeExecutor,
     config: &BotConfig,
     state: &mut BotState,
@@ -159,6 +393,16 @@ async fn process_slot_update(
     info!("📦 Processing slot: {}", slot_data.slot);
     state.update_slot(slot_data.slot);
 
+    // Don't trade on a stalled subscription's last data
+    let age = slot_data.age_secs(chrono::Utc::now());
+    if config.max_slot_age_secs > 0 && age > config.max_slot_age_secs as i64 {
+        warn!(
+            "🐢 Slot {} data is {}s old (limit {}s), subscription may be stalled: skipping trading",
+            slot_data.slot, age, config.max_slot_age_secs
+        );
+        return Ok(());
+    }
+
     // Update price tracker with swap data
     if let Some(swaps) = slot_data.swaps {
         info!("🔄 Processing {} swap(s) from slot", swaps.len());
@@ -193,6 +437,18 @@ async fn process_slot_update(
         info!("📭 No swap data in this slot");
     }
 
+    // Paper orders fill as the tracked market price crosses them
+    if let Some(price) = price_tracker.current_price() {
+        let filled = executor.on_market_price(price);
+        if !filled.is_empty() {
+            handle_order_updates(&filled, strategy, executor, state);
+        }
+    }
+
+    if let Some(orders) = slot_data.limit_orders {
+        handle_order_updates(&orders, strategy, executor, state);
+    }
+
     // Check cooldown
     if state.is_in_cooldown() {
         if let Some(until) = state.cooldown_until {
@@ -215,14 +471,39 @@ async fn process_slot_update(
     
     info!("📊 Daily trades: {}/{}", state.daily_trade_count, config.max_daily_trades);
 
+    // Feed book depth so order sizes respect MAX_ORDER_DEPTH_RATIO
+    if config.max_order_depth_ratio > 0.0 {
+        if let Some(price) = price_tracker.current_price() {
+            match executor.get_book_depth(price).await {
+                Ok(depth) => strategy.on_book_depth(depth),
+                Err(e) => warn!("⚠️  Book depth unavailable, order sizes not capped: {}", e),
+            }
+        }
+    }
+
+    // Cross-venue prices are read by the strategy synchronously
+    if let Some(prices) = arbitrage_prices {
+        prices.refresh(executor).await;
+    }
+
     // Generate trading signal
     info!("🤖 Analyzing market with {} strategy...", config.strategy_type);
     info!("   Price history: {} data points", price_tracker.len());
     
-    if let Some(signal) = strategy.generate_signal(price_tracker) {
+    let mut signal = strategy.generate_signal(price_tracker);
+    // Requotes pull stale orders first; each cancel lets the strategy move on to the next
+    while let Some(TradeSignal::CancelOrder { order_id, is_bid, price }) = &signal {
+        info!("🔄 Requoting {} {} resting at ${:.4}", if *is_bid { "bid" } else { "ask" }, order_id, price);
+        if !cancel_for_requote(order_id, strategy, executor, state).await {
+            return Ok(());
+        }
+        signal = strategy.generate_signal(price_tracker);
+    }
+
+    if let Some(signal) = signal {
         // Check if it's a Hold signal
-        if matches!(signal, strategies::TradeSignal::Hold) {
-            info!("⏸️  Strategy decision: HOLD - no action taken");
+        if let strategies::TradeSignal::Hold { reason } = &signal {
+            info!("⏸️  Strategy decision: HOLD ({}) - no action taken", reason);
             return Ok(());
         }
         
@@ -233,25 +514,100 @@ async fn process_slot_update(
 
         // Execute trade
         info!("🚀 Executing trade...");
-        match executor.execute_trade(&signal, config).await {
-            Ok(signature) => {
-                info!("✅ ═══════════════════════════════════════");
-                info!("✅ TRADE EXECUTED SUCCESSFULLY");
-                info!("✅ Signature: {}", signature);
-                info!("✅ ═══════════════════════════════════════");
-                state.record_trade();
-                state.set_cooldown(config.cooldown_minutes);
-            }
-            Err(e) => {
-                error!("❌ ═══════════════════════════════════════");
-                error!("❌ TRADE EXECUTION FAILED");
-                error!("❌ Error: {}", e);
-                error!("❌ ═══════════════════════════════════════");
-            }
-        }
+        execute_signal(&signal, strategy, price_tracker, executor, config, state).await;
     } else {
         info!("⏸️  No trading signal - market conditions not met");
     }
 
     Ok(())
 }
+
+/// Execute `signal`, then count it against the daily limit, start the cooldown
+/// and hand the strategy the order it left resting or the fill it made
+async fn execute_signal(
+    signal: &TradeSignal,
+    strategy: &mut Box<dyn strategies::Strategy>,
+    price_tracker: &PriceTracker,
+    executor: &TradeExecutor,
+    config: &BotConfig,
+    state: &mut BotState,
+) {
+    match executor.execute_trade(signal, config).await {
+        Ok(receipt) => {
+            info!("✅ ═══════════════════════════════════════");
+            info!("✅ TRADE EXECUTED SUCCESSFULLY");
+            info!("✅ Signature: {}", receipt.signature);
+            info!("✅ ═══════════════════════════════════════");
+            state.record_trade();
+            state.set_cooldown(config.cooldown_minutes);
+            // An order left resting is booked as its fills are observed;
+            // anything else filled on execution
+            if let Some(order_id) = &receipt.order_id {
+                strategy.on_order_placed(order_id, signal);
+            } else if let Some(fill_price) = price_tracker.current_price() {
+                if let Some(fill) = Fill::from_signal(signal, fill_price, chrono::Utc::now().timestamp(), &executor.pool_ticks()) {
+                    strategy.on_fill(&fill);
+                    state.pnl.record(&fill);
+                }
+            } else {
+                // A 0.0 fill would corrupt the cost basis and the FIFO PnL
+                warn!("⚠️  No market price yet: fill of {} not booked", receipt.signature);
+            }
+        }
+        Err(e) => {
+            error!("❌ ═══════════════════════════════════════");
+            error!("❌ TRADE EXECUTION FAILED");
+            error!("❌ Error: {}", e);
+            error!("❌ ═══════════════════════════════════════");
+        }
+    }
+}
+
+/// Cancel a resting order the strategy is requoting. Only its observed fills
+/// were booked, so nothing is booked back. False when the cancel failed,
+/// leaving the order in place.
+async fn cancel_for_requote(
+    order_id: &str,
+    strategy: &mut Box<dyn strategies::Strategy>,
+    executor: &TradeExecutor,
+    state: &mut BotState,
+) -> bool {
+    match executor.cancel_order(order_id).await {
+        Ok(cancelled) => {
+            info!("🗑️  Cancelled {} for requote: {}", order_id, cancelled.signature);
+            strategy.on_order_closed(order_id);
+            state.order_fills.forget(order_id);
+            true
+        }
+        Err(e) => {
+            warn!("⚠️  Failed to cancel {} for requote, keeping it: {}", order_id, e);
+            false
+        }
+    }
+}
+
+/// Book what the bot's own limit orders filled since last seen. Orders keep
+/// no fill state on chain, so an order fills in full once its tick is crossed.
+fn handle_order_updates(
+    orders: &[LimitOrderUpdate],
+    strategy: &mut Box<dyn strategies::Strategy>,
+    executor: &TradeExecutor,
+    state: &mut BotState,
+) {
+    let ticks = executor.pool_ticks();
+    for order in orders.iter().filter(|o| executor.owns_order(&o.pubkey)) {
+        let new_fill = state.order_fills.new_fill(order);
+        if new_fill > 0 {
+            let fill = Fill::from_limit_order(order, new_fill, chrono::Utc::now().timestamp(), &ticks);
+            info!(
+                "📗 Order {} filled {} more ({}/{}) at ${:.4}",
+                order.pubkey, new_fill, order.filled, order.size, order.price
+            );
+            strategy.on_fill(&fill);
+            state.pnl.record(&fill);
+        }
+        if !order.is_active {
+            strategy.on_order_closed(&order.pubkey);
+        }
+    }
+}
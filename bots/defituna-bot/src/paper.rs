@@ -0,0 +1,377 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::defituna_client::{CancelledOrder, PoolTicks};
+use crate::solana_rpc_client::LimitOrderUpdate;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const MICRO_USDC_PER_USDC: f64 = 1_000_000.0;
+
+/// Whether trades go on-chain or to the simulated `PaperBroker`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    Live,
+    Paper,
+}
+
+impl FromStr for ExecutionMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "live" => Ok(Self::Live),
+            "paper" => Ok(Self::Paper),
+            other => anyhow::bail!("Unknown execution mode: {}. Options: live, paper", other),
+        }
+    }
+}
+
+/// One simulated fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperTrade {
+    pub signature: String,
+    pub is_buy: bool,
+    /// SOL bought or sold, in lamports
+    pub base_amount: u64,
+    /// USDC paid or received, in micro-USDC
+    pub quote_amount: u64,
+    /// USDC per SOL
+    pub price: f64,
+}
+
+/// A simulated limit order waiting for the price to reach it. Its input is
+/// set aside from the balances until it fills or is cancelled.
+#[derive(Debug, Clone, PartialEq)]
+struct RestingOrder {
+    order_id: String,
+    is_bid: bool,
+    /// Tick-rounded price it rests at
+    price: f64,
+    /// Input set aside: micro-USDC for a bid, lamports for an ask
+    input: u64,
+}
+
+#[derive(Debug)]
+struct PaperAccount {
+    base_balance: u64,
+    quote_balance: u64,
+    spot_price: Option<f64>,
+    resting: Vec<RestingOrder>,
+    orders_placed: u64,
+    /// Orders placed and not cancelled, resting or filled
+    open_orders: HashSet<String>,
+    trades: Vec<PaperTrade>,
+}
+
+/// Paper trading: the same order methods as `DefiTunaClient`, filled against
+/// simulated SOL/USDC balances instead of sending transactions. Market orders
+/// fill at the market price moved `slippage_bps` against the order; limit
+/// orders rest until the market price crosses them, then fill at their price.
+/// Order sizes are quote, as for the live client.
+#[derive(Debug)]
+pub struct PaperBroker {
+    slippage_bps: u16,
+    ticks: PoolTicks,
+    account: Mutex<PaperAccount>,
+}
+
+impl PaperBroker {
+    /// Start with `base_balance` lamports and `quote_balance` micro-USDC
+    pub fn new(base_balance: u64, quote_balance: u64, slippage_bps: u16) -> Self {
+        Self {
+            slippage_bps,
+            ticks: PoolTicks::default(),
+            account: Mutex::new(PaperAccount {
+                base_balance,
+                quote_balance,
+                spot_price: None,
+                resting: Vec::new(),
+                orders_placed: 0,
+                open_orders: HashSet::new(),
+                trades: Vec::new(),
+            }),
+        }
+    }
+
+    /// Round limit prices to this pool's ticks, as the live client does
+    pub fn with_ticks(mut self, ticks: PoolTicks) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Observe the market price (USDC per SOL): market orders fill around it,
+    /// and resting limit orders it has crossed fill at their own price. Returns
+    /// the orders that filled, as the order feed would report them.
+    pub fn on_market_price(&self, price: f64) -> Vec<LimitOrderUpdate> {
+        let mut account = self.account.lock().unwrap();
+        account.spot_price = Some(price);
+
+        let (crossed, resting): (Vec<_>, Vec<_>) = std::mem::take(&mut account.resting)
+            .into_iter()
+            .partition(|order| if order.is_bid { price <= order.price } else { price >= order.price });
+        account.resting = resting;
+
+        crossed
+            .into_iter()
+            .map(|order| {
+                let (base_amount, quote_amount) = if order.is_bid {
+                    (self.ticks.quote_to_base(order.input, order.price), order.input)
+                } else {
+                    (order.input, self.ticks.base_to_quote(order.input, order.price))
+                };
+                account.settle(order.is_bid, base_amount, quote_amount, order.price);
+                LimitOrderUpdate {
+                    pubkey: order.order_id,
+                    mint: String::new(),
+                    pool: String::new(),
+                    tick_index: self.ticks.price_to_tick_index(order.price),
+                    is_bid: order.is_bid,
+                    price: order.price,
+                    size: order.input,
+                    filled: order.input,
+                    is_active: false,
+                }
+            })
+            .collect()
+    }
+
+    /// Buy with `amount` micro-USDC, or sell `amount` lamports, at the spot
+    /// price plus slippage. Fails like a real swap would when the simulated
+    /// slippage exceeds `max_slippage_bps` or the balance is short.
+    pub async fn execute_market_order(&self, is_buy: bool, amount: u64, max_slippage_bps: u16) -> Result<String> {
+        if self.slippage_bps > max_slippage_bps {
+            anyhow::bail!(
+                "Paper slippage {}bps exceeds max slippage {}bps",
+                self.slippage_bps,
+                max_slippage_bps
+            );
+        }
+
+        let mut account = self.account.lock().unwrap();
+        let Some(spot) = account.spot_price else {
+            anyhow::bail!("No spot price to fill the paper order at");
+        };
+        let slippage = self.slippage_bps as f64 / 10_000.0;
+
+        if is_buy {
+            let price = spot * (1.0 + slippage);
+            let base_amount = self.ticks.quote_to_base(amount, price);
+            account.fill(true, base_amount, amount, price)
+        } else {
+            let price = spot * (1.0 - slippage);
+            let quote_amount = self.ticks.base_to_quote(amount, price);
+            account.fill(false, amount, quote_amount, price)
+        }
+    }
+
+    /// Rest a bid or ask worth `size` micro-USDC at `price` (rounded to a tick),
+    /// setting its input aside. Returns the order id.
+    pub async fn place_limit_order(&self, is_bid: bool, price: f64, size: u64) -> Result<String> {
+        let price = self.ticks.round_price_to_tick(price);
+        let input = if is_bid { size } else { self.ticks.quote_to_base(size, price) };
+        let mut account = self.account.lock().unwrap();
+        let balance = if is_bid { &mut account.quote_balance } else { &mut account.base_balance };
+        if input > *balance {
+            anyhow::bail!(
+                "Insufficient paper {}: need {}, have {}",
+                if is_bid { "USDC" } else { "SOL" },
+                input,
+                balance
+            );
+        }
+        *balance -= input;
+
+        account.orders_placed += 1;
+        let order_id = format!("PAPER_ORDER_{}", account.orders_placed);
+        info!("📝 Paper {} resting @ ${:.4}: {}", if is_bid { "BID" } else { "ASK" }, price, order_id);
+        account.open_orders.insert(order_id.clone());
+        account.resting.push(RestingOrder { order_id: order_id.clone(), is_bid, price, input });
+        Ok(order_id)
+    }
+
+    /// Take a resting order off the book, returning its input to the balances
+    pub async fn cancel_order(&self, order_id: &str) -> Result<CancelledOrder> {
+        let mut account = self.account.lock().unwrap();
+        let Some(index) = account.resting.iter().position(|order| order.order_id == order_id) else {
+            anyhow::bail!("No resting paper order {}", order_id);
+        };
+        let order = account.resting.remove(index);
+        account.open_orders.remove(order_id);
+        if order.is_bid {
+            account.quote_balance += order.input;
+        } else {
+            account.base_balance += order.input;
+        }
+        info!("📝 Paper cancel of {}", order_id);
+        Ok(CancelledOrder { signature: format!("PAPER_CANCEL_{}", order_id), remaining: order.input })
+    }
+
+    /// Whether `order_id` was placed here and not cancelled
+    pub fn owns_order(&self, order_id: &str) -> bool {
+        self.account.lock().unwrap().open_orders.contains(order_id)
+    }
+
+    /// Whether `order_id` is a paper order still resting
+    pub fn is_resting(&self, order_id: &str) -> bool {
+        self.account.lock().unwrap().resting.iter().any(|order| order.order_id == order_id)
+    }
+
+    /// Simulated (lamports, micro-USDC) balances, not counting resting orders' input
+    pub fn balances(&self) -> (u64, u64) {
+        let account = self.account.lock().unwrap();
+        (account.base_balance, account.quote_balance)
+    }
+
+    pub fn trades(&self) -> Vec<PaperTrade> {
+        self.account.lock().unwrap().trades.clone()
+    }
+}
+
+impl PaperAccount {
+    fn fill(&mut self, is_buy: bool, base_amount: u64, quote_amount: u64, price: f64) -> Result<String> {
+        let available = if is_buy { self.quote_balance } else { self.base_balance };
+        let needed = if is_buy { quote_amount } else { base_amount };
+        if needed > available {
+            anyhow::bail!(
+                "Insufficient paper {}: need {}, have {}",
+                if is_buy { "USDC" } else { "SOL" },
+                needed,
+                available
+            );
+        }
+        if is_buy {
+            self.quote_balance -= quote_amount;
+        } else {
+            self.base_balance -= base_amount;
+        }
+        Ok(self.settle(is_buy, base_amount, quote_amount, price))
+    }
+
+    /// Credit the output of a trade whose input was already taken, and record it
+    fn settle(&mut self, is_buy: bool, base_amount: u64, quote_amount: u64, price: f64) -> String {
+        if is_buy {
+            self.base_balance += base_amount;
+        } else {
+            self.quote_balance += quote_amount;
+        }
+
+        let signature = format!("PAPER_{}", self.trades.len() + 1);
+        info!(
+            "📝 Paper {} {:.4} SOL for ${:.2} @ ${:.4} → balances {:.4} SOL, ${:.2}",
+            if is_buy { "BUY" } else { "SELL" },
+            base_amount as f64 / LAMPORTS_PER_SOL,
+            quote_amount as f64 / MICRO_USDC_PER_USDC,
+            price,
+            self.base_balance as f64 / LAMPORTS_PER_SOL,
+            self.quote_balance as f64 / MICRO_USDC_PER_USDC,
+        );
+        self.trades.push(PaperTrade {
+            signature: signature.clone(),
+            is_buy,
+            base_amount,
+            quote_amount,
+            price,
+        });
+        signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paper_fills_update_balances() {
+        // 1 SOL and 1000 USDC, 50bps of simulated slippage
+        let broker = PaperBroker::new(1_000_000_000, 1_000_000_000, 50);
+        broker.on_market_price(100.0);
+
+        // $100.50 buys 1 SOL at 100.5
+        broker.execute_market_order(true, 100_500_000, 100).await.unwrap();
+        assert_eq!(broker.balances(), (2_000_000_000, 899_500_000));
+
+        // The price rises to 110: 1 SOL sells for 109.45 after slippage
+        broker.on_market_price(110.0);
+        broker.execute_market_order(false, 1_000_000_000, 100).await.unwrap();
+        assert_eq!(broker.balances(), (1_000_000_000, 899_500_000 + 109_450_000));
+
+        // A $52.50 bid at ~105 rests, its USDC set aside, until the price trades down through it
+        let ticks = PoolTicks::default();
+        let bid_price = ticks.round_price_to_tick(105.0);
+        let order_id = broker.place_limit_order(true, 105.0, 52_500_000).await.unwrap();
+        assert_eq!(broker.balances(), (1_000_000_000, 1_008_950_000 - 52_500_000));
+        assert!(broker.on_market_price(106.0).is_empty());
+        assert!(broker.is_resting(&order_id));
+
+        // It fills at its own price, not the market's
+        let filled = broker.on_market_price(100.0);
+        assert_eq!(filled.len(), 1);
+        assert_eq!((filled[0].pubkey.as_str(), filled[0].filled, filled[0].is_bid), (order_id.as_str(), 52_500_000, true));
+        assert_eq!(filled[0].price, bid_price);
+        let bought = ticks.quote_to_base(52_500_000, bid_price);
+        assert_eq!(broker.balances(), (1_000_000_000 + bought, 1_008_950_000 - 52_500_000));
+        assert!(!broker.is_resting(&order_id));
+        assert!(broker.owns_order(&order_id));
+
+        let trades = broker.trades();
+        assert_eq!(trades.len(), 3);
+        assert_eq!(trades[1].signature, "PAPER_2");
+        assert!(!trades[1].is_buy);
+        assert!((trades[1].price - 109.45).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_paper_orders_are_rejected_like_real_ones() {
+        let broker = PaperBroker::new(0, 10_000_000, 50);
+
+        // No price yet
+        assert!(broker.execute_market_order(true, 1_000_000, 100).await.is_err());
+        broker.on_market_price(100.0);
+
+        // Slippage above the order's limit
+        assert!(broker.execute_market_order(true, 1_000_000, 10).await.is_err());
+        // Short balances
+        assert!(broker.execute_market_order(true, 20_000_000, 100).await.is_err());
+        assert!(broker.execute_market_order(false, 1, 100).await.is_err());
+
+        assert_eq!(broker.balances(), (0, 10_000_000));
+        assert!(broker.trades().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paper_ask_rests_and_cancels() {
+        let broker = PaperBroker::new(2_000_000_000, 0, 50);
+        broker.on_market_price(100.0);
+        let ticks = PoolTicks::default();
+        let ask_price = ticks.round_price_to_tick(120.0);
+
+        // Sized in quote like every order: $120 of SOL at ~120 sets ~1 SOL aside
+        let order_id = broker.place_limit_order(false, 120.0, 120_000_000).await.unwrap();
+        let offered = ticks.quote_to_base(120_000_000, ask_price);
+        assert_eq!(broker.balances(), (2_000_000_000 - offered, 0));
+        assert!(broker.on_market_price(119.0).is_empty());
+
+        // Cancelled before the price gets there: the SOL comes back, nothing traded
+        let cancelled = broker.cancel_order(&order_id).await.unwrap();
+        assert_eq!(cancelled.remaining, offered);
+        assert_eq!(broker.balances(), (2_000_000_000, 0));
+        assert!(broker.on_market_price(130.0).is_empty());
+        assert!(broker.trades().is_empty());
+        assert!(broker.cancel_order(&order_id).await.is_err());
+        assert!(!broker.owns_order(&order_id));
+
+        // More than the balance can't rest
+        assert!(broker.place_limit_order(false, 120.0, 1_000_000_000).await.is_err());
+        assert!(broker.place_limit_order(true, 90.0, 1).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_execution_mode() {
+        assert_eq!("Paper".parse::<ExecutionMode>().unwrap(), ExecutionMode::Paper);
+        assert_eq!("live".parse::<ExecutionMode>().unwrap(), ExecutionMode::Live);
+        assert!("dry".parse::<ExecutionMode>().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+
+use crate::strategies::{Fill, FillSide};
+
+/// Open quantity from one fill, waiting to be closed by an opposite fill
+#[derive(Debug, Clone)]
+struct Lot {
+    side: FillSide,
+    size: u64,
+    price: f64,
+}
+
+/// Fills and realized PnL since the last day rollover
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DailyPnlSummary {
+    pub fills: usize,
+    /// Traded notional, size × price
+    pub volume: f64,
+    pub realized_pnl: f64,
+    /// Net open position at the time of the summary: positive long, negative short
+    pub position: i64,
+}
+
+/// Realized and unrealized PnL over executed fills, matching round trips FIFO.
+/// PnL is in size × price units.
+#[derive(Debug, Clone, Default)]
+pub struct PnlTracker {
+    /// Open lots, all on the same side, oldest first
+    lots: VecDeque<Lot>,
+    realized: f64,
+    day: DailyPnlSummary,
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Close open lots of the opposite side oldest first; any remainder opens a new lot
+    pub fn record(&mut self, fill: &Fill) {
+        let mut remaining = fill.size;
+        while remaining > 0 {
+            let Some(lot) = self.lots.front_mut().filter(|lot| lot.side != fill.side) else {
+                break;
+            };
+            let matched = remaining.min(lot.size);
+            let per_unit = match fill.side {
+                FillSide::Sell => fill.price - lot.price,
+                FillSide::Buy => lot.price - fill.price,
+            };
+            let pnl = per_unit * matched as f64;
+            self.realized += pnl;
+            self.day.realized_pnl += pnl;
+
+            lot.size -= matched;
+            remaining -= matched;
+            if lot.size == 0 {
+                self.lots.pop_front();
+            }
+        }
+
+        if remaining > 0 {
+            self.lots.push_back(Lot {
+                side: fill.side,
+                size: remaining,
+                price: fill.price,
+            });
+        }
+
+        self.day.fills += 1;
+        self.day.volume += fill.size as f64 * fill.price;
+    }
+
+    /// PnL locked in by closed round trips
+    pub fn realized_pnl(&self) -> f64 {
+        self.realized
+    }
+
+    /// PnL of the open lots if closed at `current_price`
+    pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
+        self.lots
+            .iter()
+            .map(|lot| {
+                let per_unit = match lot.side {
+                    FillSide::Buy => current_price - lot.price,
+                    FillSide::Sell => lot.price - current_price,
+                };
+                per_unit * lot.size as f64
+            })
+            .sum()
+    }
+
+    /// Net open position: positive long, negative short
+    pub fn position(&self) -> i64 {
+        self.lots
+            .iter()
+            .map(|lot| match lot.side {
+                FillSide::Buy => lot.size as i64,
+                FillSide::Sell => -(lot.size as i64),
+            })
+            .sum()
+    }
+
+    /// Today's fills and realized PnL so far
+    pub fn daily_summary(&self) -> DailyPnlSummary {
+        DailyPnlSummary {
+            position: self.position(),
+            ..self.day.clone()
+        }
+    }
+
+    /// Close out the day: returns its summary and starts counting afresh.
+    /// Open lots carry over.
+    pub fn roll_day(&mut self) -> DailyPnlSummary {
+        let summary = self.daily_summary();
+        self.day = DailyPnlSummary::default();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: FillSide, size: u64, price: f64) -> Fill {
+        Fill { side, price, size, timestamp: 0 }
+    }
+
+    #[test]
+    fn test_buy_then_sell_round_trip() {
+        let mut pnl = PnlTracker::new();
+        pnl.record(&fill(FillSide::Buy, 100, 10.0));
+        pnl.record(&fill(FillSide::Buy, 100, 12.0));
+        assert_eq!(pnl.position(), 200);
+        assert_eq!(pnl.realized_pnl(), 0.0);
+        assert!(pnl.unrealized_pnl(11.0).abs() < 1e-9);
+
+        // FIFO: the sell closes the 10.0 lot first
+        pnl.record(&fill(FillSide::Sell, 150, 13.0));
+        assert!((pnl.realized_pnl() - (100.0 * 3.0 + 50.0 * 1.0)).abs() < 1e-9);
+        assert_eq!(pnl.position(), 50);
+        // The 50 left from 12.0 are under water at 11.0
+        assert!((pnl.unrealized_pnl(11.0) - -50.0).abs() < 1e-9);
+
+        let summary = pnl.roll_day();
+        assert_eq!(summary.fills, 3);
+        assert!((summary.volume - (1_000.0 + 1_200.0 + 1_950.0)).abs() < 1e-9);
+        assert!((summary.realized_pnl - 350.0).abs() < 1e-9);
+        assert_eq!(summary.position, 50);
+
+        // The new day starts from zero but keeps the open lot and total PnL
+        assert_eq!(pnl.daily_summary(), DailyPnlSummary { position: 50, ..Default::default() });
+        assert!((pnl.realized_pnl() - 350.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sell_through_flat_opens_short() {
+        let mut pnl = PnlTracker::new();
+        pnl.record(&fill(FillSide::Buy, 100, 10.0));
+        pnl.record(&fill(FillSide::Sell, 150, 9.0));
+        assert!((pnl.realized_pnl() - -100.0).abs() < 1e-9);
+        assert_eq!(pnl.position(), -50);
+        // Short 50 from 9.0 gains as the price falls
+        assert!((pnl.unrealized_pnl(8.0) - 50.0).abs() < 1e-9);
+
+        pnl.record(&fill(FillSide::Buy, 50, 8.5));
+        assert!((pnl.realized_pnl() - -75.0).abs() < 1e-9);
+        assert_eq!(pnl.position(), 0);
+        assert_eq!(pnl.unrealized_pnl(100.0), 0.0);
+    }
+}
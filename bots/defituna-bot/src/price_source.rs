@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use jupiter_client::JupiterClient;
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+
+use crate::config::BotConfig;
+use crate::executor::TradeExecutor;
+
+/// A venue price the strategies can read synchronously. Implementors cache the
+/// last fetched price; the main loop refreshes them before each signal.
+pub trait PriceSource: Send + Sync {
+    fn name(&self) -> &str;
+    /// Latest price in quote per base, None until fetched or after a failed refresh
+    fn latest_price(&self) -> Option<f64>;
+}
+
+/// Price implied by a Jupiter quote for selling one base token into quote,
+/// from the same quote API (`JUPITER_QUOTE_URL`) market orders route through
+pub struct JupiterPriceSource {
+    jupiter: JupiterClient,
+    base_mint: String,
+    quote_mint: String,
+    base_decimals: u8,
+    quote_decimals: u8,
+    latest: RwLock<Option<f64>>,
+}
+
+impl JupiterPriceSource {
+    pub fn new(config: &BotConfig) -> Self {
+        let mut jupiter = JupiterClient::builder();
+        if let Some(url) = &config.jupiter_quote_url {
+            jupiter = jupiter.quote_url(url);
+        }
+        Self {
+            jupiter: jupiter.build(),
+            base_mint: config.base_mint.clone(),
+            quote_mint: config.quote_mint.clone(),
+            base_decimals: config.base_decimals,
+            quote_decimals: config.quote_decimals,
+            latest: RwLock::new(None),
+        }
+    }
+
+    pub async fn refresh(&self) -> Result<f64> {
+        let result = self.fetch().await;
+        *self.latest.write().unwrap() = result.as_ref().ok().copied();
+        result
+    }
+
+    async fn fetch(&self) -> Result<f64> {
+        let quote = self
+            .jupiter
+            .get_quote(&self.base_mint, &self.quote_mint, 10u64.pow(self.base_decimals as u32), 0)
+            .await
+            .context("Failed to fetch Jupiter quote")?;
+        let out_amount: u64 = quote.out_amount.parse().context("Invalid Jupiter outAmount")?;
+        Ok(out_amount as f64 / 10f64.powi(self.quote_decimals as i32))
+    }
+}
+
+impl PriceSource for JupiterPriceSource {
+    fn name(&self) -> &str {
+        "Jupiter"
+    }
+
+    fn latest_price(&self) -> Option<f64> {
+        *self.latest.read().unwrap()
+    }
+}
+
+/// DeFiTuna pool spot price
+#[derive(Default)]
+pub struct DefiTunaPriceSource {
+    latest: RwLock<Option<f64>>,
+}
+
+impl DefiTunaPriceSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn refresh(&self, executor: &TradeExecutor) -> Result<f64> {
+        let result = executor.get_spot_price().await;
+        *self.latest.write().unwrap() = result.as_ref().ok().copied();
+        result
+    }
+}
+
+impl PriceSource for DefiTunaPriceSource {
+    fn name(&self) -> &str {
+        "DeFiTuna"
+    }
+
+    fn latest_price(&self) -> Option<f64> {
+        *self.latest.read().unwrap()
+    }
+}
+
+/// The two venues the arbitrage strategy compares
+pub struct ArbitragePrices {
+    pub jupiter: Arc<JupiterPriceSource>,
+    pub defituna: Arc<DefiTunaPriceSource>,
+}
+
+impl ArbitragePrices {
+    pub fn new(config: &BotConfig) -> Self {
+        Self {
+            jupiter: Arc::new(JupiterPriceSource::new(config)),
+            defituna: Arc::new(DefiTunaPriceSource::new()),
+        }
+    }
+
+    /// Refresh both venues; a failed venue reads as missing until the next refresh
+    pub async fn refresh(&self, executor: &TradeExecutor) {
+        let (jupiter, defituna) = tokio::join!(self.jupiter.refresh(), self.defituna.refresh(executor));
+        if let Err(e) = jupiter {
+            warn!("⚠️  Jupiter price unavailable: {}", e);
+        }
+        if let Err(e) = defituna {
+            warn!("⚠️  DeFiTuna price unavailable: {}", e);
+        }
+    }
+}
@@ -1,8 +1,17 @@
+use bot_common::indicators;
 use std::collections::VecDeque;
+use tracing::warn;
+
+/// Default absolute cap on stored points
+const DEFAULT_MAX_RETAINED_POINTS: usize = 10_000;
 
 pub struct PriceTracker {
     prices: VecDeque<PricePoint>,
     max_history_minutes: usize,
+    /// Backstop for the time-based trim in case timestamps stop advancing
+    max_retained_points: usize,
+    /// Points dropped by the absolute cap
+    capped_count: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -16,9 +25,16 @@ impl PriceTracker {
         Self {
             prices: VecDeque::new(),
             max_history_minutes,
+            max_retained_points: DEFAULT_MAX_RETAINED_POINTS,
+            capped_count: 0,
         }
     }
 
+    pub fn with_max_retained_points(mut self, max_retained_points: usize) -> Self {
+        self.max_retained_points = max_retained_points.max(1);
+        self
+    }
+
     pub fn add_price(&mut self, price: f64) {
         let now = chrono::Utc::now();
 
@@ -37,6 +53,18 @@ impl PriceTracker {
             price,
             timestamp: now,
         });
+
+        if self.prices.len() > self.max_retained_points {
+            self.prices.pop_front();
+            self.capped_count += 1;
+            // Log the first trim and then periodically, not once per update
+            if self.capped_count % 1000 == 1 {
+                warn!(
+                    "⚠️  Price history hit the {}-point cap inside the {}-minute window; dropped {} oldest point(s) so far",
+                    self.max_retained_points, self.max_history_minutes, self.capped_count
+                );
+            }
+        }
     }
 
     pub fn current_price(&self) -> Option<f64> {
@@ -48,24 +76,22 @@ impl PriceTracker {
     }
 
     pub fn moving_average(&self, minutes: usize) -> Option<f64> {
-        if self.prices.is_empty() {
-            return None;
-        }
-
-        let cutoff = chrono::Utc::now() - chrono::Duration::minutes(minutes as i64);
-        let relevant_prices: Vec<f64> = self
-            .prices
-            .iter()
-            .filter(|p| p.timestamp >= cutoff)
-            .map(|p| p.price)
-            .collect();
+        indicators::mean(&self.window(minutes))
+    }
 
-        if relevant_prices.is_empty() {
-            return None;
-        }
+    /// Exponential moving average over the stored window, oldest to newest,
+    /// with smoothing factor `2 / (periods + 1)`
+    pub fn ema(&self, periods: usize) -> Option<f64> {
+        let prices: Vec<f64> = self.prices.iter().map(|p| p.price).collect();
+        indicators::ema(&prices, periods)
+    }
 
-        let sum: f64 = relevant_prices.iter().sum();
-        Some(sum / relevant_prices.len() as f64)
+    /// MACD over the stored prices: returns (MACD line, signal line, histogram).
+    /// None when `fast >= slow` (a misconfiguration, not a market state) or
+    /// there are fewer than `slow` points.
+    pub fn macd(&self, fast: usize, slow: usize, signal: usize) -> Option<(f64, f64, f64)> {
+        let prices: Vec<f64> = self.prices.iter().map(|p| p.price).collect();
+        indicators::macd(&prices, fast, slow, signal)
     }
 
     pub fn price_change(&self, minutes: usize) -> Option<f64> {
@@ -76,32 +102,130 @@ impl PriceTracker {
     }
 
     pub fn volatility(&self, minutes: usize) -> Option<f64> {
+        indicators::std_dev(&self.window(minutes))
+    }
+
+    /// (lower, middle, upper) Bollinger Bands: the window's moving average
+    /// plus/minus `std_devs` standard deviations
+    pub fn bollinger_bands(&self, minutes: usize, std_devs: f64) -> Option<(f64, f64, f64)> {
+        indicators::bollinger_bands(&self.window(minutes), std_devs)
+    }
+
+    /// Relative Strength Index with Wilder smoothing over the lookback window.
+    /// None until the window holds `periods + 1` samples.
+    pub fn rsi(&self, periods: usize) -> Option<f64> {
+        indicators::rsi(&self.window(self.max_history_minutes), periods)
+    }
+
+    /// Prices from the last `minutes`, oldest first
+    fn window(&self, minutes: usize) -> Vec<f64> {
         let cutoff = chrono::Utc::now() - chrono::Duration::minutes(minutes as i64);
-        let relevant_prices: Vec<f64> = self
-            .prices
+        self.prices
             .iter()
             .filter(|p| p.timestamp >= cutoff)
             .map(|p| p.price)
-            .collect();
+            .collect()
+    }
+
+    pub fn update_count(&self) -> usize {
+        self.prices.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_converges_faster_than_sma() {
+        let mut tracker = PriceTracker::new(60);
+        assert!(tracker.ema(5).is_none());
+
+        tracker.add_price(100.0);
+        assert_eq!(tracker.ema(5), Some(100.0));
 
-        if relevant_prices.len() < 2 {
-            return None;
+        for i in 1..30 {
+            tracker.add_price(100.0 - i as f64);
         }
+        let last = tracker.current_price().unwrap();
+        let ema = tracker.ema(5).unwrap();
+        let sma = tracker.moving_average(60).unwrap();
+        // Falling series: both sit above the last price, the EMA much closer
+        assert!(last < ema && ema < sma);
+        assert!(ema - last < 2.01);
+    }
 
-        let mean = relevant_prices.iter().sum::<f64>() / relevant_prices.len() as f64;
-        let variance = relevant_prices
-            .iter()
-            .map(|p| {
-                let diff = p - mean;
-                diff * diff
-            })
-            .sum::<f64>()
-            / relevant_prices.len() as f64;
+    #[test]
+    fn test_macd_histogram_is_line_minus_signal() {
+        let mut tracker = PriceTracker::new(60);
+        for i in 0..40 {
+            tracker.add_price(150.0 - i as f64 * 0.25 + (i % 3) as f64);
+        }
 
-        Some(variance.sqrt())
+        let (line, signal, histogram) = tracker.macd(12, 26, 9).unwrap();
+        assert_eq!(histogram, line - signal);
+        // Downtrend: fast EMA below slow
+        assert!(line < 0.0);
+
+        // fast >= slow is rejected rather than silently swapped
+        assert!(tracker.macd(26, 12, 9).is_none());
     }
 
-    pub fn update_count(&self) -> usize {
-        self.prices.len()
+    #[test]
+    fn test_bollinger_bands() {
+        let mut flat = PriceTracker::new(60);
+        flat.add_price(20.0);
+        assert!(flat.bollinger_bands(5, 2.0).is_none());
+        flat.add_price(20.0);
+        assert_eq!(flat.bollinger_bands(5, 2.0), Some((20.0, 20.0, 20.0)));
+
+        // 18, 20, 22, 20: mean 20, population std dev sqrt(2)
+        let mut noisy = PriceTracker::new(60);
+        for price in [18.0, 20.0, 22.0, 20.0] {
+            noisy.add_price(price);
+        }
+        let (lower, middle, upper) = noisy.bollinger_bands(5, 1.5).unwrap();
+        let band = 1.5 * 2f64.sqrt();
+        assert!((middle - 20.0).abs() < 1e-9);
+        assert!((lower - (20.0 - band)).abs() < 1e-9);
+        assert!((upper - (20.0 + band)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rsi_hand_calculated() {
+        let mut tracker = PriceTracker::new(60);
+        for price in [44.0, 44.5, 43.5, 44.5, 45.5] {
+            tracker.add_price(price);
+        }
+        assert!(tracker.rsi(5).is_none());
+
+        // Changes +0.5, -1, +1, +1: avg gain 2.5/4, avg loss 1/4, RS 2.5 => 100 - 100/3.5
+        assert!((tracker.rsi(4).unwrap() - 71.428_571).abs() < 1e-4);
+
+        // -1.5: avg gain (2.5/4*3)/4 = 0.46875, avg loss (0.25*3 + 1.5)/4 = 0.5625
+        tracker.add_price(44.0);
+        assert!((tracker.rsi(4).unwrap() - 45.454_545).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_absolute_cap_bounds_history() {
+        // All points share roughly the same timestamp, so the time trim never fires
+        let mut tracker = PriceTracker::new(60).with_max_retained_points(100);
+        for i in 0..1_000 {
+            tracker.add_price(100.0 + i as f64);
+        }
+        assert_eq!(tracker.len(), 100);
+        // The newest points are kept
+        assert_eq!(tracker.current_price(), Some(1_099.0));
+        assert_eq!(tracker.moving_average(60), Some(1_049.5));
+    }
+
+    #[test]
+    fn test_rsi_all_losses_is_zero() {
+        let mut tracker = PriceTracker::new(60);
+        for i in 0..15 {
+            tracker.add_price(100.0 - i as f64);
+        }
+        assert_eq!(tracker.rsi(14), Some(0.0));
     }
 }
@@ -1,21 +1,46 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
-use anchor_client::solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::TokenAccountsFilter;
+use anchor_client::solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding};
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::account::Account;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::str::FromStr;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tracing::{info, warn, debug};
 
+use crate::defituna_client::{
+    anchor_account_discriminator, decode_pool, limit_order_address, PoolTicks, SPL_TOKEN_2022_ID,
+};
+use crate::idl::ProgramIdl;
+
 #[derive(Debug, Clone)]
 pub struct SlotData {
     pub slot: u64,
     pub swaps: Option<Vec<SwapData>>,
     pub limit_orders: Option<Vec<LimitOrderUpdate>>,
+    /// When the subscription last delivered any update: an account or a new slot
+    pub observed_at: DateTime<Utc>,
+}
+
+impl SlotData {
+    /// Seconds since the subscription last delivered an update
+    pub fn age_secs(&self, now: DateTime<Utc>) -> i64 {
+        now.signed_duration_since(self.observed_at).num_seconds()
+    }
+}
+
+/// Record that the subscription delivered `slot` just now. Slot notifications
+/// and account updates interleave, so the slot never goes backwards.
+fn observe_slot(latest: &mut (u64, DateTime<Utc>), slot: u64) {
+    *latest = (latest.0.max(slot), Utc::now());
 }
 
 #[derive(Debug, Clone)]
@@ -40,36 +65,408 @@ impl SwapData {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A FusionAMM limit order. Orders have no owner field: whoever holds the
+/// order's NFT (`mint`) owns it, and the account sits at the `[limit_order, mint]` PDA.
+#[derive(Debug, Clone, PartialEq)]
 pub struct LimitOrderUpdate {
     pub pubkey: String,
-    pub owner: String,
-    pub input_mint: String,
-    pub output_mint: String,
+    /// Position NFT of the order
+    pub mint: String,
+    pub pool: String,
+    pub tick_index: i32,
+    /// Bids spend the pool's token B (quote) on token A (base); asks the reverse
+    pub is_bid: bool,
     pub price: f64,
+    /// Input deposited, in input-token base units
     pub size: u64,
+    /// Input filled, in the same units. The account keeps no fill state, so
+    /// this is 0 until the pool price is seen crossing the order's tick (see
+    /// `observe_pool_tick`), then all of `size`.
     pub filled: u64,
     pub is_active: bool,
 }
 
+impl LimitOrderUpdate {
+    /// Whether the pool's current tick has moved through the order: above an
+    /// ask's tick, below a bid's
+    pub fn is_crossed_by(&self, pool_tick: i32) -> bool {
+        if self.is_bid {
+            pool_tick < self.tick_index
+        } else {
+            pool_tick >= self.tick_index
+        }
+    }
+
+    /// Mark the order filled once `pool_tick` crosses it; true when that changed
+    /// it. A filled order stays filled when the price moves back.
+    pub fn observe_pool_tick(&mut self, pool_tick: i32) -> bool {
+        if !self.is_active || !self.is_crossed_by(pool_tick) {
+            return false;
+        }
+        self.filled = self.size;
+        self.is_active = false;
+        true
+    }
+}
+
+/// Where `decode_limit_order` reads a FusionAMM `LimitOrder` account's fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitOrderLayout {
+    pub discriminator: [u8; 8],
+    pub fusion_pool: usize,
+    pub limit_order_mint: usize,
+    pub tick_index: usize,
+    pub amount: usize,
+    pub a_to_b: usize,
+}
+
+impl Default for LimitOrderLayout {
+    /// As the program declares it: discriminator, version (u16), fusion_pool,
+    /// limit_order_mint, tick_index (i32), amount (u64), a_to_b (bool), packed
+    fn default() -> Self {
+        Self {
+            discriminator: anchor_account_discriminator("LimitOrder"),
+            fusion_pool: 10,
+            limit_order_mint: 42,
+            tick_index: 74,
+            amount: 78,
+            a_to_b: 86,
+        }
+    }
+}
+
+impl LimitOrderLayout {
+    /// The layout the IDL gives `LimitOrder`; the built-in one when the IDL
+    /// doesn't define it. Errors when it does but lacks a field the bot reads.
+    pub fn from_idl(idl: &ProgramIdl) -> Result<Self> {
+        let Some(fields) = idl.account_layout("LimitOrder") else {
+            return Ok(Self::default());
+        };
+        let offset = |name: &str| {
+            fields
+                .iter()
+                .find(|field| field.name == name)
+                .map(|field| field.offset)
+                .with_context(|| format!("IDL LimitOrder has no fixed-size {} field", name))
+        };
+        Ok(Self {
+            discriminator: idl.account_discriminator("LimitOrder"),
+            fusion_pool: offset("fusion_pool")?,
+            limit_order_mint: offset("limit_order_mint")?,
+            tick_index: offset("tick_index")?,
+            amount: offset("amount")?,
+            a_to_b: offset("a_to_b")?,
+        })
+    }
+
+    /// Bytes needed to read every field
+    fn len(&self) -> usize {
+        [self.fusion_pool + 32, self.limit_order_mint + 32, self.tick_index + 4, self.amount + 8, self.a_to_b + 1]
+            .into_iter()
+            .max()
+            .unwrap_or(8)
+    }
+}
+
+/// Decode a FusionAMM limit order account. Fails on anything else the program
+/// owns (pools, positions, tick arrays), which carry different discriminators.
+/// The account address isn't part of the data, so `pubkey` is left empty.
+/// `ticks` turns the order's tick back into a price.
+pub fn decode_limit_order(data: &[u8], layout: &LimitOrderLayout, ticks: &PoolTicks) -> Result<LimitOrderUpdate> {
+    if data.len() < layout.len() {
+        anyhow::bail!("Account data too short for a limit order: {} bytes", data.len());
+    }
+    if data[..8] != layout.discriminator {
+        anyhow::bail!("Not a limit order account");
+    }
+
+    let pubkey_at = |offset: usize| -> Result<String> {
+        let bytes: [u8; 32] = data[offset..offset + 32].try_into()?;
+        Ok(Pubkey::new_from_array(bytes).to_string())
+    };
+    let tick_index = i32::from_le_bytes(data[layout.tick_index..layout.tick_index + 4].try_into()?);
+    let size = u64::from_le_bytes(data[layout.amount..layout.amount + 8].try_into()?);
+
+    Ok(LimitOrderUpdate {
+        pubkey: String::new(),
+        mint: pubkey_at(layout.limit_order_mint)?,
+        pool: pubkey_at(layout.fusion_pool)?,
+        tick_index,
+        is_bid: data[layout.a_to_b] == 0,
+        price: ticks.tick_index_to_price(tick_index),
+        size,
+        filled: 0,
+        is_active: true,
+    })
+}
+
+/// Subscription lifecycle, published so the bot can pause trading during gaps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// First successful subscription
+    Subscribed,
+    /// Stream ended or the connection failed; no updates until resubscribed
+    Disconnected,
+    /// Subscribed again after a disconnect
+    Resubscribed,
+}
+
+/// Turns raw connect/disconnect observations into `ConnectionEvent`s, so repeated
+/// failed reconnect attempts publish a single `Disconnected`
+struct ConnectionTracker {
+    events: mpsc::UnboundedSender<ConnectionEvent>,
+    connected: bool,
+    ever_subscribed: bool,
+}
+
+impl ConnectionTracker {
+    fn new(events: mpsc::UnboundedSender<ConnectionEvent>) -> Self {
+        Self {
+            events,
+            connected: false,
+            ever_subscribed: false,
+        }
+    }
+
+    fn on_subscribed(&mut self) {
+        let event = if self.ever_subscribed {
+            ConnectionEvent::Resubscribed
+        } else {
+            ConnectionEvent::Subscribed
+        };
+        self.connected = true;
+        self.ever_subscribed = true;
+        // The bot may have dropped its receiver; the stream keeps running regardless
+        let _ = self.events.send(event);
+    }
+
+    fn on_disconnected(&mut self) {
+        if !self.connected {
+            return;
+        }
+        self.connected = false;
+        let _ = self.events.send(ConnectionEvent::Disconnected);
+    }
+}
+
+/// getProgramAccounts filters matching the limit order accounts resting on `pool`
+pub fn limit_order_filters(layout: &LimitOrderLayout, pool: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &layout.discriminator)),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(layout.fusion_pool, pool.as_ref())),
+    ]
+}
+
+/// Every limit order on `pool`, with its account address filled in and its fill
+/// state read against the pool's current tick. Accounts that don't decode are skipped.
+pub fn fetch_limit_orders(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    layout: &LimitOrderLayout,
+    ticks: &PoolTicks,
+) -> Result<Vec<LimitOrderUpdate>> {
+    let pool_tick = decode_pool(
+        *pool,
+        &rpc_client.get_account_data(pool).context("Failed to fetch pool account")?,
+    )?
+    .tick_current_index;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(limit_order_filters(layout, pool)),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = rpc_client
+        .get_program_accounts_with_config(program_id, config)
+        .context("Failed to fetch limit order accounts")?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(pubkey, account)| match decode_limit_order(&account.data, layout, ticks) {
+            Ok(order) => {
+                let mut order = LimitOrderUpdate { pubkey: pubkey.to_string(), ..order };
+                order.observe_pool_tick(pool_tick);
+                Some(order)
+            }
+            Err(e) => {
+                debug!("Skipping account {}: {}", pubkey, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Most accounts a single getMultipleAccounts call returns
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Every limit order `owner` holds. Orders record no owner: they belong to
+/// whoever holds their NFT, so the owner's token-2022 accounts holding exactly
+/// one indivisible token are read, and each mint's `[limit_order, mint]` PDA
+/// fetched. NFTs of anything else have no order account there and drop out.
+/// Fill state is read against each order's pool.
+pub fn fetch_owned_limit_orders(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    layout: &LimitOrderLayout,
+    ticks: &PoolTicks,
+) -> Result<Vec<LimitOrderUpdate>> {
+    let token_2022 = Pubkey::from_str(SPL_TOKEN_2022_ID)?;
+    let token_accounts = rpc_client
+        .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(token_2022))
+        .context("Failed to fetch the owner's token-2022 accounts")?;
+    let addresses: Vec<Pubkey> = token_accounts
+        .iter()
+        .filter_map(|keyed| nft_mint(&keyed.account))
+        .map(|mint| limit_order_address(program_id, &mint))
+        .collect();
+
+    let mut orders = Vec::new();
+    for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc_client
+            .get_multiple_accounts(chunk)
+            .context("Failed to fetch limit order accounts")?;
+        for (address, account) in chunk.iter().zip(accounts) {
+            let Some(account) = account.filter(|account| account.owner == *program_id) else {
+                continue;
+            };
+            match decode_limit_order(&account.data, layout, ticks) {
+                Ok(order) => orders.push(LimitOrderUpdate { pubkey: address.to_string(), ..order }),
+                Err(e) => debug!("Skipping account {}: {}", address, e),
+            }
+        }
+    }
+
+    let pools: HashSet<String> = orders.iter().map(|order| order.pool.clone()).collect();
+    for pool in pools {
+        let address = Pubkey::from_str(&pool)?;
+        let data = rpc_client
+            .get_account_data(&address)
+            .with_context(|| format!("Failed to fetch pool account {}", address))?;
+        let pool_tick = decode_pool(address, &data)?.tick_current_index;
+        for order in orders.iter_mut().filter(|order| order.pool == pool) {
+            order.observe_pool_tick(pool_tick);
+        }
+    }
+    Ok(orders)
+}
+
+/// Mint of a jsonParsed token account holding exactly one indivisible token
+fn nft_mint(account: &UiAccount) -> Option<Pubkey> {
+    let UiAccountData::Json(parsed) = &account.data else {
+        return None;
+    };
+    let info = &parsed.parsed["info"];
+    let amount = &info["tokenAmount"];
+    if amount["amount"].as_str() != Some("1") || amount["decimals"].as_u64() != Some(0) {
+        return None;
+    }
+    info["mint"].as_str().and_then(|mint| Pubkey::from_str(mint).ok())
+}
+
+/// Limit orders on the traded pool as the subscription reports them. Order
+/// accounts don't change when they fill, so pool updates re-check every known
+/// order against the new tick.
+struct OrderBook {
+    pool: String,
+    layout: LimitOrderLayout,
+    ticks: PoolTicks,
+    pool_tick: Option<i32>,
+    orders: HashMap<String, LimitOrderUpdate>,
+    /// Orders that changed since the bot last read them
+    changed: Vec<LimitOrderUpdate>,
+}
+
+impl OrderBook {
+    fn new(pool: &Pubkey, layout: LimitOrderLayout, ticks: PoolTicks) -> Self {
+        Self {
+            pool: pool.to_string(),
+            layout,
+            ticks,
+            pool_tick: None,
+            orders: HashMap::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Apply one program account update; false when it is neither the pool
+    /// nor one of its limit orders
+    fn on_account(&mut self, pubkey: &str, data: &[u8]) -> bool {
+        if pubkey == self.pool {
+            let Ok(pool) = Pubkey::from_str(pubkey).map_err(anyhow::Error::from).and_then(|key| decode_pool(key, data)) else {
+                return false;
+            };
+            self.pool_tick = Some(pool.tick_current_index);
+            for order in self.orders.values_mut() {
+                if order.observe_pool_tick(pool.tick_current_index) {
+                    self.changed.push(order.clone());
+                }
+            }
+            return true;
+        }
+
+        let mut order = match decode_limit_order(data, &self.layout, &self.ticks) {
+            Ok(order) if order.pool == self.pool => LimitOrderUpdate { pubkey: pubkey.to_string(), ..order },
+            Ok(_) => return false,
+            Err(e) => {
+                debug!("Skipping account {}: {}", pubkey, e);
+                return false;
+            }
+        };
+        // Once seen filled, an order stays filled
+        if let Some(known) = self.orders.get(pubkey).filter(|known| !known.is_active) {
+            order.filled = known.filled;
+            order.is_active = false;
+        }
+        if let Some(tick) = self.pool_tick {
+            order.observe_pool_tick(tick);
+        }
+        self.orders.insert(pubkey.to_string(), order.clone());
+        self.changed.push(order);
+        true
+    }
+
+    fn take_changed(&mut self) -> Vec<LimitOrderUpdate> {
+        std::mem::take(&mut self.changed)
+    }
+}
+
 pub struct SolanaRpcClient {
-    latest_data: Arc<Mutex<Option<SlotData>>>,
-    latest_slot: Arc<Mutex<u64>>,
+    book: Arc<Mutex<OrderBook>>,
+    /// Latest slot seen, and when
+    latest_slot: Arc<Mutex<(u64, DateTime<Utc>)>>,
     defituna_program: String,
+    /// Account filters applied on every (re)subscription
+    filters: Arc<RwLock<Option<Vec<RpcFilterType>>>>,
+    events: std::sync::Mutex<Option<mpsc::UnboundedReceiver<ConnectionEvent>>>,
 }
 
 impl SolanaRpcClient {
-    pub async fn new(ws_endpoint: &str, defituna_program: &str) -> Result<Self> {
+    /// Follows the limit orders on `pool`: `layout` decodes them, `ticks` prices them
+    pub async fn new(
+        ws_endpoint: &str,
+        defituna_program: &str,
+        pool: &Pubkey,
+        layout: LimitOrderLayout,
+        ticks: PoolTicks,
+    ) -> Result<Self> {
         info!("📡 Connecting to Solana RPC WebSocket for DeFiTuna limit order monitoring...");
         info!("   Endpoint: {}", ws_endpoint);
         info!("   Program: {}", defituna_program);
 
-        let latest_data: Arc<Mutex<Option<SlotData>>> = Arc::new(Mutex::new(None));
-        let latest_slot = Arc::new(Mutex::new(0));
+        let book = Arc::new(Mutex::new(OrderBook::new(pool, layout, ticks)));
+        let latest_slot = Arc::new(Mutex::new((0, Utc::now())));
+        let filters: Arc<RwLock<Option<Vec<RpcFilterType>>>> = Arc::new(RwLock::new(None));
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
         
         // Start WebSocket subscription in background
-        let latest_data_clone = latest_data.clone();
+        let book_clone = book.clone();
         let latest_slot_clone = latest_slot.clone();
+        let filters_clone = filters.clone();
+        let mut connection = ConnectionTracker::new(events_tx);
         let ws_endpoint = ws_endpoint.to_string();
         let program_id_str = defituna_program.to_string();
         
@@ -88,9 +485,10 @@ impl SolanaRpcClient {
                             }
                         };
                         
-                        // Subscribe to program accounts (limit orders owned by DeFiTuna)
+                        // Subscribe to program accounts (limit orders owned by DeFiTuna),
+                        // re-reading the filters so updates apply on reconnect
                         let config = RpcProgramAccountsConfig {
-                            filters: None,
+                            filters: filters_clone.read().await.clone(),
                             account_config: RpcAccountInfoConfig {
                                 encoding: Some(UiAccountEncoding::Base64),
                                 data_slice: None,
@@ -101,40 +499,34 @@ impl SolanaRpcClient {
                             sort_results: None,
                         };
                         
-                        match pubsub_client.program_subscribe(&program_id, Some(config)).await {
-                            Ok((mut stream, _unsub)) => {
-                                info!("📡 Subscribed to DeFiTuna program accounts");
+                        // Slot notifications keep the feed's clock running while the program is quiet
+                        let subscriptions = tokio::try_join!(
+                            pubsub_client.program_subscribe(&program_id, Some(config)),
+                            pubsub_client.slot_subscribe(),
+                        );
+                        match subscriptions {
+                            Ok(((mut stream, _unsub), (mut slots, _unsub_slots))) => {
+                                info!("📡 Subscribed to DeFiTuna program accounts and slots");
+                                connection.on_subscribed();
                                 
-                                while let Some(response) = stream.next().await {
-                                    let slot = response.context.slot;
-                                    let pubkey = response.value.pubkey;
-                                    
-                                    debug!("Account update: {} @ slot {}", pubkey, slot);
-                                    
-                                    // Update slot
-                                    *latest_slot_clone.lock().await = slot;
-                                    
-                                    // Parse account data
-                                    if let Some(account) = response.value.account.decode::<Account>() {
-                                        // TODO: Decode limit order account data properly
-                                        let limit_order = LimitOrderUpdate {
-                                            pubkey: pubkey.clone(),
-                                            owner: account.owner.to_string(),
-                                            input_mint: "SOL".to_string(),
-                                            output_mint: "USDC".to_string(),
-                                            price: 0.0,
-                                            size: 0,
-                                            filled: 0,
-                                            is_active: true,
-                                        };
-                                        
-                                        // Update latest data
-                                        let mut data = latest_data_clone.lock().await;
-                                        *data = Some(SlotData {
-                                            slot,
-                                            swaps: None,
-                                            limit_orders: Some(vec![limit_order]),
-                                        });
+                                loop {
+                                    tokio::select! {
+                                        response = stream.next() => {
+                                            let Some(response) = response else { break };
+                                            let slot = response.context.slot;
+                                            let pubkey = response.value.pubkey;
+                                            
+                                            debug!("Account update: {} @ slot {}", pubkey, slot);
+                                            observe_slot(&mut *latest_slot_clone.lock().await, slot);
+                                            
+                                            // Track the pool and its orders; other program accounts are skipped
+                                            if let Some(account) = response.value.account.decode::<Account>() {
+                                                book_clone.lock().await.on_account(&pubkey, &account.data);
+                                            }
+                                        }
+                                        Some(slot_info) = slots.next() => {
+                                            observe_slot(&mut *latest_slot_clone.lock().await, slot_info.slot);
+                                        }
                                     }
                                 }
                                 
@@ -149,26 +541,349 @@ impl SolanaRpcClient {
                         warn!("WebSocket connection error: {}, reconnecting in 5s...", e);
                     }
                 }
+                connection.on_disconnected();
                 
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
             }
         });
 
         Ok(Self {
-            latest_data,
+            book,
             latest_slot,
             defituna_program: defituna_program.to_string(),
+            filters,
+            events: std::sync::Mutex::new(Some(events_rx)),
         })
     }
 
+    /// Connection events for the bot; the receiver can be taken once
+    pub fn take_events(&self) -> Option<mpsc::UnboundedReceiver<ConnectionEvent>> {
+        self.events.lock().unwrap().take()
+    }
+
+    /// Replace the account filters; takes effect on the next (re)subscription
+    pub async fn set_filters(&self, filters: Option<Vec<RpcFilterType>>) {
+        *self.filters.write().await = filters;
+    }
+
+    /// Latest slot, with the limit orders that changed since the last call.
+    /// Any account update shows the subscription is live, decodable or not.
     pub async fn get_latest_slot(&self) -> Result<SlotData> {
-        let data = self.latest_data.lock().await;
-        let slot = *self.latest_slot.lock().await;
-        
-        Ok(data.clone().unwrap_or_else(|| SlotData {
+        let changed = self.book.lock().await.take_changed();
+        let (slot, observed_at) = *self.latest_slot.lock().await;
+        Ok(SlotData {
             slot,
             swaps: None,
+            limit_orders: (!changed.is_empty()).then_some(changed),
+            observed_at,
+        })
+    }
+}
+
+/// A `LimitOrder` account in the built-in layout, with a zeroed reserved tail
+#[cfg(test)]
+pub(crate) fn order_account_data(pool: &Pubkey, mint: &Pubkey, tick_index: i32, amount: u64, a_to_b: bool) -> Vec<u8> {
+    let mut data = anchor_account_discriminator("LimitOrder").to_vec();
+    data.extend_from_slice(&1u16.to_le_bytes()); // version
+    data.extend_from_slice(pool.as_ref());
+    data.extend_from_slice(mint.as_ref());
+    data.extend_from_slice(&tick_index.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(a_to_b as u8);
+    data.extend_from_slice(&[0; 64]);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defituna_client::pool_account_data;
+    use base64::Engine;
+    use solana_client::rpc_client::Mocks;
+    use solana_client::rpc_request::RpcRequest;
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn pool_blob(tick_current_index: i32) -> Vec<u8> {
+        let sol = Pubkey::from_str(SOL_MINT).unwrap();
+        let usdc = Pubkey::from_str(USDC_MINT).unwrap();
+        pool_account_data(&sol, &usdc, 1 << 64, tick_current_index)
+    }
+
+    #[test]
+    fn test_fetch_limit_orders() {
+        let program_id = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let layout = LimitOrderLayout::default();
+        // A 2 SOL ask at tick 5056 (~$1659), and a 150 USDC bid at ~$150 the
+        // pool (at ~$149.6) has already traded through
+        let ask = Pubkey::new_unique();
+        let bid = Pubkey::new_unique();
+        let keyed = |pubkey: Pubkey, data: Vec<u8>| {
+            serde_json::json!({
+                "pubkey": pubkey.to_string(),
+                "account": {
+                    "lamports": 2_039_280,
+                    "space": data.len(),
+                    "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                    "owner": program_id.to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                },
+            })
+        };
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            serde_json::json!({ "context": { "slot": 1 }, "value": keyed(pool, pool_blob(-19_000))["account"] }),
+        );
+        mocks.insert(
+            RpcRequest::GetProgramAccounts,
+            serde_json::json!([
+                keyed(ask, order_account_data(&pool, &Pubkey::new_unique(), 5056, 2_000_000_000, true)),
+                keyed(bid, order_account_data(&pool, &Pubkey::new_unique(), -18_944, 150_000_000, false)),
+            ]),
+        );
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let ticks = PoolTicks::default();
+
+        let orders = fetch_limit_orders(&rpc_client, &program_id, &pool, &layout, &ticks).unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].pubkey, ask.to_string());
+        assert!(!orders[0].is_bid);
+        assert!(orders[0].is_active);
+        assert_eq!(orders[1].pubkey, bid.to_string());
+        assert!(orders[1].is_bid);
+        assert!((orders[1].price - ticks.tick_index_to_price(-18_944)).abs() < 1e-9);
+        assert_eq!(orders[1].filled, 150_000_000);
+        assert!(!orders[1].is_active);
+
+        // Only orders on the pool are asked for
+        let matches = |data: Vec<u8>| {
+            limit_order_filters(&layout, &pool).iter().all(|filter| match filter {
+                RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(&data),
+                _ => false,
+            })
+        };
+        assert!(matches(order_account_data(&pool, &Pubkey::new_unique(), 5056, 1, true)));
+        assert!(!matches(order_account_data(&Pubkey::new_unique(), &Pubkey::new_unique(), 5056, 1, true)));
+        assert!(!matches(pool_blob(0)));
+    }
+
+    #[test]
+    fn test_fetch_owned_limit_orders() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        // An order NFT, an NFT from another program, and a fungible token balance
+        let (order_nft, other_nft, fungible) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let token_account = |mint: &Pubkey, amount: &str, decimals: u8| {
+            serde_json::json!({
+                "pubkey": Pubkey::new_unique().to_string(),
+                "account": {
+                    "lamports": 2_074_080,
+                    "space": 170,
+                    "owner": SPL_TOKEN_2022_ID,
+                    "executable": false,
+                    "rentEpoch": 0,
+                    "data": {
+                        "program": "spl-token-2022",
+                        "space": 170,
+                        "parsed": {
+                            "type": "account",
+                            "info": {
+                                "mint": mint.to_string(),
+                                "owner": owner.to_string(),
+                                "state": "initialized",
+                                "isNative": false,
+                                "tokenAmount": { "amount": amount, "decimals": decimals, "uiAmountString": amount },
+                            },
+                        },
+                    },
+                },
+            })
+        };
+        let account = |owner: &Pubkey, data: Vec<u8>| {
+            serde_json::json!({
+                "lamports": 2_039_280,
+                "space": data.len(),
+                "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+            })
+        };
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetTokenAccountsByOwner,
+            serde_json::json!({ "context": { "slot": 1 }, "value": [
+                token_account(&order_nft, "1", 0),
+                token_account(&other_nft, "1", 0),
+                token_account(&fungible, "1", 6),
+            ] }),
+        );
+        // Only the two NFTs' order PDAs are asked for, and only one has an order
+        mocks.insert(
+            RpcRequest::GetMultipleAccounts,
+            serde_json::json!({ "context": { "slot": 1 }, "value": [
+                account(&program_id, order_account_data(&pool, &order_nft, -18_944, 150_000_000, false)),
+                null,
+            ] }),
+        );
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            serde_json::json!({ "context": { "slot": 1 }, "value": account(&program_id, pool_blob(-19_000)) }),
+        );
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+
+        let orders = fetch_owned_limit_orders(&rpc_client, &program_id, &owner, &LimitOrderLayout::default(), &PoolTicks::default()).unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].pubkey, limit_order_address(&program_id, &order_nft).to_string());
+        assert_eq!(orders[0].mint, order_nft.to_string());
+        assert!(orders[0].is_bid);
+        // The pool has traded down through the bid
+        assert!(!orders[0].is_active);
+    }
+
+    #[test]
+    fn test_decode_limit_order() {
+        let ticks = PoolTicks::default();
+        let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let layout = LimitOrderLayout::default();
+        let order = decode_limit_order(&order_account_data(&pool, &mint, 5056, 2_000_000_000, true), &layout, &ticks).unwrap();
+        assert_eq!(order.pool, pool.to_string());
+        assert_eq!(order.mint, mint.to_string());
+        assert_eq!(order.tick_index, 5056);
+        assert!(!order.is_bid);
+        assert!((order.price - ticks.tick_index_to_price(5056)).abs() < 1e-9);
+        assert_eq!(order.size, 2_000_000_000);
+        assert_eq!(order.filled, 0);
+        assert!(order.is_active);
+
+        // Another program account (e.g. a pool) is not misread as an order
+        assert!(decode_limit_order(&pool_blob(0), &layout, &ticks).is_err());
+        assert!(decode_limit_order(&order_account_data(&pool, &mint, 0, 1, true)[..80], &layout, &ticks).is_err());
+
+        // An IDL that moves the fields is followed
+        let idl = ProgramIdl::from_idl_json(
+            r#"{
+                "instructions": [],
+                "accounts": [{ "name": "LimitOrder", "discriminator": [7, 7, 7, 7, 7, 7, 7, 7] }],
+                "types": [{ "name": "LimitOrder", "type": { "kind": "struct", "fields": [
+                    { "name": "version", "type": "u16" },
+                    { "name": "fusionPool", "type": "pubkey" },
+                    { "name": "limitOrderMint", "type": "pubkey" },
+                    { "name": "tickIndex", "type": "i32" },
+                    { "name": "aToB", "type": "bool" },
+                    { "name": "amount", "type": "u64" }
+                ] } }]
+            }"#,
+        )
+        .unwrap();
+        let layout = LimitOrderLayout::from_idl(&idl).unwrap();
+        assert_eq!((layout.a_to_b, layout.amount), (78, 79));
+        let mut data = vec![7; 8];
+        data.extend_from_slice(&[1, 0]);
+        data.extend_from_slice(pool.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(&(-64i32).to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&42u64.to_le_bytes());
+        let order = decode_limit_order(&data, &layout, &ticks).unwrap();
+        assert_eq!((order.tick_index, order.is_bid, order.size), (-64, true, 42));
+        assert_eq!(LimitOrderLayout::from_idl(&ProgramIdl::default()).unwrap(), LimitOrderLayout::default());
+    }
+
+    #[test]
+    fn test_orders_fill_as_the_pool_crosses_them() {
+        let pool = Pubkey::new_unique();
+        let mut book = OrderBook::new(&pool, LimitOrderLayout::default(), PoolTicks::default());
+        let (ask, bid) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+
+        assert!(book.on_account(&pool.to_string(), &pool_blob(0)));
+        assert!(book.on_account(&ask, &order_account_data(&pool, &Pubkey::new_unique(), 128, 1_000, true)));
+        assert!(book.on_account(&bid, &order_account_data(&pool, &Pubkey::new_unique(), -128, 2_000, false)));
+        // Orders on other pools and unrelated accounts are ignored
+        assert!(!book.on_account("other", &order_account_data(&Pubkey::new_unique(), &Pubkey::new_unique(), 0, 1, true)));
+        assert!(!book.on_account("other", &[0; 16]));
+        let placed = book.take_changed();
+        assert_eq!(placed.len(), 2);
+        assert!(placed.iter().all(|order| order.is_active && order.filled == 0));
+
+        // Moving inside the spread fills nothing
+        assert!(book.on_account(&pool.to_string(), &pool_blob(64)));
+        assert!(book.take_changed().is_empty());
+
+        // Up through the ask: it fills, and stays filled when the price comes back
+        book.on_account(&pool.to_string(), &pool_blob(130));
+        let filled = book.take_changed();
+        assert_eq!(filled.len(), 1);
+        assert_eq!((filled[0].pubkey.as_str(), filled[0].filled, filled[0].is_active), (ask.as_str(), 1_000, false));
+        book.on_account(&pool.to_string(), &pool_blob(0));
+        book.on_account(&ask, &order_account_data(&pool, &Pubkey::new_unique(), 128, 1_000, true));
+        assert!(book.take_changed().iter().all(|order| !order.is_active));
+
+        // Down through the bid
+        book.on_account(&pool.to_string(), &pool_blob(-200));
+        let filled = book.take_changed();
+        assert_eq!((filled[0].pubkey.as_str(), filled[0].filled), (bid.as_str(), 2_000));
+    }
+
+    #[test]
+    fn test_slot_data_age() {
+        let now = Utc::now();
+        let slot_data = |age_secs| SlotData {
+            slot: 1,
+            swaps: None,
             limit_orders: None,
-        }))
+            observed_at: now - chrono::Duration::seconds(age_secs),
+        };
+        assert_eq!(slot_data(0).age_secs(now), 0);
+        assert_eq!(slot_data(5).age_secs(now), 5);
+        // A stalled subscription keeps serving the same old data
+        assert_eq!(slot_data(600).age_secs(now), 600);
+    }
+
+    #[test]
+    fn test_slot_notifications_refresh_the_feed_clock() {
+        let stale = Utc::now() - chrono::Duration::seconds(600);
+        let mut latest = (100, stale);
+
+        observe_slot(&mut latest, 105);
+        assert_eq!(latest.0, 105);
+        assert!(latest.1 > stale);
+
+        // An account update from an earlier slot refreshes the clock, not the slot
+        let (_, refreshed) = latest;
+        observe_slot(&mut latest, 103);
+        assert_eq!(latest.0, 105);
+        assert!(latest.1 >= refreshed);
+    }
+
+    #[test]
+    fn test_disconnect_reconnect_publishes_events_in_order() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut connection = ConnectionTracker::new(tx);
+
+        connection.on_subscribed();
+        connection.on_disconnected();
+        // Failed reconnect attempts don't repeat the disconnect
+        connection.on_disconnected();
+        connection.on_disconnected();
+        connection.on_subscribed();
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            vec![
+                ConnectionEvent::Subscribed,
+                ConnectionEvent::Disconnected,
+                ConnectionEvent::Resubscribed,
+            ]
+        );
     }
 }
@@ -0,0 +1,185 @@
+use super::{Strategy, TradeSignal};
+use crate::price_source::PriceSource;
+use crate::price_tracker::PriceTracker;
+use std::sync::Arc;
+
+/// Costs a round trip has to cover: the swap fee on each venue plus the
+/// network/priority fee of each leg's transaction
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArbitrageCosts {
+    pub jupiter_fee_bps: u16,
+    pub defituna_fee_bps: u16,
+    /// Base plus priority fee of one leg
+    pub network_fee_lamports: u64,
+}
+
+impl ArbitrageCosts {
+    /// Both legs' costs in bps of a `notional` trade (quote units), valuing the
+    /// SOL network fees at `sol_price`
+    pub fn total_bps(&self, notional: f64, sol_price: f64) -> f64 {
+        let swap_bps = (self.jupiter_fee_bps + self.defituna_fee_bps) as f64;
+        if notional <= 0.0 {
+            return swap_bps;
+        }
+        let network_cost = 2.0 * self.network_fee_lamports as f64 / 1_000_000_000.0 * sol_price;
+        swap_bps + network_cost / notional * 10_000.0
+    }
+}
+
+/// Trades DeFiTuna toward the Jupiter price: buys when DeFiTuna is cheaper than
+/// Jupiter by more than the round-trip costs plus `min_profit_bps`, sells when
+/// it is richer by as much
+pub struct ArbitrageStrategy {
+    jupiter: Arc<dyn PriceSource>,
+    defituna: Arc<dyn PriceSource>,
+    /// Net edge required after costs
+    min_profit_bps: u16,
+    trade_amount: u64,
+    costs: ArbitrageCosts,
+}
+
+impl ArbitrageStrategy {
+    pub fn new(
+        jupiter: Arc<dyn PriceSource>,
+        defituna: Arc<dyn PriceSource>,
+        min_profit_bps: u16,
+        trade_amount: u64,
+    ) -> Self {
+        Self {
+            jupiter,
+            defituna,
+            min_profit_bps,
+            trade_amount,
+            costs: ArbitrageCosts::default(),
+        }
+    }
+
+    pub fn with_costs(mut self, costs: ArbitrageCosts) -> Self {
+        self.costs = costs;
+        self
+    }
+}
+
+impl Strategy for ArbitrageStrategy {
+    fn generate_signal(&self, _tracker: &PriceTracker) -> Option<TradeSignal> {
+        let (Some(jupiter), Some(defituna)) = (self.jupiter.latest_price(), self.defituna.latest_price()) else {
+            return Some(TradeSignal::Hold { reason: "waiting for both venue prices".to_string() });
+        };
+        if defituna <= 0.0 {
+            return Some(TradeSignal::Hold {
+                reason: format!("invalid {} price ${:.4}", self.defituna.name(), defituna),
+            });
+        }
+
+        // Positive when Jupiter pays more than DeFiTuna charges
+        let spread_bps = (jupiter - defituna) / defituna * 10_000.0;
+        // Trade amount is in quote units (6 decimals); fees are paid in SOL, the base token
+        let fees_bps = self.costs.total_bps(self.trade_amount as f64 / 1_000_000.0, defituna);
+        let required_bps = fees_bps + self.min_profit_bps as f64;
+        let reason = format!(
+            "{} ${:.4} vs {} ${:.4} ({:+.1}bps, fees {:.1}bps)",
+            self.jupiter.name(),
+            jupiter,
+            self.defituna.name(),
+            defituna,
+            spread_bps,
+            fees_bps
+        );
+
+        if spread_bps > required_bps {
+            Some(TradeSignal::Buy { amount: self.trade_amount, reason })
+        } else if -spread_bps > required_bps {
+            Some(TradeSignal::Sell { amount: self.trade_amount, reason })
+        } else {
+            Some(TradeSignal::Hold {
+                reason: format!("{}, need {:.1}bps", reason, required_bps),
+            })
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Arbitrage"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedPrice(Option<f64>);
+
+    impl PriceSource for FixedPrice {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn latest_price(&self) -> Option<f64> {
+            self.0
+        }
+    }
+
+    fn strategy(jupiter: Option<f64>, defituna: Option<f64>) -> ArbitrageStrategy {
+        ArbitrageStrategy::new(
+            Arc::new(FixedPrice(jupiter)),
+            Arc::new(FixedPrice(defituna)),
+            20,
+            10_000_000,
+        )
+    }
+
+    #[test]
+    fn test_spread_above_threshold_trades() {
+        let tracker = PriceTracker::new(60);
+
+        // 50bps: Jupiter richer buys on DeFiTuna, Jupiter cheaper sells there
+        assert!(matches!(
+            strategy(Some(100.5), Some(100.0)).generate_signal(&tracker),
+            Some(TradeSignal::Buy { amount: 10_000_000, .. })
+        ));
+        assert!(matches!(
+            strategy(Some(99.5), Some(100.0)).generate_signal(&tracker),
+            Some(TradeSignal::Sell { amount: 10_000_000, .. })
+        ));
+
+        // 10bps is inside MIN_PROFIT_BPS
+        assert!(matches!(
+            strategy(Some(100.1), Some(100.0)).generate_signal(&tracker),
+            Some(TradeSignal::Hold { .. })
+        ));
+    }
+
+    #[test]
+    fn test_edge_must_cover_fees() {
+        let tracker = PriceTracker::new(60);
+        // 5 + 5bps swap fees, plus 2 x 10k lamports at $100 on a $10 trade = 2bps
+        let costs = ArbitrageCosts {
+            jupiter_fee_bps: 5,
+            defituna_fee_bps: 5,
+            network_fee_lamports: 10_000,
+        };
+        assert!((costs.total_bps(10.0, 100.0) - 12.0).abs() < 1e-9);
+
+        // 25bps clears MIN_PROFIT_BPS alone but not fees + MIN_PROFIT_BPS (32bps)
+        let small_edge = strategy(Some(100.25), Some(100.0)).with_costs(costs);
+        assert!(matches!(small_edge.generate_signal(&tracker), Some(TradeSignal::Hold { .. })));
+
+        let large_edge = strategy(Some(100.5), Some(100.0)).with_costs(costs);
+        assert!(matches!(
+            large_edge.generate_signal(&tracker),
+            Some(TradeSignal::Buy { amount: 10_000_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_price_holds() {
+        let tracker = PriceTracker::new(60);
+        assert!(matches!(
+            strategy(None, Some(100.0)).generate_signal(&tracker),
+            Some(TradeSignal::Hold { .. })
+        ));
+        assert!(matches!(
+            strategy(Some(100.5), None).generate_signal(&tracker),
+            Some(TradeSignal::Hold { .. })
+        ));
+    }
+}
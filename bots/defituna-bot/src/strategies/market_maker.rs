@@ -1,48 +1,275 @@
-use super::{Strategy, TradeSignal};
+use super::{BookDepth, Fill, FillSide, Strategy, TradeSignal};
+use crate::defituna_client::PoolTicks;
+use crate::inventory::Inventory;
 use crate::price_tracker::PriceTracker;
-use tracing::info;
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Mid, inventory skew and time of the last quote sent, for requote suppression
+#[derive(Debug, Clone, Copy)]
+struct LastQuote {
+    mid: f64,
+    /// How far (bps of the mid) inventory skew shifted both sides down; negative is up
+    skew_bps: f64,
+    timestamp: i64,
+}
+
+impl LastQuote {
+    /// Price both sides were quoted around: the mid less the skew
+    fn center(&self) -> f64 {
+        self.mid * (1.0 - self.skew_bps / 10000.0)
+    }
+}
+
+/// A limit order of ours resting on chain, and the quote it came from
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    order_id: String,
+    is_bid: bool,
+    price: f64,
+    quote: LastQuote,
+}
 
 /// Market maker strategy that places both bid and ask orders
-/// around the current market price with a defined spread
+/// around the current market price with a defined spread.
+/// Inventory comes from `on_fill`; bids stop at `max_position_size` and asks when flat.
 pub struct MarketMakerStrategy {
     spread_bps: u16,
     order_size: u64,
     max_position_size: u64,
-    current_position: u64,
+    min_ask_profit_bps: u16,
+    /// MAKER_FEE_BPS until the pool's own fee tier is read
+    maker_fee_bps: f64,
+    max_order_depth_ratio: f64,
+    max_hold_minutes: u64,
+    book_depth: Option<BookDepth>,
+    inventory: Inventory,
+    /// Skip requoting until the mid or inventory skew moves the quotes this far; 0 disables
+    requote_min_move_bps: u16,
+    /// Requote anyway once the last quote is this old
+    requote_max_age_secs: u64,
+    last_quote: Mutex<Option<LastQuote>>,
+    /// Cancelled before requoting once stale, so orders don't stack up
+    resting_orders: Vec<RestingOrder>,
+    /// Half-spreads both quotes shift down by at `max_position_size`, and up by
+    /// when flat; 0 quotes symmetrically
+    skew_factor: f64,
+    /// Asks are checked against the price they'll rest at on this pool
+    ticks: PoolTicks,
 }
 
 impl MarketMakerStrategy {  
-    pub fn new(spread_bps: u16, order_size: u64, max_position_size: u64) -> Self {
+    pub fn new(
+        spread_bps: u16,
+        order_size: u64,
+        max_position_size: u64,
+        min_ask_profit_bps: u16,
+        maker_fee_bps: u16,
+        max_order_depth_ratio: f64,
+        max_hold_minutes: u64,
+    ) -> Self {
         Self {
             spread_bps,
             order_size,
             max_position_size,
-            current_position: 0,
+            min_ask_profit_bps,
+            maker_fee_bps: maker_fee_bps as f64,
+            max_order_depth_ratio,
+            max_hold_minutes,
+            book_depth: None,
+            inventory: Inventory::new(),
+            requote_min_move_bps: 0,
+            requote_max_age_secs: 0,
+            last_quote: Mutex::new(None),
+            resting_orders: Vec::new(),
+            skew_factor: 0.0,
+            ticks: PoolTicks::default(),
         }
     }
 
+    /// Only requote after the mid moves `min_move_bps` or the last quote is `max_age_secs` old
+    pub fn with_requote_threshold(mut self, min_move_bps: u16, max_age_secs: u64) -> Self {
+        self.requote_min_move_bps = min_move_bps;
+        self.requote_max_age_secs = max_age_secs;
+        self
+    }
+
+    /// Lean quotes against inventory: above the half-full target both sides sit
+    /// below the mid, down to `skew_factor` half-spreads at `max_position_size`;
+    /// below it they sit above, up to `skew_factor` half-spreads when flat
+    pub fn with_skew_factor(mut self, skew_factor: f64) -> Self {
+        self.skew_factor = skew_factor;
+        self
+    }
+
     fn calculate_bid_ask_prices(&self, mid_price: f64) -> (f64, f64) {
         let spread_factor = self.spread_bps as f64 / 10000.0;
         let half_spread = mid_price * spread_factor / 2.0;
+        let skew = mid_price * self.skew_bps() / 10000.0;
 
-        let bid_price = mid_price - half_spread;
-        let ask_price = mid_price + half_spread;
+        let bid_price = mid_price - half_spread - skew;
+        let ask_price = mid_price + half_spread - skew;
 
         (bid_price, ask_price)
     }
 
-    fn can_place_bid(&self) -> bool {
-        self.current_position < self.max_position_size
+    /// How far (bps of the mid) the current inventory shifts both quotes down
+    fn skew_bps(&self) -> f64 {
+        self.spread_bps as f64 / 2.0 * self.skew_factor * self.inventory_ratio()
+    }
+
+    /// Price both sides are quoted around at `mid` with the current skew
+    fn quote_center(&self, mid: f64) -> f64 {
+        mid * (1.0 - self.skew_bps() / 10000.0)
+    }
+
+    /// Position against the target of half `max_position_size`, from -1 when
+    /// flat to 1 at the cap. Spot inventory can't go short, so holding less
+    /// than the target is this book's short side.
+    fn inventory_ratio(&self) -> f64 {
+        if self.max_position_size == 0 {
+            return 0.0;
+        }
+        let target = self.max_position_size as f64 / 2.0;
+        ((self.inventory.position() as f64 - target) / target).clamp(-1.0, 1.0)
+    }
+
+    /// Whether an ask at `ask_price`, after tick rounding and fees, clears
+    /// `min_ask_profit_bps` over the inventory's average entry price
+    fn ask_meets_profit_floor(&self, ask_price: f64) -> bool {
+        let Some(entry) = self.inventory.avg_entry_price() else {
+            return true;
+        };
+        let net_ask = self.ticks.round_price_to_tick(ask_price) * (1.0 - self.maker_fee_bps / 10000.0);
+        let floor = entry * (1.0 + self.min_ask_profit_bps as f64 / 10000.0);
+
+        if net_ask < floor {
+            info!(
+                "🚫 Ask suppressed: net ${:.4} (after tick rounding and {}bps fee) below floor ${:.4} (entry ${:.4} + {}bps)",
+                net_ask, self.maker_fee_bps, floor, entry, self.min_ask_profit_bps
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Flattening sell once the open position is older than `max_hold_minutes`,
+    /// sized at what the base held is worth at `price`
+    fn forced_exit(&self, now: i64, price: f64) -> Option<TradeSignal> {
+        if self.max_hold_minutes == 0 {
+            return None;
+        }
+        let opened_at = self.inventory.opened_at()?;
+        let held_minutes = (now - opened_at) / 60;
+        if held_minutes < self.max_hold_minutes as i64 {
+            return None;
+        }
+
+        let reason = format!(
+            "Max hold time exceeded: position open {}min > {}min",
+            held_minutes, self.max_hold_minutes
+        );
+        warn!("⏳ Forced exit: {}", reason);
+        Some(TradeSignal::Sell {
+            amount: self.inventory.value_at(price),
+            reason,
+        })
     }
 
-    fn can_place_ask(&self) -> bool {
-        self.current_position > 0
+    /// How far (bps) and how long ago (secs) the quoting center has moved
+    /// since `quote`: with the mid, or with the skew as inventory changes
+    fn drift(&self, quote: LastQuote, mid: f64, now: i64) -> (f64, u64) {
+        let moved_bps = (self.quote_center(mid) - quote.center()).abs() / quote.center() * 10000.0;
+        let age_secs = (now - quote.timestamp).max(0) as u64;
+        (moved_bps, age_secs)
+    }
+
+    fn requote_due(&self, moved_bps: f64, age_secs: u64) -> bool {
+        moved_bps >= self.requote_min_move_bps as f64 || age_secs >= self.requote_max_age_secs
+    }
+
+    /// Whether the last quote still stands: the mid and skew have moved the quotes
+    /// less than `requote_min_move_bps` and the quote is younger than `requote_max_age_secs`
+    fn last_quote_is_current(&self, mid: f64, now: i64) -> bool {
+        if self.requote_min_move_bps == 0 {
+            return false;
+        }
+        let Some(last) = *self.last_quote.lock().unwrap() else {
+            return false;
+        };
+        let (moved_bps, age_secs) = self.drift(last, mid, now);
+        if self.requote_due(moved_bps, age_secs) {
+            return false;
+        }
+
+        info!(
+            "⏸️  Requote suppressed: quotes moved {:.1}bps (< {}bps) in {}s (< {}s)",
+            moved_bps, self.requote_min_move_bps, age_secs, self.requote_max_age_secs
+        );
+        true
+    }
+
+    /// Cancel for the first resting order whose quote is due a refresh, so the
+    /// requote replaces it rather than stacking on top. Requoting every tick,
+    /// every resting order is due.
+    fn stale_order_cancel(&self, mid: f64, now: i64) -> Option<TradeSignal> {
+        let order = self.resting_orders.iter().find(|order| {
+            let (moved_bps, age_secs) = self.drift(order.quote, mid, now);
+            self.requote_due(moved_bps, age_secs)
+        })?;
+
+        info!(
+            "🔁 Requote: cancelling {} {} at ${:.4}, quoted around ${:.4} (now ${:.4})",
+            if order.is_bid { "bid" } else { "ask" },
+            order.order_id,
+            order.price,
+            order.quote.center(),
+            self.quote_center(mid)
+        );
+        Some(TradeSignal::CancelOrder {
+            order_id: order.order_id.clone(),
+            is_bid: order.is_bid,
+            price: order.price,
+        })
+    }
+
+    fn record_quote(&self, mid: f64, now: i64) {
+        *self.last_quote.lock().unwrap() = Some(LastQuote { mid, skew_bps: self.skew_bps(), timestamp: now });
+    }
+
+    /// Cap an order at `max_order_depth_ratio` of the same-side depth, when depth is known
+    fn size_for_depth(&self, side_depth: Option<u64>) -> u64 {
+        let Some(depth) = side_depth.filter(|_| self.max_order_depth_ratio > 0.0) else {
+            return self.order_size;
+        };
+        let cap = (depth as f64 * self.max_order_depth_ratio) as u64;
+        if cap < self.order_size {
+            info!(
+                "📉 Order shrunk to {} (depth {} × ratio {:.2}), configured size {}",
+                cap, depth, self.max_order_depth_ratio, self.order_size
+            );
+        }
+        self.order_size.min(cap)
     }
 }
 
 impl Strategy for MarketMakerStrategy {
     fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal> {
+        let now = chrono::Utc::now().timestamp();
         let current_price = tracker.current_price()?;
+
+        // Time-based exit overrides the quoting logic
+        if let Some(exit) = self.forced_exit(now, current_price) {
+            return Some(exit);
+        }
+
+        if let Some(cancel) = self.stale_order_cancel(current_price, now) {
+            return Some(cancel);
+        }
+        if self.last_quote_is_current(current_price, now) {
+            return Some(TradeSignal::Hold { reason: "last quote still current".to_string() });
+        }
+
         let (bid_price, ask_price) = self.calculate_bid_ask_prices(current_price);
 
         info!(
@@ -51,24 +278,373 @@ impl Strategy for MarketMakerStrategy {
         );
 
         // Simple market making: place both orders if we can
-        if self.can_place_bid() {
-            return Some(TradeSignal::PlaceBid {
-                price: bid_price,
-                size: self.order_size,
-            });
+        let position = self.inventory.position();
+        let mut held = Vec::new();
+
+        // Bids never take the position past the cap
+        if position >= self.max_position_size {
+            held.push(format!(
+                "bid suppressed: position {} at max_position_size {}",
+                position, self.max_position_size
+            ));
+        } else {
+            let size = self
+                .size_for_depth(self.book_depth.map(|d| d.bid_depth))
+                .min(self.max_position_size - position);
+            if size > 0 {
+                self.record_quote(current_price, now);
+                return Some(TradeSignal::PlaceBid { price: bid_price, size });
+            }
         }
 
-        if self.can_place_ask() {
-            return Some(TradeSignal::PlaceAsk {
-                price: ask_price,
-                size: self.order_size,
-            });
+        // Asks never sell more than is held
+        if position == 0 {
+            held.push("ask suppressed: position is flat".to_string());
+        } else if !self.ask_meets_profit_floor(ask_price) {
+            held.push("ask suppressed: below profit floor".to_string());
+        } else {
+            let size = self
+                .size_for_depth(self.book_depth.map(|d| d.ask_depth))
+                .min(self.inventory.value_at(ask_price));
+            if size > 0 {
+                self.record_quote(current_price, now);
+                return Some(TradeSignal::PlaceAsk { price: ask_price, size });
+            }
         }
 
-        Some(TradeSignal::Hold)
+        let reason = if held.is_empty() {
+            "no book depth to quote into".to_string()
+        } else {
+            held.join("; ")
+        };
+        info!("⏸️  Market maker holding: {}", reason);
+        Some(TradeSignal::Hold { reason })
     }
 
     fn name(&self) -> &str {
         "Market Maker"
     }
+
+    /// Buys enter inventory at their net price, so the cost basis includes the maker fee
+    fn on_fill(&mut self, fill: &Fill) {
+        match fill.side {
+            FillSide::Buy => self.inventory.record_buy(fill.size, fill.net_price(self.maker_fee_bps), fill.timestamp),
+            FillSide::Sell => self.inventory.record_sell(fill.size, fill.price),
+        }
+    }
+
+    fn on_book_depth(&mut self, depth: BookDepth) {
+        self.book_depth = Some(depth);
+    }
+
+    fn on_pool_fee(&mut self, fee_bps: f64) {
+        self.maker_fee_bps = fee_bps;
+    }
+
+    fn on_pool_ticks(&mut self, ticks: PoolTicks) {
+        self.ticks = ticks;
+    }
+
+    fn on_order_placed(&mut self, order_id: &str, signal: &TradeSignal) {
+        let (is_bid, price) = match signal {
+            TradeSignal::PlaceBid { price, .. } => (true, *price),
+            TradeSignal::PlaceAsk { price, .. } => (false, *price),
+            _ => return,
+        };
+        // Orders this strategy didn't quote count from now at their own price
+        let quote = self.last_quote.lock().unwrap().unwrap_or(LastQuote {
+            mid: price,
+            skew_bps: 0.0,
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+        self.resting_orders.push(RestingOrder { order_id: order_id.to_string(), is_bid, price, quote });
+    }
+
+    fn on_order_closed(&mut self, order_id: &str) {
+        self.resting_orders.retain(|order| order.order_id != order_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker_at(price: f64) -> PriceTracker {
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(price);
+        tracker
+    }
+
+    fn long_maker(entry: f64) -> MarketMakerStrategy {
+        // Position cap equals one order so the bid side is full and only asks remain
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 1_000, 50, 10, 0.0, 0);
+        maker.on_fill(&Fill { side: FillSide::Buy, price: entry, size: 1_000, timestamp: 0 });
+        maker
+    }
+
+    #[test]
+    fn test_ask_below_profit_floor_is_suppressed() {
+        let maker = long_maker(100.0);
+        // Ask ~100.05 rounds down a tick and pays fees: below entry + 50bps
+        let signal = maker.generate_signal(&tracker_at(100.0)).unwrap();
+        assert!(matches!(signal, TradeSignal::Hold { .. }));
+    }
+
+    #[test]
+    fn test_ask_above_profit_floor_is_allowed() {
+        let maker = long_maker(100.0);
+        let signal = maker.generate_signal(&tracker_at(110.0)).unwrap();
+        match signal {
+            TradeSignal::PlaceAsk { price, size } => {
+                assert!(price > 110.0);
+                assert_eq!(size, 1_000);
+            }
+            other => panic!("expected PlaceAsk, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_order_shrunk_on_shallow_book() {
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.25, 0);
+
+        // Deep book: configured size is used
+        maker.on_book_depth(BookDepth { bid_depth: 1_000_000, ask_depth: 1_000_000 });
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::PlaceBid { size, .. } => assert_eq!(size, 1_000),
+            other => panic!("expected PlaceBid, got {:?}", other),
+        }
+
+        // Shallow book: 25% of 2_000 bid depth
+        maker.on_book_depth(BookDepth { bid_depth: 2_000, ask_depth: 1_000_000 });
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::PlaceBid { size, .. } => assert_eq!(size, 500),
+            other => panic!("expected PlaceBid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_position_past_max_hold_is_flattened() {
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 60);
+        let now = chrono::Utc::now().timestamp();
+
+        // Fresh position: normal quoting continues
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_000, timestamp: now - 30 * 60 });
+        assert!(matches!(
+            maker.generate_signal(&tracker_at(100.0)).unwrap(),
+            TradeSignal::PlaceBid { .. }
+        ));
+
+        // Opened 2h ago: next tick sells the whole position
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 60);
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_500, timestamp: now - 120 * 60 });
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::Sell { amount, reason } => {
+                assert_eq!(amount, 1_500);
+                assert!(reason.contains("Max hold time"));
+            }
+            other => panic!("expected Sell, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_max_hold_exit_after_price_drop_sells_base_held() {
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 60);
+        let now = chrono::Utc::now().timestamp();
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_500, timestamp: now - 120 * 60 });
+
+        // Price fell 20%: the exit asks for what the position is worth now, which
+        // the executor turns back into exactly the base bought
+        match maker.generate_signal(&tracker_at(80.0)).unwrap() {
+            TradeSignal::Sell { amount, .. } => {
+                assert_eq!(amount, 1_200);
+                let ticks = PoolTicks::default();
+                assert_eq!(ticks.quote_to_base(amount, 80.0), ticks.quote_to_base(1_500, 100.0));
+            }
+            other => panic!("expected Sell, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_requote_needs_move_or_age() {
+        let maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 0).with_requote_threshold(20, 60);
+        assert!(matches!(maker.generate_signal(&tracker_at(100.0)).unwrap(), TradeSignal::PlaceBid { .. }));
+
+        // 5bps move on a fresh quote: keep the resting order
+        assert!(matches!(maker.generate_signal(&tracker_at(100.05)).unwrap(), TradeSignal::Hold { .. }));
+
+        // 50bps move: requote
+        assert!(matches!(maker.generate_signal(&tracker_at(100.5)).unwrap(), TradeSignal::PlaceBid { .. }));
+
+        // Same mid, but the last quote is older than the max age: refresh
+        maker.last_quote.lock().unwrap().as_mut().unwrap().timestamp -= 61;
+        assert!(matches!(maker.generate_signal(&tracker_at(100.5)).unwrap(), TradeSignal::PlaceBid { .. }));
+    }
+
+    #[test]
+    fn test_mid_move_past_threshold_cancels_then_replaces() {
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 0).with_requote_threshold(20, 60);
+        let bid = maker.generate_signal(&tracker_at(100.0)).unwrap();
+        assert!(matches!(bid, TradeSignal::PlaceBid { .. }));
+        maker.on_order_placed("order-1", &bid);
+
+        // 50bps move: the bid quoted around 100 is pulled first...
+        match maker.generate_signal(&tracker_at(100.5)).unwrap() {
+            TradeSignal::CancelOrder { order_id, is_bid, price } => {
+                assert_eq!(order_id, "order-1");
+                assert!(is_bid);
+                assert!((price - 99.95).abs() < 1e-9);
+            }
+            other => panic!("expected CancelOrder, got {:?}", other),
+        }
+
+        // ...and once it's gone, replaced around the new mid
+        maker.on_order_closed("order-1");
+        match maker.generate_signal(&tracker_at(100.5)).unwrap() {
+            TradeSignal::PlaceBid { price, .. } => assert!(price > 100.4),
+            other => panic!("expected PlaceBid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_small_mid_move_keeps_resting_order() {
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 0).with_requote_threshold(20, 60);
+        let bid = maker.generate_signal(&tracker_at(100.0)).unwrap();
+        maker.on_order_placed("order-1", &bid);
+
+        // 5bps: neither cancelled nor requoted
+        assert!(matches!(maker.generate_signal(&tracker_at(100.05)).unwrap(), TradeSignal::Hold { .. }));
+        assert_eq!(maker.resting_orders.len(), 1);
+
+        // Requoting every tick pulls the resting order before each new quote, so none stack up
+        let mut every_tick = MarketMakerStrategy::new(10, 1_000, 10_000, 0, 0, 0.0, 0);
+        let bid = every_tick.generate_signal(&tracker_at(100.0)).unwrap();
+        every_tick.on_order_placed("order-2", &bid);
+        match every_tick.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::CancelOrder { order_id, .. } => assert_eq!(order_id, "order-2"),
+            other => panic!("expected CancelOrder, got {:?}", other),
+        }
+        every_tick.on_order_closed("order-2");
+        let bid = every_tick.generate_signal(&tracker_at(105.0)).unwrap();
+        assert!(matches!(bid, TradeSignal::PlaceBid { .. }));
+        every_tick.on_order_placed("order-3", &bid);
+        assert_eq!(every_tick.resting_orders.len(), 1);
+    }
+
+    #[test]
+    fn test_bids_stop_at_max_position_size() {
+        // Cap of 2_500 with 1_000-unit orders
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 2_500, 50, 10, 0.0, 0);
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 2_000, timestamp: 0 });
+
+        // Below the cap: the bid is trimmed to the remaining room
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::PlaceBid { size, .. } => assert_eq!(size, 500),
+            other => panic!("expected PlaceBid, got {:?}", other),
+        }
+
+        // At the cap: no bid, and the ask is below the profit floor
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 500, timestamp: 0 });
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::Hold { reason } => {
+                assert!(reason.contains("bid suppressed: position 2500 at max_position_size 2500"));
+            }
+            other => panic!("expected Hold, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_asks_stop_when_flat() {
+        // At the cap only the ask side quotes, and never more than is held
+        let mut maker = MarketMakerStrategy::new(10, 1_000, 1_000, 0, 0, 0.0, 0);
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_000, timestamp: 0 });
+        assert!(matches!(
+            maker.generate_signal(&tracker_at(110.0)).unwrap(),
+            TradeSignal::PlaceAsk { size: 1_000, .. }
+        ));
+
+        // Sold out, or no room to buy at all: nothing to sell either
+        maker.on_fill(&Fill { side: FillSide::Sell, price: 110.0, size: 1_100, timestamp: 0 });
+        let zero_cap = MarketMakerStrategy::new(10, 1_000, 0, 0, 0, 0.0, 0);
+        for flat in [&maker, &zero_cap] {
+            if let Some(TradeSignal::PlaceAsk { .. }) = flat.generate_signal(&tracker_at(110.0)) {
+                panic!("flat maker placed an ask");
+            }
+        }
+        match zero_cap.generate_signal(&tracker_at(110.0)).unwrap() {
+            TradeSignal::Hold { reason } => assert!(reason.contains("ask suppressed: position is flat")),
+            other => panic!("expected Hold, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quotes_lean_against_inventory() {
+        // 20bps spread: half-spread of 0.1 at a mid of 100
+        let mut maker = MarketMakerStrategy::new(20, 1_000, 2_000, 0, 0, 0.0, 0).with_skew_factor(1.0);
+
+        // Flat (the furthest below target spot inventory gets): the bid sits on the mid
+        let (bid, ask) = maker.calculate_bid_ask_prices(100.0);
+        assert!((bid - 100.0).abs() < 1e-9);
+        assert!((ask - 100.2).abs() < 1e-9);
+
+        // At the half-cap target: symmetric around the mid
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_000, timestamp: 0 });
+        let (bid, ask) = maker.calculate_bid_ask_prices(100.0);
+        assert!((bid - 99.9).abs() < 1e-9);
+        assert!((ask - 100.1).abs() < 1e-9);
+
+        // At the cap: the ask sits on the mid, the bid a full spread below it
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 1_000, timestamp: 0 });
+        let (bid, ask) = maker.calculate_bid_ask_prices(100.0);
+        assert!((bid - 99.8).abs() < 1e-9);
+        assert!((ask - 100.0).abs() < 1e-9);
+
+        // Selling back down to the target undoes the skew; no skew factor means no skew at all
+        maker.on_fill(&Fill { side: FillSide::Sell, price: 100.0, size: 1_000, timestamp: 0 });
+        assert_eq!(maker.calculate_bid_ask_prices(100.0), (99.9, 100.1));
+        let mut neutral = MarketMakerStrategy::new(20, 1_000, 2_000, 0, 0, 0.0, 0);
+        neutral.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 2_000, timestamp: 0 });
+        assert_eq!(neutral.calculate_bid_ask_prices(100.0), (99.9, 100.1));
+    }
+
+    #[test]
+    fn test_below_target_inventory_skews_up() {
+        let mut maker = MarketMakerStrategy::new(20, 1_000, 2_000, 0, 0, 0.0, 0).with_skew_factor(2.0);
+
+        // A quarter of the cap, half way from target to flat: both sides up one half-spread
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 500, timestamp: 0 });
+        let (bid, ask) = maker.calculate_bid_ask_prices(100.0);
+        assert!((bid - 100.0).abs() < 1e-9);
+        assert!((ask - 100.2).abs() < 1e-9);
+
+        // Selling more than is held leaves the book flat, the full skew up
+        maker.on_fill(&Fill { side: FillSide::Sell, price: 100.0, size: 3_000, timestamp: 0 });
+        assert_eq!(maker.inventory.position(), 0);
+        assert!((maker.skew_bps() + 20.0).abs() < 1e-9);
+        let (bid, ask) = maker.calculate_bid_ask_prices(100.0);
+        assert!((bid - 100.1).abs() < 1e-9);
+        assert!((ask - 100.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skew_change_requotes_resting_orders() {
+        // 100bps spread, two half-spreads of skew at the cap: a quarter-cap fill moves the quotes 50bps
+        let mut maker = MarketMakerStrategy::new(100, 1_000, 2_000, 0, 0, 0.0, 0)
+            .with_requote_threshold(20, 60)
+            .with_skew_factor(2.0);
+        let bid = maker.generate_signal(&tracker_at(100.0)).unwrap();
+        maker.on_order_placed("order-1", &bid);
+        assert!(matches!(maker.generate_signal(&tracker_at(100.0)).unwrap(), TradeSignal::Hold { .. }));
+
+        // Same mid, but the fill shifted the skew: the resting bid is pulled for a requote
+        maker.on_fill(&Fill { side: FillSide::Buy, price: 100.0, size: 500, timestamp: 0 });
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::CancelOrder { order_id, .. } => assert_eq!(order_id, "order-1"),
+            other => panic!("expected CancelOrder, got {:?}", other),
+        }
+        maker.on_order_closed("order-1");
+        match maker.generate_signal(&tracker_at(100.0)).unwrap() {
+            TradeSignal::PlaceBid { price, .. } => assert!((price - 100.0).abs() < 1e-9),
+            other => panic!("expected PlaceBid, got {:?}", other),
+        }
+    }
 }
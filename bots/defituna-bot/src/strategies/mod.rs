@@ -1,34 +1,219 @@
 use crate::config::BotConfig;
+use crate::defituna_client::PoolTicks;
+use crate::price_source::ArbitragePrices;
 use crate::price_tracker::PriceTracker;
+use crate::solana_rpc_client::LimitOrderUpdate;
 
+pub mod arbitrage;
 pub mod market_maker;
 
+use arbitrage::{ArbitrageCosts, ArbitrageStrategy};
 use market_maker::MarketMakerStrategy;
 
 #[derive(Debug, Clone)]
 pub enum TradeSignal {
+    /// Market orders. `amount` is in quote units on both sides, like limit
+    /// order sizes; the executor converts a sell to base at the market price.
     Buy { amount: u64, reason: String },
     Sell { amount: u64, reason: String },
     PlaceBid { price: f64, size: u64 },
     PlaceAsk { price: f64, size: u64 },
-    Hold,
+    /// Pull a resting limit order (by account address) quoted at `price`
+    CancelOrder { order_id: String, is_bid: bool, price: f64 },
+    Hold { reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+/// An executed order reported back to the strategy
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub side: FillSide,
+    pub price: f64,
+    pub size: u64,
+    pub timestamp: i64,
+}
+
+impl Fill {
+    /// Fill implied by a successfully executed signal (None for Hold and CancelOrder).
+    /// Limit orders are priced on the pool's `ticks`.
+    pub fn from_signal(signal: &TradeSignal, price: f64, timestamp: i64, ticks: &PoolTicks) -> Option<Self> {
+        let (side, price, size) = match signal {
+            TradeSignal::Buy { amount, .. } => (FillSide::Buy, price, *amount),
+            TradeSignal::Sell { amount, .. } => (FillSide::Sell, price, *amount),
+            // Limit orders rest at the tick-rounded price, not the requested one
+            TradeSignal::PlaceBid { price, size } => (FillSide::Buy, ticks.round_price_to_tick(*price), *size),
+            TradeSignal::PlaceAsk { price, size } => (FillSide::Sell, ticks.round_price_to_tick(*price), *size),
+            TradeSignal::CancelOrder { .. } | TradeSignal::Hold { .. } => return None,
+        };
+        Some(Self { side, price, size, timestamp })
+    }
+
+    /// Fill of `filled` of a resting limit order's input, as observed on chain.
+    /// A bid's input is already quote; an ask's base is valued at its price.
+    pub fn from_limit_order(order: &LimitOrderUpdate, filled: u64, timestamp: i64, ticks: &PoolTicks) -> Self {
+        let (side, size) = if order.is_bid {
+            (FillSide::Buy, filled)
+        } else {
+            (FillSide::Sell, ticks.base_to_quote(filled, order.price))
+        };
+        Self { side, price: order.price, size, timestamp }
+    }
+
+    /// Price net of a `fee_bps` maker fee: a buy costs more, a sell realizes less
+    pub fn net_price(&self, fee_bps: f64) -> f64 {
+        let fee = fee_bps / 10000.0;
+        match self.side {
+            FillSide::Buy => self.price * (1.0 + fee),
+            FillSide::Sell => self.price * (1.0 - fee),
+        }
+    }
+}
+
+/// Same-side liquidity available to the maker, in quote base units (same as order sizes)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookDepth {
+    pub bid_depth: u64,
+    pub ask_depth: u64,
+}
+
+impl BookDepth {
+    /// Depth from the pool's vault balances: the quote vault backs the bid side,
+    /// the base vault (valued at `price` with the pool's decimals) the ask side
+    pub fn from_reserves(base_reserve: u64, quote_reserve: u64, price: f64, ticks: &PoolTicks) -> Self {
+        Self {
+            bid_depth: quote_reserve,
+            ask_depth: ticks.base_to_quote(base_reserve, price),
+        }
+    }
 }
 
 pub trait Strategy: Send + Sync {
     fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal>;
     fn name(&self) -> &str;
+
+    /// Called after an order executes so the strategy can update inventory
+    fn on_fill(&mut self, _fill: &Fill) {}
+
+    /// Called with the latest book depth when it is available
+    fn on_book_depth(&mut self, _depth: BookDepth) {}
+
+    /// Called with the pool's maker fee tier, in bps, when it can be read
+    fn on_pool_fee(&mut self, _fee_bps: f64) {}
+
+    /// Called at startup with the tick math of the pool orders rest on
+    fn on_pool_ticks(&mut self, _ticks: PoolTicks) {}
+
+    /// Called when `signal` left a limit order resting on chain at `order_id`
+    fn on_order_placed(&mut self, _order_id: &str, _signal: &TradeSignal) {}
+
+    /// Called once a resting order is gone: cancelled, or filled in full
+    fn on_order_closed(&mut self, _order_id: &str) {}
 }
 
-pub fn create_strategy(config: &BotConfig) -> anyhow::Result<Box<dyn Strategy>> {
+/// `prices` is required for the arbitrage strategy and ignored otherwise
+pub fn create_strategy(
+    config: &BotConfig,
+    prices: Option<&ArbitragePrices>,
+) -> anyhow::Result<Box<dyn Strategy>> {
     match config.strategy_type.to_lowercase().as_str() {
         "market_maker" => Ok(Box::new(MarketMakerStrategy::new(
             config.spread_bps,
             config.order_size,
             config.max_position_size,
-        ))),
+            config.min_ask_profit_bps,
+            config.maker_fee_bps,
+            config.max_order_depth_ratio,
+            config.max_hold_minutes,
+        )
+        .with_requote_threshold(config.requote_min_move_bps, config.requote_max_age_secs)
+        .with_skew_factor(config.skew_factor))),
+        "arbitrage" => {
+            let prices = prices.ok_or_else(|| anyhow::anyhow!("Arbitrage strategy needs price sources"))?;
+            Ok(Box::new(ArbitrageStrategy::new(
+                prices.jupiter.clone(),
+                prices.defituna.clone(),
+                config.min_profit_bps,
+                config.trade_amount,
+            )
+            .with_costs(ArbitrageCosts {
+                jupiter_fee_bps: config.jupiter_swap_fee_bps,
+                defituna_fee_bps: config.defituna_swap_fee_bps,
+                network_fee_lamports: config.network_fee_lamports,
+            })))
+        }
         _ => Err(anyhow::anyhow!(
-            "Unknown strategy: {}. Only 'market_maker' is supported for limit orders",
+            "Unknown strategy: {}. Options: market_maker, arbitrage",
             config.strategy_type
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_fill_price_is_tick_derived() {
+        let requested = 152.37;
+        let ticks = PoolTicks::default();
+        let tick_price = ticks.tick_index_to_price(ticks.initializable_tick(ticks.price_to_tick_index(requested)));
+
+        let bid = Fill::from_signal(&TradeSignal::PlaceBid { price: requested, size: 1_000_000 }, 0.0, 0, &ticks).unwrap();
+        let ask = Fill::from_signal(&TradeSignal::PlaceAsk { price: requested, size: 1_000_000 }, 0.0, 0, &ticks).unwrap();
+
+        assert_eq!(bid.price, tick_price);
+        assert_eq!(ask.price, tick_price);
+        assert_ne!(bid.price, requested);
+    }
+
+    #[test]
+    fn test_limit_order_fill_is_sized_in_quote() {
+        let ticks = PoolTicks::default();
+        let order = LimitOrderUpdate {
+            pubkey: "order".to_string(),
+            mint: "mint".to_string(),
+            pool: "pool".to_string(),
+            tick_index: 5056,
+            is_bid: false,
+            price: 150.0,
+            size: 2_000_000_000,
+            filled: 0,
+            is_active: true,
+        };
+
+        // Half of a 2 SOL ask is 150 USDC sold
+        let ask = Fill::from_limit_order(&order, 1_000_000_000, 7, &ticks);
+        assert_eq!((ask.side, ask.size, ask.timestamp), (FillSide::Sell, 150_000_000, 7));
+        let bid = Fill::from_limit_order(&LimitOrderUpdate { is_bid: true, ..order }, 150_000_000, 7, &ticks);
+        assert_eq!((bid.side, bid.size), (FillSide::Buy, 150_000_000));
+    }
+
+    #[test]
+    fn test_book_depth_uses_pool_decimals() {
+        // 10 SOL and 2000 USDC at $150
+        let depth = BookDepth::from_reserves(10_000_000_000, 2_000_000_000, 150.0, &PoolTicks::default());
+        assert_eq!(depth, BookDepth { bid_depth: 2_000_000_000, ask_depth: 1_500_000_000 });
+
+        // 10 of a 6-decimal base token at $2
+        let ticks = PoolTicks::new(64, 6, 6);
+        let depth = BookDepth::from_reserves(10_000_000, 0, 2.0, &ticks);
+        assert_eq!(depth.ask_depth, 20_000_000);
+    }
+
+    #[test]
+    fn test_net_fill_price_includes_fee_tier() {
+        // 30bps tier (fee rate 3000)
+        let fee_bps = 3000.0 / 100.0;
+        let buy = Fill { side: FillSide::Buy, price: 150.0, size: 1_000_000, timestamp: 0 };
+        let sell = Fill { side: FillSide::Sell, ..buy.clone() };
+
+        assert!((buy.net_price(fee_bps) - 150.45).abs() < 1e-9);
+        assert!((sell.net_price(fee_bps) - 149.55).abs() < 1e-9);
+        assert!((buy.net_price(0.0) - buy.price).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::pnl::{Fill, FillSide, PnlTracker};
+use crate::price_tracker::PriceTracker;
+use crate::strategies::{Strategy, TradeSignal};
+
+/// One recorded observation: unix seconds, quote per base, traded volume
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DataPoint {
+    pub timestamp: i64,
+    pub price: f64,
+    #[serde(default)]
+    pub volume: f64,
+}
+
+/// Read `timestamp,price,volume` rows from a CSV file (header optional, volume
+/// optional), or a JSON array of `{timestamp, price, volume}` from a `.json` file
+pub fn load_data(path: &Path) -> Result<Vec<DataPoint>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        return serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid price data in {}", path.display()));
+    }
+
+    let mut points = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.starts_with(|c: char| c.is_alphabetic())) {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let row = || format!("{} line {}", path.display(), i + 1);
+        let (timestamp, price) = match fields.as_slice() {
+            [timestamp, price, ..] => (timestamp, price),
+            _ => anyhow::bail!("Expected timestamp,price[,volume] at {}", row()),
+        };
+        points.push(DataPoint {
+            timestamp: timestamp.parse().with_context(|| format!("Invalid timestamp at {}", row()))?,
+            price: price.parse().with_context(|| format!("Invalid price at {}", row()))?,
+            volume: match fields.get(2) {
+                Some(volume) => volume.parse().with_context(|| format!("Invalid volume at {}", row()))?,
+                None => 0.0,
+            },
+        });
+    }
+    Ok(points)
+}
+
+/// How signals are turned into simulated fills
+#[derive(Debug, Clone)]
+pub struct BacktestSettings {
+    pub lookback_minutes: usize,
+    /// Buy amounts are raw quote units, sell amounts raw base units, as in the live bot
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Quote tokens the simulated wallet starts with
+    pub starting_quote: f64,
+}
+
+/// A simulated fill, in token units
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestTrade {
+    pub timestamp: i64,
+    pub side: FillSide,
+    pub price: f64,
+    pub base_amount: f64,
+    pub quote_amount: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub trades: Vec<BacktestTrade>,
+    /// Sells that realized a profit against the average entry
+    pub winning_sells: usize,
+    pub sells: usize,
+    pub starting_value: f64,
+    /// Quote balance plus the base held, valued at the last price
+    pub final_value: f64,
+}
+
+impl BacktestReport {
+    /// Share of sells that closed at a profit, 0..=1
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.sells > 0).then(|| self.winning_sells as f64 / self.sells as f64)
+    }
+
+    pub fn total_return_pct(&self) -> f64 {
+        (self.final_value / self.starting_value - 1.0) * 100.0
+    }
+}
+
+/// Replay `data` through `strategy`: each point is added to a PriceTracker,
+/// and any buy or sell it signals fills at the next point's price. Buys are
+/// capped at the quote balance and sells at the base held.
+pub fn run(strategy: &dyn Strategy, data: &[DataPoint], settings: &BacktestSettings) -> BacktestReport {
+    let base_unit = 10f64.powi(settings.base_decimals as i32);
+    let quote_unit = 10f64.powi(settings.quote_decimals as i32);
+
+    let mut tracker = PriceTracker::new(settings.lookback_minutes);
+    let mut pnl = PnlTracker::new();
+    let mut quote = settings.starting_quote;
+    let mut base = 0.0;
+    let mut report = BacktestReport {
+        trades: Vec::new(),
+        winning_sells: 0,
+        sells: 0,
+        starting_value: settings.starting_quote,
+        final_value: settings.starting_quote,
+    };
+
+    for (point, next) in data.iter().zip(data.iter().skip(1)) {
+        tracker.add_price(point.price, point.volume, point.timestamp);
+
+        let Some(signal) = strategy.generate_signal(&tracker) else {
+            continue;
+        };
+        let (side, base_amount) = match &signal {
+            TradeSignal::Buy { amount, .. } => {
+                let spend = (*amount as f64 / quote_unit).min(quote);
+                (FillSide::Buy, spend / next.price)
+            }
+            TradeSignal::Sell { amount, .. } => (FillSide::Sell, (*amount as f64 / base_unit).min(base)),
+            TradeSignal::Hold => continue,
+        };
+        strategy.on_trade_result("", &signal, base_amount > 0.0);
+        if base_amount <= 0.0 {
+            continue;
+        }
+
+        let quote_amount = base_amount * next.price;
+        match side {
+            FillSide::Buy => {
+                quote -= quote_amount;
+                base += base_amount;
+            }
+            FillSide::Sell => {
+                quote += quote_amount;
+                base -= base_amount;
+            }
+        }
+
+        let realized_before = pnl.realized_pnl();
+        pnl.record_fill(Fill {
+            pair: "backtest".to_string(),
+            side,
+            price: next.price,
+            base_amount,
+            fee: 0.0,
+            timestamp: next.timestamp,
+            signature: format!("backtest-{}", report.trades.len() + 1),
+        });
+        if side == FillSide::Sell {
+            report.sells += 1;
+            if pnl.realized_pnl() > realized_before {
+                report.winning_sells += 1;
+            }
+        }
+
+        report.trades.push(BacktestTrade {
+            timestamp: next.timestamp,
+            side,
+            price: next.price,
+            base_amount,
+            quote_amount,
+        });
+    }
+
+    let last_price = data.last().map_or(0.0, |p| p.price);
+    report.final_value = quote + base * last_price;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::momentum::MomentumStrategy;
+
+    #[test]
+    fn test_momentum_backtest_over_tiny_dataset() {
+        // A flat minute at 100, a rally to 110, then a drop to 90
+        let mut csv = String::from("timestamp,price,volume\n");
+        for t in 0..30 {
+            csv.push_str(&format!("{},100,1\n", t));
+        }
+        csv.push_str("30,110,1\n31,110,1\n32,90,1\n33,90,1\n");
+        let path = std::env::temp_dir().join(format!("backtest-{}.csv", std::process::id()));
+        std::fs::write(&path, csv).unwrap();
+        let data = load_data(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(data.len(), 34);
+        assert_eq!(data[30], DataPoint { timestamp: 30, price: 110.0, volume: 1.0 });
+
+        // $100 buys, 0.1 SOL sells; signals need 18 points in the 1-minute lookback
+        let strategy = MomentumStrategy::new(100_000_000, 0.01, 1);
+        let settings = BacktestSettings {
+            lookback_minutes: 1,
+            base_decimals: 9,
+            quote_decimals: 6,
+            starting_quote: 1_000.0,
+        };
+        let report = run(&strategy, &data, &settings);
+
+        // The rally signals at t=30 and t=31, filling at the next prices (110, then 90);
+        // the crash at t=32 sells at t=33's 90, below the average entry
+        let sides: Vec<(i64, FillSide, f64)> =
+            report.trades.iter().map(|t| (t.timestamp, t.side, t.price)).collect();
+        assert_eq!(
+            sides,
+            vec![(31, FillSide::Buy, 110.0), (32, FillSide::Buy, 90.0), (33, FillSide::Sell, 90.0)]
+        );
+        assert_eq!(report.sells, 1);
+        assert_eq!(report.win_rate(), Some(0.0));
+        assert!(report.total_return_pct() < 0.0);
+    }
+
+    #[test]
+    fn test_load_json_data() {
+        let path = std::env::temp_dir().join(format!("backtest-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{"timestamp": 1, "price": 2.5}, {"timestamp": 2, "price": 3.0, "volume": 4.0}]"#)
+            .unwrap();
+        let data = load_data(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(data[0], DataPoint { timestamp: 1, price: 2.5, volume: 0.0 });
+        assert_eq!(data[1].volume, 4.0);
+    }
+}
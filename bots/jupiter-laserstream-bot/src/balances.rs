@@ -0,0 +1,81 @@
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// SOL held by the executor across the native account and the wSOL ATA.
+/// Jupiter swaps run with `wrapAndUnwrapSol=true`, so SOL moves between the two
+/// constantly; accounting treats them as one asset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolBalance {
+    pub native_lamports: u64,
+    pub wrapped_lamports: u64,
+}
+
+impl SolBalance {
+    pub fn new(native_lamports: u64, wrapped_lamports: u64) -> Self {
+        Self {
+            native_lamports,
+            wrapped_lamports,
+        }
+    }
+
+    /// Tradable SOL position in lamports: native SOL minus the reserve kept for
+    /// transaction fees and rent, plus wrapped SOL
+    pub fn position_lamports(&self, fee_reserve_lamports: u64) -> u64 {
+        self.native_lamports.saturating_sub(fee_reserve_lamports) + self.wrapped_lamports
+    }
+
+    pub fn position_sol(&self, fee_reserve_lamports: u64) -> f64 {
+        self.position_lamports(fee_reserve_lamports) as f64 / LAMPORTS_PER_SOL as f64
+    }
+}
+
+/// Lamports to request from the faucet at startup, if any. Only devnet has a
+/// faucet, so every other cluster (and a disabled `amount_lamports` of 0) gets `None`.
+pub fn airdrop_request(
+    network: &str,
+    native_lamports: u64,
+    below_lamports: u64,
+    amount_lamports: u64,
+) -> Option<u64> {
+    if amount_lamports == 0 || !network.eq_ignore_ascii_case("devnet") {
+        return None;
+    }
+    (native_lamports < below_lamports).then_some(amount_lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_and_wrapped_combine_into_one_position() {
+        // 1.5 SOL native, 0.25 SOL wrapped, 0.01 SOL kept for fees
+        let balance = SolBalance::new(1_500_000_000, 250_000_000);
+        assert_eq!(balance.position_lamports(10_000_000), 1_740_000_000);
+        assert!((balance.position_sol(10_000_000) - 1.74).abs() < 1e-9);
+
+        // Same total SOL in a different wrap state gives the same position
+        let unwrapped = SolBalance::new(1_750_000_000, 0);
+        assert_eq!(
+            unwrapped.position_lamports(10_000_000),
+            balance.position_lamports(10_000_000)
+        );
+    }
+
+    #[test]
+    fn test_reserve_larger_than_native_balance() {
+        // Reserve only applies to native SOL; wrapped SOL is still counted
+        let balance = SolBalance::new(5_000_000, 100_000_000);
+        assert_eq!(balance.position_lamports(10_000_000), 100_000_000);
+    }
+
+    #[test]
+    fn test_airdrop_only_on_devnet() {
+        let sol = LAMPORTS_PER_SOL;
+        assert_eq!(airdrop_request("devnet", sol / 100, sol / 2, 2 * sol), Some(2 * sol));
+        // Enough balance, disabled, or any other cluster: no airdrop
+        assert_eq!(airdrop_request("devnet", sol, sol / 2, 2 * sol), None);
+        assert_eq!(airdrop_request("devnet", 0, sol / 2, 0), None);
+        assert_eq!(airdrop_request("mainnet", 0, sol / 2, 2 * sol), None);
+        assert_eq!(airdrop_request("mainnet-beta", 0, sol / 2, 2 * sol), None);
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use clap::Parser;
+use dotenv::dotenv;
+use std::path::PathBuf;
+use tracing::info;
+
+use jupiter_laserstream_bot::backtest::{self, BacktestSettings};
+use jupiter_laserstream_bot::config::StrategyConfig;
+use jupiter_laserstream_bot::strategies::create_strategy_by_name;
+
+/// Replay recorded prices through a strategy and report how it would have traded
+#[derive(Parser)]
+struct Args {
+    /// CSV (timestamp,price,volume) or JSON file of recorded prices
+    #[arg(long)]
+    data: PathBuf,
+    /// Strategy to evaluate: dca, momentum, mean_reversion or twap
+    #[arg(long)]
+    strategy: String,
+    /// Quote tokens the simulated wallet starts with
+    #[arg(long, default_value_t = 1_000.0)]
+    starting_quote: f64,
+    #[arg(long, default_value_t = 9)]
+    base_decimals: u8,
+    #[arg(long, default_value_t = 6)]
+    quote_decimals: u8,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenv().ok();
+    let args = Args::parse();
+
+    // Only the strategy parameters are needed; nothing is sent
+    let config = StrategyConfig::from_env()?;
+    let strategy = create_strategy_by_name(&args.strategy, &config, args.base_decimals, args.quote_decimals)?;
+
+    let data = backtest::load_data(&args.data)?;
+    info!("📼 Backtesting {} over {} price points from {}", strategy.name(), data.len(), args.data.display());
+
+    let report = backtest::run(
+        strategy.as_ref(),
+        &data,
+        &BacktestSettings {
+            lookback_minutes: config.lookback_minutes,
+            base_decimals: args.base_decimals,
+            quote_decimals: args.quote_decimals,
+            starting_quote: args.starting_quote,
+        },
+    );
+
+    for trade in &report.trades {
+        info!(
+            "   {} {} {:.6} @ {:.4} ({:.2} quote)",
+            trade.timestamp, trade.side, trade.base_amount, trade.price, trade.quote_amount
+        );
+    }
+    info!("📊 Trades: {} ({} sells)", report.trades.len(), report.sells);
+    match report.win_rate() {
+        Some(rate) => info!("📊 Win rate: {:.1}%", rate * 100.0),
+        None => info!("📊 Win rate: n/a (no sells)"),
+    }
+    info!(
+        "📊 Total return: {:+.2}% ({:.2} → {:.2})",
+        report.total_return_pct(),
+        report.starting_value,
+        report.final_value
+    );
+    Ok(())
+}
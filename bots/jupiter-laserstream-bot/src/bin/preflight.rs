@@ -4,7 +4,8 @@ use solana_client::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, signature::{Keypair, Signer}};
 use tracing::info;
 
-use jupiter_laserstream_bot::{config::BotConfig, jupiter_client::JupiterClient};
+use jupiter_client::JupiterClient;
+use jupiter_laserstream_bot::config::BotConfig;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -80,7 +81,7 @@ async fn main() -> Result<()> {
             info!("   ✅ Quote successful");
             info!("   0.01 SOL → {} USDC", 
                 quote.out_amount.parse::<u64>().unwrap_or(0) as f64 / 1_000_000.0);
-            info!("   Price Impact: {}%", quote.price_impact_pct);
+            info!("   Price Impact: {:.4}%", quote.price_impact_percent().unwrap_or(f64::NAN));
         }
         Err(e) => {
             info!("   ❌ Quote failed: {}", e);
@@ -90,8 +91,8 @@ async fn main() -> Result<()> {
 
     info!("\n5️⃣  Configuration Summary:");
     info!("   Strategy: {}", config.strategy_type);
-    info!("   Trade Amount: {} USDC", config.trade_amount / 1_000_000);
-    info!("   Min Movement: {}%", config.min_price_movement * 100.0);
+    info!("   Trade Amount: {} USDC", config.strategy.trade_amount / 1_000_000);
+    info!("   Min Movement: {}%", config.strategy.min_price_movement * 100.0);
     info!("   Slippage: {}%", config.max_slippage_bps as f64 / 100.0);
     
     info!("\n✅ Pre-flight check complete!");
@@ -45,7 +45,8 @@ async fn main() -> Result<()> {
             
             if sol_balance < 0.1 {
                 println!("⚠️  Low balance! Request airdrop:");
-                println!("   solana airdrop 2 {} --url devnet\n", keypair.pubkey());
+                println!("   solana airdrop 2 {} --url devnet", keypair.pubkey());
+                println!("   (or set AUTO_AIRDROP_SOL=2 and the bot requests it at startup)\n");
             }
         }
         Err(e) => {
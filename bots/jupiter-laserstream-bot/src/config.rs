@@ -1,32 +1,307 @@
 use anyhow::{Context, Result};
+use bot_common::parse_commitment;
 use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
 use std::env;
+use std::time::Duration;
+
+use crate::health::HealthLimits;
+use crate::strategies::triangular::parse_cycle_mints;
+use crate::pairs::{parse_pairs, validate_blocked_mints, validate_pair_count, OverflowPolicy, TradingPair};
+use crate::price_source::{parse_feed_ids, SecondarySourceKind, PYTH_SOL_USD_FEED_ID};
+use jupiter_client::{JupiterClient, JupiterClientBuilder, PriceApiVersion, RouteOptions};
+use crate::resubmit::SendRetryMode;
+use crate::slippage::SlippageMode;
+use crate::strategies::twap::TwapDirection;
+use crate::startup_trade::validate_test_trade;
+
+/// What the strategies are built from: everything `create_strategy_by_name`
+/// reads, and nothing about the wallet, RPC or container, so a backtest can
+/// load it on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    /// Seconds between ticks; TWAP spaces its slices in ticks
+    pub poll_interval_seconds: u64,
+    pub trade_amount: u64,
+    /// Split each buy into this many entries; 1 buys all at once
+    pub scale_in_levels: usize,
+    /// Percent below the first entry at which each further level buys
+    pub scale_in_step_pct: f64,
+    /// Sell the position once price falls this far below entry (unset = no stop)
+    pub stop_loss_bps: Option<u16>,
+    /// Sell the position once price rises this far above entry (unset = no target)
+    pub take_profit_bps: Option<u16>,
+    /// TWAP strategy: raw input-token amount split into `twap_slices` trades
+    /// spread over `twap_window_minutes`
+    pub twap_total_amount: u64,
+    pub twap_slices: usize,
+    pub twap_direction: TwapDirection,
+    pub twap_window_minutes: u64,
+    pub min_price_movement: f64,
+    /// Momentum compares price to the lookback VWAP instead of the SMA, weighted
+    /// by the swaps the LaserStream container parses
+    pub use_vwap: bool,
+    pub lookback_minutes: usize,
+    /// Momentum holds instead of buying into an overbought RSI over this many
+    /// ticks (or selling into an oversold one); 0 turns the filter off
+    pub momentum_rsi_periods: usize,
+}
+
+impl StrategyConfig {
+    pub fn from_env() -> Result<Self> {
+        let poll_interval_seconds = env::var("POLL_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "2".to_string())
+            .parse()?;
+
+        let trade_amount = env::var("TRADE_AMOUNT_USDC")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse::<u64>()?
+            * 1_000_000; // Convert to lamports (6 decimals for USDC)
+
+        let scale_in_levels = env::var("SCALE_IN_LEVELS")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .context("Invalid SCALE_IN_LEVELS")?;
+
+        let scale_in_step_pct = env::var("SCALE_IN_STEP_PCT")
+            .unwrap_or_else(|_| "1.0".to_string())
+            .parse()
+            .context("Invalid SCALE_IN_STEP_PCT")?;
+
+        let stop_loss_bps = env::var("STOP_LOSS_BPS")
+            .ok()
+            .map(|v| v.parse())
+            .transpose()
+            .context("Invalid STOP_LOSS_BPS")?;
+
+        let take_profit_bps = env::var("TAKE_PROFIT_BPS")
+            .ok()
+            .map(|v| v.parse())
+            .transpose()
+            .context("Invalid TAKE_PROFIT_BPS")?;
+
+        let twap_total_amount = env::var("TWAP_TOTAL_AMOUNT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid TWAP_TOTAL_AMOUNT")?;
+
+        let twap_slices = env::var("TWAP_SLICES")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .context("Invalid TWAP_SLICES")?;
+
+        let twap_direction = env::var("TWAP_DIRECTION")
+            .unwrap_or_else(|_| "buy".to_string())
+            .parse()?;
+
+        let twap_window_minutes = env::var("TWAP_WINDOW_MINUTES")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .context("Invalid TWAP_WINDOW_MINUTES")?;
+
+        let min_price_movement = env::var("MIN_PRICE_MOVEMENT")
+            .unwrap_or_else(|_| "0.02".to_string())
+            .parse()?;
+
+        let use_vwap = env::var("USE_VWAP")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("Invalid USE_VWAP")?;
+
+        let lookback_minutes = env::var("LOOKBACK_MINUTES")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()?;
+
+        let momentum_rsi_periods = env::var("MOMENTUM_RSI_PERIODS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid MOMENTUM_RSI_PERIODS")?;
+
+        Ok(Self {
+            poll_interval_seconds,
+            trade_amount,
+            scale_in_levels,
+            scale_in_step_pct,
+            stop_loss_bps,
+            take_profit_bps,
+            twap_total_amount,
+            twap_slices,
+            twap_direction,
+            twap_window_minutes,
+            min_price_movement,
+            use_vwap,
+            lookback_minutes,
+            momentum_rsi_periods,
+        })
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BotConfig {
     // LaserStream container
     pub laserstream_url: String,
-    pub poll_interval_seconds: u64,
+    /// Ceiling the poll interval backs off to while the container errors or has no new data
+    pub poll_interval_max_seconds: u64,
+    /// How long `/latest?since=` may block for a newer slot (0 = plain polling)
+    pub long_poll_wait_ms: u64,
+    pub readiness_timeout_seconds: u64,
+    /// Max wait after `/start` for the container's first slot
+    pub stream_warmup_timeout_seconds: u64,
 
     // Trading pair
     pub base_token: String,
     pub quote_token: String,
     pub base_mint: String,
     pub quote_mint: String,
+    /// All pairs monitored each tick; the first is the primary pair above
+    pub pairs: Vec<TradingPair>,
+    /// Mints never traded; pairs using them are rejected
+    pub blocked_mints: Vec<String>,
+    pub concurrent_pair_fetch: bool,
+    /// Most pairs fetched and evaluated per tick; 0 = unlimited
+    pub max_concurrent_pairs: usize,
+    /// Reject extra pairs or rotate through them in batches
+    pub pair_overflow_policy: OverflowPolicy,
+    pub jupiter_max_requests_per_second: u32,
+    pub jupiter_price_api: PriceApiVersion,
+    /// Self-hosted or paid Jupiter endpoints; public API when unset. The
+    /// quote URL falls back to `swap_api_url`.
+    pub jupiter_quote_url: Option<String>,
+    pub jupiter_price_url: Option<String>,
+    /// Tries per Jupiter request; 429/5xx/timeouts are retried with exponential backoff
+    pub jupiter_max_attempts: u32,
+    pub jupiter_retry_base_delay_ms: u64,
+    /// Quote single-hop routes only
+    pub jupiter_only_direct_routes: bool,
+    /// Most accounts a quoted route may touch; Jupiter's default when unset
+    pub jupiter_max_accounts: Option<u32>,
+    /// AMM labels quotes must not route through
+    pub jupiter_excluded_dexes: Vec<String>,
+    /// Raw base-token amount for the per-tick impact probe; overrides the notional
+    pub price_probe_amount: Option<u64>,
+    /// Quote-token value of the impact probe when no raw amount is set
+    pub price_probe_notional: f64,
 
     // Strategy
     pub strategy_type: String,
-    pub trade_amount: u64,
-    pub min_price_movement: f64,
-    pub lookback_minutes: usize,
+    /// Strategies run in paper mode alongside the live one on every pair, for comparison
+    pub shadow_strategies: Vec<String>,
+    /// Run the live strategy on paper too and send no real trades
+    pub shadow_only: bool,
+    pub shadow_report_minutes: u64,
+    #[serde(flatten)]
+    pub strategy: StrategyConfig,
+    /// Triangular arbitrage cycle (start/end mint first); unset disables it
+    pub triangular_mints: Option<[String; 3]>,
+    /// Raw amount of the first cycle mint each cycle starts with
+    pub triangular_amount: u64,
+    /// Return a cycle must beat, after route and network fees, to be traded
+    pub min_net_profit_bps: u16,
+    /// Network fee (signature plus priority) budgeted for each cycle leg's transaction
+    pub triangular_leg_fee_lamports: u64,
+    /// Volatility-based features stay off until the window holds this many points
+    pub min_points_for_volatility: usize,
+    /// Price used to prime the primary pair's tracker at startup
+    pub seed_price: Option<f64>,
+    /// Fetch a seed price from Jupiter at startup for each pair `seed_price` doesn't cover
+    pub seed_price_from_jupiter: bool,
+    /// Price history is dropped (and warmup restarts) after a feed gap longer than this; 0 disables
+    pub max_gap_before_reset_secs: u64,
+    /// Skip trading on a slot whose stream timestamp is older than this; 0 disables
+    pub max_slot_age_secs: u64,
 
     // Risk management
     pub max_position_size: u64,
+    /// Native lamports kept aside for fees/rent and excluded from the SOL position
+    pub sol_fee_reserve_lamports: u64,
     pub max_slippage_bps: u16,
+    pub slippage_mode: SlippageMode,
+    pub slippage_margin_bps: u16,
+    pub slippage_ewma_alpha: f64,
+    /// Shrink trades to the largest size whose quoted price impact (percent) stays under
+    /// this, and refuse any swap whose quote exceeds it
+    pub max_price_impact_pct: Option<f64>,
+    /// Second price source cross-checked before each trade (disabled when unset)
+    pub secondary_price_source: Option<SecondarySourceKind>,
+    /// Pyth feed id per pair label (`SOL/USDC`)
+    pub pyth_feed_ids: HashMap<String, String>,
+    pub max_price_divergence_pct: f64,
     pub cooldown_minutes: u64,
+    /// Only count a trade and start its cooldown once the transaction is finalized
+    pub confirm_finalized_before_cooldown: bool,
+    pub finalization_timeout_secs: u64,
+    pub max_daily_trades: usize,
+    /// Signal guards in evaluation order; guards not listed are disabled
+    pub signal_guards: Vec<String>,
+
+    // One-shot startup trade to validate a deployment end-to-end
+    pub test_trade_on_start: bool,
+    pub test_trade_amount: u64,
+
+    // Shutdown: force exit if draining takes longer, saving unconfirmed signatures
+    pub shutdown_timeout_secs: u64,
+    pub pending_signatures_path: String,
+    /// Trade ledger written on shutdown (and every `pnl_export_minutes` if > 0)
+    pub pnl_csv_path: String,
+    pub pnl_export_minutes: u64,
+    /// Cooldown, daily trade count and last slot, kept across restarts
+    pub state_path: String,
+
+    // Status server (disabled when unset)
+    pub health_port: Option<u16>,
+    /// Serve Prometheus `/metrics` on this port (disabled when unset)
+    pub metrics_port: Option<u16>,
+
+    // Alerts
+    /// Webhook receiving `{"text": ...}` alerts; alerts are only logged when unset
+    pub alert_webhook_url: Option<String>,
+    /// Repeats of the same alert inside this window are dropped
+    pub alert_dedup_secs: u64,
+    /// Alert when a trade takes longer than this to confirm; 0 disables
+    pub max_confirm_latency_secs: u64,
+
+    // Health: signals past the degraded limits shrink trading, past the unhealthy ones halt it
+    pub health_degraded: HealthLimits,
+    pub health_unhealthy: HealthLimits,
+    /// Trade size multiplier while degraded
+    pub degraded_size_factor: f64,
+    /// Cooldown multiplier while degraded
+    pub degraded_cooldown_multiplier: u64,
+    /// A failed-trade streak is forgotten this long after its last failure
+    pub health_failure_reset_secs: u64,
+
+    // Swap routing
+    /// Jupiter-compatible quote/swap API to use instead of the public Jupiter API
+    pub swap_api_url: Option<String>,
+    /// Build, sign and simulate swaps but never send them
+    pub dry_run: bool,
+    /// What to do when a sent swap is slow to confirm
+    pub send_retry_mode: SendRetryMode,
+    /// Rebroadcasts of the same transaction in `resubmit_same` mode
+    pub max_resubmits: usize,
+    pub send_confirm_timeout_secs: u64,
+    /// Stop waiting on a sent swap after this long and report it as not landed
+    pub max_confirm_wait_secs: u64,
+    /// Fresh quotes tried after a transaction's blockhash expires unconfirmed
+    pub max_requotes: usize,
+    /// Re-quote before sending once a quote is older than this; 0 disables the check
+    pub max_quote_age_ms: u64,
+    /// Fetch each confirmed swap with getTransaction and reconcile it against its quote
+    pub verify_trades: bool,
+    /// Fixed compute unit price for swaps; unset lets Jupiter choose ("auto")
+    pub priority_fee_micro_lamports: Option<u64>,
 
     // Solana
     pub rpc_url: String,
+    /// Commitment RPC reads and transaction confirmation wait for
+    pub commitment: CommitmentConfig,
+    /// Cluster the bot trades on; when set, the container must stream the same one
+    pub solana_network: Option<String>,
+    /// Devnet only: airdrop this much SOL at startup when native SOL is below
+    /// `auto_airdrop_below_lamports` (0 disables)
+    pub auto_airdrop_lamports: u64,
+    pub auto_airdrop_below_lamports: u64,
     pub executor_keypair: String,
 
     // Jupiter vault
@@ -39,8 +314,22 @@ impl BotConfig {
         let laserstream_url = env::var("LASERSTREAM_CONTAINER_URL")
             .unwrap_or_else(|_| "https://laserstream-container.eeeew.workers.dev".to_string());
 
-        let poll_interval_seconds = env::var("POLL_INTERVAL_SECONDS")
-            .unwrap_or_else(|_| "2".to_string())
+        let poll_interval_max_seconds = env::var("POLL_INTERVAL_MAX_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .context("Invalid POLL_INTERVAL_MAX_SECONDS")?;
+
+        let long_poll_wait_ms = env::var("LONG_POLL_WAIT_MS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid LONG_POLL_WAIT_MS")?;
+
+        let readiness_timeout_seconds = env::var("READINESS_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()?;
+
+        let stream_warmup_timeout_seconds = env::var("STREAM_WARMUP_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
             .parse()?;
 
         let base_token = env::var("BASE_TOKEN").unwrap_or_else(|_| "SOL".to_string());
@@ -54,35 +343,423 @@ impl BotConfig {
         let quote_mint = env::var("QUOTE_MINT")
             .unwrap_or_else(|_| "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string());
 
-        let strategy_type = env::var("STRATEGY").unwrap_or_else(|_| "momentum".to_string());
+        // Multi-pair mode: TRADING_PAIRS overrides the single BASE/QUOTE pair
+        let pairs = match env::var("TRADING_PAIRS") {
+            Ok(value) => parse_pairs(&value).context("Invalid TRADING_PAIRS")?,
+            Err(_) => vec![TradingPair {
+                base_token: base_token.clone(),
+                quote_token: quote_token.clone(),
+                base_mint: base_mint.clone(),
+                quote_mint: quote_mint.clone(),
+            }],
+        };
+        let blocked_mints: Vec<String> = env::var("BLOCKED_MINTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        validate_blocked_mints(&pairs, &blocked_mints).context("Invalid trading pairs")?;
 
-        let trade_amount = env::var("TRADE_AMOUNT_USDC")
+        let max_concurrent_pairs = env::var("MAX_CONCURRENT_PAIRS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid MAX_CONCURRENT_PAIRS")?;
+        let pair_overflow_policy = env::var("PAIR_OVERFLOW_POLICY")
+            .unwrap_or_else(|_| "error".to_string())
+            .parse()?;
+        validate_pair_count(&pairs, max_concurrent_pairs, pair_overflow_policy)
+            .context("Invalid trading pairs")?;
+
+        let primary = pairs.first().cloned().context("TRADING_PAIRS is empty")?;
+        let TradingPair {
+            base_token,
+            quote_token,
+            base_mint,
+            quote_mint,
+        } = primary;
+
+        let concurrent_pair_fetch = env::var("CONCURRENT_PAIR_FETCH")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()?;
+
+        let jupiter_max_requests_per_second = env::var("JUPITER_MAX_REQUESTS_PER_SECOND")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()?;
+
+        let jupiter_price_api = env::var("JUPITER_PRICE_API")
+            .unwrap_or_else(|_| "v4".to_string())
+            .parse()?;
+
+        let jupiter_quote_url = env::var("JUPITER_QUOTE_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty());
+
+        let jupiter_price_url = env::var("JUPITER_PRICE_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty());
+
+        let jupiter_max_attempts = env::var("JUPITER_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .context("Invalid JUPITER_MAX_ATTEMPTS")?;
+
+        let jupiter_retry_base_delay_ms = env::var("JUPITER_RETRY_BASE_DELAY_MS")
+            .unwrap_or_else(|_| "250".to_string())
+            .parse()
+            .context("Invalid JUPITER_RETRY_BASE_DELAY_MS")?;
+
+        let jupiter_only_direct_routes = env::var("JUPITER_ONLY_DIRECT_ROUTES")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("Invalid JUPITER_ONLY_DIRECT_ROUTES")?;
+
+        let jupiter_max_accounts = env::var("JUPITER_MAX_ACCOUNTS")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .context("Invalid JUPITER_MAX_ACCOUNTS")?;
+
+        let jupiter_excluded_dexes = env::var("JUPITER_EXCLUDE_DEXES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let price_probe_amount = env::var("PRICE_PROBE_AMOUNT")
+            .ok()
+            .map(|a| a.parse())
+            .transpose()
+            .context("Invalid PRICE_PROBE_AMOUNT")?;
+
+        let price_probe_notional = env::var("PRICE_PROBE_NOTIONAL")
             .unwrap_or_else(|_| "100".to_string())
-            .parse::<u64>()?
-            * 1_000_000; // Convert to lamports (6 decimals for USDC)
+            .parse()
+            .context("Invalid PRICE_PROBE_NOTIONAL")?;
 
-        let min_price_movement = env::var("MIN_PRICE_MOVEMENT")
-            .unwrap_or_else(|_| "0.02".to_string())
+        let strategy_type = env::var("STRATEGY").unwrap_or_else(|_| "momentum".to_string());
+        let strategy = StrategyConfig::from_env()?;
+
+        let shadow_strategies = env::var("SHADOW_STRATEGIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let shadow_only = env::var("SHADOW_ONLY")
+            .unwrap_or_else(|_| "false".to_string())
             .parse()?;
 
-        let lookback_minutes = env::var("LOOKBACK_MINUTES")
+        let shadow_report_minutes = env::var("SHADOW_REPORT_MINUTES")
             .unwrap_or_else(|_| "60".to_string())
             .parse()?;
 
+        let triangular_mints = env::var("TRIANGULAR_MINTS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| parse_cycle_mints(&v))
+            .transpose()
+            .context("Invalid TRIANGULAR_MINTS")?;
+        if let Some(mint) = triangular_mints
+            .iter()
+            .flatten()
+            .find(|mint| blocked_mints.contains(mint))
+        {
+            anyhow::bail!("TRIANGULAR_MINTS uses blocked mint {}", mint);
+        }
+
+        let triangular_amount = env::var("TRIANGULAR_AMOUNT")
+            .ok()
+            .map(|v| v.parse())
+            .transpose()
+            .context("Invalid TRIANGULAR_AMOUNT")?
+            .unwrap_or(strategy.trade_amount);
+
+        let min_net_profit_bps = env::var("MIN_NET_PROFIT_BPS")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .context("Invalid MIN_NET_PROFIT_BPS")?;
+
+        let triangular_leg_fee_lamports = env::var("TRIANGULAR_LEG_FEE_LAMPORTS")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()
+            .context("Invalid TRIANGULAR_LEG_FEE_LAMPORTS")?;
+
+        let min_points_for_volatility = env::var("MIN_POINTS_FOR_VOLATILITY")
+            .unwrap_or_else(|_| "2".to_string())
+            .parse()
+            .context("Invalid MIN_POINTS_FOR_VOLATILITY")?;
+
+        let seed_price = env::var("SEED_PRICE")
+            .ok()
+            .map(|p| p.parse())
+            .transpose()
+            .context("Invalid SEED_PRICE")?;
+
+        let seed_price_from_jupiter = env::var("SEED_PRICE_FROM_JUPITER")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()?;
+
+        let max_gap_before_reset_secs = env::var("MAX_GAP_BEFORE_RESET_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .context("Invalid MAX_GAP_BEFORE_RESET_SECS")?;
+
+        let max_slot_age_secs = env::var("MAX_SLOT_AGE_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .context("Invalid MAX_SLOT_AGE_SECS")?;
+
         let max_position_size = env::var("MAX_POSITION_SIZE")
             .unwrap_or_else(|_| "10000".to_string())
             .parse::<u64>()?
             * 1_000_000;
 
+        let sol_fee_reserve_lamports = env::var("SOL_FEE_RESERVE_LAMPORTS")
+            .unwrap_or_else(|_| "10000000".to_string())
+            .parse()?;
+
         let max_slippage_bps = env::var("MAX_SLIPPAGE_BPS")
             .unwrap_or_else(|_| "50".to_string())
             .parse()?;
 
+        let slippage_mode = env::var("SLIPPAGE_MODE")
+            .unwrap_or_else(|_| "fixed".to_string())
+            .parse()?;
+
+        let slippage_margin_bps = env::var("SLIPPAGE_MARGIN_BPS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()?;
+
+        let slippage_ewma_alpha = env::var("SLIPPAGE_EWMA_ALPHA")
+            .unwrap_or_else(|_| "0.2".to_string())
+            .parse()?;
+
+        let max_price_impact_pct = env::var("MAX_PRICE_IMPACT_PCT")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .context("Invalid MAX_PRICE_IMPACT_PCT")?;
+
+        let secondary_price_source = env::var("SECONDARY_PRICE_SOURCE")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.parse())
+            .transpose()?;
+
+        let pyth_feed_ids = parse_feed_ids(
+            &env::var("PYTH_FEED_IDS").unwrap_or_else(|_| format!("SOL/USDC={}", PYTH_SOL_USD_FEED_ID)),
+        )
+        .context("Invalid PYTH_FEED_IDS")?;
+
+        let max_price_divergence_pct = env::var("MAX_PRICE_DIVERGENCE_PCT")
+            .unwrap_or_else(|_| "1.0".to_string())
+            .parse()
+            .context("Invalid MAX_PRICE_DIVERGENCE_PCT")?;
+
         let cooldown_minutes = env::var("COOLDOWN_MINUTES")
             .unwrap_or_else(|_| "60".to_string())
             .parse()?;
 
+        let confirm_finalized_before_cooldown = env::var("CONFIRM_FINALIZED_BEFORE_COOLDOWN")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()?;
+
+        let finalization_timeout_secs = env::var("FINALIZATION_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()?;
+
+        let max_daily_trades = env::var("MAX_DAILY_TRADES")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()?;
+
+        let signal_guards = env::var("SIGNAL_GUARDS")
+            .unwrap_or_else(|_| "cooldown,daily_limit".to_string())
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let test_trade_on_start = env::var("TEST_TRADE_ON_START")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()?;
+
+        let test_trade_amount = env::var("TEST_TRADE_AMOUNT_USDC")
+            .ok()
+            .map(|a| a.parse::<f64>())
+            .transpose()
+            .context("Invalid TEST_TRADE_AMOUNT_USDC")?
+            .map(|usdc| (usdc * 1_000_000.0) as u64);
+        let test_trade_amount = validate_test_trade(test_trade_on_start, test_trade_amount)?;
+
+        let shutdown_timeout_secs = env::var("SHUTDOWN_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()?;
+
+        let pending_signatures_path = env::var("PENDING_SIGNATURES_PATH")
+            .unwrap_or_else(|_| "pending_signatures.json".to_string());
+
+        let pnl_csv_path = env::var("PNL_CSV_PATH")
+            .unwrap_or_else(|_| "pnl_ledger.csv".to_string());
+
+        let state_path = env::var("STATE_PATH")
+            .unwrap_or_else(|_| "bot_state.json".to_string());
+
+        let pnl_export_minutes = env::var("PNL_EXPORT_MINUTES")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("Invalid PNL_EXPORT_MINUTES")?;
+
+        let health_port = env::var("HEALTH_PORT")
+            .ok()
+            .map(|p| p.parse())
+            .transpose()
+            .context("Invalid HEALTH_PORT")?;
+
+        let metrics_port = env::var("METRICS_PORT")
+            .ok()
+            .map(|p| p.parse())
+            .transpose()
+            .context("Invalid METRICS_PORT")?;
+
+        let alert_webhook_url = env::var("ALERT_WEBHOOK_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty());
+
+        let alert_dedup_secs = env::var("ALERT_DEDUP_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .context("Invalid ALERT_DEDUP_SECS")?;
+
+        let max_confirm_latency_secs = env::var("MAX_CONFIRM_LATENCY_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .context("Invalid MAX_CONFIRM_LATENCY_SECS")?;
+
+        let health_limits = |level: &str, defaults: HealthLimits| -> Result<HealthLimits> {
+            let var = |name: &str, default: u64| -> Result<u64> {
+                let key = format!("HEALTH_{}_{}", level, name);
+                env::var(&key)
+                    .map(|v| v.parse().with_context(|| format!("Invalid {}", key)))
+                    .unwrap_or(Ok(default))
+            };
+            Ok(HealthLimits {
+                max_feed_age_secs: var("FEED_AGE_SECS", defaults.max_feed_age_secs)?,
+                max_rpc_latency_ms: var("RPC_LATENCY_MS", defaults.max_rpc_latency_ms)?,
+                max_slot_lag: var("SLOT_LAG", defaults.max_slot_lag)?,
+                max_consecutive_failures: var("FAILURES", defaults.max_consecutive_failures as u64)? as u32,
+            })
+        };
+        let health_degraded = health_limits(
+            "DEGRADED",
+            HealthLimits {
+                max_feed_age_secs: 15,
+                max_rpc_latency_ms: 1_500,
+                max_slot_lag: 30,
+                max_consecutive_failures: 2,
+            },
+        )?;
+        let health_unhealthy = health_limits(
+            "UNHEALTHY",
+            HealthLimits {
+                max_feed_age_secs: 60,
+                max_rpc_latency_ms: 5_000,
+                max_slot_lag: 150,
+                max_consecutive_failures: 5,
+            },
+        )?;
+
+        let degraded_size_factor: f64 = env::var("DEGRADED_SIZE_FACTOR")
+            .unwrap_or_else(|_| "0.5".to_string())
+            .parse()
+            .context("Invalid DEGRADED_SIZE_FACTOR")?;
+        if !(degraded_size_factor > 0.0 && degraded_size_factor <= 1.0) {
+            anyhow::bail!("DEGRADED_SIZE_FACTOR must be in (0, 1], got {}", degraded_size_factor);
+        }
+
+        let degraded_cooldown_multiplier = env::var("DEGRADED_COOLDOWN_MULTIPLIER")
+            .unwrap_or_else(|_| "2".to_string())
+            .parse()
+            .context("Invalid DEGRADED_COOLDOWN_MULTIPLIER")?;
+
+        let health_failure_reset_secs = env::var("HEALTH_FAILURE_RESET_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .context("Invalid HEALTH_FAILURE_RESET_SECS")?;
+
+        let swap_api_url = env::var("SWAP_API_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty());
+
+        let dry_run = env::var("DRY_RUN")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("Invalid DRY_RUN")?;
+
+        let send_retry_mode = env::var("SEND_RETRY_MODE")
+            .unwrap_or_else(|_| "requote".to_string())
+            .parse()?;
+
+        let max_resubmits = env::var("MAX_RESUBMITS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .context("Invalid MAX_RESUBMITS")?;
+
+        let send_confirm_timeout_secs = env::var("SEND_CONFIRM_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "15".to_string())
+            .parse()
+            .context("Invalid SEND_CONFIRM_TIMEOUT_SECS")?;
+
+        let max_confirm_wait_secs = env::var("MAX_CONFIRM_WAIT_SECS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse()
+            .context("Invalid MAX_CONFIRM_WAIT_SECS")?;
+
+        let max_requotes = env::var("MAX_REQUOTES")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .context("Invalid MAX_REQUOTES")?;
+
+        let max_quote_age_ms = env::var("MAX_QUOTE_AGE_MS")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse()
+            .context("Invalid MAX_QUOTE_AGE_MS")?;
+
+        let verify_trades = env::var("VERIFY_TRADES")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .context("Invalid VERIFY_TRADES")?;
+
+        let priority_fee_micro_lamports = env::var("PRIORITY_FEE_MICRO_LAMPORTS")
+            .ok()
+            .map(|p| p.parse())
+            .transpose()
+            .context("Invalid PRIORITY_FEE_MICRO_LAMPORTS")?;
+
         let rpc_url = env::var("RPC_URL").context("RPC_URL not set")?;
+        let commitment = parse_commitment(
+            &env::var("COMMITMENT").unwrap_or_else(|_| "confirmed".to_string()),
+        )?;
+
+        let solana_network = env::var("SOLANA_NETWORK")
+            .ok()
+            .filter(|n| !n.trim().is_empty());
+
+        let auto_airdrop_lamports = (env::var("AUTO_AIRDROP_SOL")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<f64>()
+            .context("Invalid AUTO_AIRDROP_SOL")?
+            * 1_000_000_000.0) as u64;
+
+        let auto_airdrop_below_lamports = (env::var("AUTO_AIRDROP_BELOW_SOL")
+            .unwrap_or_else(|_| "0.5".to_string())
+            .parse::<f64>()
+            .context("Invalid AUTO_AIRDROP_BELOW_SOL")?
+            * 1_000_000_000.0) as u64;
 
         let executor_keypair =
             env::var("EXECUTOR_PRIVATE_KEY").context("EXECUTOR_PRIVATE_KEY not set")?;
@@ -95,22 +772,122 @@ impl BotConfig {
 
         Ok(Self {
             laserstream_url,
-            poll_interval_seconds,
+            poll_interval_max_seconds,
+            long_poll_wait_ms,
+            readiness_timeout_seconds,
+            stream_warmup_timeout_seconds,
             base_token,
             quote_token,
             base_mint,
             quote_mint,
+            pairs,
+            blocked_mints,
+            concurrent_pair_fetch,
+            max_concurrent_pairs,
+            pair_overflow_policy,
+            jupiter_max_requests_per_second,
+            jupiter_price_api,
+            jupiter_quote_url,
+            jupiter_price_url,
+            jupiter_max_attempts,
+            jupiter_retry_base_delay_ms,
+            jupiter_only_direct_routes,
+            jupiter_max_accounts,
+            jupiter_excluded_dexes,
+            price_probe_amount,
+            price_probe_notional,
             strategy_type,
-            trade_amount,
-            min_price_movement,
-            lookback_minutes,
+            shadow_strategies,
+            shadow_only,
+            shadow_report_minutes,
+            strategy,
+            triangular_mints,
+            triangular_amount,
+            min_net_profit_bps,
+            triangular_leg_fee_lamports,
+            min_points_for_volatility,
+            seed_price,
+            seed_price_from_jupiter,
+            max_gap_before_reset_secs,
+            max_slot_age_secs,
             max_position_size,
+            sol_fee_reserve_lamports,
             max_slippage_bps,
+            slippage_mode,
+            slippage_margin_bps,
+            slippage_ewma_alpha,
+            max_price_impact_pct,
+            secondary_price_source,
+            pyth_feed_ids,
+            max_price_divergence_pct,
             cooldown_minutes,
+            confirm_finalized_before_cooldown,
+            finalization_timeout_secs,
+            max_daily_trades,
+            signal_guards,
+            test_trade_on_start,
+            test_trade_amount,
+            shutdown_timeout_secs,
+            pending_signatures_path,
+            pnl_csv_path,
+            pnl_export_minutes,
+            state_path,
+            health_port,
+            metrics_port,
+            alert_webhook_url,
+            alert_dedup_secs,
+            max_confirm_latency_secs,
+            health_degraded,
+            health_unhealthy,
+            degraded_size_factor,
+            degraded_cooldown_multiplier,
+            health_failure_reset_secs,
+            swap_api_url,
+            dry_run,
+            send_retry_mode,
+            max_resubmits,
+            send_confirm_timeout_secs,
+            max_confirm_wait_secs,
+            max_requotes,
+            max_quote_age_ms,
+            verify_trades,
+            priority_fee_micro_lamports,
             rpc_url,
+            commitment,
+            solana_network,
+            auto_airdrop_lamports,
+            auto_airdrop_below_lamports,
             executor_keypair,
             vault_program_id,
             vault_state_address,
         })
     }
+
+    /// Quote/swap API: `JUPITER_QUOTE_URL`, else `SWAP_API_URL`; None for public Jupiter
+    pub fn quote_api_url(&self) -> Option<&str> {
+        self.jupiter_quote_url.as_deref().or(self.swap_api_url.as_deref())
+    }
+
+    /// Jupiter client builder preset with `quote_api_url`, `JUPITER_PRICE_URL`,
+    /// `JUPITER_PRICE_API`, the `JUPITER_MAX_ATTEMPTS` retry policy and the route constraints
+    pub fn jupiter_client_builder(&self) -> JupiterClientBuilder {
+        let mut builder = JupiterClient::builder()
+            .price_api_version(self.jupiter_price_api)
+            .retry_policy(
+                self.jupiter_max_attempts,
+                Duration::from_millis(self.jupiter_retry_base_delay_ms),
+            )
+            .route_options(RouteOptions {
+                only_direct_routes: self.jupiter_only_direct_routes,
+                max_accounts: self.jupiter_max_accounts,
+                excluded_dexes: self.jupiter_excluded_dexes.clone(),
+            });
+        if let Some(url) = self.quote_api_url() {
+            builder = builder.quote_url(url);
+        }
+        if let Some(url) = &self.jupiter_price_url {
+            builder = builder.price_url(url);
+        }
+        builder
+    }
 }
@@ -1,31 +1,150 @@
 use anyhow::{Context, Result};
 use base64::Engine;
 use bincode;
-use solana_client::rpc_client::RpcClient;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     transaction::VersionedTransaction,
 };
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionTokenBalance,
+};
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::{info, warn};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
+use crate::balances::SolBalance;
 use crate::config::BotConfig;
-use crate::jupiter_client::JupiterClient;
-use crate::strategies::TradeSignal;
+use jupiter_client::{check_quote_route, JupiterQuoteResponse, PriceLimit, RateLimiter};
+use crate::notifier::ConfirmLatencyMonitor;
+use crate::pairs::TradingPair;
+use crate::resubmit::{ResubmitPolicy, RetryAction};
+use crate::shutdown::InFlight;
+use crate::slippage::{realized_slippage_bps, SlippageModel};
+use crate::strategies::{triangular::{ArbCycle, CycleLeg}, TradeSignal};
+use crate::swap_parser::{Base64Transaction, SwapParser};
+use crate::swap_provider::{create_swap_provider, SwapProvider};
+use crate::token_decimals::fetch_decimals;
+use crate::verification::{native_delta, token_delta, TokenBalance, TradeVerification};
+
+const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWQbn2ZsHTGv9XH1N";
+/// Per-request RPC timeout, so a hung endpoint can't stall the trading loop
+const RPC_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a price impact search keeps answering trades in the same direction
+/// before it's probed again
+const IMPACT_SIZE_TTL: Duration = Duration::from_secs(60);
+
+/// Outcome of a sent swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapResult {
+    pub signature: String,
+    /// Slot the transaction was confirmed in
+    pub confirmed_slot: Option<u64>,
+    /// False when confirmation timed out (the transaction may still land) or on a dry run
+    pub landed: bool,
+    /// On-chain fee and balance deltas reconciled against the quote (when VERIFY_TRADES is on)
+    pub verification: Option<TradeVerification>,
+    /// Input and output of the quote the swap was sent with, in raw units
+    pub quoted_in: u64,
+    pub quoted_out: u64,
+}
+
+impl SwapResult {
+    /// Raw input spent and output received: the verified balance deltas when
+    /// there are any, else the quote's amounts
+    pub fn amounts(&self) -> (u64, u64) {
+        let verified = self.verification.as_ref();
+        let spent = verified
+            .filter(|v| v.input_delta < 0)
+            .map_or(self.quoted_in, |v| (-v.input_delta) as u64);
+        let received = verified
+            .filter(|v| v.output_delta > 0)
+            .map_or(self.quoted_out, |v| v.output_delta as u64);
+        (spent, received)
+    }
+}
+
+/// How a send attempt ended
+enum SendOutcome {
+    Confirmed { signature: Signature, slot: u64 },
+    /// The blockhash expired unconfirmed; the swap needs a fresh quote
+    Expired,
+    /// Still unconfirmed when `max_confirm_wait` ran out
+    TimedOut(Signature),
+}
+
+/// Result of a `max_size_for_impact` search for one swap direction
+struct ImpactSize {
+    searched_at: Instant,
+    up_to: u64,
+    /// Largest size within the cap; None when even the smallest probe was over it
+    size: Option<u64>,
+}
+
+impl ImpactSize {
+    /// The size to trade for `amount`, when the search still covers it
+    fn answer(&self, amount: u64) -> Option<Option<u64>> {
+        if self.searched_at.elapsed() > IMPACT_SIZE_TTL {
+            return None;
+        }
+        match self.size {
+            // Capped below what was asked: larger trades hit the same cap
+            Some(size) if size < self.up_to => Some(Some(amount.min(size))),
+            Some(_) => (amount <= self.up_to).then_some(Some(amount)),
+            // Nothing fit, and the probes only grow with the amount
+            None => (amount >= self.up_to).then_some(None),
+        }
+    }
+}
+
+/// A signed swap ready to send, with the block height its blockhash expires at
+struct BuiltSwap {
+    quote: JupiterQuoteResponse,
+    /// When the quote was fetched
+    quoted_at: Instant,
+    transaction: VersionedTransaction,
+    last_valid_block_height: u64,
+}
 
 pub struct TradeExecutor {
     rpc_client: RpcClient,
     executor: Keypair,
     vault_program_id: Pubkey,
     vault_state: Pubkey,
-    jupiter_client: JupiterClient,
+    swap_provider: Box<dyn SwapProvider>,
+    slippage: Mutex<SlippageModel>,
+    in_flight: InFlight,
+    /// Stop after simulation instead of sending
+    dry_run: bool,
+    confirm_latency: Option<ConfirmLatencyMonitor>,
+    resubmit_policy: ResubmitPolicy,
+    /// How long to poll for confirmation before consulting the resubmit policy
+    confirm_timeout: Duration,
+    /// Give up waiting on a sent transaction after this long
+    max_confirm_wait: Duration,
+    /// Fresh quotes to try after a transaction expires unconfirmed
+    max_requotes: usize,
+    /// Re-quote before sending once a quote is older than this
+    max_quote_age: Option<Duration>,
+    /// Fixed compute unit price for swaps; None lets Jupiter pick ("auto")
+    priority_fee_micro_lamports: Option<u64>,
+    /// Re-read confirmed swaps with getTransaction and reconcile them against the quote
+    verify_trades: bool,
+    /// Shrink trades to the largest size quoting within this price impact (percent)
+    max_price_impact_pct: Option<f64>,
+    /// Recent price impact searches by (input mint, output mint)
+    impact_sizes: Mutex<HashMap<(String, String), ImpactSize>>,
 }
 
 impl TradeExecutor {
-    pub async fn new(config: &BotConfig) -> Result<Self> {
-        let rpc_client = RpcClient::new(&config.rpc_url);
+    pub async fn new(config: &BotConfig, rate_limiter: Arc<RateLimiter>) -> Result<Self> {
+        let rpc_client = RpcClient::new_with_timeout_and_commitment(config.rpc_url.clone(), RPC_TIMEOUT, config.commitment);
         
         // Decode executor keypair from base58
         let keypair_bytes = bs58::decode(&config.executor_keypair)
@@ -36,42 +155,256 @@ impl TradeExecutor {
         
         let vault_program_id = Pubkey::from_str(&config.vault_program_id)?;
         let vault_state = Pubkey::from_str(&config.vault_state_address)?;
-        let jupiter_client = JupiterClient::new();
+        let swap_provider = create_swap_provider(config, rate_limiter);
+        let slippage = Mutex::new(SlippageModel::new(
+            config.slippage_mode,
+            config.max_slippage_bps,
+            config.slippage_margin_bps,
+            config.slippage_ewma_alpha,
+        ));
         
         info!("Executor pubkey: {}", executor.pubkey());
         info!("Vault program: {}", vault_program_id);
+        info!("Swap provider: {}", swap_provider.name());
+        if config.dry_run {
+            warn!("🧪 DRY_RUN: swaps are simulated, never sent");
+        }
         
         Ok(Self {
             rpc_client,
             executor,
             vault_program_id,
             vault_state,
-            jupiter_client,
+            swap_provider,
+            slippage,
+            in_flight: InFlight::default(),
+            dry_run: config.dry_run,
+            confirm_latency: None,
+            resubmit_policy: ResubmitPolicy {
+                mode: config.send_retry_mode,
+                max_resubmits: config.max_resubmits,
+            },
+            confirm_timeout: Duration::from_secs(config.send_confirm_timeout_secs),
+            max_confirm_wait: Duration::from_secs(config.max_confirm_wait_secs),
+            max_requotes: config.max_requotes,
+            max_quote_age: (config.max_quote_age_ms > 0).then(|| Duration::from_millis(config.max_quote_age_ms)),
+            priority_fee_micro_lamports: config.priority_fee_micro_lamports,
+            verify_trades: config.verify_trades,
+            max_price_impact_pct: config.max_price_impact_pct,
+            impact_sizes: Mutex::new(HashMap::new()),
         })
     }
     
+    /// Time every confirmation and alert on slow ones
+    pub fn with_confirm_latency_monitor(mut self, monitor: ConfirmLatencyMonitor) -> Self {
+        self.confirm_latency = Some(monitor);
+        self
+    }
+    
+    /// Current slot from the executor's RPC endpoint (used as a liveness probe)
+    pub async fn get_slot(&self) -> Result<u64> {
+        self.rpc_client.get_slot().await.context("Failed to get slot from RPC")
+    }
+
+    /// Whether swaps stop after simulation (DRY_RUN); nothing they return was traded
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Slippage tolerance (bps) the next swap will request
+    pub fn current_slippage_bps(&self) -> u16 {
+        self.slippage.lock().unwrap().current_bps()
+    }
+
+    /// Transactions sent but not yet confirmed (reported on forced shutdown)
+    pub fn in_flight(&self) -> InFlight {
+        self.in_flight.clone()
+    }
+    
+    /// Whether `signature` has reached `finalized` commitment
+    pub async fn is_finalized(&self, signature: &str) -> Result<bool> {
+        let signature = Signature::from_str(signature).context("Invalid signature")?;
+        let response = self
+            .rpc_client
+            .confirm_transaction_with_commitment(&signature, CommitmentConfig::finalized())
+            .await
+            .context("Failed to check finalization")?;
+        Ok(response.value)
+    }
+    
+    /// Provider the executor quotes and routes through
+    pub fn swap_provider(&self) -> &dyn SwapProvider {
+        self.swap_provider.as_ref()
+    }
+
+    /// Execute a cycle's legs in order, each spending what the previous leg
+    /// delivered. The provider returns one transaction per swap, so legs can't
+    /// share a transaction: a failed or unconfirmed leg aborts the rest and the
+    /// intermediate token stays in the wallet. So does a leg whose fresh quote
+    /// no longer meets the rate its cycle quote guaranteed.
+    pub async fn execute_cycle(&self, cycle: &ArbCycle) -> Result<Vec<SwapResult>> {
+        let slippage_bps = self.current_slippage_bps();
+        let mut results = Vec::with_capacity(cycle.legs.len());
+        let mut amount_in = cycle.legs.first().map(|leg| leg.amount_in).unwrap_or(0);
+
+        for (i, leg) in cycle.legs.iter().enumerate() {
+            info!(
+                "🔺 Leg {}/{}: {} {} -> {}",
+                i + 1,
+                cycle.legs.len(),
+                amount_in,
+                leg.input_mint,
+                leg.output_mint
+            );
+            let limit = self.leg_limit(leg).await?;
+            let result = self
+                .execute_swap(&leg.input_mint, &leg.output_mint, amount_in, slippage_bps, Some(&limit))
+                .await
+                .with_context(|| format!("Cycle aborted at leg {} ({} -> {})", i + 1, leg.input_mint, leg.output_mint))?;
+            if !result.landed && !self.dry_run {
+                anyhow::bail!(
+                    "Cycle aborted: leg {} ({}) not confirmed, holding {}",
+                    i + 1,
+                    result.signature,
+                    leg.output_mint
+                );
+            }
+
+            // What actually arrived when verified, else the quote's guaranteed minimum
+            amount_in = result
+                .verification
+                .as_ref()
+                .filter(|v| v.output_delta > 0)
+                .map(|v| v.output_delta as u64)
+                .unwrap_or(leg.min_out);
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Worst rate `leg` may fill at: its quoted minimum out per unit in, as a sell
+    /// of the input mint for the output. Being a rate, it scales with whatever
+    /// amount the previous leg actually delivered.
+    async fn leg_limit(&self, leg: &CycleLeg) -> Result<PriceLimit> {
+        let base_decimals = self.token_decimals(&leg.input_mint).await?;
+        let quote_decimals = self.token_decimals(&leg.output_mint).await?;
+        let ui = |amount: u64, decimals: u8| amount as f64 / 10_f64.powi(decimals as i32);
+        Ok(PriceLimit {
+            is_buy: false,
+            price: ui(leg.min_out, quote_decimals) / ui(leg.amount_in, base_decimals),
+            base_decimals,
+            quote_decimals,
+        })
+    }
+
+    /// EWMA of realized slippage across confirmed trades, if any
+    pub fn slippage_ewma_bps(&self) -> Option<f64> {
+        self.slippage.lock().unwrap().ewma_bps()
+    }
+
     pub async fn execute_trade(
         &self,
         signal: &TradeSignal,
         config: &BotConfig,
     ) -> Result<String> {
+        let pair = TradingPair {
+            base_token: config.base_token.clone(),
+            quote_token: config.quote_token.clone(),
+            base_mint: config.base_mint.clone(),
+            quote_mint: config.quote_mint.clone(),
+        };
+        self.execute_trade_for_pair(signal, &pair).await
+    }
+
+    /// Signature of a confirmed (or dry-run) trade; a confirmation timeout is an error
+    pub async fn execute_trade_for_pair(
+        &self,
+        signal: &TradeSignal,
+        pair: &TradingPair,
+    ) -> Result<String> {
+        let result = self.execute_trade_with_result(signal, pair).await?;
+        if !result.landed && !self.dry_run {
+            anyhow::bail!(
+                "Transaction {} not confirmed within {}s; it may still land",
+                result.signature,
+                self.max_confirm_wait.as_secs()
+            );
+        }
+        Ok(result.signature)
+    }
+
+    /// `signal` with its amount clamped to the largest size whose quoted price
+    /// impact stays within `MAX_PRICE_IMPACT_PCT`. Errors when no size does.
+    /// A search costs several quotes, so its result is reused for
+    /// `IMPACT_SIZE_TTL` by later trades it covers.
+    pub async fn size_for_impact(&self, signal: TradeSignal, pair: &TradingPair) -> Result<TradeSignal> {
+        let Some(max_impact_pct) = self.max_price_impact_pct else {
+            return Ok(signal);
+        };
+        let (input_mint, output_mint, amount) = match &signal {
+            TradeSignal::Buy { amount, .. } => (&pair.quote_mint, &pair.base_mint, *amount),
+            TradeSignal::Sell { amount, .. } => (&pair.base_mint, &pair.quote_mint, *amount),
+            TradeSignal::Hold => return Ok(signal),
+        };
+        let key = (input_mint.clone(), output_mint.clone());
+        let cached = self.impact_sizes.lock().unwrap().get(&key).and_then(|search| search.answer(amount));
+        let size = match cached {
+            Some(size) => size,
+            None => {
+                let size = self
+                    .swap_provider
+                    .max_size_for_impact(input_mint, output_mint, max_impact_pct, amount)
+                    .await?;
+                let search = ImpactSize { searched_at: Instant::now(), up_to: amount, size };
+                self.impact_sizes.lock().unwrap().insert(key, search);
+                size
+            }
+        };
+        let size = size
+            .with_context(|| format!("No size up to {} quotes within {}% price impact", amount, max_impact_pct))?;
+        if size >= amount {
+            return Ok(signal);
+        }
+        
+        info!("📏 Clamping {} to {} to stay within {}% price impact", amount, size, max_impact_pct);
+        Ok(match signal {
+            TradeSignal::Buy { reason, limit_price, .. } => TradeSignal::Buy { amount: size, reason, limit_price },
+            TradeSignal::Sell { reason, limit_price, .. } => TradeSignal::Sell { amount: size, reason, limit_price },
+            TradeSignal::Hold => TradeSignal::Hold,
+        })
+    }
+
+    /// Execute `signal`, reporting whether the transaction landed and in which slot
+    pub async fn execute_trade_with_result(
+        &self,
+        signal: &TradeSignal,
+        pair: &TradingPair,
+    ) -> Result<SwapResult> {
+        let slippage_bps = self.current_slippage_bps();
+        let limit = match signal {
+            TradeSignal::Buy { limit_price: Some(price), .. } => Some(self.price_limit(true, *price, pair).await?),
+            TradeSignal::Sell { limit_price: Some(price), .. } => Some(self.price_limit(false, *price, pair).await?),
+            _ => None,
+        };
         match signal {
-            TradeSignal::Buy { amount, reason } => {
+            TradeSignal::Buy { amount, reason, .. } => {
                 info!("Executing BUY: {} | Reason: {}", amount, reason);
                 self.execute_swap(
-                    &config.quote_mint,
-                    &config.base_mint,
+                    &pair.quote_mint,
+                    &pair.base_mint,
                     *amount,
-                    config.max_slippage_bps,
+                    slippage_bps,
+                    limit.as_ref(),
                 ).await
             }
-            TradeSignal::Sell { amount, reason } => {
+            TradeSignal::Sell { amount, reason, .. } => {
                 info!("Executing SELL: {} | Reason: {}", amount, reason);
                 self.execute_swap(
-                    &config.base_mint,
-                    &config.quote_mint,
+                    &pair.base_mint,
+                    &pair.quote_mint,
                     *amount,
-                    config.max_slippage_bps,
+                    slippage_bps,
+                    limit.as_ref(),
                 ).await
             }
             TradeSignal::Hold => {
@@ -81,13 +414,245 @@ impl TradeExecutor {
         }
     }
     
+    /// Decimals of `mint`, from the known-token table or its mint account
+    pub async fn token_decimals(&self, mint: &str) -> Result<u8> {
+        let mint = Pubkey::from_str(mint).context("Invalid mint")?;
+        fetch_decimals(&self.rpc_client, &mint).await
+    }
+    
+    async fn price_limit(&self, is_buy: bool, price: f64, pair: &TradingPair) -> Result<PriceLimit> {
+        Ok(PriceLimit {
+            is_buy,
+            price,
+            base_decimals: self.token_decimals(&pair.base_mint).await?,
+            quote_decimals: self.token_decimals(&pair.quote_mint).await?,
+        })
+    }
+    
+    /// Swap `amount_in` of `input_mint`, refusing any quote worse than `limit`
     async fn execute_swap(
         &self,
         input_mint: &str,
         output_mint: &str,
         amount_in: u64,
         slippage_bps: u16,
-    ) -> Result<String> {
+        limit: Option<&PriceLimit>,
+    ) -> Result<SwapResult> {
+        let output_mint_pubkey = Pubkey::from_str(output_mint)?;
+        
+        let mut swap = self.build_fresh_swap(input_mint, output_mint, amount_in, slippage_bps, limit).await?;
+        
+        if self.dry_run {
+            let signature = format!("DRY_RUN_{}", chrono::Utc::now().timestamp_millis());
+            info!(
+                "🧪 Dry run: would swap {} {} -> {} {} (min out {}), not sending",
+                swap.quote.in_amount,
+                input_mint.split_at(8).0,
+                swap.quote.out_amount,
+                output_mint.split_at(8).0,
+                swap.quote.other_amount_threshold
+            );
+            return Ok(SwapResult {
+                signature,
+                confirmed_slot: None,
+                landed: false,
+                verification: None,
+                quoted_in: swap.quote.in_amount.parse().unwrap_or(0),
+                quoted_out: swap.quote.out_amount.parse().unwrap_or(0),
+            });
+        }
+        
+        // Snapshot output balance so realized slippage can be measured after confirmation
+        let balance_before = self.output_balance(&output_mint_pubkey).await.ok();
+        
+        // Step 5: Send and confirm transaction, re-quoting once a blockhash expires unconfirmed
+        let mut requotes = 0;
+        let (quote, signature, slot, latency) = loop {
+            info!("📤 Sending transaction...");
+            let pending_signature = swap.transaction.signatures[0].to_string();
+            self.in_flight.insert(&pending_signature);
+            let sent_at = Instant::now();
+            let result = self.send_until_confirmed(&swap.transaction, swap.last_valid_block_height).await;
+            self.in_flight.remove(&pending_signature);
+            
+            match result? {
+                SendOutcome::Confirmed { signature, slot } => break (swap.quote, signature, slot, sent_at.elapsed()),
+                SendOutcome::TimedOut(signature) => {
+                    warn!(
+                        "⌛ {} not confirmed after {}s, giving up waiting",
+                        signature,
+                        self.max_confirm_wait.as_secs()
+                    );
+                    return Ok(SwapResult {
+                        signature: signature.to_string(),
+                        confirmed_slot: None,
+                        landed: false,
+                        verification: None,
+                        quoted_in: swap.quote.in_amount.parse().unwrap_or(0),
+                        quoted_out: swap.quote.out_amount.parse().unwrap_or(0),
+                    });
+                }
+                SendOutcome::Expired => {}
+            }
+            requotes += 1;
+            if requotes > self.max_requotes {
+                anyhow::bail!("Transaction {} expired unconfirmed after {} re-quote(s)", pending_signature, self.max_requotes);
+            }
+            warn!("⌛ {} expired unconfirmed, re-quoting ({}/{})", pending_signature, requotes, self.max_requotes);
+            swap = self.build_fresh_swap(input_mint, output_mint, amount_in, slippage_bps, limit).await?;
+        };
+        
+        info!("✅ Transaction confirmed: {} in slot {} ({:.1}s)", signature, slot, latency.as_secs_f64());
+        if let Some(monitor) = &self.confirm_latency {
+            monitor.observe(&signature.to_string(), latency);
+        }
+        
+        // Step 6: Feed realized slippage back into the model
+        let expected_out: u64 = quote.out_amount.parse().unwrap_or(0);
+        match (balance_before, self.output_balance(&output_mint_pubkey).await) {
+            (Some(before), Ok(after)) => {
+                let actual_out = after.saturating_sub(before);
+                self.record_realized_slippage(expected_out, actual_out);
+            }
+            (_, Err(e)) => warn!("Could not measure realized slippage: {}", e),
+            (None, _) => warn!("Could not measure realized slippage: no pre-trade balance"),
+        }
+        
+        // Step 7: Check what actually happened on-chain against the quote
+        // The swap has landed either way: a failed check is logged, never fatal
+        let verification = if self.verify_trades {
+            self.verify_swap(&signature, &quote).await.unwrap_or_else(|e| {
+                warn!("🧾 Could not verify {}: {:#}", signature, e);
+                None
+            })
+        } else {
+            None
+        };
+        
+        Ok(SwapResult {
+            signature: signature.to_string(),
+            confirmed_slot: Some(slot),
+            landed: true,
+            verification,
+            quoted_in: quote.in_amount.parse().unwrap_or(0),
+            quoted_out: expected_out,
+        })
+    }
+    
+    /// Fetch `signature` with its status meta and reconcile the executor's fee and
+    /// balance deltas against `quote`. Errors if it failed on-chain or can't be
+    /// matched to the executor's wallet; None when the RPC can't serve it yet.
+    async fn verify_swap(&self, signature: &Signature, quote: &JupiterQuoteResponse) -> Result<Option<TradeVerification>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = match self.rpc_client.get_transaction_with_config(signature, config).await {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                warn!("Could not fetch {} for verification: {}", signature, e);
+                return Ok(None);
+            }
+        };
+        let owner = self.executor.pubkey().to_string();
+        let route = Base64Transaction::from_rpc(&transaction).and_then(|tx| {
+            SwapParser::new(Some(quote.input_mint.clone()), Some(quote.output_mint.clone()))
+                .parse_encoded_transaction(&tx, &owner)
+        });
+        let Some(meta) = transaction.transaction.meta else {
+            warn!("Could not verify {}: no status meta", signature);
+            return Ok(None);
+        };
+        if let Some(err) = &meta.err {
+            anyhow::bail!("Transaction {} failed on-chain: {:?}", signature, err);
+        }
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .with_context(|| format!("Could not decode transaction {}", signature))?;
+        // Balances are indexed by account; find the executor's wallet among them
+        let wallet = decoded
+            .message
+            .static_account_keys()
+            .iter()
+            .position(|key| *key == self.executor.pubkey())
+            .with_context(|| format!("Transaction {} does not involve the executor", signature))?;
+        
+        let pre = token_balances(meta.pre_token_balances);
+        let post = token_balances(meta.post_token_balances);
+        // SOL legs move through the wallet's lamports, plus any persistent wSOL account;
+        // the fee comes out of them too when the wallet paid it
+        let fee_paid = if wallet == 0 { meta.fee } else { 0 };
+        let delta = |mint: &str| {
+            let tokens = token_delta(&pre, &post, &owner, mint);
+            if mint == NATIVE_MINT {
+                let pre_lamports = meta.pre_balances.get(wallet).copied().unwrap_or(0);
+                let post_lamports = meta.post_balances.get(wallet).copied().unwrap_or(0);
+                tokens + native_delta(pre_lamports, post_lamports, fee_paid)
+            } else {
+                tokens
+            }
+        };
+        
+        let mut verification = TradeVerification::reconcile(
+            meta.fee,
+            delta(&quote.input_mint),
+            delta(&quote.output_mint),
+            quote,
+        );
+        info!(
+            "🧾 Verified {}: fee {} lamports, in {}, out {}",
+            signature, verification.fee_lamports, verification.input_delta, verification.output_delta
+        );
+        match &route {
+            Some(route) => info!("🧾 Route transfers: {} in, {} out", route.input_amount, route.output_amount),
+            None => debug!("No Jupiter route transfers found in {}", signature),
+        }
+        verification.route = route;
+        for discrepancy in &verification.discrepancies {
+            warn!("🧾 {} does not match its quote: {}", signature, discrepancy);
+        }
+        Ok(Some(verification))
+    }
+    
+    /// `build_swap`, re-quoting once if building took so long the quote went stale
+    /// before it could be sent
+    async fn build_fresh_swap(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount_in: u64,
+        slippage_bps: u16,
+        limit: Option<&PriceLimit>,
+    ) -> Result<BuiltSwap> {
+        let swap = self.build_swap(input_mint, output_mint, amount_in, slippage_bps, limit).await?;
+        let age = swap.quoted_at.elapsed();
+        match self.max_quote_age {
+            Some(max_age) if age > max_age => {
+                warn!(
+                    "⏱️  Quote is {}ms old (limit {}ms), re-quoting before send",
+                    age.as_millis(),
+                    max_age.as_millis()
+                );
+                self.build_swap(input_mint, output_mint, amount_in, slippage_bps, limit).await
+            }
+            _ => Ok(swap),
+        }
+    }
+    
+    /// Quote, build, sign and simulate a swap (steps 1-4). A quote worse than
+    /// `limit`, without a route or past MAX_PRICE_IMPACT_PCT is an error before
+    /// anything is built.
+    async fn build_swap(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount_in: u64,
+        slippage_bps: u16,
+        limit: Option<&PriceLimit>,
+    ) -> Result<BuiltSwap> {
         let input_mint_pubkey = Pubkey::from_str(input_mint)?;
         let output_mint_pubkey = Pubkey::from_str(output_mint)?;
         
@@ -96,28 +661,35 @@ impl TradeExecutor {
               input_mint_pubkey.to_string().split_at(8).0,
               output_mint_pubkey.to_string().split_at(8).0);
         
-        // Step 1: Get quote from Jupiter
-        info!("📊 Getting quote from Jupiter...");
-        let quote = self.jupiter_client.get_quote(
+        // Step 1: Get quote from the swap provider
+        info!("📊 Getting quote from {}...", self.swap_provider.name());
+        let quote = self.swap_provider.get_quote(
             input_mint,
             output_mint,
             amount_in,
             slippage_bps,
         ).await?;
+        let quoted_at = Instant::now();
         
-        info!("✅ Quote received: {} {} -> {} {} (impact: {}%)",
+        info!("✅ Quote received: {} {} -> {} {} (impact: {:.4}%)",
               quote.in_amount,
               input_mint_pubkey.to_string().split_at(8).0,
               quote.out_amount,
               output_mint_pubkey.to_string().split_at(8).0,
-              quote.price_impact_pct);
+              quote.price_impact_percent().unwrap_or(f64::NAN));
+        
+        check_quote_route(&quote, self.max_price_impact_pct).context("Refusing swap")?;
+        if let Some(limit) = limit {
+            limit.check(&quote).context("Refusing swap")?;
+        }
         
         // Step 2: Get swap transaction
         info!("🔨 Building swap transaction...");
-        let swap_response = self.jupiter_client.get_swap_transaction(
+        let swap_response = self.swap_provider.get_swap_transaction(
             &quote,
             &self.executor.pubkey().to_string(),
             true, // Wrap/unwrap SOL if needed
+            self.priority_fee_micro_lamports,
         ).await?;
         
         // Step 3: Deserialize and sign transaction
@@ -128,8 +700,10 @@ impl TradeExecutor {
         let mut transaction: VersionedTransaction = bincode::deserialize(&transaction_bytes)
             .context("Failed to deserialize transaction")?;
         
-        // Get latest blockhash for transaction
-        let blockhash = self.rpc_client.get_latest_blockhash()
+        // Get latest blockhash (and how long it stays valid) for the transaction
+        let (blockhash, last_valid_block_height) = self.rpc_client
+            .get_latest_blockhash_with_commitment(self.rpc_client.commitment())
+            .await
             .context("Failed to get latest blockhash")?;
         
         // Sign the transaction with our executor keypair
@@ -141,7 +715,7 @@ impl TradeExecutor {
         
         // Step 4: Simulate transaction first (safety check)
         info!("🔍 Simulating transaction...");
-        match self.rpc_client.simulate_transaction(&transaction) {
+        match self.rpc_client.simulate_transaction(&transaction).await {
             Ok(result) => {
                 if let Some(err) = result.value.err {
                     anyhow::bail!("Transaction simulation failed: {:?}", err);
@@ -153,13 +727,735 @@ impl TradeExecutor {
             }
         }
         
-        // Step 5: Send and confirm transaction
-        info!("📤 Sending transaction...");
-        let signature = self.rpc_client.send_and_confirm_transaction(&transaction)
+        Ok(BuiltSwap {
+            quote,
+            quoted_at,
+            transaction,
+            last_valid_block_height,
+        })
+    }
+    
+    /// Send `transaction` and poll its signature status for up to `max_confirm_wait`,
+    /// rebroadcasting the same bytes as the resubmit policy allows. Once the
+    /// blockhash expires it's only `Expired` if the signature is unknown to the
+    /// full status history; one that landed below our commitment is waited on.
+    async fn send_until_confirmed(
+        &self,
+        transaction: &VersionedTransaction,
+        last_valid_block_height: u64,
+    ) -> Result<SendOutcome> {
+        let signature = self.rpc_client
+            .send_transaction(transaction)
+            .await
             .context("Failed to send transaction")?;
+        let give_up_at = Instant::now() + self.max_confirm_wait;
+        let mut resubmits = 0;
         
-        info!("✅ Transaction confirmed: {}", signature);
+        loop {
+            let deadline = (Instant::now() + self.confirm_timeout).min(give_up_at);
+            while Instant::now() < deadline {
+                if let Some(slot) = self.confirmed_slot(&signature).await? {
+                    return Ok(SendOutcome::Confirmed { signature, slot });
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            if Instant::now() >= give_up_at {
+                return Ok(SendOutcome::TimedOut(signature));
+            }
+            
+            let block_height = self.rpc_client
+                .get_block_height()
+                .await
+                .context("Failed to get block height")?;
+            match self.resubmit_policy.after_timeout(resubmits, block_height, last_valid_block_height) {
+                RetryAction::Resubmit => {
+                    resubmits += 1;
+                    warn!(
+                        "🔁 {} not confirmed after {}s, resubmitting ({}/{})",
+                        signature,
+                        self.confirm_timeout.as_secs(),
+                        resubmits,
+                        self.resubmit_policy.max_resubmits
+                    );
+                    if let Err(e) = self.rpc_client.send_transaction(transaction).await {
+                        warn!("Resubmission failed: {}", e);
+                    }
+                }
+                RetryAction::Wait => {
+                    info!("⏳ {} still pending (block {} / valid until {})", signature, block_height, last_valid_block_height);
+                }
+                RetryAction::Requote => {
+                    if let Some(outcome) = self.outcome_past_expiry(signature).await? {
+                        return Ok(outcome);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Final check of a `signature` whose blockhash expired: `Expired` only when
+    /// the full status history has never seen it, since re-quoting next to one
+    /// that did land would swap twice. None (keep waiting) when it landed below
+    /// the client's commitment; `TimedOut` (outcome unknown) when the history
+    /// can't be read.
+    async fn outcome_past_expiry(&self, signature: Signature) -> Result<Option<SendOutcome>> {
+        let status = match self.rpc_client.get_signature_statuses_with_history(&[signature]).await {
+            Ok(response) => response.value.into_iter().next().flatten(),
+            Err(e) => {
+                warn!("Signature history unavailable for {}, not re-quoting: {}", signature, e);
+                return Ok(Some(SendOutcome::TimedOut(signature)));
+            }
+        };
+        let Some(status) = status else {
+            return Ok(Some(SendOutcome::Expired));
+        };
+        if let Some(err) = &status.err {
+            anyhow::bail!("Transaction {} failed: {:?}", signature, err);
+        }
+        info!(
+            "⏳ {} landed in slot {} as its blockhash expired, waiting for {:?}",
+            signature,
+            status.slot,
+            self.rpc_client.commitment().commitment
+        );
+        Ok(None)
+    }
+    
+    /// Slot `signature` landed in once it reaches the client's commitment.
+    /// Errors if it landed but failed; RPC hiccups read as still pending.
+    async fn confirmed_slot(&self, signature: &Signature) -> Result<Option<u64>> {
+        let status = match self.rpc_client.get_signature_statuses(&[*signature]).await {
+            Ok(response) => response.value.into_iter().next().flatten(),
+            Err(e) => {
+                debug!("Signature status unavailable for {}: {}", signature, e);
+                None
+            }
+        };
+        match status {
+            Some(status) => {
+                if let Some(err) = &status.err {
+                    anyhow::bail!("Transaction {} failed: {:?}", signature, err);
+                }
+                Ok(status
+                    .satisfies_commitment(self.rpc_client.commitment())
+                    .then_some(status.slot))
+            }
+            None => Ok(None),
+        }
+    }
+    
+    fn record_realized_slippage(&self, expected_out: u64, actual_out: u64) {
+        let realized = realized_slippage_bps(expected_out, actual_out);
+        let mut model = self.slippage.lock().unwrap();
+        model.observe(realized);
+        info!(
+            "📐 Realized slippage: {:.2} bps (expected {}, got {}) | EWMA: {:.2} bps | next: {} bps",
+            realized,
+            expected_out,
+            actual_out,
+            model.ewma_bps().unwrap_or(realized),
+            model.current_bps()
+        );
+    }
+    
+    /// Executor's balance of `mint` in base units. SOL counts native and wrapped
+    /// together (swaps unwrap wSOL), so network fees show up as a tiny extra slippage.
+    async fn output_balance(&self, mint: &Pubkey) -> Result<u64> {
+        if *mint == Pubkey::from_str(NATIVE_MINT)? {
+            let sol = self.get_sol_balance().await?;
+            return Ok(sol.native_lamports + sol.wrapped_lamports);
+        }
+        self.token_balance(mint).await
+    }
+    
+    /// Request a devnet airdrop to the executor and wait until it is confirmed
+    pub async fn request_airdrop(&self, lamports: u64) -> Result<Signature> {
+        airdrop_and_confirm(&self.rpc_client, &self.executor.pubkey(), lamports).await
+    }
+    
+    /// Native SOL and wSOL held by the executor
+    pub async fn get_sol_balance(&self) -> Result<SolBalance> {
+        let native_lamports = self.rpc_client.get_balance(&self.executor.pubkey())
+            .await
+            .context("Failed to get SOL balance")?;
+        let wrapped_lamports = self.token_balance(&Pubkey::from_str(NATIVE_MINT)?).await?;
+        Ok(SolBalance::new(native_lamports, wrapped_lamports))
+    }
+    
+    /// Balance of the executor's associated token account for `mint`
+    async fn token_balance(&self, mint: &Pubkey) -> Result<u64> {
+        let owner = self.executor.pubkey();
+        let token_program = Pubkey::from_str(TOKEN_PROGRAM_ID)?;
+        let (ata, _) = Pubkey::find_program_address(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)?,
+        );
         
-        Ok(signature.to_string())
+        // A missing ATA simply means a zero balance (Jupiter creates it during the swap);
+        // any other failure is an error, not a zero that would skew realized slippage
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&ata, self.rpc_client.commitment())
+            .await
+            .with_context(|| format!("Failed to fetch token account {}", ata))?
+            .value;
+        match account {
+            Some(account) => token_account_amount(&account.data)
+                .with_context(|| format!("{} is not a token account", ata)),
+            None => Ok(0),
+        }
+    }
+}
+
+/// `amount` of an SPL token account: it follows the mint and owner pubkeys
+fn token_account_amount(data: &[u8]) -> Result<u64> {
+    let amount = data
+        .get(64..72)
+        .with_context(|| format!("Account data is {} bytes, a token account is 165", data.len()))?;
+    Ok(u64::from_le_bytes(amount.try_into()?))
+}
+
+fn token_balances(balances: OptionSerializer<Vec<UiTransactionTokenBalance>>) -> Vec<TokenBalance> {
+    Option::<Vec<_>>::from(balances)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|balance| {
+            Some(TokenBalance {
+                owner: Option::<String>::from(balance.owner)?,
+                mint: balance.mint,
+                amount: balance.ui_token_amount.amount.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Ask the faucet for `lamports` and poll until the airdrop lands (the faucet
+/// takes a few seconds and rate limits aggressively)
+async fn airdrop_and_confirm(rpc_client: &RpcClient, to: &Pubkey, lamports: u64) -> Result<Signature> {
+    let signature = rpc_client
+        .request_airdrop(to, lamports)
+        .await
+        .context("Airdrop request failed")?;
+    
+    for _ in 0..30 {
+        if rpc_client.confirm_transaction(&signature).await.unwrap_or(false) {
+            return Ok(signature);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    anyhow::bail!("Airdrop {} not confirmed after 30s", signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balances::{airdrop_request, LAMPORTS_PER_SOL};
+    use jupiter_client::{JupiterSwapResponse, RoutePlan, SwapInfo};
+    use crate::resubmit::SendRetryMode;
+    use crate::slippage::SlippageMode;
+    use futures::future::BoxFuture;
+    use solana_client::rpc_client::Mocks;
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::{instruction::Instruction, message::Message, transaction::Transaction};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    /// Quotes 1:1 and returns a no-op transaction paid by the requesting wallet
+    #[derive(Default)]
+    struct MockSwapProvider {
+        quotes: Arc<AtomicUsize>,
+        swaps: Arc<AtomicUsize>,
+        /// Stall building the first swap transaction this long, aging its quote
+        first_build_delay: Duration,
+        impact_searches: Arc<AtomicUsize>,
+        /// Largest size `max_size_for_impact` allows; unset allows any
+        max_impact_size: Option<u64>,
+    }
+
+    impl SwapProvider for MockSwapProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn get_quote<'a>(
+            &'a self,
+            input_mint: &'a str,
+            output_mint: &'a str,
+            amount: u64,
+            slippage_bps: u16,
+        ) -> BoxFuture<'a, Result<JupiterQuoteResponse>> {
+            self.quotes.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                Ok(JupiterQuoteResponse {
+                    input_mint: input_mint.to_string(),
+                    in_amount: amount.to_string(),
+                    output_mint: output_mint.to_string(),
+                    out_amount: amount.to_string(),
+                    other_amount_threshold: amount.to_string(),
+                    swap_mode: "ExactIn".to_string(),
+                    slippage_bps,
+                    platform_fee: None,
+                    price_impact_pct: "0".to_string(),
+                    route_plan: vec![RoutePlan {
+                        swap_info: SwapInfo {
+                            amm_key: "mock-amm".to_string(),
+                            label: None,
+                            input_mint: input_mint.to_string(),
+                            output_mint: output_mint.to_string(),
+                            in_amount: amount.to_string(),
+                            out_amount: amount.to_string(),
+                            fee_amount: "0".to_string(),
+                            fee_mint: input_mint.to_string(),
+                        },
+                        percent: 100,
+                    }],
+                })
+            })
+        }
+
+        fn get_swap_transaction<'a>(
+            &'a self,
+            _quote: &'a JupiterQuoteResponse,
+            user_public_key: &'a str,
+            _wrap_unwrap_sol: bool,
+            _priority_fee_micro_lamports: Option<u64>,
+        ) -> BoxFuture<'a, Result<JupiterSwapResponse>> {
+            let delay = if self.swaps.fetch_add(1, Ordering::SeqCst) == 0 {
+                self.first_build_delay
+            } else {
+                Duration::ZERO
+            };
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                let payer = Pubkey::from_str(user_public_key)?;
+                let noop = Instruction::new_with_bytes(Pubkey::new_unique(), &[], Vec::new());
+                let transaction =
+                    VersionedTransaction::from(Transaction::new_unsigned(Message::new(&[noop], Some(&payer))));
+                Ok(JupiterSwapResponse {
+                    swap_transaction: base64::engine::general_purpose::STANDARD
+                        .encode(bincode::serialize(&transaction)?),
+                    last_valid_block_height: 0,
+                })
+            })
+        }
+
+        fn get_price<'a>(&'a self, _input_mint: &'a str, _output_mint: &'a str) -> BoxFuture<'a, Result<f64>> {
+            Box::pin(async { Ok(1.0) })
+        }
+
+        fn max_size_for_impact<'a>(
+            &'a self,
+            _input_mint: &'a str,
+            _output_mint: &'a str,
+            _max_impact_pct: f64,
+            up_to: u64,
+        ) -> BoxFuture<'a, Result<Option<u64>>> {
+            self.impact_searches.fetch_add(1, Ordering::SeqCst);
+            let size = self.max_impact_size.map_or(up_to, |max| up_to.min(max));
+            Box::pin(async move { Ok(Some(size)) })
+        }
+    }
+
+    fn mock_executor(rpc_client: RpcClient, provider: MockSwapProvider, dry_run: bool) -> TradeExecutor {
+        TradeExecutor {
+            rpc_client,
+            executor: Keypair::new(),
+            vault_program_id: Pubkey::default(),
+            vault_state: Pubkey::default(),
+            swap_provider: Box::new(provider),
+            slippage: Mutex::new(SlippageModel::new(SlippageMode::Fixed, 50, 10, 0.2)),
+            in_flight: InFlight::default(),
+            dry_run,
+            confirm_latency: None,
+            resubmit_policy: ResubmitPolicy { mode: SendRetryMode::Requote, max_resubmits: 0 },
+            confirm_timeout: Duration::from_secs(30),
+            max_confirm_wait: Duration::from_secs(30),
+            max_requotes: 0,
+            max_quote_age: None,
+            priority_fee_micro_lamports: None,
+            verify_trades: false,
+            max_price_impact_pct: None,
+            impact_sizes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_swap_through_mock_provider() {
+        let provider = MockSwapProvider::default();
+        let (quotes, swaps) = (provider.quotes.clone(), provider.swaps.clone());
+        // Mock RPC answers blockhash and simulation requests successfully, but
+        // any send would fail: it answers with a signature that doesn't match
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::SendTransaction,
+            serde_json::json!(Signature::new_unique().to_string()),
+        );
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let executor = mock_executor(rpc_client, provider, true);
+
+        let result = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+
+        assert!(result.signature.starts_with("DRY_RUN_"));
+        assert!(!result.landed);
+        assert_eq!(quotes.load(Ordering::SeqCst), 1);
+        assert_eq!(swaps.load(Ordering::SeqCst), 1);
+        assert!(executor.in_flight().snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quote_beyond_limit_price_is_not_built() {
+        let provider = MockSwapProvider::default();
+        let swaps = provider.swaps.clone();
+        let executor = mock_executor(RpcClient::new_mock("succeeds".to_string()), provider, true);
+        // The mock quotes 1:1 in raw units: 0.001 SOL sells for 1 USDC, $1000 per SOL
+        let limit = |price| PriceLimit { is_buy: false, price, base_decimals: 9, quote_decimals: 6 };
+
+        let err = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, Some(&limit(1_001.0)))
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("below the sell limit"), "{:#}", err);
+        assert_eq!(swaps.load(Ordering::SeqCst), 0);
+
+        let result = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, Some(&limit(999.0)))
+            .await
+            .unwrap();
+        assert!(result.signature.starts_with("DRY_RUN_"));
+        assert_eq!(swaps.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_leg_below_its_quoted_minimum_aborts() {
+        let provider = MockSwapProvider::default();
+        let swaps = provider.swaps.clone();
+        let executor = mock_executor(RpcClient::new_mock("succeeds".to_string()), provider, true);
+        let leg = |input_mint: &str, output_mint: &str, min_out| CycleLeg {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            amount_in: 1_000_000,
+            expected_out: min_out,
+            min_out,
+        };
+
+        // The mock still quotes 1:1: the first leg holds its rate, the second fell short
+        let cycle = ArbCycle {
+            legs: vec![leg(NATIVE_MINT, USDC_MINT, 1_000_000), leg(USDC_MINT, NATIVE_MINT, 1_000_001)],
+            net_bps: 0.0,
+            fee_cost: 0,
+        };
+        let err = executor.execute_cycle(&cycle).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Cycle aborted at leg 2"), "{:#}", err);
+        assert!(format!("{:#}", err).contains("below the sell limit"), "{:#}", err);
+        assert_eq!(swaps.load(Ordering::SeqCst), 1);
+
+        // Quoted at the cycle's rates, every leg runs
+        let cycle = ArbCycle {
+            legs: vec![leg(NATIVE_MINT, USDC_MINT, 1_000_000), leg(USDC_MINT, NATIVE_MINT, 1_000_000)],
+            ..cycle
+        };
+        assert_eq!(executor.execute_cycle(&cycle).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_quote_is_refreshed_before_send() {
+        let provider = MockSwapProvider {
+            first_build_delay: Duration::from_millis(300),
+            ..Default::default()
+        };
+        let (quotes, swaps) = (provider.quotes.clone(), provider.swaps.clone());
+        let mut executor = mock_executor(RpcClient::new_mock("succeeds".to_string()), provider, true);
+        executor.max_quote_age = Some(Duration::from_millis(200));
+
+        executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+
+        // The first quote aged past the limit while its transaction was built
+        assert_eq!(quotes.load(Ordering::SeqCst), 2);
+        assert_eq!(swaps.load(Ordering::SeqCst), 2);
+
+        // Within the limit, the first quote is used as is
+        let provider = MockSwapProvider::default();
+        let quotes = provider.quotes.clone();
+        let mut executor = mock_executor(RpcClient::new_mock("succeeds".to_string()), provider, true);
+        executor.max_quote_age = Some(Duration::from_millis(200));
+        executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+        assert_eq!(quotes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_swap_reports_slot_once_confirmed() {
+        // First status poll finds nothing yet; later polls fall through to the
+        // mock's default finalized status at slot 1
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetSignatureStatuses,
+            serde_json::json!({ "context": { "slot": 1 }, "value": [null] }),
+        );
+        let rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let mut executor = mock_executor(rpc_client, MockSwapProvider::default(), false);
+        // The mock's stock transaction isn't the executor's: verification fails, the trade still counts
+        executor.verify_trades = true;
+
+        let started = Instant::now();
+        let result = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+
+        assert!(result.landed);
+        assert_eq!(result.confirmed_slot, Some(1));
+        assert_eq!(result.verification, None);
+        assert!(started.elapsed() >= Duration::from_millis(500));
+        assert!(executor.in_flight().snapshot().is_empty());
+    }
+
+    /// Status of the mock's transaction at slot 7 and `commitment`
+    fn signature_status(commitment: &str) -> serde_json::Value {
+        // Finalized transactions report no confirmation count
+        let confirmations = (commitment != "finalized").then_some(0);
+        serde_json::json!({
+            "context": { "slot": 7 },
+            "value": [{
+                "slot": 7,
+                "confirmations": confirmations,
+                "err": null,
+                "status": { "Ok": null },
+                "confirmationStatus": commitment,
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_expired_swap_that_landed_is_not_requoted() {
+        // The mock's blockhash is valid through block height 1234 and the first
+        // height read is past it. The only poll before expiry finds nothing; the
+        // history check does find the transaction, processed, and the next poll
+        // sees it finalized, the mock client's commitment.
+        let mut mocks = MocksMap::default();
+        mocks.insert(RpcRequest::GetBlockHeight, serde_json::json!(1235));
+        mocks.insert(RpcRequest::GetSignatureStatuses, serde_json::json!({ "context": { "slot": 1 }, "value": [null] }));
+        mocks.insert(RpcRequest::GetSignatureStatuses, signature_status("processed"));
+        mocks.insert(RpcRequest::GetSignatureStatuses, signature_status("finalized"));
+        let provider = MockSwapProvider::default();
+        let swaps = provider.swaps.clone();
+        let mut executor = mock_executor(RpcClient::new_mock_with_mocks_map("succeeds", mocks), provider, false);
+        executor.confirm_timeout = Duration::from_millis(100);
+        executor.max_requotes = 1;
+
+        let result = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+
+        assert!(result.landed);
+        assert_eq!(result.confirmed_slot, Some(7));
+        assert_eq!(swaps.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_history_after_expiry_is_unknown_not_expired() {
+        let mut mocks = MocksMap::default();
+        mocks.insert(RpcRequest::GetBlockHeight, serde_json::json!(1235));
+        mocks.insert(RpcRequest::GetSignatureStatuses, serde_json::json!({ "context": { "slot": 1 }, "value": [null] }));
+        mocks.insert(RpcRequest::GetSignatureStatuses, serde_json::json!("not a status response"));
+        let provider = MockSwapProvider::default();
+        let swaps = provider.swaps.clone();
+        let mut executor = mock_executor(RpcClient::new_mock_with_mocks_map("succeeds", mocks), provider, false);
+        executor.confirm_timeout = Duration::from_millis(100);
+        executor.max_requotes = 1;
+
+        let result = executor
+            .execute_swap(NATIVE_MINT, USDC_MINT, 1_000_000, 50, None)
+            .await
+            .unwrap();
+
+        assert!(!result.landed);
+        assert_eq!(swaps.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_balance_errors_are_not_zero() {
+        let usdc = Pubkey::from_str(USDC_MINT).unwrap();
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&42u64.to_le_bytes());
+        let account = |value| {
+            let mut mocks = Mocks::new();
+            mocks.insert(RpcRequest::GetAccountInfo, serde_json::json!({ "context": { "slot": 1 }, "value": value }));
+            RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+        };
+
+        let executor = mock_executor(
+            account(serde_json::json!({
+                "data": [base64::engine::general_purpose::STANDARD.encode(&data), "base64"],
+                "executable": false,
+                "lamports": 2_039_280,
+                "owner": TOKEN_PROGRAM_ID,
+                "rentEpoch": 0,
+                "space": 165,
+            })),
+            MockSwapProvider::default(),
+            false,
+        );
+        assert_eq!(executor.token_balance(&usdc).await.unwrap(), 42);
+
+        // No ATA yet: nothing held
+        let executor = mock_executor(account(serde_json::Value::Null), MockSwapProvider::default(), false);
+        assert_eq!(executor.token_balance(&usdc).await.unwrap(), 0);
+
+        // An unreachable RPC is an error, not an empty wallet
+        let executor = mock_executor(RpcClient::new_mock("fails".to_string()), MockSwapProvider::default(), false);
+        assert!(executor.token_balance(&usdc).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verification_reads_fee_and_deltas() {
+        let mut executor = mock_executor(
+            RpcClient::new_mock("succeeds".to_string()),
+            MockSwapProvider::default(),
+            false,
+        );
+        let owner = executor.executor.pubkey().to_string();
+        // The landed swap, signed by the executor as fee payer
+        let noop = Instruction::new_with_bytes(Pubkey::new_unique(), &[], Vec::new());
+        let message = Message::new(&[noop], Some(&executor.executor.pubkey()));
+        let transaction = VersionedTransaction::from(Transaction::new(
+            &[&executor.executor],
+            message,
+            solana_sdk::hash::Hash::new_unique(),
+        ));
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap());
+
+        // A USDC -> SOL buy: 7.5 USDC out of the wallet, 0.05 SOL in, 5000 lamports fee
+        let usdc = |amount: u64| {
+            serde_json::json!({
+                "accountIndex": 1,
+                "mint": USDC_MINT,
+                "uiTokenAmount": { "uiAmount": null, "decimals": 6, "amount": amount.to_string(), "uiAmountString": "" },
+                "owner": owner,
+                "programId": TOKEN_PROGRAM_ID,
+            })
+        };
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetTransaction,
+            serde_json::json!({
+                "slot": 1,
+                "blockTime": null,
+                "transaction": [encoded, "base64"],
+                "meta": {
+                    "err": null,
+                    "status": { "Ok": null },
+                    "fee": 5_000,
+                    "preBalances": [1_000_000_000u64, 0],
+                    "postBalances": [1_049_995_000u64, 0],
+                    "preTokenBalances": [usdc(10_000_000)],
+                    "postTokenBalances": [usdc(2_500_000)],
+                },
+            }),
+        );
+        executor.rpc_client = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+
+        let quote = JupiterQuoteResponse {
+            input_mint: USDC_MINT.to_string(),
+            in_amount: "7500000".to_string(),
+            output_mint: NATIVE_MINT.to_string(),
+            out_amount: "50100000".to_string(),
+            other_amount_threshold: "49900000".to_string(),
+            swap_mode: "ExactIn".to_string(),
+            slippage_bps: 50,
+            platform_fee: None,
+            price_impact_pct: "0".to_string(),
+            route_plan: Vec::new(),
+        };
+        let verification = executor
+            .verify_swap(&transaction.signatures[0], &quote)
+            .await
+            .unwrap()
+            .expect("transaction is available");
+
+        assert_eq!(verification.fee_lamports, 5_000);
+        assert_eq!(verification.input_delta, -7_500_000);
+        // The fee is added back: the SOL leg is the swap output alone
+        assert_eq!(verification.output_delta, 50_000_000);
+        assert!(verification.is_clean());
+    }
+
+    /// Hits the public devnet faucet: `cargo test -- --ignored test_airdrop_on_devnet`
+    #[tokio::test]
+    #[ignore]
+    async fn test_airdrop_on_devnet() {
+        let rpc_client = RpcClient::new("https://api.devnet.solana.com".to_string());
+        let wallet = Keypair::new();
+        
+        assert_eq!(airdrop_request("mainnet", 0, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL / 10), None);
+        let lamports = airdrop_request("devnet", 0, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL / 10).unwrap();
+        
+        airdrop_and_confirm(&rpc_client, &wallet.pubkey(), lamports).await.unwrap();
+        assert_eq!(rpc_client.get_balance(&wallet.pubkey()).await.unwrap(), lamports);
+    }
+
+    #[tokio::test]
+    async fn test_impact_search_is_reused_while_fresh() {
+        let provider = MockSwapProvider { max_impact_size: Some(3_000), ..Default::default() };
+        let searches = provider.impact_searches.clone();
+        let mut executor = mock_executor(RpcClient::new_mock("succeeds".to_string()), provider, true);
+        executor.max_price_impact_pct = Some(0.5);
+        let pair = TradingPair {
+            base_token: "SOL".to_string(),
+            quote_token: "USDC".to_string(),
+            base_mint: NATIVE_MINT.to_string(),
+            quote_mint: USDC_MINT.to_string(),
+        };
+        let buy = |amount| TradeSignal::Buy { amount, reason: String::new(), limit_price: None };
+        let amount = |signal: TradeSignal| match signal {
+            TradeSignal::Buy { amount, .. } => amount,
+            other => panic!("expected Buy, got {:?}", other),
+        };
+
+        // One search clamps 5_000 to 3_000, and answers larger and smaller buys after it
+        assert_eq!(amount(executor.size_for_impact(buy(5_000), &pair).await.unwrap()), 3_000);
+        assert_eq!(amount(executor.size_for_impact(buy(8_000), &pair).await.unwrap()), 3_000);
+        assert_eq!(amount(executor.size_for_impact(buy(2_000), &pair).await.unwrap()), 2_000);
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+
+        // Sells quote the other way round, so they search for themselves
+        let sell = TradeSignal::Sell { amount: 1_000, reason: String::new(), limit_price: None };
+        executor.size_for_impact(sell, &pair).await.unwrap();
+        assert_eq!(searches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_swap_amounts_prefer_verified_deltas() {
+        let mut result = SwapResult {
+            signature: "sig".to_string(),
+            confirmed_slot: Some(1),
+            landed: true,
+            verification: None,
+            quoted_in: 150_000_000,
+            quoted_out: 1_000_000_000,
+        };
+        assert_eq!(result.amounts(), (150_000_000, 1_000_000_000));
+
+        // Slipped: fewer base units arrived than quoted
+        result.verification = Some(TradeVerification {
+            fee_lamports: 5_000,
+            input_delta: -150_000_000,
+            output_delta: 990_000_000,
+            discrepancies: Vec::new(),
+            route: None,
+        });
+        assert_eq!(result.amounts(), (150_000_000, 990_000_000));
     }
 }
@@ -0,0 +1,259 @@
+use anyhow::Result;
+
+use crate::price_tracker::PriceTracker;
+use crate::state::BotState;
+
+/// Outcome of a single guard check
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuardDecision {
+    Allow,
+    Suppress(String),
+}
+
+/// Everything a guard may inspect when deciding whether to let a signal through
+pub struct GuardContext<'a> {
+    pub state: &'a BotState,
+    pub price_tracker: &'a PriceTracker,
+}
+
+/// A pre-trade check that can veto signal generation
+pub trait SignalGuard: Send + Sync {
+    fn name(&self) -> &str;
+    fn check(&self, ctx: &GuardContext) -> GuardDecision;
+}
+
+/// Suppresses trading while a post-trade cooldown is active
+pub struct CooldownGuard;
+
+impl SignalGuard for CooldownGuard {
+    fn name(&self) -> &str {
+        "cooldown"
+    }
+
+    fn check(&self, ctx: &GuardContext) -> GuardDecision {
+        match ctx.state.cooldown_until {
+            Some(until) if chrono::Utc::now() < until => {
+                let remaining = until.signed_duration_since(chrono::Utc::now());
+                GuardDecision::Suppress(format!(
+                    "in cooldown for {} more seconds",
+                    remaining.num_seconds()
+                ))
+            }
+            _ => GuardDecision::Allow,
+        }
+    }
+}
+
+/// Suppresses trading once the daily trade budget is spent
+pub struct DailyLimitGuard {
+    max_daily_trades: usize,
+}
+
+impl DailyLimitGuard {
+    pub fn new(max_daily_trades: usize) -> Self {
+        Self { max_daily_trades }
+    }
+}
+
+impl SignalGuard for DailyLimitGuard {
+    fn name(&self) -> &str {
+        "daily_limit"
+    }
+
+    fn check(&self, ctx: &GuardContext) -> GuardDecision {
+        if ctx.state.daily_trade_count >= self.max_daily_trades {
+            GuardDecision::Suppress(format!(
+                "daily trade limit reached: {}/{}",
+                ctx.state.daily_trade_count, self.max_daily_trades
+            ))
+        } else {
+            GuardDecision::Allow
+        }
+    }
+}
+
+/// Suppresses trading until the tracker holds enough history for the lookback window
+pub struct WarmupGuard {
+    lookback_minutes: usize,
+}
+
+impl WarmupGuard {
+    pub fn new(lookback_minutes: usize) -> Self {
+        Self { lookback_minutes }
+    }
+}
+
+impl SignalGuard for WarmupGuard {
+    fn name(&self) -> &str {
+        "warmup"
+    }
+
+    fn check(&self, ctx: &GuardContext) -> GuardDecision {
+        if ctx.price_tracker.has_sufficient_data(self.lookback_minutes) {
+            GuardDecision::Allow
+        } else {
+            GuardDecision::Suppress(format!(
+                "warming up: {} price updates so far, need {}min of history",
+                ctx.price_tracker.update_count(),
+                self.lookback_minutes
+            ))
+        }
+    }
+}
+
+/// Ordered list of guards; the first guard to suppress wins
+pub struct GuardChain {
+    guards: Vec<Box<dyn SignalGuard>>,
+}
+
+/// A suppression produced by the chain, naming the guard responsible
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    pub guard: String,
+    pub reason: String,
+}
+
+impl GuardChain {
+    pub fn new(guards: Vec<Box<dyn SignalGuard>>) -> Self {
+        Self { guards }
+    }
+
+    /// Build a chain from guard names in evaluation order (e.g. `SIGNAL_GUARDS=warmup,cooldown`).
+    /// Guards not listed are disabled.
+    pub fn from_names(
+        names: &[String],
+        max_daily_trades: usize,
+        lookback_minutes: usize,
+    ) -> Result<Self> {
+        let mut guards: Vec<Box<dyn SignalGuard>> = Vec::with_capacity(names.len());
+
+        for name in names {
+            let guard: Box<dyn SignalGuard> = match name.trim().to_lowercase().as_str() {
+                "cooldown" => Box::new(CooldownGuard),
+                "daily_limit" => Box::new(DailyLimitGuard::new(max_daily_trades)),
+                "warmup" => Box::new(WarmupGuard::new(lookback_minutes)),
+                other => anyhow::bail!(
+                    "Unknown signal guard: {}. Options: cooldown, daily_limit, warmup",
+                    other
+                ),
+            };
+            guards.push(guard);
+        }
+
+        Ok(Self::new(guards))
+    }
+
+    /// Evaluate guards in order, returning the first suppression (if any)
+    pub fn evaluate(&self, ctx: &GuardContext) -> Option<Suppression> {
+        self.guards.iter().find_map(|guard| match guard.check(ctx) {
+            GuardDecision::Allow => None,
+            GuardDecision::Suppress(reason) => Some(Suppression {
+                guard: guard.name().to_string(),
+                reason,
+            }),
+        })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.guards.iter().map(|g| g.name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn warm_tracker() -> PriceTracker {
+        // 1 minute lookback needs 18 points (30% of 60)
+        let mut tracker = PriceTracker::new(1);
+        let now = chrono::Utc::now().timestamp();
+        for i in 0..20 {
+            tracker.add_price(100.0, 1.0, now + i);
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_all_guards_allow() {
+        let chain = GuardChain::from_names(&names(&["cooldown", "daily_limit", "warmup"]), 5, 1)
+            .unwrap();
+        let state = BotState::new();
+        let tracker = warm_tracker();
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        assert_eq!(chain.evaluate(&ctx), None);
+    }
+
+    #[test]
+    fn test_cooldown_suppresses() {
+        let chain = GuardChain::from_names(&names(&["cooldown", "daily_limit"]), 5, 1).unwrap();
+        let mut state = BotState::new();
+        state.set_cooldown(10);
+        let tracker = warm_tracker();
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        let suppression = chain.evaluate(&ctx).expect("cooldown should suppress");
+        assert_eq!(suppression.guard, "cooldown");
+    }
+
+    #[test]
+    fn test_daily_limit_suppresses() {
+        let chain = GuardChain::from_names(&names(&["cooldown", "daily_limit"]), 2, 1).unwrap();
+        let mut state = BotState::new();
+        state.record_trade();
+        state.record_trade();
+        let tracker = warm_tracker();
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        let suppression = chain.evaluate(&ctx).expect("daily limit should suppress");
+        assert_eq!(suppression.guard, "daily_limit");
+        assert!(suppression.reason.contains("2/2"));
+    }
+
+    #[test]
+    fn test_warmup_suppresses_empty_tracker() {
+        let chain = GuardChain::from_names(&names(&["warmup"]), 5, 1).unwrap();
+        let state = BotState::new();
+        let tracker = PriceTracker::new(1);
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        assert_eq!(chain.evaluate(&ctx).unwrap().guard, "warmup");
+    }
+
+    #[test]
+    fn test_order_determines_reported_guard() {
+        let mut state = BotState::new();
+        state.set_cooldown(10);
+        state.record_trade();
+        let tracker = PriceTracker::new(1);
+
+        let warmup_first =
+            GuardChain::from_names(&names(&["warmup", "cooldown", "daily_limit"]), 1, 1).unwrap();
+        let limit_first =
+            GuardChain::from_names(&names(&["daily_limit", "cooldown", "warmup"]), 1, 1).unwrap();
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        assert_eq!(warmup_first.evaluate(&ctx).unwrap().guard, "warmup");
+        assert_eq!(limit_first.evaluate(&ctx).unwrap().guard, "daily_limit");
+    }
+
+    #[test]
+    fn test_disabled_guard_is_skipped() {
+        let chain = GuardChain::from_names(&names(&["daily_limit"]), 5, 1).unwrap();
+        let mut state = BotState::new();
+        state.set_cooldown(10);
+        let tracker = PriceTracker::new(1);
+
+        let ctx = GuardContext { state: &state, price_tracker: &tracker };
+        assert_eq!(chain.evaluate(&ctx), None);
+    }
+
+    #[test]
+    fn test_unknown_guard_is_rejected() {
+        assert!(GuardChain::from_names(&names(&["cooldown", "moon_phase"]), 5, 1).is_err());
+    }
+}
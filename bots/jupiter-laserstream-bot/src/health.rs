@@ -0,0 +1,294 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::BotConfig;
+use crate::strategies::TradeSignal;
+
+/// Overall health, from best to worst. Trading runs at full size when
+/// healthy, at reduced size with longer cooldowns when degraded, and stops
+/// when unhealthy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    #[default]
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Inputs sampled once per tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HealthSignals {
+    /// Seconds since the stream last delivered a new slot
+    pub feed_age_secs: u64,
+    /// Round trip of the RPC liveness probe; `u64::MAX` when it failed
+    pub rpc_latency_ms: u64,
+    /// How far the stream's slot trails the RPC's
+    pub slot_lag: u64,
+}
+
+/// Highest value of each signal still inside a state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthLimits {
+    pub max_feed_age_secs: u64,
+    pub max_rpc_latency_ms: u64,
+    pub max_slot_lag: u64,
+    /// Trades that failed in a row
+    pub max_consecutive_failures: u32,
+}
+
+impl HealthLimits {
+    /// First limit `signals` break, as a log-friendly reason
+    fn breach(&self, signals: &HealthSignals, consecutive_failures: u32) -> Option<String> {
+        if signals.feed_age_secs > self.max_feed_age_secs {
+            Some(format!("feed {}s stale (limit {}s)", signals.feed_age_secs, self.max_feed_age_secs))
+        } else if signals.rpc_latency_ms == u64::MAX {
+            Some("RPC unreachable".to_string())
+        } else if signals.rpc_latency_ms > self.max_rpc_latency_ms {
+            Some(format!("RPC latency {}ms (limit {}ms)", signals.rpc_latency_ms, self.max_rpc_latency_ms))
+        } else if signals.slot_lag > self.max_slot_lag {
+            Some(format!("stream {} slots behind RPC (limit {})", signals.slot_lag, self.max_slot_lag))
+        } else if consecutive_failures > self.max_consecutive_failures {
+            Some(format!(
+                "{} consecutive failed trades (limit {})",
+                consecutive_failures, self.max_consecutive_failures
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Derives a `HealthState` from the latest signals and scales trading to it.
+/// A failure streak is forgotten `failure_reset` after its last failure, so a
+/// halt caused by failed trades (which stops new trades) can clear.
+pub struct HealthMonitor {
+    degraded: HealthLimits,
+    unhealthy: HealthLimits,
+    degraded_size_factor: f64,
+    degraded_cooldown_multiplier: u64,
+    failure_reset: Duration,
+    state: HealthState,
+    reason: Option<String>,
+    consecutive_failures: u32,
+    last_failure_at: Option<DateTime<Utc>>,
+}
+
+impl HealthMonitor {
+    /// Signals above `degraded` limits degrade trading; above `unhealthy` they halt it
+    pub fn new(degraded: HealthLimits, unhealthy: HealthLimits) -> Self {
+        Self {
+            degraded,
+            unhealthy,
+            degraded_size_factor: 0.5,
+            degraded_cooldown_multiplier: 2,
+            failure_reset: Duration::minutes(10),
+            state: HealthState::Healthy,
+            reason: None,
+            consecutive_failures: 0,
+            last_failure_at: None,
+        }
+    }
+
+    /// Trade size factor and cooldown multiplier applied while degraded
+    pub fn with_degraded_trading(mut self, size_factor: f64, cooldown_multiplier: u64) -> Self {
+        self.degraded_size_factor = size_factor.clamp(0.0, 1.0);
+        self.degraded_cooldown_multiplier = cooldown_multiplier.max(1);
+        self
+    }
+
+    pub fn with_failure_reset(mut self, secs: u64) -> Self {
+        self.failure_reset = Duration::seconds(secs as i64);
+        self
+    }
+
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self::new(config.health_degraded, config.health_unhealthy)
+            .with_degraded_trading(config.degraded_size_factor, config.degraded_cooldown_multiplier)
+            .with_failure_reset(config.health_failure_reset_secs)
+    }
+
+    pub fn state(&self) -> HealthState {
+        self.state
+    }
+
+    /// Why the monitor is not healthy
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub fn record_trade(&mut self, success: bool, now: DateTime<Utc>) {
+        if success {
+            self.consecutive_failures = 0;
+            self.last_failure_at = None;
+        } else {
+            self.consecutive_failures += 1;
+            self.last_failure_at = Some(now);
+        }
+    }
+
+    /// Re-derive the state from `signals`, logging any transition
+    pub fn update(&mut self, signals: HealthSignals, now: DateTime<Utc>) -> HealthState {
+        if self
+            .last_failure_at
+            .is_some_and(|at| now.signed_duration_since(at) >= self.failure_reset)
+        {
+            self.consecutive_failures = 0;
+            self.last_failure_at = None;
+        }
+
+        let (state, reason) = if let Some(reason) = self.unhealthy.breach(&signals, self.consecutive_failures) {
+            (HealthState::Unhealthy, Some(reason))
+        } else if let Some(reason) = self.degraded.breach(&signals, self.consecutive_failures) {
+            (HealthState::Degraded, Some(reason))
+        } else {
+            (HealthState::Healthy, None)
+        };
+
+        if state != self.state {
+            match (state, &reason) {
+                (HealthState::Healthy, _) => info!("💚 Health: {:?} -> Healthy", self.state),
+                (_, Some(reason)) => warn!("🩺 Health: {:?} -> {:?}: {}", self.state, state, reason),
+                (_, None) => {}
+            }
+        }
+        self.state = state;
+        self.reason = reason;
+        state
+    }
+
+    pub fn allows_trading(&self) -> bool {
+        self.state != HealthState::Unhealthy
+    }
+
+    /// `signal` sized for the current state: unchanged when healthy, scaled down
+    /// when degraded, None when trading is halted
+    pub fn scale_signal(&self, signal: TradeSignal) -> Option<TradeSignal> {
+        let factor = match self.state {
+            HealthState::Healthy => return Some(signal),
+            HealthState::Degraded => self.degraded_size_factor,
+            HealthState::Unhealthy => return None,
+        };
+        let scale = |amount: u64| (amount as f64 * factor) as u64;
+        match signal {
+            TradeSignal::Buy { amount, reason, limit_price } => Some(TradeSignal::Buy {
+                amount: scale(amount),
+                reason: format!("{} (degraded: {:.0}% size)", reason, factor * 100.0),
+                limit_price,
+            }),
+            TradeSignal::Sell { amount, reason, limit_price } => Some(TradeSignal::Sell {
+                amount: scale(amount),
+                reason: format!("{} (degraded: {:.0}% size)", reason, factor * 100.0),
+                limit_price,
+            }),
+            TradeSignal::Hold => Some(TradeSignal::Hold),
+        }
+    }
+
+    /// Post-trade cooldown for the current state
+    pub fn cooldown_minutes(&self, base_minutes: u64) -> u64 {
+        match self.state {
+            HealthState::Degraded => base_minutes * self.degraded_cooldown_multiplier,
+            _ => base_minutes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> HealthMonitor {
+        let degraded = HealthLimits {
+            max_feed_age_secs: 15,
+            max_rpc_latency_ms: 1_500,
+            max_slot_lag: 30,
+            max_consecutive_failures: 2,
+        };
+        let unhealthy = HealthLimits {
+            max_feed_age_secs: 60,
+            max_rpc_latency_ms: 5_000,
+            max_slot_lag: 150,
+            max_consecutive_failures: 5,
+        };
+        HealthMonitor::new(degraded, unhealthy)
+            .with_degraded_trading(0.5, 2)
+            .with_failure_reset(600)
+    }
+
+    fn signals(feed_age_secs: u64, rpc_latency_ms: u64, slot_lag: u64) -> HealthSignals {
+        HealthSignals {
+            feed_age_secs,
+            rpc_latency_ms,
+            slot_lag,
+        }
+    }
+
+    fn buy(amount: u64) -> TradeSignal {
+        TradeSignal::Buy { amount, reason: "test".to_string(), limit_price: None }
+    }
+
+    fn buy_amount(signal: Option<TradeSignal>) -> Option<u64> {
+        match signal {
+            Some(TradeSignal::Buy { amount, .. }) => Some(amount),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_health_transitions_scale_trading() {
+        let mut health = monitor();
+        let now = Utc::now();
+
+        assert_eq!(health.update(signals(2, 200, 3), now), HealthState::Healthy);
+        assert_eq!(buy_amount(health.scale_signal(buy(1_000))), Some(1_000));
+        assert_eq!(health.cooldown_minutes(10), 10);
+
+        // Slow RPC: half size, double cooldown
+        assert_eq!(health.update(signals(2, 2_000, 3), now), HealthState::Degraded);
+        assert!(health.reason().unwrap().contains("RPC latency"));
+        assert!(health.allows_trading());
+        assert_eq!(buy_amount(health.scale_signal(buy(1_000))), Some(500));
+        assert_eq!(health.cooldown_minutes(10), 20);
+
+        // Stale feed past the unhealthy limit halts trading
+        assert_eq!(health.update(signals(90, 200, 3), now), HealthState::Unhealthy);
+        assert!(!health.allows_trading());
+        assert!(health.scale_signal(buy(1_000)).is_none());
+
+        assert_eq!(health.update(signals(2, u64::MAX, 3), now), HealthState::Unhealthy);
+        assert_eq!(health.reason(), Some("RPC unreachable"));
+
+        // Everything recovers
+        assert_eq!(health.update(signals(2, 200, 3), now), HealthState::Healthy);
+        assert_eq!(health.reason(), None);
+        assert_eq!(health.cooldown_minutes(10), 10);
+
+        // Slot lag degrades too
+        assert_eq!(health.update(signals(2, 200, 40), now), HealthState::Degraded);
+
+        // A failure streak halts trading until it ages out
+        for _ in 0..3 {
+            health.record_trade(false, now);
+        }
+        assert_eq!(health.update(signals(2, 200, 3), now), HealthState::Degraded);
+        for _ in 0..3 {
+            health.record_trade(false, now);
+        }
+        assert_eq!(health.update(signals(2, 200, 3), now), HealthState::Unhealthy);
+        assert!(health.reason().unwrap().contains("6 consecutive failed trades"));
+        let later = now + Duration::seconds(599);
+        assert_eq!(health.update(signals(2, 200, 3), later), HealthState::Unhealthy);
+        let later = now + Duration::seconds(600);
+        assert_eq!(health.update(signals(2, 200, 3), later), HealthState::Healthy);
+
+        // A successful trade ends a streak straight away
+        health.record_trade(false, later);
+        health.record_trade(false, later);
+        health.record_trade(false, later);
+        assert_eq!(health.update(signals(2, 200, 3), later), HealthState::Degraded);
+        health.record_trade(true, later);
+        assert_eq!(health.update(signals(2, 200, 3), later), HealthState::Healthy);
+    }
+}
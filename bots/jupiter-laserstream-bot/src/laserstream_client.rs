@@ -1,18 +1,170 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use prometheus::IntCounter;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 use tracing::{info, debug, warn};
 
+/// Container `/latest` response. The containers serve `LatestSlot`, whose
+/// `created_at_rfc3339` is read as `timestamp` (empty when the stream sent none)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlotUpdate {
     pub slot: u64,
+    #[serde(alias = "created_at_rfc3339", default, deserialize_with = "null_as_empty")]
     pub timestamp: String,
 }
 
+fn null_as_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl SlotUpdate {
+    /// When the stream produced the slot: `timestamp` as RFC 3339, or unix seconds
+    pub fn observed_at(&self) -> Result<DateTime<Utc>> {
+        if let Ok(at) = DateTime::parse_from_rfc3339(&self.timestamp) {
+            return Ok(at.with_timezone(&Utc));
+        }
+        self.timestamp
+            .parse()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .with_context(|| format!("Unrecognized slot timestamp: {:?}", self.timestamp))
+    }
+
+    /// Seconds between the slot's timestamp and `now`
+    pub fn age_secs(&self, now: DateTime<Utc>) -> Result<i64> {
+        Ok(now.signed_duration_since(self.observed_at()?).num_seconds())
+    }
+}
+
+/// Container `/status` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerStatus {
+    pub network: String,
+    pub started: bool,
+    pub latest_slot: Option<u64>,
+}
+
+/// A swap the container parsed from a streamed transaction, in base units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerSwap {
+    pub slot: u64,
+    pub input_mint: String,
+    pub input_amount: u64,
+    pub output_mint: String,
+    pub output_amount: u64,
+}
+
+/// Container `/transactions` response, newest swap first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentSwaps {
+    /// False when the container streams slots only (no TRANSACTION_PROGRAM_IDS)
+    pub enabled: bool,
+    pub transactions: Vec<ContainerSwap>,
+}
+
+/// Fail when the container streams a different cluster than the bot trades on.
+/// `mainnet-beta` and `mainnet` are treated as the same cluster.
+pub fn check_network(expected: &str, reported: &str) -> Result<()> {
+    fn normalize(network: &str) -> String {
+        let network = network.trim().to_lowercase();
+        match network.as_str() {
+            "mainnet-beta" => "mainnet".to_string(),
+            _ => network,
+        }
+    }
+
+    if normalize(expected) != normalize(reported) {
+        anyhow::bail!(
+            "LaserStream container streams {} but SOLANA_NETWORK is {}",
+            reported,
+            expected
+        );
+    }
+    Ok(())
+}
+
+/// Adaptive `/latest` poll interval: doubles on each error or poll without a
+/// new slot, up to `max`, and drops back to `min` once fresh slots arrive
+#[derive(Debug, Clone)]
+pub struct PollBackoff {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+    last_slot: Option<u64>,
+}
+
+impl PollBackoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            current: min,
+            last_slot: None,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.current
+    }
+
+    /// Whether errors or stalls have pushed the interval above `min`
+    pub fn is_backed_off(&self) -> bool {
+        self.current > self.min
+    }
+
+    /// The newest slot seen so far
+    pub fn last_slot(&self) -> Option<u64> {
+        self.last_slot
+    }
+
+    /// Adjust the interval after a poll; returns the new one
+    pub fn observe(&mut self, result: &Result<Option<SlotUpdate>>) -> Duration {
+        let fresh_slot = match result {
+            Ok(Some(update)) if self.last_slot.is_none_or(|last| update.slot > last) => Some(update.slot),
+            _ => None,
+        };
+
+        match fresh_slot {
+            Some(slot) => {
+                if self.current > self.min {
+                    info!("📡 LaserStream data resumed, polling every {:?}", self.min);
+                }
+                self.last_slot = Some(slot);
+                self.current = self.min;
+            }
+            None => {
+                let next = (self.current * 2).min(self.max);
+                if next > self.current {
+                    debug!("No fresh LaserStream data, backing off to {:?}", next);
+                }
+                self.current = next;
+            }
+        }
+        self.current
+    }
+}
+
+/// Outcome of a `/latest?since=` request
+enum LongPoll {
+    Answered(Option<SlotUpdate>),
+    /// The container is healthy but saw no slot past `since` within the wait
+    NothingNewer,
+}
+
 pub struct LaserStreamClient {
     base_url: String,
     client: Client,
+    backoff: Mutex<PollBackoff>,
+    /// Counts each recovery from a backed-off (erroring or stalled) feed
+    reconnections: Option<IntCounter>,
+    /// Longest a `/latest?since=` request may block; None polls plainly
+    long_poll: Option<Duration>,
+    /// Set once the container shows it doesn't understand `since`
+    long_poll_unsupported: AtomicBool,
 }
 
 impl LaserStreamClient {
@@ -25,6 +177,44 @@ impl LaserStreamClient {
         Self {
             base_url: base_url.into(),
             client,
+            backoff: Mutex::new(PollBackoff::new(Duration::from_secs(2), Duration::from_secs(2))),
+            reconnections: None,
+            long_poll: None,
+            long_poll_unsupported: AtomicBool::new(false),
+        }
+    }
+
+    /// Poll every `min` while data flows, backing off towards `max` on errors or no data
+    pub fn with_poll_intervals(self, min: Duration, max: Duration) -> Self {
+        *self.backoff.lock().unwrap() = PollBackoff::new(min, max);
+        self
+    }
+
+    /// Increment `counter` whenever fresh data resumes after a backoff
+    pub fn with_reconnect_counter(mut self, counter: IntCounter) -> Self {
+        self.reconnections = Some(counter);
+        self
+    }
+
+    /// Have `get_latest` long-poll for up to `wait`; zero keeps plain polling
+    pub fn with_long_poll(mut self, wait: Duration) -> Self {
+        self.long_poll = (!wait.is_zero()).then_some(wait);
+        self
+    }
+
+    /// Whether `get_latest` long-polls (configured and not refused by the container)
+    pub fn is_long_polling(&self) -> bool {
+        self.long_poll.is_some() && !self.long_poll_unsupported.load(Ordering::Relaxed)
+    }
+
+    /// How long to wait before the next `/latest` poll. A long poll already
+    /// waited for the slot, so this is zero unless errors backed it off.
+    pub fn poll_interval(&self) -> Duration {
+        let backoff = self.backoff.lock().unwrap();
+        if self.is_long_polling() && !backoff.is_backed_off() {
+            Duration::ZERO
+        } else {
+            backoff.interval()
         }
     }
 
@@ -52,8 +242,98 @@ impl LaserStreamClient {
         Ok(())
     }
 
-    /// Get the latest slot update
+    /// Get the latest slot update, adapting `poll_interval` to the outcome.
+    /// Long-polls for a slot past the last one seen when `with_long_poll` is set.
     pub async fn get_latest(&self) -> Result<Option<SlotUpdate>> {
+        match self.long_poll {
+            Some(wait) => {
+                let last_slot = self.backoff.lock().unwrap().last_slot().unwrap_or(0);
+                let result = self.fetch_latest_since(last_slot, wait).await;
+                self.record_long_poll(result)
+            }
+            None => {
+                let result = self.fetch_latest().await;
+                self.record(result)
+            }
+        }
+    }
+
+    /// Ask the container to hold the request until a slot newer than
+    /// `last_slot` arrives or `wait` elapses (then `Ok(None)`). Falls back to a
+    /// plain `/latest` poll when the container has no long-poll support.
+    pub async fn get_latest_since(&self, last_slot: u64, wait: Duration) -> Result<Option<SlotUpdate>> {
+        let result = self.fetch_latest_since(last_slot, wait).await;
+        self.record_long_poll(result)
+    }
+
+    /// A long poll that waited out its window found the container healthy,
+    /// so it leaves the backoff alone rather than counting as a stall
+    fn record_long_poll(&self, result: Result<LongPoll>) -> Result<Option<SlotUpdate>> {
+        match result {
+            Ok(LongPoll::NothingNewer) => Ok(None),
+            Ok(LongPoll::Answered(update)) => self.record(Ok(update)),
+            Err(e) => self.record(Err(e)),
+        }
+    }
+
+    fn record(&self, result: Result<Option<SlotUpdate>>) -> Result<Option<SlotUpdate>> {
+        let mut backoff = self.backoff.lock().unwrap();
+        let was_backed_off = backoff.is_backed_off();
+        backoff.observe(&result);
+        if was_backed_off && !backoff.is_backed_off() {
+            if let Some(counter) = &self.reconnections {
+                counter.inc();
+            }
+        }
+        result
+    }
+
+    async fn fetch_latest_since(&self, last_slot: u64, wait: Duration) -> Result<LongPoll> {
+        if self.long_poll_unsupported.load(Ordering::Relaxed) {
+            return self.fetch_latest().await.map(LongPoll::Answered);
+        }
+
+        let url = format!("{}/latest?since={}&wait_ms={}", self.base_url, last_slot, wait.as_millis());
+
+        debug!("Long-polling LaserStream at {}", url);
+
+        let response = self.client
+            .get(&url)
+            .timeout(wait + Duration::from_secs(10))
+            .send()
+            .await
+            .context("Failed to long-poll latest slot")?;
+
+        match response.status() {
+            // Nothing newer within `wait`
+            StatusCode::NO_CONTENT => Ok(LongPoll::NothingNewer),
+            StatusCode::NOT_FOUND => {
+                info!("📡 LaserStream container does not support long polling, polling instead");
+                self.long_poll_unsupported.store(true, Ordering::Relaxed);
+                self.fetch_latest().await.map(LongPoll::Answered)
+            }
+            status if status.is_success() => {
+                let text = response.text().await?;
+                let update: SlotUpdate = serde_json::from_str(&text)
+                    .with_context(|| format!("Could not parse slot update: {}", text))?;
+                if update.slot <= last_slot {
+                    // The container ignored `since` and answered at once
+                    info!("📡 LaserStream container ignores ?since=, polling instead");
+                    self.long_poll_unsupported.store(true, Ordering::Relaxed);
+                    return Ok(LongPoll::Answered(Some(update)));
+                }
+                debug!("Received slot update: {:?}", update);
+                Ok(LongPoll::Answered(Some(update)))
+            }
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                debug!("LaserStream returned {}: {}", status, text);
+                Ok(LongPoll::Answered(None))
+            }
+        }
+    }
+
+    async fn fetch_latest(&self) -> Result<Option<SlotUpdate>> {
         let url = format!("{}/latest", self.base_url);
         
         debug!("Polling LaserStream at {}", url);
@@ -107,6 +387,38 @@ impl LaserStreamClient {
         Ok(response.status().is_success())
     }
 
+    /// The swaps the container parsed most recently
+    pub async fn get_recent_swaps(&self) -> Result<RecentSwaps> {
+        let url = format!("{}/transactions", self.base_url);
+
+        self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to get recent swaps")?
+            .error_for_status()
+            .context("LaserStream transactions request failed")?
+            .json()
+            .await
+            .context("Failed to parse recent swaps")
+    }
+
+    /// Container status, including the network it streams
+    pub async fn get_status(&self) -> Result<ContainerStatus> {
+        let url = format!("{}/status", self.base_url);
+
+        self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to get container status")?
+            .error_for_status()
+            .context("LaserStream status request failed")?
+            .json()
+            .await
+            .context("Failed to parse container status")
+    }
+
     /// Poll for slot updates with a callback
     pub async fn poll_updates<F>(&self, mut callback: F, interval: Duration) -> Result<()>
     where
@@ -136,6 +448,130 @@ impl LaserStreamClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_network_mismatch_is_rejected() {
+        assert!(check_network("devnet", "devnet").is_ok());
+        assert!(check_network("mainnet-beta", "mainnet").is_ok());
+        assert!(check_network("Devnet", " devnet ").is_ok());
+
+        let err = check_network("mainnet", "devnet").unwrap_err();
+        assert!(err.to_string().contains("streams devnet"));
+        assert!(check_network("devnet", "unknown").is_err());
+    }
+
+    #[test]
+    fn test_poll_interval_backs_off_and_resets() {
+        let mut backoff = PollBackoff::new(Duration::from_secs(2), Duration::from_secs(20));
+        let update = |slot| -> Result<Option<SlotUpdate>> {
+            Ok(Some(SlotUpdate { slot, timestamp: String::new() }))
+        };
+
+        assert_eq!(backoff.observe(&update(100)), Duration::from_secs(2));
+
+        // Errors, no data and a stuck slot all back off, capped at the max
+        let intervals: Vec<u64> = [
+            Err(anyhow::anyhow!("connection refused")),
+            Ok(None),
+            update(100),
+            Err(anyhow::anyhow!("connection refused")),
+            Err(anyhow::anyhow!("connection refused")),
+        ]
+        .iter()
+        .map(|result| backoff.observe(result).as_secs())
+        .collect();
+        assert_eq!(intervals, vec![4, 8, 16, 20, 20]);
+
+        // A new slot resets to the base interval
+        assert_eq!(backoff.observe(&update(101)), Duration::from_secs(2));
+        assert_eq!(backoff.interval(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_slot_age_from_timestamp() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let update = |timestamp: &str| SlotUpdate { slot: 1, timestamp: timestamp.to_string() };
+
+        assert_eq!(update("2025-01-01T11:59:58.500+00:00").age_secs(now).unwrap(), 1);
+        // A stalled stream keeps serving the same old slot
+        assert_eq!(update("2025-01-01T11:58:00Z").age_secs(now).unwrap(), 120);
+        assert_eq!(update(&(now.timestamp() - 45).to_string()).age_secs(now).unwrap(), 45);
+        assert!(update("").age_secs(now).is_err());
+        assert!(update("yesterday").age_secs(now).is_err());
+    }
+
+    #[test]
+    fn test_parses_container_latest_slot() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let update: SlotUpdate = serde_json::from_str(
+            r#"{"slot":42,"parent":41,"status":"SlotProcessed","created_at_rfc3339":"2025-01-01T11:59:50Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(update.slot, 42);
+        assert_eq!(update.age_secs(now).unwrap(), 10);
+
+        // No creation time from the stream: parses, but its age is unknown
+        let update: SlotUpdate = serde_json::from_str(
+            r#"{"slot":42,"parent":null,"status":"SlotProcessed","created_at_rfc3339":null}"#,
+        )
+        .unwrap();
+        assert!(update.age_secs(now).is_err());
+    }
+
+    /// Container whose `/latest` long-polls (answering slot `since + 1`, or 204
+    /// once `since` reaches 100) or, without long-poll support, 404s any `?since=` request
+    async fn mock_container(long_poll: bool) -> String {
+        let app = axum::Router::new().route(
+            "/latest",
+            axum::routing::get(move |query: axum::extract::RawQuery| async move {
+                let since = query.0.as_deref().and_then(|q| {
+                    q.split('&').find_map(|kv| kv.strip_prefix("since="))?.parse::<u64>().ok()
+                });
+                let slot = match (since, long_poll) {
+                    (Some(_), false) => return (axum::http::StatusCode::NOT_FOUND, String::new()),
+                    (Some(since), true) if since >= 100 => return (axum::http::StatusCode::NO_CONTENT, String::new()),
+                    (Some(since), true) => since + 1,
+                    (None, _) => 500,
+                };
+                let body = format!(
+                    r#"{{"slot":{},"parent":{},"status":"SlotProcessed","created_at_rfc3339":"2025-01-01T00:00:00Z"}}"#,
+                    slot,
+                    slot - 1
+                );
+                (axum::http::StatusCode::OK, body)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_and_fallback() {
+        let wait = Duration::from_millis(500);
+
+        let client = LaserStreamClient::new(mock_container(true).await).with_long_poll(wait);
+        let update = client.get_latest_since(41, wait).await.unwrap().unwrap();
+        assert_eq!(update.slot, 42);
+        // Following slots are requested past the last one seen, with no pause between polls
+        assert_eq!(client.get_latest().await.unwrap().unwrap().slot, 43);
+        assert!(client.is_long_polling());
+        assert_eq!(client.poll_interval(), Duration::ZERO);
+
+        // "Nothing newer" is a healthy answer, not a stall to back off from
+        for _ in 0..3 {
+            assert!(client.get_latest_since(100, wait).await.unwrap().is_none());
+        }
+        assert_eq!(client.poll_interval(), Duration::ZERO);
+
+        // A container without long polling 404s `?since=`: fall back to `/latest`
+        let client = LaserStreamClient::new(mock_container(false).await).with_long_poll(wait);
+        let update = client.get_latest_since(41, wait).await.unwrap().unwrap();
+        assert_eq!(update.slot, 500);
+        assert!(!client.is_long_polling());
+        assert_eq!(client.poll_interval(), Duration::from_secs(2));
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let client = LaserStreamClient::new("https://laserstream-container.eeeew.workers.dev");
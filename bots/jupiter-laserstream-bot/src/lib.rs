@@ -1,14 +1,32 @@
 // Library modules for jupiter-laserstream-bot
 // This allows binaries and tests to access shared code
 
+pub mod backtest;
+pub mod balances;
 pub mod config;
 pub mod executor;
-pub mod jupiter_client;
+pub mod guards;
+pub mod health;
 pub mod laserstream_client;
 pub mod metrics;
+pub mod notifier;
+pub mod pairs;
+pub mod pnl;
+pub mod price_source;
 pub mod price_tracker;
+pub mod readiness;
+pub mod resubmit;
+pub mod shadow;
+pub mod shutdown;
+pub mod slippage;
+pub mod state;
+pub mod startup_trade;
+pub mod status;
 pub mod strategies;
 pub mod swap_parser;
+pub mod swap_provider;
+pub mod token_decimals;
+pub mod verification;
 
 // Re-export commonly used types for easier testing
 pub use config::BotConfig;
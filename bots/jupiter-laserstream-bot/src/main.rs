@@ -1,69 +1,61 @@
 use anyhow::Result;
 use dotenv::dotenv;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info, warn};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
 
+mod balances;
 mod config;
 mod executor;
-mod jupiter_client;
+mod guards;
+mod health;
 mod laserstream_client;
 mod metrics;
+mod notifier;
+mod pairs;
+mod pnl;
+mod price_source;
 mod price_tracker;
+mod readiness;
+mod resubmit;
+mod shadow;
+mod shutdown;
+mod slippage;
+mod state;
+mod startup_trade;
+mod status;
 mod strategies;
 mod swap_parser;
+mod swap_provider;
+mod token_decimals;
+mod verification;
 
+use balances::{airdrop_request, LAMPORTS_PER_SOL};
 use config::BotConfig;
 use executor::TradeExecutor;
-use jupiter_client::JupiterClient;
-use laserstream_client::LaserStreamClient;
+use guards::{GuardChain, GuardContext};
+use health::{HealthMonitor, HealthSignals};
+use jupiter_client::{JupiterClient, JupiterError, RateLimiter};
+use laserstream_client::{check_network, LaserStreamClient};
+use notifier::{ConfirmLatencyMonitor, Notifier};
+use pairs::{fetch_all, probe_amount, PairTracker};
+use pnl::{Fill, FillSide, PnlTracker};
+use price_source::{PriceSanityCheck, PythPriceSource, SecondarySourceKind};
 use price_tracker::PriceTracker;
-use strategies::create_strategy;
-use swap_parser::get_token_decimals;
-
-/// Bot state to track cooldowns and processed slots
-struct BotState {
-    cooldown_until: Option<chrono::DateTime<chrono::Utc>>,
-    last_slot: Option<u64>,
-}
-
-impl BotState {
-    fn new() -> Self {
-        Self {
-            cooldown_until: None,
-            last_slot: None,
-        }
-    }
-
-    fn is_in_cooldown(&self) -> bool {
-        self.cooldown_until
-            .map_or(false, |until| chrono::Utc::now() < until)
-    }
-
-    fn clear_cooldown(&mut self) {
-        info!("✅ Cooldown period ended");
-        self.cooldown_until = None;
-    }
-
-    fn set_cooldown(&mut self, minutes: u64) {
-        self.cooldown_until = Some(
-            chrono::Utc::now() + chrono::Duration::minutes(minutes as i64)
-        );
-        info!("⏰ Cooldown until: {}", self.cooldown_until.unwrap());
-    }
-
-    fn should_skip_slot(&self, slot: u64) -> bool {
-        self.last_slot.map_or(false, |s| s >= slot)
-    }
-
-    fn update_slot(&mut self, slot: u64) {
-        self.last_slot = Some(slot);
-    }
-}
+use readiness::{wait_for_data, wait_until_ready, Dependency, ReadinessConfig};
+use shadow::ShadowRunner;
+use state::{BotState, FinalizationOutcome};
+use status::{BotStatus, SharedStatus};
+use strategies::triangular::{ArbCycle, TriangularArb};
+use strategies::{create_strategy, create_strategy_by_name, TradeSignal};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
     dotenv().ok();
+    let _telemetry = bot_common::telemetry::init("jupiter-laserstream-bot")?;
 
     info!("🚀 Starting Jupiter LaserStream Trading Bot");
 
@@ -73,105 +65,412 @@ async fn main() -> Result<()> {
         config.strategy_type, config.base_token, config.quote_token
     );
 
+    // Every Jupiter request, the executor's included, shares one rate limit
+    let rate_limiter = Arc::new(RateLimiter::new(config.jupiter_max_requests_per_second));
+
     // Initialize all components
-    let (laserstream, mut price_tracker, strategy, executor, metrics, jupiter_client, quote_decimals) =
-        initialize_components(&config).await?;
+    let (laserstream, mut pair_trackers, executor, metrics, jupiter_client) =
+        initialize_components(&config, &rate_limiter).await?;
+
+    let guards = GuardChain::from_names(
+        &config.signal_guards,
+        config.max_daily_trades,
+        config.strategy.lookback_minutes,
+    )?;
+    info!("Signal guards: {}", guards.names().join(" -> "));
+
+    let price_check = config.secondary_price_source.map(|kind| {
+        let secondary = match kind {
+            SecondarySourceKind::Pyth => Box::new(PythPriceSource::new(config.pyth_feed_ids.clone())),
+        };
+        info!(
+            "Price sanity check: jupiter vs {:?}, max divergence {:.2}%",
+            kind, config.max_price_divergence_pct
+        );
+        PriceSanityCheck::new(secondary, config.max_price_divergence_pct)
+    });
+
+    // Paper-traded strategies for side-by-side comparison
+    let mut shadow_names = config.shadow_strategies.clone();
+    if config.shadow_only {
+        shadow_names.insert(0, config.strategy_type.clone());
+    }
+    // One set per pair, on that pair's decimals, so paper positions never mix
+    let mut shadows = pair_trackers
+        .iter()
+        .map(|pt| {
+            let strategies = shadow_names
+                .iter()
+                .map(|name| create_strategy_by_name(name, &config.strategy, pt.base_decimals, pt.quote_decimals))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ShadowRunner::new(
+                strategies,
+                pt.base_decimals,
+                pt.quote_decimals,
+                Duration::from_secs(config.shadow_report_minutes * 60),
+            )
+            .with_label(pt.pair.label()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !shadow_names.is_empty() {
+        info!(
+            "👻 Shadow strategies: {}{}",
+            shadow_names.join(", "),
+            if config.shadow_only { " (shadow only, no live trades)" } else { "" }
+        );
+    }
+
+    let status: SharedStatus = Arc::new(RwLock::new(BotStatus::default()));
+    refresh_status(&status, &executor, &config).await;
+    if let Some(port) = config.health_port {
+        let status = status.clone();
+        tokio::spawn(async move {
+            if let Err(e) = status::serve(port, status).await {
+                error!("Status server failed: {}", e);
+            }
+        });
+    }
+
+    // Optional one-shot trade proving the signing/send/confirm path with real funds
+    startup_trade::run_test_trade(config.test_trade_on_start, config.test_trade_amount, |signal| {
+        let executor = &executor;
+        let config = &config;
+        async move { executor.execute_trade(&signal, config).await }
+    })
+    .await?;
+
+    // Transactions a previous forced shutdown left unconfirmed
+    let pending_path = std::path::Path::new(&config.pending_signatures_path);
+    for signature in shutdown::take_pending(pending_path)? {
+        match executor.is_finalized(&signature).await {
+            Ok(true) => info!("🔁 Recovered signature finalized: {}", signature),
+            Ok(false) => warn!("🔁 Recovered signature not finalized: {}", signature),
+            Err(e) => warn!("🔁 Could not check recovered signature {}: {}", signature, e),
+        }
+    }
 
-    let mut state = BotState::new();
-    let poll_interval = Duration::from_secs(config.poll_interval_seconds);
+    let triangular = TriangularArb::from_config(&config).filter(|_| !config.shadow_only);
+    if let Some(mints) = triangular.as_ref().and(config.triangular_mints.as_ref()) {
+        info!(
+            "🔺 Triangular arbitrage: {} (min {}bps net)",
+            mints.join(" -> "),
+            config.min_net_profit_bps
+        );
+    }
 
+    // Restore cooldown and daily count so a restart can't reset the trade limits
+    let state_path = std::path::Path::new(&config.state_path);
+    let mut state = BotState::load(state_path)?;
+    if let Some(slot) = state.last_slot {
+        info!(
+            "💾 Restored state: {} trades today, last slot {}, cooldown until {:?}",
+            state.daily_trade_count, slot, state.cooldown_until
+        );
+    }
+    let mut health = HealthMonitor::from_config(&config);
+    let mut last_pnl_export = std::time::Instant::now();
+
+    publish_state(&status, &state, &pair_trackers).await;
     info!("✅ Bot is running! Monitoring slot updates...");
 
-    // Main event loop
+    // Main event loop; Ctrl-C/SIGTERM lets the current tick finish, bounded by SHUTDOWN_TIMEOUT_SECS
+    let shutdown = bot_common::shutdown_signal();
+    tokio::pin!(shutdown);
+
     loop {
-        if let Err(e) = process_slot_update(
-            &laserstream,
-            &mut price_tracker,
-            &strategy,
-            &executor,
-            &metrics,
-            &jupiter_client,
-            &config,
-            &mut state,
-            quote_decimals,
-        )
-        .await
+        if config.pnl_export_minutes > 0
+            && last_pnl_export.elapsed() >= Duration::from_secs(config.pnl_export_minutes * 60)
         {
+            export_pnl(&state.pnl, &config.pnl_csv_path);
+            last_pnl_export = std::time::Instant::now();
+        }
+
+        assess_health(&mut health, &executor, &state, &status).await;
+
+        // The tick borrows the health monitor; release it before the arbitrage pass
+        let (result, stopping) = {
+            let tick = process_slot_update(
+                &laserstream,
+                &mut pair_trackers,
+                &mut shadows,
+                &guards,
+                price_check.as_ref(),
+                &executor,
+                &metrics,
+                &jupiter_client,
+                &config,
+                &mut state,
+                &mut health,
+                &status,
+            );
+            drain_on_shutdown(tick, shutdown.as_mut(), &config, &executor).await
+        };
+
+        if let Err(e) = result {
             warn!("Error processing slot update: {}", e);
         }
+        if stopping {
+            break;
+        }
+        save_state(&mut state, state_path);
+        publish_state(&status, &state, &pair_trackers).await;
+
+        if let Some(arb) = triangular.as_ref().filter(|_| health.allows_trading()) {
+            let cycle = run_triangular(
+                arb,
+                &executor,
+                &guards,
+                &pair_trackers[0].tracker,
+                &metrics,
+                &config,
+                &mut state,
+                &mut health,
+            );
+            let (result, stopping) = drain_on_shutdown(cycle, shutdown.as_mut(), &config, &executor).await;
+            if let Err(e) = result {
+                warn!("Triangular arbitrage failed: {}", e);
+            }
+            if stopping {
+                break;
+            }
+        }
 
-        tokio::time::sleep(poll_interval).await;
+        tokio::select! {
+            // Backs off while the container errors or has nothing new; zero while long polling
+            _ = tokio::time::sleep(laserstream.poll_interval()) => {}
+            _ = &mut shutdown => {
+                info!("🛑 Shutdown requested");
+                break;
+            }
+        }
     }
+
+    if let Err(e) = state.save(state_path) {
+        warn!("Could not save bot state: {:#}", e);
+    }
+    export_pnl(&state.pnl, &config.pnl_csv_path);
+    info!("👋 Bot stopped");
+    Ok(())
 }
 
 async fn initialize_components(
     config: &BotConfig,
+    rate_limiter: &Arc<RateLimiter>,
 ) -> Result<(
     LaserStreamClient,
-    PriceTracker,
-    Box<dyn strategies::Strategy>,
+    Vec<PairTracker>,
     TradeExecutor,
     std::sync::Arc<metrics::Metrics>,
     JupiterClient,
-    u8,
 )> {
-    let mut price_tracker = PriceTracker::new(config.lookback_minutes);
-    let strategy = create_strategy(&config)?;
-    let executor = TradeExecutor::new(&config).await?;
+    info!(
+        "Monitoring {} pair(s): {}",
+        config.pairs.len(),
+        config.pairs.iter().map(|pair| pair.label()).collect::<Vec<_>>().join(", ")
+    );
     let metrics = metrics::init_metrics();
-    let jupiter_client = JupiterClient::new();
-
-    let quote_decimals = get_token_decimals(&config.quote_mint);
+    if let Some(port) = config.metrics_port {
+        metrics.serve(port);
+    }
+    let notifier = Arc::new(Notifier::new(
+        config.alert_webhook_url.clone(),
+        Duration::from_secs(config.alert_dedup_secs),
+    ));
+    let mut executor = TradeExecutor::new(config, rate_limiter.clone()).await?;
+    if config.max_confirm_latency_secs > 0 {
+        executor = executor.with_confirm_latency_monitor(ConfirmLatencyMonitor::new(
+            Duration::from_secs(config.max_confirm_latency_secs),
+            notifier,
+            metrics.clone(),
+        ));
+    }
+    let jupiter_client = config.jupiter_client_builder().rate_limiter(rate_limiter.clone()).build();
 
     // Connect and verify LaserStream container
     info!(
         "Connecting to LaserStream container at {}",
         config.laserstream_url
     );
-    let laserstream = LaserStreamClient::new(&config.laserstream_url);
+    let laserstream = LaserStreamClient::new(&config.laserstream_url)
+        .with_poll_intervals(
+            Duration::from_secs(config.strategy.poll_interval_seconds),
+            Duration::from_secs(config.poll_interval_max_seconds),
+        )
+        .with_long_poll(Duration::from_millis(config.long_poll_wait_ms))
+        .with_reconnect_counter(metrics.reconnections.clone());
+
+    // Block until RPC, Jupiter and the container all answer
+    wait_for_dependencies(config, &executor, &jupiter_client, &laserstream).await?;
+
+    // Decimals come from the mint accounts, so tokens outside the static table work too.
+    // Each pair gets its own strategy so price history and positions never mix.
+    let mut pair_trackers = Vec::with_capacity(config.pairs.len());
+    for pair in &config.pairs {
+        let base_decimals = executor.token_decimals(&pair.base_mint).await?;
+        let quote_decimals = executor.token_decimals(&pair.quote_mint).await?;
+        pair_trackers.push(PairTracker {
+            pair: pair.clone(),
+            tracker: PriceTracker::new(config.strategy.lookback_minutes)
+                .with_min_points_for_volatility(config.min_points_for_volatility),
+            strategy: create_strategy(config, base_decimals, quote_decimals)?,
+            base_decimals,
+            quote_decimals,
+        });
+    }
+
+    // Refuse to trade one cluster on another cluster's data
+    if let Some(expected) = &config.solana_network {
+        let status = laserstream.get_status().await?;
+        check_network(expected, &status.network)?;
+        info!("✅ LaserStream container network: {}", status.network);
+    }
+
+    // The VWAP weighs prices by the swaps the container parses
+    if config.strategy.use_vwap {
+        match laserstream.get_recent_swaps().await {
+            Ok(recent) if !recent.enabled => warn!(
+                "⚠️  USE_VWAP is set but the LaserStream container parses no swaps (TRANSACTION_PROGRAM_IDS unset): momentum will use the SMA"
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("⚠️  Could not read the LaserStream container's swaps for USE_VWAP: {}", e),
+        }
+    }
+
+    top_up_devnet_sol(config, &executor).await?;
+
+    match executor.get_sol_balance().await {
+        Ok(sol) => info!(
+            "💼 SOL position: {:.4} SOL (native: {} + wSOL: {} lamports, reserve: {})",
+            sol.position_sol(config.sol_fee_reserve_lamports),
+            sol.native_lamports,
+            sol.wrapped_lamports,
+            config.sol_fee_reserve_lamports
+        ),
+        Err(e) => warn!("Could not read SOL position: {}", e),
+    }
 
-    if !laserstream.health_check().await? {
-        error!("LaserStream container is not healthy");
-        return Err(anyhow::anyhow!("LaserStream container health check failed"));
+    // SEED_PRICE is the primary pair's; the others can only be seeded from Jupiter
+    for (i, pt) in pair_trackers.iter_mut().enumerate() {
+        let seed_price = if i == 0 { config.seed_price } else { None };
+        seed_price_tracker(config, &jupiter_client, pt, seed_price).await;
     }
-    info!("✅ LaserStream container is healthy");
 
     info!("Starting LaserStream subscription...");
     laserstream.start().await?;
     info!("✅ LaserStream subscription started");
 
+    // The gRPC stream takes a few seconds to produce its first slot
+    if let Some(first) = wait_for_data(
+        "LaserStream",
+        || laserstream.get_latest(),
+        Duration::from_secs(config.stream_warmup_timeout_seconds),
+        Duration::from_millis(500),
+    )
+    .await
+    {
+        info!("📡 First slot: {}", first.slot);
+    }
+
     Ok((
         laserstream,
-        price_tracker,
-        strategy,
+        pair_trackers,
         executor,
         metrics,
         jupiter_client,
-        quote_decimals,
     ))
 }
 
+/// Prime the pair's tracker with `seed_price` (or one Jupiter quote) so
+/// short-lookback indicators don't wait out the whole window on a cold start
+async fn seed_price_tracker(
+    config: &BotConfig,
+    jupiter_client: &JupiterClient,
+    pt: &mut PairTracker,
+    seed_price: Option<f64>,
+) {
+    let seed = match seed_price {
+        Some(price) => Some(price),
+        None if config.seed_price_from_jupiter => {
+            match jupiter_client.get_price(&pt.pair.base_mint, &pt.pair.quote_mint).await {
+                Ok(price) => Some(price),
+                Err(e) => {
+                    warn!("Failed to fetch {} seed price from Jupiter: {}", pt.pair.label(), e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    if let Some(price) = seed {
+        pt.tracker.seed(price, chrono::Utc::now().timestamp());
+        info!("🌱 Seeded {} price tracker at ${:.4}", pt.pair.label(), price);
+    }
+}
+
+/// Poll RPC, Jupiter and the LaserStream container until all respond or the
+/// readiness timeout expires, so the first ticks don't fail on a cold dependency.
+async fn wait_for_dependencies(
+    config: &BotConfig,
+    executor: &TradeExecutor,
+    jupiter_client: &JupiterClient,
+    laserstream: &LaserStreamClient,
+) -> Result<()> {
+    info!("Waiting for dependencies to become ready...");
+
+    let dependencies = [
+        Dependency::new("RPC", || async {
+            let slot = executor.get_slot().await?;
+            info!("   RPC slot: {}", slot);
+            Ok(())
+        }),
+        Dependency::new("Jupiter", || async {
+            jupiter_client
+                .get_price(&config.base_mint, &config.quote_mint)
+                .await?;
+            Ok(())
+        }),
+        Dependency::new("LaserStream container", || async {
+            if laserstream.health_check().await? {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("health check returned non-success status"))
+            }
+        }),
+    ];
+
+    let readiness = ReadinessConfig::new(Duration::from_secs(config.readiness_timeout_seconds));
+    if let Err(e) = wait_until_ready(&dependencies, &readiness).await {
+        error!("Dependencies failed to become ready: {}", e);
+        return Err(e);
+    }
+
+    info!("✅ All dependencies are ready");
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
 async fn process_slot_update(
     laserstream: &LaserStreamClient,
-    price_tracker: &mut PriceTracker,
-    strategy: &Box<dyn strategies::Strategy>,
+    pair_trackers: &mut [PairTracker],
+    shadows: &mut [ShadowRunner],
+    guards: &GuardChain,
+    price_check: Option<&PriceSanityCheck>,
     executor: &TradeExecutor,
     metrics: &std::sync::Arc<metrics::Metrics>,
     jupiter_client: &JupiterClient,
     config: &BotConfig,
     state: &mut BotState,
-    quote_decimals: u8,
+    health: &mut HealthMonitor,
+    status: &SharedStatus,
 ) -> Result<()> {
     let update = match laserstream.get_latest().await? {
         Some(update) => update,
         None => {
             // Log periodically that we're polling but no data yet
-            static mut POLL_COUNT: u64 = 0;
-            unsafe {
-                POLL_COUNT += 1;
-                if POLL_COUNT % 30 == 0 {
-                    info!("⏱️  Polling LaserStream container... (no data yet - needs valid HELIUS_API_KEY)");
-                }
+            state.empty_polls += 1;
+            if state.empty_polls % 30 == 0 {
+                info!("⏱️  Polling LaserStream container... (no data yet - needs valid HELIUS_API_KEY)");
             }
             return Ok(());
         }
@@ -182,39 +481,225 @@ async fn process_slot_update(
         return Ok(());
     }
 
+    let previous_slot = state.last_slot;
     state.update_slot(update.slot);
     info!("📊 New slot: {} at {}", update.slot, update.timestamp);
 
-    // Fetch and update price data
-    update_price_data(
-        jupiter_client,
-        price_tracker,
-        metrics,
-        config,
-        quote_decimals,
-    )
+    // Don't trade on a stalled stream's last slot
+    if config.max_slot_age_secs > 0 {
+        match update.age_secs(chrono::Utc::now()) {
+            Ok(age) if age > config.max_slot_age_secs as i64 => {
+                warn!(
+                    "🐢 Slot {} is {}s old (limit {}s), stream may be stalled: skipping trading",
+                    update.slot, age, config.max_slot_age_secs
+                );
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("🐢 Cannot tell how old slot {} is, skipping trading: {}", update.slot, e);
+                return Ok(());
+            }
+        }
+    }
+
+    // This tick's pairs: all of them, or a rotating batch over MAX_CONCURRENT_PAIRS
+    let batch = state
+        .pair_rotation
+        .next_batch(pair_trackers.len(), config.max_concurrent_pairs);
+    let selected: Vec<&PairTracker> = batch.iter().map(|&i| &pair_trackers[i]).collect();
+
+    // Fetch the batch's price data (concurrently unless disabled)
+    let samples = fetch_all(&selected, config.concurrent_pair_fetch, |pt| {
+        fetch_price_sample(jupiter_client, pt, config)
+    })
     .await;
 
-    // Check cooldown status
-    if state.is_in_cooldown() {
-        return Ok(());
-    } else if state.cooldown_until.is_some() {
-        state.clear_cooldown();
+    // Traded volume for the VWAP: the swaps the container parsed since the last slot
+    let swaps = if config.strategy.use_vwap {
+        match laserstream.get_recent_swaps().await {
+            Ok(recent) => recent.transactions,
+            Err(e) => {
+                warn!("Failed to get recent swaps for volume: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let timestamp = chrono::Utc::now().timestamp();
+    for (&i, sample) in batch.iter().zip(samples) {
+        let pt = &mut pair_trackers[i];
+        if let Some(sample) = sample {
+            // Don't let the window straddle a long feed outage
+            if pt.tracker.reset_if_gap(timestamp, config.max_gap_before_reset_secs) {
+                warn!(
+                    "🔌 {} feed gap over {}s: price history reset, re-entering warmup",
+                    pt.pair.label(),
+                    config.max_gap_before_reset_secs
+                );
+            }
+            let volume = pt.traded_volume(&swaps, previous_slot, update.slot);
+            record_price_sample(pt, sample, volume, timestamp, metrics);
+        }
     }
 
-    // Generate and execute trading signals
-    if let Some(signal) = strategy.generate_signal(&price_tracker) {
-        info!("📊 Signal: {:?}", signal);
+    // Paper-trade the shadow strategies on each pair this tick updated
+    for &i in &batch {
+        let (shadow, tracker) = (&mut shadows[i], &pair_trackers[i].tracker);
+        if shadow.is_empty() {
+            continue;
+        }
+        shadow.on_tick(tracker);
+        if let Some(price) = tracker.current_price() {
+            shadow.maybe_log_report(price);
+        }
+    }
+    if config.shadow_only {
+        return Ok(());
+    }
 
-        match executor.execute_trade(&signal, &config).await {
-            Ok(signature) => {
-                info!("✅ Trade executed: {}", signature);
+    if !state.is_in_cooldown() && state.cooldown_until.is_some() {
+        state.clear_cooldown();
+    }
+    state.roll_day();
+
+    // Settle trades still waiting for finalization before trading again
+    let mut waiting = false;
+    for signature in state.pending_signatures() {
+        let finalized = executor.is_finalized(&signature).await.unwrap_or_else(|e| {
+            warn!("Finalization check failed: {}", e);
+            false
+        });
+        match state.resolve_finalization(
+            &signature,
+            finalized,
+            chrono::Utc::now(),
+            config.finalization_timeout_secs,
+            health.cooldown_minutes(config.cooldown_minutes),
+        ) {
+            FinalizationOutcome::Finalized(_) => {
                 metrics.record_trade(true);
-                state.set_cooldown(config.cooldown_minutes);
+                health.record_trade(true, chrono::Utc::now());
+                refresh_status(status, executor, config).await;
             }
-            Err(e) => {
-                error!("❌ Trade failed: {}", e);
+            FinalizationOutcome::RolledBack(_) => {
                 metrics.record_trade(false);
+                health.record_trade(false, chrono::Utc::now());
+            }
+            FinalizationOutcome::Waiting => waiting = true,
+        }
+    }
+    if waiting {
+        debug!("⏳ Waiting for finalization before trading again");
+        return Ok(());
+    }
+
+    if !health.allows_trading() {
+        debug!(
+            "🩺 Trading halted while unhealthy: {}",
+            health.reason().unwrap_or("unknown")
+        );
+        metrics.record_suppression("health");
+        return Ok(());
+    }
+
+    for pt in batch.iter().map(|&i| &pair_trackers[i]) {
+        // Run suppression guards in configured order
+        let ctx = GuardContext {
+            state,
+            price_tracker: &pt.tracker,
+        };
+        if let Some(suppression) = guards.evaluate(&ctx) {
+            debug!(
+                "🚫 {} signal suppressed by {}: {}",
+                pt.pair.label(),
+                suppression.guard,
+                suppression.reason
+            );
+            metrics.record_suppression(&suppression.guard);
+            continue;
+        }
+
+        // Generate and execute trading signals, sized for the current health
+        if let Some(signal) = pt
+            .strategy
+            .generate_signal_for(&pt.pair.label(), &pt.tracker)
+            .and_then(|signal| health.scale_signal(signal))
+        {
+            info!("📊 {} signal: {:?}", pt.pair.label(), signal);
+
+            // Cross-check Jupiter against the secondary source before sending
+            if let Some(check) = price_check {
+                if let Err(e) = check.check(jupiter_client, &pt.pair).await {
+                    warn!("🛑 {} trade blocked by price sanity check: {}", pt.pair.label(), e);
+                    metrics.record_suppression("price_sanity");
+                    continue;
+                }
+            }
+
+            // Shrink the trade to what the route can absorb within MAX_PRICE_IMPACT_PCT;
+            // the strategy is told the shrunk amount, so TWAP sends the rest later
+            let signal = match executor.size_for_impact(signal, &pt.pair).await {
+                Ok(signal) => signal,
+                Err(e) => {
+                    warn!("🛑 {} trade blocked by price impact: {:#}", pt.pair.label(), e);
+                    metrics.record_suppression("price_impact");
+                    continue;
+                }
+            };
+
+            match executor.execute_trade_with_result(&signal, &pt.pair).await {
+                Ok(result) if executor.is_dry_run() => {
+                    // Nothing was sent: no fill, trade count or cooldown to book,
+                    // but the strategy moves on as if it had filled
+                    info!("🧪 {} dry run: {}", pt.pair.label(), result.signature);
+                    pt.strategy.on_trade_result(&pt.pair.label(), &signal, true);
+                }
+                Ok(result) if !result.landed => {
+                    // Outcome unknown: cool down and settle it by its signature status.
+                    // The strategy treats it as not executed until then.
+                    pt.strategy.on_trade_result(&pt.pair.label(), &signal, false);
+                    warn!(
+                        "⌛ {} trade {} unconfirmed after {}s; holding off until it settles",
+                        pt.pair.label(),
+                        result.signature,
+                        config.max_confirm_wait_secs
+                    );
+                    state.begin_unconfirmed(
+                        result.signature,
+                        chrono::Utc::now(),
+                        config.finalization_timeout_secs,
+                        health.cooldown_minutes(config.cooldown_minutes),
+                    );
+                }
+                Ok(result) => {
+                    info!("✅ Trade executed: {}", result.signature);
+                    pt.strategy.on_trade_result(&pt.pair.label(), &signal, true);
+                    record_fill(&mut state.pnl, pt, &signal, &result);
+                    let signature = result.signature;
+                    if config.confirm_finalized_before_cooldown {
+                        // Counted and cooled down once finalized (see above)
+                        state.begin_finalization(
+                            signature,
+                            chrono::Utc::now(),
+                            config.finalization_timeout_secs,
+                        );
+                    } else {
+                        metrics.record_trade(true);
+                        health.record_trade(true, chrono::Utc::now());
+                        state.record_trade();
+                        state.set_cooldown(health.cooldown_minutes(config.cooldown_minutes));
+                    }
+                    refresh_status(status, executor, config).await;
+                }
+                Err(e) => {
+                    error!("❌ Trade failed: {}", e);
+                    pt.strategy.on_trade_result(&pt.pair.label(), &signal, false);
+                    metrics.record_trade(false);
+                    health.record_trade(false, chrono::Utc::now());
+                }
             }
         }
     }
@@ -222,53 +707,317 @@ async fn process_slot_update(
     Ok(())
 }
 
-async fn update_price_data(
-    jupiter_client: &JupiterClient,
-    price_tracker: &mut PriceTracker,
-    metrics: &std::sync::Arc<metrics::Metrics>,
+/// Add an executed signal to the ledger at the price it filled at: the
+/// verified balance deltas when there are any, else the quote it was sent with.
+/// Jupiter's route fees are already in those amounts, so the fee is 0.
+fn record_fill(pnl: &mut PnlTracker, pt: &PairTracker, signal: &TradeSignal, result: &executor::SwapResult) {
+    let (spent, received) = result.amounts();
+    let (side, base_raw, quote_raw) = match signal {
+        TradeSignal::Buy { .. } => (FillSide::Buy, received, spent),
+        TradeSignal::Sell { .. } => (FillSide::Sell, spent, received),
+        TradeSignal::Hold => return,
+    };
+    let base_amount = base_raw as f64 / 10f64.powi(pt.base_decimals as i32);
+    let quote_amount = quote_raw as f64 / 10f64.powi(pt.quote_decimals as i32);
+    if base_amount <= 0.0 {
+        warn!("No fill amounts for {}, not added to the ledger", result.signature);
+        return;
+    }
+    pnl.record_fill(Fill {
+        pair: pt.pair.label(),
+        side,
+        price: quote_amount / base_amount,
+        base_amount,
+        fee: 0.0,
+        timestamp: chrono::Utc::now().timestamp(),
+        signature: result.signature.clone(),
+    });
+}
+
+/// Write the trade ledger to `PNL_CSV_PATH`
+fn export_pnl(pnl: &PnlTracker, path: &str) {
+    match pnl.export_csv(std::path::Path::new(path)) {
+        Ok(()) => info!(
+            "💾 Wrote {} fill(s) to {} (realized PnL ${:+.4})",
+            pnl.fills().len(),
+            path,
+            pnl.realized_pnl()
+        ),
+        Err(e) => warn!("Could not export PnL ledger: {}", e),
+    }
+}
+
+fn save_state(state: &mut BotState, path: &std::path::Path) {
+    if let Err(e) = state.save_if_changed(path, chrono::Utc::now()) {
+        warn!("Could not save bot state: {:#}", e);
+    }
+}
+
+/// Airdrop SOL to the executor when `AUTO_AIRDROP_SOL` is set, the bot runs on
+/// devnet (`SOLANA_NETWORK`, else the RPC URL) and native SOL is below the threshold
+async fn top_up_devnet_sol(config: &BotConfig, executor: &TradeExecutor) -> Result<()> {
+    if config.auto_airdrop_lamports == 0 {
+        return Ok(());
+    }
+    let network = config.solana_network.clone().unwrap_or_else(|| {
+        if config.rpc_url.contains("devnet") { "devnet" } else { "mainnet" }.to_string()
+    });
+    let native_lamports = executor.get_sol_balance().await?.native_lamports;
+
+    let Some(lamports) = airdrop_request(
+        &network,
+        native_lamports,
+        config.auto_airdrop_below_lamports,
+        config.auto_airdrop_lamports,
+    ) else {
+        if network != "devnet" {
+            info!("AUTO_AIRDROP_SOL ignored on {}", network);
+        }
+        return Ok(());
+    };
+
+    info!(
+        "🚰 Requesting devnet airdrop of {:.2} SOL (balance {:.4} SOL)",
+        lamports as f64 / LAMPORTS_PER_SOL as f64,
+        native_lamports as f64 / LAMPORTS_PER_SOL as f64
+    );
+    let signature = executor.request_airdrop(lamports).await?;
+    info!("✅ Airdrop confirmed: {}", signature);
+    Ok(())
+}
+
+/// Run `work` to completion. A shutdown request meanwhile lets it finish, since
+/// it may have transactions in flight, under the SHUTDOWN_TIMEOUT_SECS watchdog.
+/// Also returns whether shutdown was requested.
+async fn drain_on_shutdown<T>(
+    work: impl Future<Output = T>,
+    mut shutdown: Pin<&mut impl Future<Output = ()>>,
     config: &BotConfig,
-    quote_decimals: u8,
+    executor: &TradeExecutor,
+) -> (T, bool) {
+    tokio::pin!(work);
+    tokio::select! {
+        output = &mut work => (output, false),
+        _ = &mut shutdown => {
+            info!("🛑 Shutdown requested, draining in-flight work (max {}s)...", config.shutdown_timeout_secs);
+            let watchdog = shutdown::arm_watchdog(
+                Duration::from_secs(config.shutdown_timeout_secs),
+                executor.in_flight(),
+                config.pending_signatures_path.clone().into(),
+                || std::process::exit(1),
+            );
+            let output = work.await;
+            watchdog.disarm();
+            (output, true)
+        }
+    }
+}
+
+/// Quote the configured triangle and trade the cycle when it clears MIN_NET_PROFIT_BPS.
+/// A cycle passes the same guards as a signal and books like one trade: it
+/// counts toward the daily limit, starts the cooldown and lands in the ledger.
+#[allow(clippy::too_many_arguments)]
+async fn run_triangular(
+    arb: &TriangularArb,
+    executor: &TradeExecutor,
+    guards: &GuardChain,
+    primary: &PriceTracker,
+    metrics: &metrics::Metrics,
+    config: &BotConfig,
+    state: &mut BotState,
+    health: &mut HealthMonitor,
+) -> Result<()> {
+    if !state.pending_finalization.is_empty() {
+        return Ok(());
+    }
+    let ctx = GuardContext {
+        state,
+        price_tracker: primary,
+    };
+    if let Some(suppression) = guards.evaluate(&ctx) {
+        debug!("🚫 Triangular cycle suppressed by {}: {}", suppression.guard, suppression.reason);
+        metrics.record_suppression(&suppression.guard);
+        return Ok(());
+    }
+
+    let Some(cycle) = arb.find_cycle(executor.swap_provider()).await? else {
+        return Ok(());
+    };
+    info!("🔺 Arbitrage cycle {}: {:+.1}bps net, executing", cycle.path(), cycle.net_bps);
+
+    let result = executor.execute_cycle(&cycle).await;
+    if executor.is_dry_run() {
+        info!("🧪 Dry run: cycle {} not booked", cycle.path());
+        return result.map(|_| ());
+    }
+    metrics.record_trade(result.is_ok());
+    health.record_trade(result.is_ok(), chrono::Utc::now());
+    // Even a cycle aborted part way may have traded: cool down either way
+    state.set_cooldown(health.cooldown_minutes(config.cooldown_minutes));
+    let results = result?;
+    state.record_trade();
+
+    let decimals = executor.token_decimals(arb.start_mint()).await?;
+    record_cycle(&mut state.pnl, &cycle, &results, decimals);
+    let signatures: Vec<String> = results.into_iter().map(|r| r.signature).collect();
+    info!("✅ Cycle complete: {}", signatures.join(", "));
+    Ok(())
+}
+
+/// Add a completed cycle to the ledger as a buy and sell of one unit priced
+/// in its start token: what the first leg spent and what the last returned,
+/// less the cycle's network fees
+fn record_cycle(pnl: &mut PnlTracker, cycle: &ArbCycle, results: &[executor::SwapResult], decimals: u8) {
+    let (Some(first), Some(last)) = (cycle.legs.first(), cycle.legs.last()) else {
+        return;
+    };
+    // What actually arrived when verified, else the quote's guaranteed minimum
+    let received = results
+        .last()
+        .and_then(|r| r.verification.as_ref())
+        .filter(|v| v.output_delta > 0)
+        .map_or(last.min_out, |v| v.output_delta as u64);
+    let units = 10f64.powi(decimals as i32);
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = results.last().map(|r| r.signature.clone()).unwrap_or_default();
+    for (side, amount, fee) in [
+        (FillSide::Buy, first.amount_in, 0),
+        (FillSide::Sell, received, cycle.fee_cost),
+    ] {
+        pnl.record_fill(Fill {
+            pair: cycle.path(),
+            side,
+            price: amount as f64 / units,
+            base_amount: 1.0,
+            fee: fee as f64 / units,
+            timestamp,
+            signature: signature.clone(),
+        });
+    }
+}
+
+/// Sample feed freshness, RPC latency and slot lag, then publish the resulting health state
+async fn assess_health(
+    health: &mut HealthMonitor,
+    executor: &TradeExecutor,
+    state: &BotState,
+    status: &SharedStatus,
 ) {
+    let now = chrono::Utc::now();
+    let started = std::time::Instant::now();
+    let (rpc_latency_ms, rpc_slot) = match executor.get_slot().await {
+        Ok(slot) => (started.elapsed().as_millis() as u64, Some(slot)),
+        Err(e) => {
+            debug!("Health probe failed: {}", e);
+            (u64::MAX, None)
+        }
+    };
+    let signals = HealthSignals {
+        feed_age_secs: state
+            .last_slot_at
+            .map_or(0, |at| now.signed_duration_since(at).num_seconds().max(0) as u64),
+        rpc_latency_ms,
+        slot_lag: match (rpc_slot, state.last_slot) {
+            (Some(rpc), Some(stream)) => rpc.saturating_sub(stream),
+            _ => 0,
+        },
+    };
+    health.update(signals, now);
+
+    let mut status = status.write().await;
+    status.health = health.state();
+    status.health_reason = health.reason().map(str::to_string);
+}
+
+/// Publish the slot, price, trade count and cooldown to the `/status` snapshot
+async fn publish_state(status: &SharedStatus, state: &BotState, pair_trackers: &[PairTracker]) {
+    let mut status = status.write().await;
+    status.last_slot = state.last_slot;
+    status.current_price = pair_trackers.first().and_then(|pt| pt.tracker.current_price());
+    status.daily_trade_count = state.daily_trade_count;
+    status.in_cooldown = state.is_in_cooldown();
+    status.cooldown_until = state.cooldown_until;
+}
+
+/// Publish the executor's slippage model to the `/status` snapshot
+async fn refresh_status(status: &SharedStatus, executor: &TradeExecutor, config: &BotConfig) {
+    let mut status = status.write().await;
+    status.slippage_mode = config.slippage_mode.as_str().to_string();
+    status.slippage_bps = executor.current_slippage_bps();
+    status.slippage_ewma_bps = executor.slippage_ewma_bps();
+}
+
+/// One tick's price observation for a pair
+struct PriceSample {
+    price: f64,
+    price_impact_pct: Option<f64>,
+}
+
+async fn fetch_price_sample(
+    jupiter_client: &JupiterClient,
+    pt: &PairTracker,
+    config: &BotConfig,
+) -> Option<PriceSample> {
+    let pair = &pt.pair;
     let price = match jupiter_client
-        .get_price(&config.base_mint, &config.quote_mint)
+        .get_price(&pair.base_mint, &pair.quote_mint)
         .await
     {
         Ok(price) => price,
+        Err(e @ JupiterError::RateLimited(_)) => {
+            warn!("🐌 {} price skipped this tick: {}", pair.label(), e);
+            return None;
+        }
         Err(e) => {
-            error!("❌ Failed to fetch price from Jupiter: {}", e);
-            return;
+            error!("❌ Failed to fetch {} price from Jupiter: {}", pair.label(), e);
+            return None;
         }
     };
 
-    let timestamp = chrono::Utc::now().timestamp();
-    let quote_amount = 100_000_000; // 100 USDC (6 decimals)
+    // Probe a fixed notional's worth of base token unless a raw amount is configured
+    let probe = config
+        .price_probe_amount
+        .unwrap_or_else(|| probe_amount(config.price_probe_notional, price, pt.base_decimals));
 
-    // Try to get volume estimate from quote
-    let volume = match jupiter_client
-        .get_quote(&config.quote_mint, &config.base_mint, quote_amount, 50)
+    let price_impact_pct = match jupiter_client
+        .get_quote(&pair.base_mint, &pair.quote_mint, probe, 50)
         .await
     {
-        Ok(quote) => {
-            let vol = quote_amount as f64 / 10_f64.powi(quote_decimals as i32);
-
-            // Log periodic updates with price impact
-            if price_tracker.update_count() % 10 == 0 {
-                info!(
-                    "💰 Price: ${:.4} | 1h avg: ${:.4} | Updates: {} | Impact: {}%",
-                    price,
-                    price_tracker.moving_average(60).unwrap_or(price),
-                    price_tracker.update_count(),
-                    quote.price_impact_pct
-                );
-            }
-            vol
-        }
+        Ok(quote) => quote.price_impact_percent().ok(),
         Err(e) => {
-            warn!("Failed to get quote for volume: {}", e);
-            100.0 // Default volume
+            warn!("Failed to get {} quote for price impact: {}", pair.label(), e);
+            None
         }
     };
 
-    price_tracker.add_price(price, volume, timestamp);
+    Some(PriceSample {
+        price,
+        price_impact_pct,
+    })
+}
+
+/// Add `sample` to the pair's history, weighted by the quote `volume` traded since the last tick
+fn record_price_sample(
+    pt: &mut PairTracker,
+    sample: PriceSample,
+    volume: f64,
+    timestamp: i64,
+    metrics: &std::sync::Arc<metrics::Metrics>,
+) {
+    // Log periodic updates with price impact
+    if let Some(impact) = sample.price_impact_pct {
+        if pt.tracker.update_count() % 10 == 0 {
+            info!(
+                "💰 {} Price: ${:.4} | 1h avg: ${:.4} | Updates: {} | Impact: {:.4}%",
+                pt.pair.label(),
+                sample.price,
+                pt.tracker.moving_average(60).unwrap_or(sample.price),
+                pt.tracker.update_count(),
+                impact
+            );
+        }
+    }
+
+    pt.tracker.add_price(sample.price, volume, timestamp);
     metrics.record_price_update();
 }
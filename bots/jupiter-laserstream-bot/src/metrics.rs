@@ -1,11 +1,24 @@
-use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
-use std::sync::Arc;
+use anyhow::Result;
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::{net::SocketAddr, sync::Arc};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
 
 pub struct Metrics {
     pub price_updates: IntCounter,
+    /// Every trade attempt, successful or not
+    pub trades_total: IntCounter,
     pub trades_executed: IntCounter,
     pub trades_failed: IntCounter,
     pub current_price_cents: IntGauge,
+    pub signals_suppressed: IntCounterVec,
+    pub confirm_latency_seconds: Histogram,
+    pub slow_confirmations: IntCounter,
+    /// LaserStream feed recoveries after errors or stalls
+    pub reconnections: IntCounter,
     registry: Registry,
 }
 
@@ -19,6 +32,12 @@ impl Metrics {
         )
         .unwrap();
         
+        let trades_total = IntCounter::new(
+            "trades_total",
+            "Total trades attempted",
+        )
+        .unwrap();
+        
         let trades_executed = IntCounter::new(
             "trades_executed_total",
             "Total successful trades",
@@ -37,16 +56,50 @@ impl Metrics {
         )
         .unwrap();
         
+        let signals_suppressed = IntCounterVec::new(
+            Opts::new("signals_suppressed_total", "Ticks suppressed by a signal guard"),
+            &["guard"],
+        )
+        .unwrap();
+        
+        let confirm_latency_seconds = Histogram::with_opts(
+            HistogramOpts::new("confirm_latency_seconds", "Time from send to confirmation")
+                .buckets(vec![1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0]),
+        )
+        .unwrap();
+        
+        let slow_confirmations = IntCounter::new(
+            "slow_confirmations_total",
+            "Confirmations slower than MAX_CONFIRM_LATENCY_SECS",
+        )
+        .unwrap();
+        
+        let reconnections = IntCounter::new(
+            "reconnections_total",
+            "LaserStream feed recoveries after errors or stalls",
+        )
+        .unwrap();
+        
         registry.register(Box::new(price_updates.clone())).unwrap();
+        registry.register(Box::new(trades_total.clone())).unwrap();
         registry.register(Box::new(trades_executed.clone())).unwrap();
         registry.register(Box::new(trades_failed.clone())).unwrap();
         registry.register(Box::new(current_price_cents.clone())).unwrap();
+        registry.register(Box::new(signals_suppressed.clone())).unwrap();
+        registry.register(Box::new(confirm_latency_seconds.clone())).unwrap();
+        registry.register(Box::new(slow_confirmations.clone())).unwrap();
+        registry.register(Box::new(reconnections.clone())).unwrap();
         
         Arc::new(Self {
             price_updates,
+            trades_total,
             trades_executed,
             trades_failed,
             current_price_cents,
+            signals_suppressed,
+            confirm_latency_seconds,
+            slow_confirmations,
+            reconnections,
             registry,
         })
     }
@@ -56,6 +109,7 @@ impl Metrics {
     }
     
     pub fn record_trade(&self, success: bool) {
+        self.trades_total.inc();
         if success {
             self.trades_executed.inc();
         } else {
@@ -63,6 +117,17 @@ impl Metrics {
         }
     }
     
+    pub fn record_suppression(&self, guard: &str) {
+        self.signals_suppressed.with_label_values(&[guard]).inc();
+    }
+    
+    pub fn record_confirm_latency(&self, seconds: f64, slow: bool) {
+        self.confirm_latency_seconds.observe(seconds);
+        if slow {
+            self.slow_confirmations.inc();
+        }
+    }
+    
     pub fn set_price(&self, price: f64) {
         self.current_price_cents.set((price * 100.0) as i64);
     }
@@ -74,8 +139,80 @@ impl Metrics {
         encoder.encode(&metric_families, &mut buffer).unwrap();
         String::from_utf8(buffer).unwrap()
     }
+    
+    /// `/metrics` in the Prometheus text format
+    pub fn router(self: &Arc<Self>) -> Router {
+        Router::new()
+            .route("/metrics", get(get_metrics))
+            .with_state(self.clone())
+    }
+    
+    /// Serve `/metrics` on `0.0.0.0:port` in the background
+    pub fn serve(self: &Arc<Self>, port: u16) -> JoinHandle<()> {
+        let router = self.router();
+        tokio::spawn(async move {
+            if let Err(e) = serve_router(port, router).await {
+                error!("Metrics server failed: {}", e);
+            }
+        })
+    }
+}
+
+async fn get_metrics(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, TextEncoder::new().format_type().to_string())], metrics.export())
+}
+
+async fn serve_router(port: u16, router: Router) -> Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("Metrics server listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router).await?;
+
+    Ok(())
 }
 
 pub fn init_metrics() -> Arc<Metrics> {
     Metrics::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_text() {
+        let metrics = Metrics::new();
+        metrics.record_trade(true);
+        metrics.record_trade(false);
+        metrics.record_price_update();
+        metrics.reconnections.inc();
+        metrics.set_price(150.25);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = metrics.router();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let response = reqwest::get(format!("http://{}/metrics", addr)).await.unwrap();
+        assert!(response.status().is_success());
+        assert!(response.headers()[reqwest::header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+
+        let body = response.text().await.unwrap();
+        for line in [
+            "# TYPE trades_total counter",
+            "trades_total 2",
+            "trades_executed_total 1",
+            "trades_failed_total 1",
+            "price_updates_total 1",
+            "reconnections_total 1",
+            "# TYPE current_price_cents gauge",
+            "current_price_cents 15025",
+        ] {
+            assert!(body.lines().any(|l| l == line), "missing {:?} in:\n{}", line, body);
+        }
+    }
+}
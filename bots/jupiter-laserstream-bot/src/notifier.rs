@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::metrics::Metrics;
+
+/// Operator alerts: logged at warn and POSTed as `{"text": ...}` to the webhook
+/// when one is configured. Repeats of the same key inside `dedup_window` are dropped.
+pub struct Notifier {
+    webhook_url: Option<String>,
+    http: reqwest::Client,
+    dedup_window: Duration,
+    last_sent: Mutex<HashMap<String, Instant>>,
+}
+
+impl Notifier {
+    pub fn new(webhook_url: Option<String>, dedup_window: Duration) -> Self {
+        Self {
+            webhook_url,
+            http: reqwest::Client::new(),
+            dedup_window,
+            last_sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Send `message` unless `key` already alerted within the dedup window.
+    /// Returns whether the alert went out.
+    pub fn notify(&self, key: &str, message: &str) -> bool {
+        {
+            let mut last_sent = self.last_sent.lock().unwrap();
+            let now = Instant::now();
+            if let Some(sent) = last_sent.get(key) {
+                if now.duration_since(*sent) < self.dedup_window {
+                    return false;
+                }
+            }
+            last_sent.insert(key.to_string(), now);
+        }
+
+        warn!("🚨 ALERT [{}]: {}", key, message);
+        if let (Some(url), Ok(runtime)) = (&self.webhook_url, tokio::runtime::Handle::try_current()) {
+            let request = self
+                .http
+                .post(url)
+                .json(&serde_json::json!({ "text": format!("[{}] {}", key, message) }));
+            runtime.spawn(async move {
+                if let Err(e) = request.send().await {
+                    warn!("Failed to deliver alert webhook: {}", e);
+                }
+            });
+        }
+        true
+    }
+}
+
+/// Flags trades that take longer than `max_latency` to confirm
+pub struct ConfirmLatencyMonitor {
+    max_latency: Duration,
+    notifier: Arc<Notifier>,
+    metrics: Arc<Metrics>,
+}
+
+impl ConfirmLatencyMonitor {
+    pub fn new(max_latency: Duration, notifier: Arc<Notifier>, metrics: Arc<Metrics>) -> Self {
+        Self {
+            max_latency,
+            notifier,
+            metrics,
+        }
+    }
+
+    /// Record one confirmation; returns whether it was over the limit
+    pub fn observe(&self, signature: &str, latency: Duration) -> bool {
+        let slow = latency > self.max_latency;
+        self.metrics.record_confirm_latency(latency.as_secs_f64(), slow);
+        if slow {
+            self.notifier.notify(
+                "slow_confirmation",
+                &format!(
+                    "Transaction {} took {:.1}s to confirm (limit {}s): RPC degraded or network congested",
+                    signature,
+                    latency.as_secs_f64(),
+                    self.max_latency.as_secs()
+                ),
+            );
+        }
+        slow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_confirmation_alerts_once() {
+        let notifier = Arc::new(Notifier::new(None, Duration::from_secs(600)));
+        let metrics = Metrics::new();
+        let monitor = ConfirmLatencyMonitor::new(Duration::from_secs(30), notifier.clone(), metrics.clone());
+
+        assert!(!monitor.observe("fast", Duration::from_secs(2)));
+        assert_eq!(metrics.slow_confirmations.get(), 0);
+
+        assert!(monitor.observe("slow", Duration::from_secs(45)));
+        assert_eq!(metrics.slow_confirmations.get(), 1);
+        // The alert for this key already went out; a repeat inside the window is dropped
+        assert!(!notifier.notify("slow_confirmation", "again"));
+
+        assert_eq!(metrics.confirm_latency_seconds.get_sample_count(), 2);
+    }
+}
@@ -0,0 +1,303 @@
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::str::FromStr;
+
+use crate::laserstream_client::ContainerSwap;
+use crate::price_tracker::PriceTracker;
+use crate::strategies::Strategy;
+
+/// A base/quote pair traded through Jupiter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradingPair {
+    pub base_token: String,
+    pub quote_token: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+}
+
+impl TradingPair {
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.base_token, self.quote_token)
+    }
+
+    /// The first of this pair's mints that appears in `blocked`
+    pub fn blocked_mint<'a>(&self, blocked: &'a [String]) -> Option<&'a str> {
+        blocked
+            .iter()
+            .find(|mint| **mint == self.base_mint || **mint == self.quote_mint)
+            .map(String::as_str)
+    }
+}
+
+/// Parse `TRADING_PAIRS`: comma-separated `BASE/QUOTE:base_mint:quote_mint` entries,
+/// e.g. `SOL/USDC:So111...112:EPjF...t1v,JUP/USDC:JUPy...vCN:EPjF...t1v`
+pub fn parse_pairs(value: &str) -> Result<Vec<TradingPair>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let mut parts = entry.split(':');
+            let (symbols, base_mint, quote_mint) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(s), Some(b), Some(q), None) => (s, b, q),
+                _ => anyhow::bail!("Invalid pair '{}': expected BASE/QUOTE:base_mint:quote_mint", entry),
+            };
+            let (base_token, quote_token) = symbols
+                .split_once('/')
+                .with_context(|| format!("Invalid pair symbols '{}': expected BASE/QUOTE", symbols))?;
+
+            Ok(TradingPair {
+                base_token: base_token.to_string(),
+                quote_token: quote_token.to_string(),
+                base_mint: base_mint.to_string(),
+                quote_mint: quote_mint.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Reject any pair that trades a mint from `BLOCKED_MINTS`
+pub fn validate_blocked_mints(pairs: &[TradingPair], blocked: &[String]) -> Result<()> {
+    for pair in pairs {
+        if let Some(mint) = pair.blocked_mint(blocked) {
+            anyhow::bail!("Pair {} uses blocked mint {}", pair.label(), mint);
+        }
+    }
+    Ok(())
+}
+
+/// What to do when `TRADING_PAIRS` lists more pairs than `MAX_CONCURRENT_PAIRS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+    /// Refuse the configuration
+    Error,
+    /// Process a different batch of at most the cap each tick
+    Rotate,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "rotate" => Ok(Self::Rotate),
+            other => anyhow::bail!("Unknown pair overflow policy: {}. Options: error, rotate", other),
+        }
+    }
+}
+
+/// Enforce `max_concurrent_pairs` (0 = unlimited) under the `Error` policy
+pub fn validate_pair_count(pairs: &[TradingPair], max_concurrent_pairs: usize, policy: OverflowPolicy) -> Result<()> {
+    if policy == OverflowPolicy::Error && max_concurrent_pairs > 0 && pairs.len() > max_concurrent_pairs {
+        anyhow::bail!(
+            "{} pairs configured but MAX_CONCURRENT_PAIRS is {}; drop pairs or set PAIR_OVERFLOW_POLICY=rotate",
+            pairs.len(),
+            max_concurrent_pairs
+        );
+    }
+    Ok(())
+}
+
+/// Cursor for rotating attention across pairs when there are more than the cap
+#[derive(Debug, Default)]
+pub struct PairRotation {
+    next: usize,
+}
+
+impl PairRotation {
+    /// Indices of the pairs to process this tick: all of them when `total` fits
+    /// in `max` (0 = unlimited), otherwise the next `max`, wrapping around
+    pub fn next_batch(&mut self, total: usize, max: usize) -> Vec<usize> {
+        if max == 0 || total <= max {
+            return (0..total).collect();
+        }
+        let batch = (0..max).map(|i| (self.next + i) % total).collect();
+        self.next = (self.next + max) % total;
+        batch
+    }
+}
+
+/// Per-pair price history and strategy used by the event loop
+pub struct PairTracker {
+    pub pair: TradingPair,
+    pub tracker: PriceTracker,
+    pub strategy: Box<dyn Strategy>,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+}
+
+impl PairTracker {
+    /// Quote-token volume traded on this pair, in either direction, by the
+    /// `swaps` in slots after `after_slot` up to `through_slot`
+    pub fn traded_volume(&self, swaps: &[ContainerSwap], after_slot: Option<u64>, through_slot: u64) -> f64 {
+        let (base, quote) = (&self.pair.base_mint, &self.pair.quote_mint);
+        let quote_amount: u64 = swaps
+            .iter()
+            .filter(|swap| swap.slot <= through_slot && after_slot.is_none_or(|after| swap.slot > after))
+            .filter_map(|swap| {
+                if swap.input_mint == *quote && swap.output_mint == *base {
+                    Some(swap.input_amount)
+                } else if swap.input_mint == *base && swap.output_mint == *quote {
+                    Some(swap.output_amount)
+                } else {
+                    None
+                }
+            })
+            .sum();
+        quote_amount as f64 / 10f64.powi(self.quote_decimals as i32)
+    }
+}
+
+/// Base-token amount (raw units) worth `notional` in the quote token at `price`,
+/// so the impact probe means the same thing across pairs
+pub fn probe_amount(notional: f64, price: f64, base_decimals: u8) -> u64 {
+    if price <= 0.0 {
+        return 0;
+    }
+    (notional / price * 10f64.powi(base_decimals as i32)).round() as u64
+}
+
+/// Run `fetch` for every item, either all at once (`join_all`) or one after another.
+/// Results come back in input order. Callers are expected to go through the
+/// shared rate limiter inside `fetch`.
+pub async fn fetch_all<'a, T, R, F, Fut>(items: &'a [T], concurrent: bool, fetch: F) -> Vec<R>
+where
+    F: Fn(&'a T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    if concurrent {
+        join_all(items.iter().map(&fetch)).await
+    } else {
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            results.push(fetch(item).await);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jupiter_client::RateLimiter;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    fn test_parse_pairs() {
+        let pairs = parse_pairs("SOL/USDC:So11111111111111111111111111111111111111112:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v, JUP/USDC:JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[1].label(), "JUP/USDC");
+        assert_eq!(pairs[1].base_mint, "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN");
+
+        assert!(parse_pairs("SOL-USDC:a:b").is_err());
+        assert!(parse_pairs("SOL/USDC:a").is_err());
+    }
+
+    #[test]
+    fn test_blocked_mint_fails_validation() {
+        let pairs = parse_pairs("SOL/USDC:So11111111111111111111111111111111111111112:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+
+        assert!(validate_blocked_mints(&pairs, &[]).is_ok());
+        assert!(validate_blocked_mints(&pairs, &["JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN".to_string()]).is_ok());
+        // Either side of the pair being blocked is enough
+        assert!(validate_blocked_mints(&pairs, &["So11111111111111111111111111111111111111112".to_string()]).is_err());
+        assert!(validate_blocked_mints(&pairs, &["EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_probe_amount_scales_with_price() {
+        // $100 of SOL (9 decimals) at $150 vs $300
+        assert_eq!(probe_amount(100.0, 150.0, 9), 666_666_667);
+        assert_eq!(probe_amount(100.0, 300.0, 9), 333_333_333);
+        // $100 of a 6-decimal token at $0.50
+        assert_eq!(probe_amount(100.0, 0.5, 6), 200_000_000);
+        assert_eq!(probe_amount(100.0, 0.0, 9), 0);
+    }
+
+    #[test]
+    fn test_traded_volume_counts_the_pairs_swaps_in_range() {
+        let pair = parse_pairs("SOL/USDC:sol:usdc").unwrap().remove(0);
+        let pt = PairTracker {
+            pair,
+            tracker: PriceTracker::new(60),
+            strategy: Box::new(crate::strategies::dca::DcaStrategy::new(1)),
+            base_decimals: 9,
+            quote_decimals: 6,
+        };
+        let swap = |slot, input_mint: &str, input_amount, output_mint: &str, output_amount| ContainerSwap {
+            slot,
+            input_mint: input_mint.to_string(),
+            input_amount,
+            output_mint: output_mint.to_string(),
+            output_amount,
+        };
+        let swaps = [
+            swap(12, "usdc", 150_000_000, "sol", 1_000_000_000), // buys 1 SOL for 150 USDC
+            swap(11, "sol", 2_000_000_000, "usdc", 299_000_000), // sells 2 SOL for 299 USDC
+            swap(11, "jup", 5_000_000, "usdc", 4_000_000),       // another pair
+            swap(10, "usdc", 75_000_000, "sol", 500_000_000),    // before the range
+            swap(13, "usdc", 15_000_000, "sol", 100_000_000),    // after it
+        ];
+
+        assert!((pt.traded_volume(&swaps, Some(10), 12) - 449.0).abs() < 1e-9);
+        assert!((pt.traded_volume(&swaps, None, 10) - 75.0).abs() < 1e-9);
+        assert_eq!(pt.traded_volume(&swaps, Some(13), 14), 0.0);
+    }
+
+    #[test]
+    fn test_pairs_over_cap_follow_overflow_policy() {
+        let pairs = parse_pairs(
+            "A/USDC:a:q,B/USDC:b:q,C/USDC:c:q,D/USDC:d:q,E/USDC:e:q",
+        )
+        .unwrap();
+
+        assert!(validate_pair_count(&pairs, 3, OverflowPolicy::Error).is_err());
+        assert!(validate_pair_count(&pairs, 5, OverflowPolicy::Error).is_ok());
+        assert!(validate_pair_count(&pairs, 0, OverflowPolicy::Error).is_ok());
+        assert!(validate_pair_count(&pairs, 3, OverflowPolicy::Rotate).is_ok());
+
+        // Rotation covers every pair, at most 3 per tick
+        let mut rotation = PairRotation::default();
+        assert_eq!(rotation.next_batch(pairs.len(), 3), vec![0, 1, 2]);
+        assert_eq!(rotation.next_batch(pairs.len(), 3), vec![3, 4, 0]);
+        assert_eq!(rotation.next_batch(pairs.len(), 3), vec![1, 2, 3]);
+
+        // Under the cap: everything, every tick
+        assert_eq!(rotation.next_batch(2, 3), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_is_faster_than_sequential() {
+        let delays_ms: Vec<u64> = vec![80, 60, 70, 50];
+        let limiter = RateLimiter::new(1_000);
+
+        let fetch = |delay: &u64| {
+            let limiter = &limiter;
+            let delay = *delay;
+            async move {
+                limiter.acquire().await;
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                delay
+            }
+        };
+
+        let started = Instant::now();
+        let results = fetch_all(&delays_ms, true, fetch).await;
+        let elapsed = started.elapsed();
+
+        // Results preserve pair order
+        assert_eq!(results, delays_ms);
+        // Bounded by the slowest pair, not the sum (260ms)
+        assert!(elapsed < Duration::from_millis(200), "took {:?}", elapsed);
+        assert!(elapsed >= Duration::from_millis(80));
+
+        let started = Instant::now();
+        fetch_all(&delays_ms, false, fetch).await;
+        assert!(started.elapsed() >= Duration::from_millis(260));
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+impl fmt::Display for FillSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FillSide::Buy => write!(f, "buy"),
+            FillSide::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+/// An executed trade to record, in token units (not raw base units)
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub pair: String,
+    pub side: FillSide,
+    pub price: f64,
+    pub base_amount: f64,
+    /// Fee in quote tokens
+    pub fee: f64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// A recorded fill with its quote value and the running realized PnL
+#[derive(Debug, Clone)]
+pub struct LedgerEntry {
+    pub timestamp: i64,
+    pub pair: String,
+    pub side: FillSide,
+    pub price: f64,
+    pub base_amount: f64,
+    pub quote_amount: f64,
+    /// Fee in quote tokens
+    pub fee: f64,
+    /// Cumulative realized PnL across all pairs after this fill
+    pub realized_pnl: f64,
+    pub signature: String,
+}
+
+/// Open position per pair at average cost
+#[derive(Debug, Clone, Default)]
+struct Position {
+    base: f64,
+    /// Quote spent on `base`, fees included
+    cost: f64,
+}
+
+/// Trade ledger with average-cost realized PnL
+#[derive(Debug, Default)]
+pub struct PnlTracker {
+    fills: Vec<LedgerEntry>,
+    positions: HashMap<String, Position>,
+    realized_pnl: f64,
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a fill. Sells beyond the tracked position realize PnL only on
+    /// the part that was bought here.
+    pub fn record_fill(&mut self, fill: Fill) {
+        let Fill {
+            pair,
+            side,
+            price,
+            base_amount,
+            fee,
+            timestamp,
+            signature,
+        } = fill;
+        let position = self.positions.entry(pair.clone()).or_default();
+        let quote_amount = base_amount * price;
+
+        match side {
+            FillSide::Buy => {
+                position.base += base_amount;
+                position.cost += quote_amount + fee;
+            }
+            FillSide::Sell => {
+                let closed = base_amount.min(position.base);
+                let avg_cost = if position.base > 0.0 { position.cost / position.base } else { 0.0 };
+                self.realized_pnl += closed * (price - avg_cost) - fee;
+                position.base -= closed;
+                position.cost -= closed * avg_cost;
+            }
+        }
+
+        self.fills.push(LedgerEntry {
+            timestamp,
+            pair,
+            side,
+            price,
+            base_amount,
+            quote_amount,
+            fee,
+            realized_pnl: self.realized_pnl,
+            signature,
+        });
+    }
+
+    pub fn fills(&self) -> &[LedgerEntry] {
+        &self.fills
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.realized_pnl
+    }
+
+    /// Write every recorded fill to `path`, replacing the file
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        let mut csv = String::from(
+            "timestamp,pair,side,price,base_amount,quote_amount,fee,realized_pnl,signature\n",
+        );
+        for fill in &self.fills {
+            let timestamp = chrono::DateTime::from_timestamp(fill.timestamp, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| fill.timestamp.to_string());
+            csv.push_str(&format!(
+                "{},{},{},{:.6},{:.9},{:.6},{:.6},{:.6},{}\n",
+                timestamp,
+                fill.pair,
+                fill.side,
+                fill.price,
+                fill.base_amount,
+                fill.quote_amount,
+                fill.fee,
+                fill.realized_pnl,
+                fill.signature
+            ));
+        }
+
+        std::fs::write(path, csv).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: FillSide, price: f64, base_amount: f64, timestamp: i64, signature: &str) -> Fill {
+        Fill {
+            pair: "SOL/USDC".to_string(),
+            side,
+            price,
+            base_amount,
+            fee: 0.1,
+            timestamp,
+            signature: signature.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_export_csv_rows() {
+        let mut pnl = PnlTracker::new();
+        pnl.record_fill(fill(FillSide::Buy, 100.0, 2.0, 1_700_000_000, "sig1"));
+        pnl.record_fill(fill(FillSide::Buy, 110.0, 2.0, 1_700_000_060, "sig2"));
+        // Average cost (200.1 + 220.1) / 4 = 105.05
+        pnl.record_fill(fill(FillSide::Sell, 120.0, 1.0, 1_700_000_120, "sig3"));
+        assert!((pnl.realized_pnl() - 14.85).abs() < 1e-9);
+
+        let path = std::env::temp_dir().join(format!("pnl-{}.csv", std::process::id()));
+        pnl.export_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "timestamp,pair,side,price,base_amount,quote_amount,fee,realized_pnl,signature",
+                "2023-11-14T22:13:20+00:00,SOL/USDC,buy,100.000000,2.000000000,200.000000,0.100000,0.000000,sig1",
+                "2023-11-14T22:14:20+00:00,SOL/USDC,buy,110.000000,2.000000000,220.000000,0.100000,0.000000,sig2",
+                "2023-11-14T22:15:20+00:00,SOL/USDC,sell,120.000000,1.000000000,120.000000,0.100000,14.850000,sig3",
+            ]
+        );
+    }
+}
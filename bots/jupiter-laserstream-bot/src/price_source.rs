@@ -0,0 +1,276 @@
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use jupiter_client::JupiterClient;
+use crate::pairs::TradingPair;
+
+/// Pyth Hermes SOL/USD price feed id
+pub const PYTH_SOL_USD_FEED_ID: &str =
+    "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+/// Anything that can quote a pair's price (quote per base)
+pub trait PriceSource: Send + Sync {
+    fn name(&self) -> &str;
+    fn fetch_price<'a>(&'a self, pair: &'a TradingPair) -> BoxFuture<'a, Result<f64>>;
+}
+
+impl PriceSource for JupiterClient {
+    fn name(&self) -> &str {
+        "jupiter"
+    }
+
+    fn fetch_price<'a>(&'a self, pair: &'a TradingPair) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(async move { Ok(self.get_price(&pair.base_mint, &pair.quote_mint).await?) })
+    }
+}
+
+/// Secondary sources selectable via `SECONDARY_PRICE_SOURCE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecondarySourceKind {
+    Pyth,
+}
+
+impl FromStr for SecondarySourceKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "pyth" => Ok(Self::Pyth),
+            other => anyhow::bail!("Unknown secondary price source '{}'. Options: pyth", other),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesResponse {
+    parsed: Vec<HermesPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPriceUpdate {
+    price: HermesPrice,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    expo: i32,
+}
+
+/// Parse `PYTH_FEED_IDS`: comma-separated `BASE/QUOTE=feed_id` entries, e.g.
+/// `SOL/USDC=ef0d...b56d,JUP/USDC=0a04...f996`
+pub fn parse_feed_ids(value: &str) -> Result<HashMap<String, String>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (pair, feed_id) = entry
+                .split_once('=')
+                .with_context(|| format!("Invalid Pyth feed '{}': expected BASE/QUOTE=feed_id", entry))?;
+            let feed_id = feed_id.trim().trim_start_matches("0x");
+            if feed_id.is_empty() {
+                anyhow::bail!("Invalid Pyth feed '{}': empty feed id", entry);
+            }
+            Ok((pair.trim().to_string(), feed_id.to_string()))
+        })
+        .collect()
+}
+
+/// Pyth price via the Hermes HTTP API, one feed id per pair label (SOL/USD
+/// stands in for SOL/USDC). A pair without a feed is an error, so the sanity
+/// check blocks it rather than comparing against another pair's price.
+pub struct PythPriceSource {
+    client: Client,
+    hermes_url: String,
+    feed_ids: HashMap<String, String>,
+}
+
+impl PythPriceSource {
+    pub fn new(feed_ids: HashMap<String, String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            hermes_url: "https://hermes.pyth.network".to_string(),
+            feed_ids,
+        }
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> Result<f64> {
+        let feed_id = self
+            .feed_ids
+            .get(&pair.label())
+            .with_context(|| format!("No Pyth feed configured for {} (PYTH_FEED_IDS)", pair.label()))?;
+        let url = format!(
+            "{}/v2/updates/price/latest?ids[]={}",
+            self.hermes_url, feed_id
+        );
+        debug!("Fetching price from Pyth: {}", url);
+
+        let response: HermesResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch price from Pyth")?
+            .error_for_status()
+            .context("Pyth Hermes API error")?
+            .json()
+            .await
+            .context("Failed to parse Pyth price response")?;
+
+        let update = response.parsed.first().context("Price not found in Pyth response")?;
+        let mantissa: f64 = update.price.price.parse().context("Invalid Pyth price")?;
+        Ok(mantissa * 10f64.powi(update.price.expo))
+    }
+}
+
+impl PriceSource for PythPriceSource {
+    fn name(&self) -> &str {
+        "pyth"
+    }
+
+    fn fetch_price<'a>(&'a self, pair: &'a TradingPair) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(self.get_price(pair))
+    }
+}
+
+/// Absolute divergence between two prices, as a percentage of `primary`
+pub fn divergence_pct(primary: f64, secondary: f64) -> f64 {
+    ((primary - secondary) / primary).abs() * 100.0
+}
+
+/// Refuses trades when the primary and secondary sources disagree by more
+/// than `max_divergence_pct`
+pub struct PriceSanityCheck {
+    secondary: Box<dyn PriceSource>,
+    max_divergence_pct: f64,
+}
+
+impl PriceSanityCheck {
+    pub fn new(secondary: Box<dyn PriceSource>, max_divergence_pct: f64) -> Self {
+        Self {
+            secondary,
+            max_divergence_pct,
+        }
+    }
+
+    /// Fetch both prices and return the divergence, or an error if the trade should be blocked
+    pub async fn check(&self, primary: &dyn PriceSource, pair: &TradingPair) -> Result<f64> {
+        let (primary_price, secondary_price) = futures::try_join!(
+            primary.fetch_price(pair),
+            self.secondary.fetch_price(pair)
+        )?;
+        let divergence = divergence_pct(primary_price, secondary_price);
+
+        if divergence > self.max_divergence_pct {
+            warn!(
+                "🛑 {} price check failed: {}=${:.4} {}=${:.4} divergence {:.2}% > {:.2}%",
+                pair.label(),
+                primary.name(),
+                primary_price,
+                self.secondary.name(),
+                secondary_price,
+                divergence,
+                self.max_divergence_pct
+            );
+            anyhow::bail!(
+                "{} and {} prices diverge by {:.2}% (max {:.2}%)",
+                primary.name(),
+                self.secondary.name(),
+                divergence,
+                self.max_divergence_pct
+            );
+        }
+
+        info!(
+            "🔎 {} price check: {}=${:.4} {}=${:.4} divergence {:.2}%",
+            pair.label(),
+            primary.name(),
+            primary_price,
+            self.secondary.name(),
+            secondary_price,
+            divergence
+        );
+        Ok(divergence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedSource(&'static str, f64);
+
+    impl PriceSource for FixedSource {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn fetch_price<'a>(&'a self, _pair: &'a TradingPair) -> BoxFuture<'a, Result<f64>> {
+            Box::pin(async move { Ok(self.1) })
+        }
+    }
+
+    fn sol_usdc() -> TradingPair {
+        TradingPair {
+            base_token: "SOL".to_string(),
+            quote_token: "USDC".to_string(),
+            base_mint: "So11111111111111111111111111111111111111112".to_string(),
+            quote_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_divergent_sources_block_trade() {
+        let check = PriceSanityCheck::new(Box::new(FixedSource("pyth", 120.0)), 1.0);
+        let primary = FixedSource("jupiter", 150.0);
+
+        assert!(check.check(&primary, &sol_usdc()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_agreeing_sources_allow_trade() {
+        let check = PriceSanityCheck::new(Box::new(FixedSource("pyth", 150.6)), 1.0);
+        let primary = FixedSource("jupiter", 150.0);
+
+        let divergence = check.check(&primary, &sol_usdc()).await.unwrap();
+        assert!((divergence - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_feed_ids() {
+        let feeds = parse_feed_ids(&format!("SOL/USDC={}, JUP/USDC=0xabc1", PYTH_SOL_USD_FEED_ID)).unwrap();
+        assert_eq!(feeds["SOL/USDC"], PYTH_SOL_USD_FEED_ID);
+        assert_eq!(feeds["JUP/USDC"], "abc1");
+
+        assert!(parse_feed_ids("SOL/USDC").is_err());
+        assert!(parse_feed_ids("SOL/USDC=").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pair_without_feed_fails_closed() {
+        let pyth = PythPriceSource::new(parse_feed_ids(&format!("SOL/USDC={}", PYTH_SOL_USD_FEED_ID)).unwrap());
+        let jup_usdc = TradingPair {
+            base_token: "JUP".to_string(),
+            base_mint: "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN".to_string(),
+            ..sol_usdc()
+        };
+
+        let err = pyth.fetch_price(&jup_usdc).await.unwrap_err();
+        assert!(err.to_string().contains("No Pyth feed configured for JUP/USDC"), "{}", err);
+        let check = PriceSanityCheck::new(Box::new(pyth), 1.0);
+        assert!(check.check(&FixedSource("jupiter", 1.0), &jup_usdc).await.is_err());
+    }
+}
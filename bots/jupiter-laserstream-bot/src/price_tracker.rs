@@ -1,16 +1,38 @@
+use bot_common::indicators;
 use std::collections::VecDeque;
 
+/// Fewest points a standard deviation can be computed from
+const DEFAULT_MIN_POINTS_FOR_VOLATILITY: usize = 2;
+
 #[derive(Debug, Clone)]
 pub struct PricePoint {
     pub price: f64,
     pub volume: f64,
     pub timestamp: i64,
+    /// Synthetic point used to prime the tracker at startup (not a market observation)
+    pub seeded: bool,
+}
+
+/// OHLCV over one fixed time bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    /// Bucket start, a multiple of the interval in unix seconds
+    pub start_ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
 }
 
 pub struct PriceTracker {
     prices: VecDeque<PricePoint>,
     max_points: usize,
+    /// Window `rsi` reads, in minutes back from the newest point
+    lookback_minutes: usize,
     update_count: u64,
+    /// `volatility` (and Bollinger Bands) read as None below this many real points
+    min_points_for_volatility: usize,
 }
 
 impl PriceTracker {
@@ -21,23 +43,72 @@ impl PriceTracker {
         Self {
             prices: VecDeque::with_capacity(max_points),
             max_points,
+            lookback_minutes,
             update_count: 0,
+            min_points_for_volatility: DEFAULT_MIN_POINTS_FOR_VOLATILITY,
         }
     }
     
+    pub fn with_min_points_for_volatility(mut self, min_points: usize) -> Self {
+        self.min_points_for_volatility = min_points.max(DEFAULT_MIN_POINTS_FOR_VOLATILITY);
+        self
+    }
+    
     pub fn add_price(&mut self, price: f64, volume: f64, timestamp: i64) {
         self.prices.push_back(PricePoint {
             price,
             volume,
             timestamp,
+            seeded: false,
+        });
+        
+        self.trim();
+        self.update_count += 1;
+    }
+    
+    /// Prime the tracker with a seed price so short-lookback averages are
+    /// available immediately. Seeded points carry no volume, don't count as
+    /// updates, and are ignored by `volatility` and `has_sufficient_data`.
+    pub fn seed(&mut self, price: f64, timestamp: i64) {
+        self.prices.push_back(PricePoint {
+            price,
+            volume: 0.0,
+            timestamp,
+            seeded: true,
         });
         
+        self.trim();
+    }
+    
+    pub fn is_seeded(&self) -> bool {
+        self.prices.iter().any(|p| p.seeded)
+    }
+    
+    fn trim(&mut self) {
         // Remove old data
         while self.prices.len() > self.max_points {
             self.prices.pop_front();
         }
-        
-        self.update_count += 1;
+    }
+    
+    /// Drop all history, e.g. after a feed outage left a hole in the window
+    pub fn reset(&mut self) {
+        self.prices.clear();
+        self.update_count = 0;
+    }
+    
+    /// Reset when more than `max_gap_secs` passed since the last point, so the
+    /// window never straddles a large hole. Returns whether the tracker was reset.
+    /// A `max_gap_secs` of 0 disables the check.
+    pub fn reset_if_gap(&mut self, now: i64, max_gap_secs: u64) -> bool {
+        let Some(last) = self.prices.back().map(|p| p.timestamp) else {
+            return false;
+        };
+        if max_gap_secs == 0 || now - last <= max_gap_secs as i64 {
+            return false;
+        }
+        self.reset();
+        true
     }
     
     pub fn current_price(&self) -> Option<f64> {
@@ -65,6 +136,21 @@ impl PriceTracker {
         Some(sum / relevant_prices.len() as f64)
     }
     
+    /// Exponential moving average over every stored point, oldest to newest,
+    /// with smoothing factor `2 / (periods + 1)`; starts from the oldest price
+    pub fn ema(&self, periods: usize) -> Option<f64> {
+        let prices: Vec<f64> = self.prices.iter().map(|p| p.price).collect();
+        indicators::ema(&prices, periods)
+    }
+    
+    /// MACD over the stored prices: returns (MACD line, signal line, histogram).
+    /// None when `fast >= slow` (a misconfiguration, not a market state) or
+    /// there are fewer than `slow` points.
+    pub fn macd(&self, fast: usize, slow: usize, signal: usize) -> Option<(f64, f64, f64)> {
+        let prices: Vec<f64> = self.prices.iter().map(|p| p.price).collect();
+        indicators::macd(&prices, fast, slow, signal)
+    }
+    
     pub fn volume_weighted_average(&self, minutes: usize) -> Option<f64> {
         if self.prices.is_empty() {
             return None;
@@ -87,6 +173,36 @@ impl PriceTracker {
         Some(total_value / total_volume)
     }
     
+    /// VWAP where no single point weighs more than `max_weight_fraction` of the
+    /// window's total volume, so one outsized print can't dominate fair value
+    pub fn capped_vwap(&self, minutes: usize, max_weight_fraction: f64) -> Option<f64> {
+        let cutoff_time = self.prices.back()?.timestamp - (minutes as i64 * 60);
+        let window: Vec<&PricePoint> = self.prices
+            .iter()
+            .filter(|p| p.timestamp >= cutoff_time)
+            .collect();
+        
+        let total_volume: f64 = window.iter().map(|p| p.volume).sum();
+        if total_volume == 0.0 {
+            return None;
+        }
+        let max_weight = total_volume * max_weight_fraction;
+        
+        let mut total_value = 0.0;
+        let mut total_weight = 0.0;
+        for point in window {
+            let weight = point.volume.min(max_weight);
+            total_value += point.price * weight;
+            total_weight += weight;
+        }
+        
+        if total_weight == 0.0 {
+            return None;
+        }
+        
+        Some(total_value / total_weight)
+    }
+    
     pub fn price_change_percent(&self, minutes: usize) -> Option<f64> {
         let current = self.current_price()?;
         let previous = self.moving_average(minutes)?;
@@ -94,31 +210,86 @@ impl PriceTracker {
         Some((current - previous) / previous * 100.0)
     }
     
+    /// Standard deviation of the window's real prices. None until the window
+    /// holds `min_points_for_volatility` of them, so callers fall back to their
+    /// base parameters rather than act on a noisy estimate.
     pub fn volatility(&self, minutes: usize) -> Option<f64> {
-        if self.prices.is_empty() {
-            return None;
-        }
-        
+        indicators::std_dev(&self.volatility_window(minutes)?)
+    }
+    
+    /// The window's real prices, or None while there are fewer than
+    /// `min_points_for_volatility` of them
+    fn volatility_window(&self, minutes: usize) -> Option<Vec<f64>> {
         let cutoff_time = self.prices.back()?.timestamp - (minutes as i64 * 60);
-        
-        let relevant_prices: Vec<f64> = self.prices
+        let window: Vec<f64> = self.prices
             .iter()
-            .filter(|p| p.timestamp >= cutoff_time)
+            .filter(|p| !p.seeded && p.timestamp >= cutoff_time)
             .map(|p| p.price)
             .collect();
         
-        if relevant_prices.len() < 2 {
-            return None;
-        }
-        
-        // Calculate standard deviation
-        let mean = relevant_prices.iter().sum::<f64>() / relevant_prices.len() as f64;
-        let variance = relevant_prices
+        (window.len() >= self.min_points_for_volatility).then_some(window)
+    }
+    
+    /// Relative Strength Index with Wilder smoothing over the lookback window's
+    /// price changes (seeded points excluded). None until the window holds
+    /// `periods + 1` samples.
+    pub fn rsi(&self, periods: usize) -> Option<f64> {
+        let cutoff_time = self.prices.back()?.timestamp - (self.lookback_minutes as i64 * 60);
+        let prices: Vec<f64> = self.prices
             .iter()
-            .map(|p| (p - mean).powi(2))
-            .sum::<f64>() / relevant_prices.len() as f64;
-        
-        Some(variance.sqrt())
+            .filter(|p| !p.seeded && p.timestamp >= cutoff_time)
+            .map(|p| p.price)
+            .collect();
+        indicators::rsi(&prices, periods)
+    }
+    
+    /// Stored points (seeded ones excluded) bucketed into `interval_secs`
+    /// candles aligned to the epoch, oldest first. Buckets without points are
+    /// skipped rather than filled.
+    pub fn candles(&self, interval_secs: i64) -> Vec<Candle> {
+        if interval_secs <= 0 {
+            return Vec::new();
+        }
+
+        let mut candles: Vec<Candle> = Vec::new();
+        for point in self.prices.iter().filter(|p| !p.seeded) {
+            let start_ts = point.timestamp - point.timestamp.rem_euclid(interval_secs);
+            match candles.last_mut() {
+                Some(candle) if candle.start_ts == start_ts => {
+                    candle.high = candle.high.max(point.price);
+                    candle.low = candle.low.min(point.price);
+                    candle.close = point.price;
+                    candle.volume += point.volume;
+                }
+                _ => candles.push(Candle {
+                    start_ts,
+                    open: point.price,
+                    high: point.price,
+                    low: point.price,
+                    close: point.price,
+                    volume: point.volume,
+                }),
+            }
+        }
+        candles
+    }
+
+    /// `rsi` over `interval_secs` candle closes instead of raw ticks
+    pub fn candle_rsi(&self, interval_secs: i64, periods: usize) -> Option<f64> {
+        let closes: Vec<f64> = self.candles(interval_secs).iter().map(|c| c.close).collect();
+        indicators::rsi(&closes, periods)
+    }
+
+    /// `macd` over `interval_secs` candle closes instead of raw ticks
+    pub fn candle_macd(&self, interval_secs: i64, fast: usize, slow: usize, signal: usize) -> Option<(f64, f64, f64)> {
+        let closes: Vec<f64> = self.candles(interval_secs).iter().map(|c| c.close).collect();
+        indicators::macd(&closes, fast, slow, signal)
+    }
+
+    /// (lower, middle, upper) Bollinger Bands: the window's mean (seeded points
+    /// excluded, as in `volatility`) plus/minus `std_devs` standard deviations
+    pub fn bollinger_bands(&self, minutes: usize, std_devs: f64) -> Option<(f64, f64, f64)> {
+        indicators::bollinger_bands(&self.volatility_window(minutes)?, std_devs)
     }
     
     pub fn update_count(&self) -> u64 {
@@ -131,7 +302,10 @@ impl PriceTracker {
         }
         
         let cutoff_time = self.prices.back().unwrap().timestamp - (minutes as i64 * 60);
-        let data_points = self.prices.iter().filter(|p| p.timestamp >= cutoff_time).count();
+        let data_points = self.prices
+            .iter()
+            .filter(|p| !p.seeded && p.timestamp >= cutoff_time)
+            .count();
         
         // Require at least 30% of expected data points (assuming ~1/sec)
         data_points >= (minutes * 60 * 30 / 100)
@@ -155,4 +329,246 @@ mod tests {
         assert_eq!(tracker.current_price(), Some(102.0));
         assert!((tracker.moving_average(2).unwrap() - 101.5).abs() < 0.01);
     }
+    
+    #[test]
+    fn test_seeded_tracker() {
+        let mut tracker = PriceTracker::new(5);
+        let now = chrono::Utc::now().timestamp();
+        
+        tracker.seed(150.0, now);
+        
+        // Moving average is available straight away from the seed
+        assert_eq!(tracker.moving_average(1), Some(150.0));
+        assert_eq!(tracker.current_price(), Some(150.0));
+        assert!(tracker.is_seeded());
+        assert_eq!(tracker.update_count(), 0);
+        
+        // Volatility and warmup ignore seeded data
+        assert_eq!(tracker.volatility(1), None);
+        assert!(!tracker.has_sufficient_data(1));
+        
+        // One real point is still not enough for volatility...
+        tracker.add_price(152.0, 1.0, now + 1);
+        assert_eq!(tracker.volatility(1), None);
+        
+        // ...but two real points are, and the seed doesn't bias it
+        tracker.add_price(154.0, 1.0, now + 2);
+        assert!((tracker.volatility(1).unwrap() - 1.0).abs() < 1e-9);
+        assert!((tracker.moving_average(1).unwrap() - 152.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_volatility_needs_min_points() {
+        let mut tracker = PriceTracker::new(5).with_min_points_for_volatility(5);
+        let now = chrono::Utc::now().timestamp();
+        
+        for (i, price) in [100.0, 102.0, 100.0, 102.0].into_iter().enumerate() {
+            tracker.add_price(price, 1.0, now + i as i64);
+        }
+        assert_eq!(tracker.volatility(1), None);
+        assert_eq!(tracker.bollinger_bands(1, 2.0), None);
+        
+        tracker.add_price(101.0, 1.0, now + 4);
+        assert!(tracker.volatility(1).unwrap() > 0.0);
+        assert!(tracker.bollinger_bands(1, 2.0).is_some());
+    }
+    
+    #[test]
+    fn test_reset_after_long_gap() {
+        let now = chrono::Utc::now().timestamp();
+        let mut tracker = PriceTracker::new(5);
+        tracker.add_price(100.0, 1.0, now);
+        tracker.add_price(101.0, 1.0, now + 1);
+        
+        // Short blip: history is kept
+        assert!(!tracker.reset_if_gap(now + 30, 60));
+        assert_eq!(tracker.update_count(), 2);
+        assert_eq!(tracker.current_price(), Some(101.0));
+        
+        // Long outage: history is dropped and warmup starts over
+        assert!(tracker.reset_if_gap(now + 600, 60));
+        assert_eq!(tracker.update_count(), 0);
+        assert_eq!(tracker.current_price(), None);
+        assert!(!tracker.has_sufficient_data(1));
+        
+        // Disabled
+        tracker.add_price(102.0, 1.0, now);
+        assert!(!tracker.reset_if_gap(now + 600, 0));
+    }
+    
+    #[test]
+    fn test_capped_vwap_limits_dominant_print() {
+        let mut tracker = PriceTracker::new(5);
+        let now = chrono::Utc::now().timestamp();
+        
+        tracker.add_price(100.0, 10.0, now);
+        tracker.add_price(100.0, 10.0, now + 1);
+        tracker.add_price(110.0, 980.0, now + 2); // one huge print
+        
+        let vwap = tracker.volume_weighted_average(5).unwrap();
+        let capped = tracker.capped_vwap(5, 0.1).unwrap();
+        
+        // Uncapped is pulled almost all the way to the outlier
+        assert!((vwap - 109.8).abs() < 1e-9);
+        // Capped at 100 volume: (100*10 + 100*10 + 110*100) / 120
+        assert!((capped - 108.333_333_333).abs() < 1e-6);
+        assert!(capped < vwap);
+        
+        // A cap of 100% is the plain VWAP
+        assert!((tracker.capped_vwap(5, 1.0).unwrap() - vwap).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ema_tracks_trend_faster_than_sma() {
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+        assert!(tracker.ema(10).is_none());
+
+        tracker.add_price(150.0, 1.0, now);
+        assert_eq!(tracker.ema(10), Some(150.0));
+
+        for i in 1..60 {
+            tracker.add_price(150.0 + i as f64, 1.0, now + i);
+        }
+        let last = tracker.current_price().unwrap();
+        let ema = tracker.ema(10).unwrap();
+        let sma = tracker.moving_average(60).unwrap();
+        // On a steady rise the EMA lags by about (periods - 1) / 2 steps; the SMA by half the window
+        assert!(sma < ema && ema < last);
+        assert!((last - ema - 4.5).abs() < 0.01);
+        assert!((last - sma - 29.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_macd_histogram_is_line_minus_signal() {
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+        for i in 0..60 {
+            // Rising with a wobble so the signal line lags the MACD line
+            tracker.add_price(150.0 + i as f64 * 0.5 + (i % 5) as f64, 1.0, now + i);
+        }
+
+        let (line, signal, histogram) = tracker.macd(12, 26, 9).unwrap();
+        assert!((histogram - (line - signal)).abs() < 1e-12);
+        // Uptrend: fast EMA above slow
+        assert!(line > 0.0);
+
+        assert!(tracker.macd(26, 12, 9).is_none());
+        assert!(tracker.macd(12, 12, 9).is_none());
+        assert!(tracker.macd(12, 61, 9).is_none());
+    }
+
+    #[test]
+    fn test_bollinger_bands() {
+        let now = chrono::Utc::now().timestamp();
+
+        let mut flat = PriceTracker::new(60);
+        flat.add_price(150.0, 1.0, now);
+        assert!(flat.bollinger_bands(10, 2.0).is_none());
+        for i in 1..20 {
+            flat.add_price(150.0, 1.0, now + i);
+        }
+        assert_eq!(flat.bollinger_bands(10, 2.0), Some((150.0, 150.0, 150.0)));
+
+        // Alternating 148/152: mean 150, population std dev 2
+        let mut noisy = PriceTracker::new(60);
+        for i in 0..20 {
+            let price = if i % 2 == 0 { 148.0 } else { 152.0 };
+            noisy.add_price(price, 1.0, now + i);
+        }
+        let (lower, middle, upper) = noisy.bollinger_bands(10, 2.0).unwrap();
+        assert!((middle - 150.0).abs() < 1e-9);
+        assert!((lower - 146.0).abs() < 1e-9);
+        assert!((upper - 154.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rsi_hand_calculated() {
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+        for (i, price) in [10.0, 11.0, 10.5, 12.0].iter().enumerate() {
+            tracker.add_price(*price, 1.0, now + i as i64);
+        }
+        assert!(tracker.rsi(4).is_none());
+
+        // Changes +1, -0.5, +1.5: avg gain 2.5/3, avg loss 0.5/3, RS 5 => 100 - 100/6
+        assert!((tracker.rsi(3).unwrap() - 83.333_333).abs() < 1e-4);
+
+        // -0.5: avg gain (2.5/3*2 + 0)/3 = 5/9, avg loss (0.5/3*2 + 0.5)/3 = 5/18, RS 2
+        tracker.add_price(11.5, 1.0, now + 4);
+        assert!((tracker.rsi(3).unwrap() - 66.666_667).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rsi_bounds() {
+        let mut rising = PriceTracker::new(60);
+        let mut flat = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+        for i in 0..15 {
+            rising.add_price(100.0 + i as f64, 1.0, now + i);
+            flat.add_price(100.0, 1.0, now + i);
+        }
+        assert_eq!(rising.rsi(14), Some(100.0));
+        assert_eq!(flat.rsi(14), Some(50.0));
+
+        // Seeded points are not samples
+        let mut seeded = PriceTracker::new(60);
+        seeded.seed(100.0, now);
+        seeded.add_price(101.0, 1.0, now + 1);
+        assert!(seeded.rsi(1).is_none());
+    }
+
+    #[test]
+    fn test_rsi_only_reads_lookback_window() {
+        // A crash two minutes ago, then a steady climb inside the 1-minute window
+        let mut tracker = PriceTracker::new(1);
+        let now = chrono::Utc::now().timestamp();
+        tracker.add_price(200.0, 1.0, now - 120);
+        tracker.add_price(100.0, 1.0, now - 119);
+        for i in 0..5 {
+            tracker.add_price(100.0 + i as f64, 1.0, now - 4 + i);
+        }
+
+        assert_eq!(tracker.rsi(4), Some(100.0));
+        assert!(tracker.rsi(5).is_none());
+    }
+
+    #[test]
+    fn test_candles_over_several_intervals() {
+        let mut tracker = PriceTracker::new(60);
+        tracker.seed(500.0, 0);
+        // (timestamp, price, volume): two ticks in [0,60), three in [60,120),
+        // none in [120,180), one in [180,240)
+        for (ts, price, volume) in [
+            (5, 100.0, 1.0),
+            (59, 102.0, 2.0),
+            (60, 101.0, 1.0),
+            (90, 105.0, 3.0),
+            (119, 99.0, 1.0),
+            (200, 110.0, 4.0),
+        ] {
+            tracker.add_price(price, volume, ts);
+        }
+
+        let candle = |start_ts, open, high, low, close, volume| Candle { start_ts, open, high, low, close, volume };
+        // The seed is skipped, as is the empty bucket
+        assert_eq!(
+            tracker.candles(60),
+            vec![
+                candle(0, 100.0, 102.0, 100.0, 102.0, 3.0),
+                candle(60, 101.0, 105.0, 99.0, 99.0, 5.0),
+                candle(180, 110.0, 110.0, 110.0, 110.0, 4.0),
+            ]
+        );
+
+        // One bucket spanning everything
+        assert_eq!(tracker.candles(3600), vec![candle(0, 100.0, 110.0, 99.0, 110.0, 12.0)]);
+        assert!(tracker.candles(0).is_empty());
+
+        // Closes 102, 99, 110: one loss of 3 and one gain of 11
+        let rsi = tracker.candle_rsi(60, 2).unwrap();
+        assert!((rsi - 100.0 * 11.0 / 14.0).abs() < 1e-9);
+        let (line, _, _) = tracker.candle_macd(60, 1, 2, 1).unwrap();
+        assert!(line > 0.0);
+    }
 }
@@ -0,0 +1,219 @@
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+pub type CheckFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// An external dependency (RPC, Jupiter, LaserStream container) probed before trading
+pub struct Dependency<'a> {
+    pub name: &'static str,
+    check: Box<dyn Fn() -> CheckFuture<'a> + Send + Sync + 'a>,
+}
+
+impl<'a> Dependency<'a> {
+    pub fn new<F, Fut>(name: &'static str, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'a,
+        Fut: Future<Output = Result<()>> + Send + 'a,
+    {
+        Self {
+            name,
+            check: Box::new(move || Box::pin(check())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    pub timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl ReadinessConfig {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(15),
+        }
+    }
+}
+
+/// Poll every dependency with exponential backoff until all of them succeed.
+/// Dependencies that already passed are not re-checked.
+/// Returns an error naming the dependencies still down once `timeout` elapses.
+pub async fn wait_until_ready(deps: &[Dependency<'_>], config: &ReadinessConfig) -> Result<()> {
+    let deadline = Instant::now() + config.timeout;
+    let mut pending: Vec<&Dependency<'_>> = deps.iter().collect();
+    let mut backoff = config.initial_backoff;
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let mut still_pending = Vec::new();
+
+        for dep in pending {
+            match (dep.check)().await {
+                Ok(()) => info!("✅ {} is ready", dep.name),
+                Err(e) => {
+                    warn!("⏳ {} not ready (attempt {}): {}", dep.name, attempt, e);
+                    still_pending.push(dep);
+                }
+            }
+        }
+
+        pending = still_pending;
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            let names: Vec<&str> = pending.iter().map(|d| d.name).collect();
+            anyhow::bail!(
+                "Dependencies not ready after {:?}: {}",
+                config.timeout,
+                names.join(", ")
+            );
+        }
+
+        tokio::time::sleep(backoff.min(deadline - now)).await;
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+}
+
+/// Poll `fetch` every `interval` until it yields data, e.g. the LaserStream
+/// container's first slot after `/start` while the gRPC stream warms up.
+/// Returns `None` if nothing arrives within `timeout`.
+pub async fn wait_for_data<T, F, Fut>(
+    name: &str,
+    fetch: F,
+    timeout: Duration,
+    interval: Duration,
+) -> Option<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let started = Instant::now();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        match fetch().await {
+            Ok(Some(data)) => {
+                info!("✅ {} data arrived after {:.1}s", name, started.elapsed().as_secs_f64());
+                return Some(data);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("⏳ {} poll failed (attempt {}): {}", name, attempt, e),
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            warn!("⚠️  No {} data after {:?}, continuing anyway", name, timeout);
+            return None;
+        }
+        if attempt % 5 == 0 {
+            info!("⏳ Waiting for {} data... ({:.0}s elapsed)", name, elapsed.as_secs_f64());
+        }
+
+        tokio::time::sleep(interval.min(timeout - elapsed)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn fast_config(timeout_ms: u64) -> ReadinessConfig {
+        ReadinessConfig {
+            timeout: Duration::from_millis(timeout_ms),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_late_dependency() {
+        let rpc_calls = AtomicUsize::new(0);
+        let jupiter_calls = AtomicUsize::new(0);
+
+        let deps = vec![
+            Dependency::new("RPC", || async {
+                rpc_calls.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }),
+            Dependency::new("Jupiter", || async {
+                // Comes up on the third probe
+                if jupiter_calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    anyhow::bail!("connection refused");
+                }
+                Ok(())
+            }),
+        ];
+
+        let started = Instant::now();
+        wait_until_ready(&deps, &fast_config(1_000)).await.unwrap();
+
+        assert_eq!(jupiter_calls.load(Ordering::SeqCst), 3);
+        // Healthy dependencies are not re-probed while waiting on others
+        assert_eq!(rpc_calls.load(Ordering::SeqCst), 1);
+        // Two backoff sleeps: 10ms + 20ms
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn test_times_out_when_dependency_never_ready() {
+        let deps = vec![Dependency::new("LaserStream", || async {
+            anyhow::bail!("503 Service Unavailable")
+        })];
+
+        let err = wait_until_ready(&deps, &fast_config(50)).await.unwrap_err();
+        assert!(err.to_string().contains("LaserStream"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_data_after_delay() {
+        let started = Instant::now();
+        let polls = AtomicUsize::new(0);
+
+        let slot = wait_for_data(
+            "LaserStream",
+            || async {
+                polls.fetch_add(1, Ordering::SeqCst);
+                // Stream is warm after 50ms
+                if started.elapsed() < Duration::from_millis(50) {
+                    Ok(None)
+                } else {
+                    Ok(Some(42u64))
+                }
+            },
+            Duration::from_secs(1),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert_eq!(slot, Some(42));
+        assert!(polls.load(Ordering::SeqCst) > 1);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_data_times_out() {
+        let data: Option<u64> = wait_for_data(
+            "LaserStream",
+            || async { Ok(None) },
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(data.is_none());
+    }
+}
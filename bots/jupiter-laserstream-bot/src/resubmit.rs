@@ -0,0 +1,99 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How the executor reacts when a sent swap has not confirmed in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SendRetryMode {
+    /// Rebroadcast the same signed transaction while its blockhash is valid
+    ResubmitSame,
+    /// Wait for the blockhash to expire, then fetch a fresh quote
+    Requote,
+}
+
+impl FromStr for SendRetryMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "resubmit_same" => Ok(Self::ResubmitSame),
+            "requote" => Ok(Self::Requote),
+            other => anyhow::bail!("Unknown send retry mode: {}. Options: resubmit_same, requote", other),
+        }
+    }
+}
+
+/// Next step after a confirmation timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryAction {
+    /// Send the same bytes again (same signature, so it can only land once)
+    Resubmit,
+    /// Keep polling: the transaction may still land
+    Wait,
+    /// The blockhash expired, so the transaction can't land any more; build a new
+    /// one unless it already landed
+    Requote,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResubmitPolicy {
+    pub mode: SendRetryMode,
+    pub max_resubmits: usize,
+}
+
+impl ResubmitPolicy {
+    /// Decide what to do after an attempt timed out, given how many
+    /// resubmissions were already sent. Re-quoting is only considered past
+    /// `last_valid_block_height`; the executor still checks the old signature's
+    /// full history before building a replacement.
+    pub fn after_timeout(&self, resubmits: usize, block_height: u64, last_valid_block_height: u64) -> RetryAction {
+        if block_height > last_valid_block_height {
+            return RetryAction::Requote;
+        }
+        match self.mode {
+            SendRetryMode::ResubmitSame if resubmits < self.max_resubmits => RetryAction::Resubmit,
+            _ => RetryAction::Wait,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replay timeouts every 10 blocks and collect the actions until a requote
+    fn run(policy: ResubmitPolicy, last_valid_block_height: u64) -> Vec<RetryAction> {
+        let mut actions = Vec::new();
+        let mut resubmits = 0;
+        let mut block_height = 100;
+        loop {
+            block_height += 10;
+            let action = policy.after_timeout(resubmits, block_height, last_valid_block_height);
+            actions.push(action);
+            match action {
+                RetryAction::Resubmit => resubmits += 1,
+                RetryAction::Wait => {}
+                RetryAction::Requote => return actions,
+            }
+        }
+    }
+
+    #[test]
+    fn test_resubmits_up_to_limit_within_validity_window() {
+        use RetryAction::*;
+        let policy = ResubmitPolicy { mode: SendRetryMode::ResubmitSame, max_resubmits: 3 };
+
+        // Window covers 6 timeouts: 3 resubmits, then wait out the blockhash, then requote
+        assert_eq!(run(policy, 160), vec![Resubmit, Resubmit, Resubmit, Wait, Wait, Wait, Requote]);
+
+        // Blockhash expires first: fewer resubmits, straight to requote
+        assert_eq!(run(policy, 125), vec![Resubmit, Resubmit, Requote]);
+    }
+
+    #[test]
+    fn test_requote_mode_never_resubmits() {
+        let policy = ResubmitPolicy { mode: SendRetryMode::Requote, max_resubmits: 3 };
+        assert_eq!(run(policy, 130), vec![RetryAction::Wait, RetryAction::Wait, RetryAction::Wait, RetryAction::Requote]);
+    }
+}
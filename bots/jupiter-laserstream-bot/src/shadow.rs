@@ -0,0 +1,213 @@
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::price_tracker::PriceTracker;
+use crate::strategies::{Strategy, TradeSignal};
+
+/// Simulated fills at the tracker's current price, no fees or slippage.
+/// Buy amounts are quote base units and sell amounts base units, as in the real executor.
+#[derive(Debug, Clone)]
+pub struct PaperExecutor {
+    base_decimals: u8,
+    quote_decimals: u8,
+    /// Net base tokens held (may go negative: shadow strategies are not funded)
+    base: f64,
+    /// Net quote tokens spent/received
+    quote: f64,
+    trades: usize,
+}
+
+impl PaperExecutor {
+    pub fn new(base_decimals: u8, quote_decimals: u8) -> Self {
+        Self {
+            base_decimals,
+            quote_decimals,
+            base: 0.0,
+            quote: 0.0,
+            trades: 0,
+        }
+    }
+
+    /// Apply `signal` at `price` (quote per base). Returns false for Hold.
+    pub fn execute(&mut self, signal: &TradeSignal, price: f64) -> bool {
+        match signal {
+            TradeSignal::Buy { amount, .. } => {
+                let spent = *amount as f64 / 10f64.powi(self.quote_decimals as i32);
+                self.quote -= spent;
+                self.base += spent / price;
+            }
+            TradeSignal::Sell { amount, .. } => {
+                let sold = *amount as f64 / 10f64.powi(self.base_decimals as i32);
+                self.base -= sold;
+                self.quote += sold * price;
+            }
+            TradeSignal::Hold => return false,
+        }
+        self.trades += 1;
+        true
+    }
+
+    /// Mark-to-market PnL in quote tokens
+    pub fn pnl(&self, mark_price: f64) -> f64 {
+        self.quote + self.base * mark_price
+    }
+
+    pub fn trades(&self) -> usize {
+        self.trades
+    }
+}
+
+/// One line of the periodic comparison
+#[derive(Debug, Clone)]
+pub struct ShadowReport {
+    pub strategy: String,
+    pub trades: usize,
+    pub pnl: f64,
+}
+
+struct ShadowEntry {
+    strategy: Box<dyn Strategy>,
+    paper: PaperExecutor,
+}
+
+/// Drives several strategies against one pair's tracker with paper executors,
+/// logging what each would have done
+pub struct ShadowRunner {
+    entries: Vec<ShadowEntry>,
+    /// Pair the report is for, when the bot trades more than one
+    label: String,
+    report_interval: Duration,
+    last_report: Instant,
+}
+
+impl ShadowRunner {
+    pub fn new(
+        strategies: Vec<Box<dyn Strategy>>,
+        base_decimals: u8,
+        quote_decimals: u8,
+        report_interval: Duration,
+    ) -> Self {
+        let entries = strategies
+            .into_iter()
+            .map(|strategy| ShadowEntry {
+                strategy,
+                paper: PaperExecutor::new(base_decimals, quote_decimals),
+            })
+            .collect();
+
+        Self {
+            entries,
+            label: String::new(),
+            report_interval,
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Name the pair in the periodic report
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Run every shadow strategy once against the latest prices
+    pub fn on_tick(&mut self, tracker: &PriceTracker) {
+        let Some(price) = tracker.current_price() else {
+            return;
+        };
+
+        for entry in &mut self.entries {
+            if let Some(signal) = entry.strategy.generate_signal(tracker) {
+                let executed = entry.paper.execute(&signal, price);
+                entry.strategy.on_trade_result("", &signal, executed);
+                if executed {
+                    info!(
+                        "👻 [shadow:{}] would execute {:?} @ ${:.4}",
+                        entry.strategy.name(),
+                        signal,
+                        price
+                    );
+                }
+            }
+        }
+    }
+
+    /// Hypothetical PnL per strategy, marked at `mark_price`
+    pub fn report(&self, mark_price: f64) -> Vec<ShadowReport> {
+        self.entries
+            .iter()
+            .map(|entry| ShadowReport {
+                strategy: entry.strategy.name().to_string(),
+                trades: entry.paper.trades(),
+                pnl: entry.paper.pnl(mark_price),
+            })
+            .collect()
+    }
+
+    /// Log the comparison once per `report_interval`
+    pub fn maybe_log_report(&mut self, mark_price: f64) {
+        if self.last_report.elapsed() < self.report_interval {
+            return;
+        }
+        self.last_report = Instant::now();
+
+        if self.label.is_empty() {
+            info!("👻 ═══════ Shadow strategy comparison ═══════");
+        } else {
+            info!("👻 ═══════ Shadow strategy comparison: {} ═══════", self.label);
+        }
+        for line in self.report(mark_price) {
+            info!(
+                "👻 {:<16} trades={:<4} pnl=${:+.4}",
+                line.strategy, line.trades, line.pnl
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Emits a fixed signal every tick
+    struct Always(&'static str, TradeSignal);
+
+    impl Strategy for Always {
+        fn generate_signal(&self, _tracker: &PriceTracker) -> Option<TradeSignal> {
+            Some(self.1.clone())
+        }
+
+        fn name(&self) -> &str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_pnl_tracked_per_strategy() {
+        let buyer = Always("buyer", TradeSignal::Buy { amount: 100_000_000, reason: String::new(), limit_price: None });
+        let holder = Always("holder", TradeSignal::Hold);
+        let mut runner = ShadowRunner::new(
+            vec![Box::new(buyer), Box::new(holder)],
+            9,
+            6,
+            Duration::from_secs(3600),
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        let mut tracker = PriceTracker::new(5);
+        tracker.add_price(100.0, 1.0, now);
+        runner.on_tick(&tracker);
+
+        // Buyer spent 100 USDC for 1 SOL; at 110 it is up 10, the holder is flat
+        let report = runner.report(110.0);
+        assert_eq!(report[0].strategy, "buyer");
+        assert_eq!(report[0].trades, 1);
+        assert!((report[0].pnl - 10.0).abs() < 1e-9);
+        assert_eq!(report[1].strategy, "holder");
+        assert_eq!(report[1].trades, 0);
+        assert_eq!(report[1].pnl, 0.0);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use bot_common::ShutdownWatchdog;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// Signatures of transactions sent but not yet confirmed
+#[derive(Debug, Clone, Default)]
+pub struct InFlight(Arc<Mutex<Vec<String>>>);
+
+impl InFlight {
+    pub fn insert(&self, signature: &str) {
+        self.0.lock().unwrap().push(signature.to_string());
+    }
+
+    pub fn remove(&self, signature: &str) {
+        self.0.lock().unwrap().retain(|s| s != signature);
+    }
+
+    pub fn snapshot(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Signatures left unconfirmed by a forced exit, checked on the next start
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingFile {
+    saved_at: String,
+    signatures: Vec<String>,
+}
+
+pub fn persist_pending(path: &Path, signatures: &[String]) -> Result<()> {
+    let file = PendingFile {
+        saved_at: chrono::Utc::now().to_rfc3339(),
+        signatures: signatures.to_vec(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&file)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read and delete the pending-signature file left by a previous run
+pub fn take_pending(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: PendingFile = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid pending signature file {}", path.display()))?;
+    std::fs::remove_file(path)?;
+    Ok(file.signatures)
+}
+
+/// Arm a `ShutdownWatchdog` that, after `timeout`, logs and persists whatever
+/// is still in flight to `path`, then calls `on_expire` (`std::process::exit`
+/// in the bot)
+pub fn arm_watchdog<F>(timeout: Duration, in_flight: InFlight, path: PathBuf, on_expire: F) -> ShutdownWatchdog
+where
+    F: FnOnce() + Send + 'static,
+{
+    ShutdownWatchdog::arm(timeout, move || {
+        let pending = in_flight.snapshot();
+        if pending.is_empty() {
+            warn!("   No unconfirmed transactions in flight");
+        } else {
+            for signature in &pending {
+                warn!("   Unconfirmed signature: {}", signature);
+            }
+            match persist_pending(&path, &pending) {
+                Ok(()) => warn!("   Saved to {} for recovery on next start", path.display()),
+                Err(e) => error!("   Could not save pending signatures: {}", e),
+            }
+        }
+        on_expire();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_hanging_drain_is_abandoned_and_signature_saved() {
+        let path = temp_path("pending-hang");
+        let in_flight = InFlight::default();
+        in_flight.insert("5xSig");
+
+        let (expired_tx, expired_rx) = mpsc::channel();
+        let _watchdog = arm_watchdog(
+            Duration::from_millis(50),
+            in_flight.clone(),
+            path.clone(),
+            move || expired_tx.send(()).unwrap(),
+        );
+
+        // The "stuck RPC call" never completes; the watchdog fires regardless
+        expired_rx.recv_timeout(Duration::from_secs(2)).expect("watchdog did not fire");
+
+        assert_eq!(take_pending(&path).unwrap(), vec!["5xSig".to_string()]);
+        // Recovery consumes the file
+        assert!(take_pending(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_disarmed_watchdog_does_nothing() {
+        let path = temp_path("pending-clean");
+        let in_flight = InFlight::default();
+        in_flight.insert("sig");
+        in_flight.remove("sig");
+
+        let (expired_tx, expired_rx) = mpsc::channel();
+        let watchdog = arm_watchdog(
+            Duration::from_millis(20),
+            in_flight,
+            path.clone(),
+            move || expired_tx.send(()).unwrap(),
+        );
+        watchdog.disarm();
+
+        assert!(expired_rx.recv_timeout(Duration::from_millis(200)).is_err());
+        assert!(!path.exists());
+    }
+}
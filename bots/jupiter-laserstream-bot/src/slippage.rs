@@ -0,0 +1,154 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How the executor picks the slippage tolerance sent to Jupiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlippageMode {
+    /// Always use `MAX_SLIPPAGE_BPS`
+    Fixed,
+    /// Use the EWMA of realized slippage plus a safety margin, capped at `MAX_SLIPPAGE_BPS`
+    Adaptive,
+}
+
+impl FromStr for SlippageMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fixed" => Ok(Self::Fixed),
+            "adaptive" => Ok(Self::Adaptive),
+            other => anyhow::bail!("Unknown slippage mode: {}. Options: fixed, adaptive", other),
+        }
+    }
+}
+
+impl SlippageMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Adaptive => "adaptive",
+        }
+    }
+}
+
+/// Realized slippage in bps: positive when we received less than quoted
+pub fn realized_slippage_bps(expected_out: u64, actual_out: u64) -> f64 {
+    if expected_out == 0 {
+        return 0.0;
+    }
+    (expected_out as f64 - actual_out as f64) / expected_out as f64 * 10_000.0
+}
+
+/// Closed-loop slippage model: confirmed trades feed an EWMA of realized
+/// slippage, which sets the tolerance for the next trade in adaptive mode.
+#[derive(Debug, Clone)]
+pub struct SlippageModel {
+    mode: SlippageMode,
+    max_bps: u16,
+    margin_bps: u16,
+    alpha: f64,
+    ewma_bps: Option<f64>,
+    observations: u64,
+}
+
+impl SlippageModel {
+    pub fn new(mode: SlippageMode, max_bps: u16, margin_bps: u16, alpha: f64) -> Self {
+        Self {
+            mode,
+            max_bps,
+            margin_bps,
+            alpha: alpha.clamp(0.0, 1.0),
+            ewma_bps: None,
+            observations: 0,
+        }
+    }
+
+    /// Feed one realized-slippage observation (bps) into the EWMA
+    pub fn observe(&mut self, realized_bps: f64) {
+        self.ewma_bps = Some(match self.ewma_bps {
+            Some(prev) => self.alpha * realized_bps + (1.0 - self.alpha) * prev,
+            None => realized_bps,
+        });
+        self.observations += 1;
+    }
+
+    pub fn ewma_bps(&self) -> Option<f64> {
+        self.ewma_bps
+    }
+
+    pub fn observations(&self) -> u64 {
+        self.observations
+    }
+
+    pub fn mode(&self) -> SlippageMode {
+        self.mode
+    }
+
+    /// Slippage tolerance (bps) to request for the next trade
+    pub fn current_bps(&self) -> u16 {
+        match (self.mode, self.ewma_bps) {
+            (SlippageMode::Adaptive, Some(ewma)) => {
+                // Better-than-quoted fills count as zero slippage
+                let target = ewma.max(0.0).round() + self.margin_bps as f64;
+                (target as u16).clamp(self.margin_bps.min(self.max_bps), self.max_bps)
+            }
+            // No history yet (or fixed mode): fall back to the configured maximum
+            _ => self.max_bps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_slippage_bps() {
+        assert_eq!(realized_slippage_bps(1_000_000, 1_000_000), 0.0);
+        assert!((realized_slippage_bps(1_000_000, 999_000) - 10.0).abs() < 1e-9);
+        assert!(realized_slippage_bps(1_000_000, 1_001_000) < 0.0);
+        assert_eq!(realized_slippage_bps(0, 5), 0.0);
+    }
+
+    #[test]
+    fn test_fixed_mode_ignores_observations() {
+        let mut model = SlippageModel::new(SlippageMode::Fixed, 50, 5, 0.3);
+        model.observe(2.0);
+        model.observe(3.0);
+        assert_eq!(model.current_bps(), 50);
+        assert!(model.ewma_bps().is_some());
+    }
+
+    #[test]
+    fn test_adaptive_setting_converges() {
+        let mut model = SlippageModel::new(SlippageMode::Adaptive, 100, 5, 0.3);
+        assert_eq!(model.current_bps(), 100);
+
+        // Market settles from a noisy first fill to ~8 bps realized slippage
+        let observations = [40.0, 12.0, 6.0, 9.0, 7.0, 8.0, 8.5, 7.5, 8.0, 8.0, 8.0, 8.0, 8.0, 8.0];
+        let mut settings = Vec::new();
+        for obs in observations {
+            model.observe(obs);
+            settings.push(model.current_bps());
+        }
+
+        // Converges to ewma (~8) + margin (5)
+        assert!((model.ewma_bps().unwrap() - 8.0).abs() < 0.5);
+        assert_eq!(*settings.last().unwrap(), 13);
+        // well below the setting driven by the noisy first fill
+        assert!(settings[0] > settings[settings.len() - 1]);
+        assert_eq!(model.observations(), observations.len() as u64);
+    }
+
+    #[test]
+    fn test_adaptive_setting_is_capped_and_floored() {
+        let mut model = SlippageModel::new(SlippageMode::Adaptive, 50, 5, 1.0);
+        model.observe(500.0);
+        assert_eq!(model.current_bps(), 50);
+
+        model.observe(-20.0);
+        assert_eq!(model.current_bps(), 5);
+    }
+}
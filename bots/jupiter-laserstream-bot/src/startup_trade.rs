@@ -0,0 +1,107 @@
+use anyhow::Result;
+use std::future::Future;
+use tracing::{error, info};
+
+use crate::strategies::TradeSignal;
+
+/// Largest amount (quote base units, 6 decimals) accepted for the startup test trade
+pub const MAX_TEST_TRADE_AMOUNT: u64 = 5_000_000; // 5 USDC
+
+/// Check the `TEST_TRADE_ON_START` settings: the amount must be given explicitly
+/// and stay under `MAX_TEST_TRADE_AMOUNT`
+pub fn validate_test_trade(enabled: bool, amount: Option<u64>) -> Result<u64> {
+    if !enabled {
+        return Ok(0);
+    }
+    match amount {
+        None | Some(0) => anyhow::bail!(
+            "TEST_TRADE_ON_START requires TEST_TRADE_AMOUNT_USDC to be set explicitly"
+        ),
+        Some(amount) if amount > MAX_TEST_TRADE_AMOUNT => anyhow::bail!(
+            "TEST_TRADE_AMOUNT_USDC too large for a test trade: {} > {} base units",
+            amount,
+            MAX_TEST_TRADE_AMOUNT
+        ),
+        Some(amount) => Ok(amount),
+    }
+}
+
+/// Fire a single tiny buy through the real signing/send/confirm path before
+/// the strategy takes over. `execute` is `FnOnce`, so at most one trade is sent.
+pub async fn run_test_trade<F, Fut>(enabled: bool, amount: u64, execute: F) -> Result<Option<String>>
+where
+    F: FnOnce(TradeSignal) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    if !enabled {
+        return Ok(None);
+    }
+
+    info!("🧪 ═══════════════════════════════════════");
+    info!("🧪 TEST TRADE ON START: buying {} base units", amount);
+    info!("🧪 ═══════════════════════════════════════");
+
+    let signal = TradeSignal::Buy {
+        amount,
+        reason: "Startup test trade (TEST_TRADE_ON_START)".to_string(),
+        limit_price: None,
+    };
+
+    match execute(signal).await {
+        Ok(signature) => {
+            info!("🧪 ✅ Test trade confirmed: {}", signature);
+            Ok(Some(signature))
+        }
+        Err(e) => {
+            error!("🧪 ❌ Test trade failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_exactly_one_trade_when_enabled() {
+        let calls = AtomicUsize::new(0);
+
+        let result = run_test_trade(true, 1_000_000, |signal| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                assert!(matches!(signal, TradeSignal::Buy { amount: 1_000_000, .. }));
+                Ok("sig".to_string())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.as_deref(), Some("sig"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_trade_when_disabled() {
+        let calls = AtomicUsize::new(0);
+
+        let result = run_test_trade(false, 1_000_000, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok("sig".to_string()) }
+        })
+        .await
+        .unwrap();
+
+        assert!(result.is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_validation_requires_small_explicit_amount() {
+        assert_eq!(validate_test_trade(false, None).unwrap(), 0);
+        assert!(validate_test_trade(true, None).is_err());
+        assert!(validate_test_trade(true, Some(MAX_TEST_TRADE_AMOUNT + 1)).is_err());
+        assert_eq!(validate_test_trade(true, Some(500_000)).unwrap(), 500_000);
+    }
+}
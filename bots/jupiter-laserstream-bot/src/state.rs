@@ -0,0 +1,322 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+use bot_common::state::PersistedState;
+
+use crate::pairs::PairRotation;
+use crate::pnl::PnlTracker;
+use tracing::{info, warn};
+
+/// A trade sent and confirmed but not yet finalized
+#[derive(Debug, Clone)]
+pub struct PendingFinalization {
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Result of checking a pending trade against finality
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FinalizationOutcome {
+    /// Finalized: the trade was counted and its cooldown started
+    Finalized(String),
+    /// Not finalized within the timeout: provisional cooldown rolled back
+    RolledBack(String),
+    Waiting,
+}
+
+/// Bot state to track cooldowns, daily trade counts and processed slots
+pub struct BotState {
+    pub cooldown_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_slot: Option<u64>,
+    /// When `last_slot` arrived, for feed freshness
+    pub last_slot_at: Option<DateTime<Utc>>,
+    /// Polls that returned no slot, for the periodic "no data yet" log
+    pub empty_polls: u64,
+    pub daily_trade_count: usize,
+    pub day_start: chrono::DateTime<chrono::Utc>,
+    /// Trades waiting for finalization, by signature
+    pub pending_finalization: HashMap<String, PendingFinalization>,
+    /// Cooldown without the pending trades' provisional holds, restored once
+    /// the last of them rolls back
+    settled_cooldown: Option<DateTime<Utc>>,
+    pub pnl: PnlTracker,
+    /// Which pairs get attention next when there are more than MAX_CONCURRENT_PAIRS
+    pub pair_rotation: PairRotation,
+    /// What was last written to the state file, and when
+    saved: Option<(PersistedState, DateTime<Utc>)>,
+}
+
+impl BotState {
+    pub fn new() -> Self {
+        Self {
+            cooldown_until: None,
+            last_slot: None,
+            last_slot_at: None,
+            empty_polls: 0,
+            daily_trade_count: 0,
+            day_start: chrono::Utc::now(),
+            pending_finalization: HashMap::new(),
+            settled_cooldown: None,
+            pnl: PnlTracker::new(),
+            pair_rotation: PairRotation::default(),
+            saved: None,
+        }
+    }
+
+    fn persisted(&self) -> PersistedState {
+        PersistedState {
+            cooldown_until: self.cooldown_until,
+            daily_trade_count: self.daily_trade_count,
+            day_start: self.day_start,
+            last_slot: self.last_slot,
+        }
+    }
+
+    /// Write the cooldown, daily count, day start and last slot to `path`
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        self.write(path, Utc::now())
+    }
+
+    fn write(&mut self, path: &Path, now: DateTime<Utc>) -> Result<()> {
+        let persisted = self.persisted();
+        persisted.save(path)?;
+        self.saved = Some((persisted, now));
+        Ok(())
+    }
+
+    /// `save` when the cooldown, daily count or day changed since the last
+    /// write, or when only the last slot did and `SLOT_SAVE_INTERVAL_SECS` has passed
+    pub fn save_if_changed(&mut self, path: &Path, now: DateTime<Utc>) -> Result<()> {
+        let due = match &self.saved {
+            None => true,
+            Some((saved, at)) => self.persisted().save_due(saved, *at, now),
+        };
+        if due {
+            self.write(path, now)?;
+        }
+        Ok(())
+    }
+
+    /// State saved by `save`, or a fresh one if `path` doesn't exist. A day
+    /// that ended while the bot was down is rolled over.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut state = Self::new();
+        let Some(persisted) = PersistedState::load(path)? else {
+            return Ok(state);
+        };
+        state.cooldown_until = persisted.cooldown_until;
+        state.daily_trade_count = persisted.daily_trade_count;
+        state.day_start = persisted.day_start;
+        state.last_slot = persisted.last_slot;
+        state.saved = Some((persisted, Utc::now()));
+        Ok(state)
+    }
+
+    pub fn is_in_cooldown(&self) -> bool {
+        self.cooldown_until
+            .is_some_and(|until| chrono::Utc::now() < until)
+    }
+
+    pub fn clear_cooldown(&mut self) {
+        info!("✅ Cooldown period ended");
+        self.cooldown_until = None;
+    }
+
+    pub fn set_cooldown(&mut self, minutes: u64) {
+        let until = chrono::Utc::now() + chrono::Duration::minutes(minutes as i64);
+        self.settled_cooldown = Some(until);
+        // Other pending trades' provisional holds stay in place
+        self.cooldown_until = match self.cooldown_until {
+            Some(hold) if !self.pending_finalization.is_empty() => Some(hold.max(until)),
+            _ => Some(until),
+        };
+        info!("⏰ Cooldown until: {}", self.cooldown_until.unwrap());
+    }
+
+    pub fn should_skip_slot(&self, slot: u64) -> bool {
+        self.last_slot.is_some_and(|s| s >= slot)
+    }
+
+    pub fn update_slot(&mut self, slot: u64) {
+        self.last_slot = Some(slot);
+        self.last_slot_at = Some(Utc::now());
+    }
+
+    /// Reset the daily trade counter once 24h have passed since `day_start`
+    pub fn roll_day(&mut self) {
+        let now = chrono::Utc::now();
+        if now.signed_duration_since(self.day_start).num_hours() >= 24 {
+            self.daily_trade_count = 0;
+            self.day_start = now;
+        }
+    }
+
+    pub fn record_trade(&mut self) {
+        self.daily_trade_count += 1;
+    }
+
+    /// Hold off further trades while `signature` waits for finalization. The
+    /// cooldown set here is provisional; the trade is not counted yet.
+    pub fn begin_finalization(&mut self, signature: String, now: DateTime<Utc>, timeout_secs: u64) {
+        if self.pending_finalization.is_empty() {
+            self.settled_cooldown = self.cooldown_until;
+        }
+        self.pending_finalization.insert(signature, PendingFinalization { submitted_at: now });
+        let hold = now + Duration::seconds(timeout_secs as i64);
+        self.cooldown_until = Some(self.cooldown_until.map_or(hold, |until| until.max(hold)));
+    }
+
+    /// Hold off trading on a trade whose confirmation timed out: it may still
+    /// land, so it is settled by its status like one awaiting finality, but the
+    /// cooldown stays in place even if it never does
+    pub fn begin_unconfirmed(&mut self, signature: String, now: DateTime<Utc>, timeout_secs: u64, cooldown_minutes: u64) {
+        self.set_cooldown(cooldown_minutes);
+        self.begin_finalization(signature, now, timeout_secs);
+    }
+
+    /// Signatures of the trades waiting for finalization
+    pub fn pending_signatures(&self) -> Vec<String> {
+        self.pending_finalization.keys().cloned().collect()
+    }
+
+    /// Settle the pending trade `signature`: count it and start the real
+    /// cooldown once finalized, or drop it once `timeout_secs` passes. The
+    /// provisional hold is lifted when the last pending trade rolls back.
+    /// An unknown signature is `Waiting`.
+    pub fn resolve_finalization(
+        &mut self,
+        signature: &str,
+        finalized: bool,
+        now: DateTime<Utc>,
+        timeout_secs: u64,
+        cooldown_minutes: u64,
+    ) -> FinalizationOutcome {
+        let Some(pending) = self.pending_finalization.get(signature) else {
+            return FinalizationOutcome::Waiting;
+        };
+        let timed_out = now.signed_duration_since(pending.submitted_at).num_seconds() >= timeout_secs as i64;
+        if !finalized && !timed_out {
+            return FinalizationOutcome::Waiting;
+        }
+        self.pending_finalization.remove(signature);
+
+        if finalized {
+            info!("🔒 Trade finalized: {}", signature);
+            self.record_trade();
+            self.set_cooldown(cooldown_minutes);
+            return FinalizationOutcome::Finalized(signature.to_string());
+        }
+
+        warn!(
+            "⚠️  Trade not finalized within {}s, rolling back: {}",
+            timeout_secs, signature
+        );
+        if self.pending_finalization.is_empty() {
+            self.cooldown_until = self.settled_cooldown;
+        }
+        FinalizationOutcome::RolledBack(signature.to_string())
+    }
+}
+
+impl Default for BotState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unfinalized_trade_is_rolled_back() {
+        let mut state = BotState::new();
+        let start = Utc::now();
+        state.begin_finalization("sig".to_string(), start, 60);
+
+        // Confirmed but not finalized: trade not counted, trading held off
+        assert!(state.is_in_cooldown());
+        assert_eq!(
+            state.resolve_finalization("sig", false, start + Duration::seconds(30), 60, 60),
+            FinalizationOutcome::Waiting
+        );
+        assert_eq!(state.daily_trade_count, 0);
+
+        // Timed out: cooldown rolled back, nothing recorded
+        assert_eq!(
+            state.resolve_finalization("sig", false, start + Duration::seconds(61), 60, 60),
+            FinalizationOutcome::RolledBack("sig".to_string())
+        );
+        assert!(state.pending_finalization.is_empty());
+        assert!(state.cooldown_until.is_none());
+        assert_eq!(state.daily_trade_count, 0);
+    }
+
+    #[test]
+    fn test_finalized_trade_starts_cooldown() {
+        let mut state = BotState::new();
+        let start = Utc::now();
+        state.begin_finalization("sig".to_string(), start, 60);
+
+        assert_eq!(
+            state.resolve_finalization("sig", true, start + Duration::seconds(15), 60, 60),
+            FinalizationOutcome::Finalized("sig".to_string())
+        );
+        assert_eq!(state.daily_trade_count, 1);
+        assert!(state.is_in_cooldown());
+    }
+
+    #[test]
+    fn test_unconfirmed_trade_keeps_cooldown() {
+        let mut state = BotState::new();
+        let start = Utc::now();
+        state.begin_unconfirmed("sig".to_string(), start, 60, 30);
+        assert!(state.is_in_cooldown());
+
+        // Never landed: not counted, but the cooldown still applies
+        assert_eq!(
+            state.resolve_finalization("sig", false, start + Duration::seconds(61), 60, 30),
+            FinalizationOutcome::RolledBack("sig".to_string())
+        );
+        assert_eq!(state.daily_trade_count, 0);
+        assert!(state.is_in_cooldown());
+
+        // Landed after all: counted like any finalized trade
+        state.begin_unconfirmed("sig2".to_string(), start, 60, 30);
+        assert_eq!(
+            state.resolve_finalization("sig2", true, start + Duration::seconds(20), 60, 30),
+            FinalizationOutcome::Finalized("sig2".to_string())
+        );
+        assert_eq!(state.daily_trade_count, 1);
+    }
+
+    #[test]
+    fn test_pending_trades_are_kept_per_signature() {
+        let mut state = BotState::new();
+        let start = Utc::now();
+        state.begin_finalization("first".to_string(), start, 60);
+        state.begin_finalization("second".to_string(), start + Duration::seconds(10), 60);
+        assert_eq!(state.pending_finalization.len(), 2);
+
+        // The first times out while the second is still waiting: the hold stays
+        assert_eq!(
+            state.resolve_finalization("first", false, start + Duration::seconds(61), 60, 30),
+            FinalizationOutcome::RolledBack("first".to_string())
+        );
+        assert_eq!(
+            state.resolve_finalization("second", false, start + Duration::seconds(61), 60, 30),
+            FinalizationOutcome::Waiting
+        );
+        assert!(state.is_in_cooldown());
+
+        // The second times out too: the hold is lifted, nothing was counted
+        assert_eq!(
+            state.resolve_finalization("second", false, start + Duration::seconds(71), 60, 30),
+            FinalizationOutcome::RolledBack("second".to_string())
+        );
+        assert!(state.pending_finalization.is_empty());
+        assert!(state.cooldown_until.is_none());
+        assert_eq!(state.daily_trade_count, 0);
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use axum::{extract::State, routing::get, Json, Router};
+use bot_common::Uptime;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::health::HealthState;
+
+/// Snapshot of bot internals served at `/status`, starting now by default
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BotStatus {
+    pub slippage_mode: String,
+    pub slippage_bps: u16,
+    pub slippage_ewma_bps: Option<f64>,
+    pub health: HealthState,
+    /// Signal that put the bot in its current degraded/unhealthy state
+    pub health_reason: Option<String>,
+    pub last_slot: Option<u64>,
+    /// Latest price of the primary pair
+    pub current_price: Option<f64>,
+    pub daily_trade_count: usize,
+    pub in_cooldown: bool,
+    pub cooldown_until: Option<DateTime<Utc>>,
+    /// Serialized as `started_at` and `uptime_secs`
+    #[serde(flatten)]
+    pub uptime: Uptime,
+}
+
+pub type SharedStatus = Arc<RwLock<BotStatus>>;
+
+pub fn router(status: SharedStatus) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/status", get(get_status))
+        .with_state(status)
+}
+
+async fn get_status(State(status): State<SharedStatus>) -> Json<BotStatus> {
+    Json(status.read().await.clone())
+}
+
+/// Serve `/health` (liveness, always ok) and `/status` on `0.0.0.0:port` until the process exits
+pub async fn serve(port: u16, status: SharedStatus) -> Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("Status server listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(status)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_reports_bot_state() {
+        let status: SharedStatus = Arc::new(RwLock::new(BotStatus {
+            uptime: Uptime::since(Utc::now() - chrono::Duration::seconds(90)),
+            last_slot: Some(250_000_000),
+            current_price: Some(151.25),
+            daily_trade_count: 3,
+            in_cooldown: true,
+            ..BotStatus::default()
+        }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(status)).await });
+
+        let health = reqwest::get(format!("http://{}/health", addr)).await.unwrap();
+        assert!(health.status().is_success());
+        assert_eq!(health.text().await.unwrap(), "ok");
+
+        let body: serde_json::Value = reqwest::get(format!("http://{}/status", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["last_slot"], 250_000_000);
+        assert_eq!(body["current_price"], 151.25);
+        assert_eq!(body["daily_trade_count"], 3);
+        assert_eq!(body["in_cooldown"], true);
+        assert!(body["uptime_secs"].as_u64().unwrap() >= 90);
+    }
+}
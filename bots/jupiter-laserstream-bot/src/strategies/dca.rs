@@ -18,6 +18,7 @@ impl Strategy for DcaStrategy {
             Some(TradeSignal::Buy {
                 amount: self.amount,
                 reason: "DCA: Regular scheduled buy".to_string(),
+                limit_price: None,
             })
         } else {
             None
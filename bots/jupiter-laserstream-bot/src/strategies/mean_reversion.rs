@@ -44,6 +44,7 @@ impl Strategy for MeanReversionStrategy {
                     deviation.abs() * 100.0,
                     self.lookback_minutes
                 ),
+                limit_price: None,
             })
         }
         // Sell when price is above average (overbought)
@@ -55,6 +56,7 @@ impl Strategy for MeanReversionStrategy {
                     deviation * 100.0,
                     self.lookback_minutes
                 ),
+                limit_price: None,
             })
         }
         else {
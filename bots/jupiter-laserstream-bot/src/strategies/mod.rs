@@ -1,39 +1,116 @@
-use crate::config::BotConfig;
+use crate::config::{BotConfig, StrategyConfig};
 use crate::price_tracker::PriceTracker;
 
 pub mod dca;
 pub mod momentum;
 pub mod mean_reversion;
+pub mod risk;
+pub mod scale_in;
+pub mod triangular;
+pub mod twap;
 
 use dca::DcaStrategy;
 use momentum::MomentumStrategy;
 use mean_reversion::MeanReversionStrategy;
+use risk::RiskManagedStrategy;
+use scale_in::ScaleInStrategy;
+use twap::TwapStrategy;
 
 #[derive(Debug, Clone)]
 pub enum TradeSignal {
-    Buy { amount: u64, reason: String },
-    Sell { amount: u64, reason: String },
+    Buy {
+        amount: u64,
+        reason: String,
+        /// Most to pay, in quote per base; the executor refuses worse quotes
+        limit_price: Option<f64>,
+    },
+    Sell {
+        amount: u64,
+        reason: String,
+        /// Least to accept, in quote per base; the executor refuses worse quotes
+        limit_price: Option<f64>,
+    },
     Hold,
 }
 
 pub trait Strategy: Send + Sync {
     fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal>;
+
+    /// Signal for the pair labelled `pair`; strategies that keep progress per
+    /// pair override this, the rest ignore the label
+    fn generate_signal_for(&self, _pair: &str, tracker: &PriceTracker) -> Option<TradeSignal> {
+        self.generate_signal(tracker)
+    }
+
+    /// Whether `signal`, emitted for `pair`, was executed
+    fn on_trade_result(&self, _pair: &str, _signal: &TradeSignal, _executed: bool) {}
+
     fn name(&self) -> &str;
 }
 
-pub fn create_strategy(config: &BotConfig) -> anyhow::Result<Box<dyn Strategy>> {
-    match config.strategy_type.to_lowercase().as_str() {
-        "dca" => Ok(Box::new(DcaStrategy::new(config.trade_amount))),
-        "momentum" => Ok(Box::new(MomentumStrategy::new(
-            config.trade_amount,
-            config.min_price_movement,
-            config.lookback_minutes,
-        ))),
-        "mean_reversion" => Ok(Box::new(MeanReversionStrategy::new(
+pub fn create_strategy(config: &BotConfig, base_decimals: u8, quote_decimals: u8) -> anyhow::Result<Box<dyn Strategy>> {
+    create_strategy_by_name(&config.strategy_type, &config.strategy, base_decimals, quote_decimals)
+}
+
+/// Build the strategy called `name` using the shared strategy parameters in `config`,
+/// for a pair whose tokens have the given decimals
+pub fn create_strategy_by_name(
+    name: &str,
+    config: &StrategyConfig,
+    base_decimals: u8,
+    quote_decimals: u8,
+) -> anyhow::Result<Box<dyn Strategy>> {
+    let strategy: Box<dyn Strategy> = match name.to_lowercase().as_str() {
+        "dca" => Box::new(DcaStrategy::new(config.trade_amount)),
+        "momentum" => Box::new(
+            MomentumStrategy::new(
+                config.trade_amount,
+                config.min_price_movement,
+                config.lookback_minutes,
+            )
+            .with_vwap(config.use_vwap)
+            .with_rsi_filter(config.momentum_rsi_periods),
+        ),
+        "mean_reversion" => Box::new(MeanReversionStrategy::new(
             config.trade_amount,
             config.min_price_movement,
             config.lookback_minutes,
-        ))),
-        _ => Err(anyhow::anyhow!("Unknown strategy: {}", config.strategy_type)),
+        )),
+        "twap" if config.twap_total_amount == 0 => {
+            anyhow::bail!("STRATEGY=twap requires TWAP_TOTAL_AMOUNT")
+        }
+        "twap" => Box::new(TwapStrategy::new(
+            config.twap_total_amount,
+            config.twap_slices,
+            config.twap_direction,
+            TwapStrategy::ticks_per_slice(
+                config.twap_window_minutes,
+                config.twap_slices,
+                config.poll_interval_seconds,
+            ),
+        )),
+        _ => return Err(anyhow::anyhow!("Unknown strategy: {}", name)),
+    };
+
+    let strategy: Box<dyn Strategy> = if config.scale_in_levels > 1 {
+        Box::new(ScaleInStrategy::new(
+            strategy,
+            config.scale_in_levels,
+            config.scale_in_step_pct,
+        ))
+    } else {
+        strategy
+    };
+
+    // Stop-loss / take-profit sees the final (possibly scaled-in) buys
+    if config.stop_loss_bps.is_some() || config.take_profit_bps.is_some() {
+        return Ok(Box::new(RiskManagedStrategy::new(
+            strategy,
+            config.stop_loss_bps,
+            config.take_profit_bps,
+            base_decimals,
+            quote_decimals,
+        )));
     }
+    Ok(strategy)
 }
@@ -2,10 +2,19 @@ use super::{Strategy, TradeSignal};
 use crate::price_tracker::PriceTracker;
 use tracing::info;
 
+/// RSI at or above which momentum won't chase a rise
+const RSI_OVERBOUGHT: f64 = 70.0;
+/// RSI at or below which momentum won't chase a fall
+const RSI_OVERSOLD: f64 = 30.0;
+
 pub struct MomentumStrategy {
     amount: u64,
     min_movement: f64,
     lookback_minutes: usize,
+    /// Measure the move from the VWAP rather than the SMA
+    use_vwap: bool,
+    /// RSI periods for the overbought/oversold filter; 0 = off
+    rsi_periods: usize,
 }
 
 impl MomentumStrategy {
@@ -14,7 +23,31 @@ impl MomentumStrategy {
             amount,
             min_movement,
             lookback_minutes,
+            use_vwap: false,
+            rsi_periods: 0,
+        }
+    }
+
+    /// Compare against the lookback VWAP, so thin prints barely move the
+    /// reference; falls back to the SMA while there is no volume
+    pub fn with_vwap(mut self, use_vwap: bool) -> Self {
+        self.use_vwap = use_vwap;
+        self
+    }
+
+    /// Hold instead of buying at an RSI of 70 or more over `periods` ticks, or
+    /// selling at 30 or less; the filter waits for `periods + 1` ticks
+    pub fn with_rsi_filter(mut self, periods: usize) -> Self {
+        self.rsi_periods = periods;
+        self
+    }
+
+    /// RSI when the filter is on and has enough ticks
+    fn rsi(&self, tracker: &PriceTracker) -> Option<f64> {
+        if self.rsi_periods == 0 {
+            return None;
         }
+        tracker.rsi(self.rsi_periods)
     }
 }
 
@@ -26,33 +59,52 @@ impl Strategy for MomentumStrategy {
         }
         
         let current_price = tracker.current_price()?;
-        let avg_price = tracker.moving_average(self.lookback_minutes)?;
+        let vwap = self
+            .use_vwap
+            .then(|| tracker.volume_weighted_average(self.lookback_minutes))
+            .flatten();
+        let (avg_price, avg_kind) = match vwap {
+            Some(vwap) => (vwap, "vwap"),
+            None => (tracker.moving_average(self.lookback_minutes)?, "avg"),
+        };
         
         let change = (current_price - avg_price) / avg_price;
         
-        info!("Momentum check: current=${:.2}, avg=${:.2}, change={:.2}%",
-              current_price, avg_price, change * 100.0);
+        info!("Momentum check: current=${:.2}, {}=${:.2}, change={:.2}%",
+              current_price, avg_kind, avg_price, change * 100.0);
         
         // Buy if price is rising above threshold
         if change > self.min_movement {
+            if let Some(rsi) = self.rsi(tracker).filter(|rsi| *rsi >= RSI_OVERBOUGHT) {
+                info!("Momentum: not buying, RSI {:.1} is overbought", rsi);
+                return Some(TradeSignal::Hold);
+            }
             Some(TradeSignal::Buy {
                 amount: self.amount,
                 reason: format!(
-                    "Momentum: Price up {:.2}% vs {}min avg",
+                    "Momentum: Price up {:.2}% vs {}min {}",
                     change * 100.0,
-                    self.lookback_minutes
+                    self.lookback_minutes,
+                    avg_kind
                 ),
+                limit_price: None,
             })
         }
         // Sell if price is falling below threshold
         else if change < -self.min_movement {
+            if let Some(rsi) = self.rsi(tracker).filter(|rsi| *rsi <= RSI_OVERSOLD) {
+                info!("Momentum: not selling, RSI {:.1} is oversold", rsi);
+                return Some(TradeSignal::Hold);
+            }
             Some(TradeSignal::Sell {
                 amount: self.amount,
                 reason: format!(
-                    "Momentum: Price down {:.2}% vs {}min avg",
+                    "Momentum: Price down {:.2}% vs {}min {}",
                     change * 100.0,
-                    self.lookback_minutes
+                    self.lookback_minutes,
+                    avg_kind
                 ),
+                limit_price: None,
             })
         }
         else {
@@ -64,3 +116,91 @@ impl Strategy for MomentumStrategy {
         "Momentum"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 30 ticks over a minute climbing from 100 to 103
+    fn steady_rise() -> PriceTracker {
+        let mut tracker = PriceTracker::new(1);
+        for t in 0..30 {
+            tracker.add_price(100.0 + t as f64 * 0.1, 1.0, t);
+        }
+        tracker
+    }
+
+    /// A minute of thin prints at 100 and heavy trade at 101.5, then one thin
+    /// print at 103
+    fn tracker_with_thin_spike(volume: f64) -> PriceTracker {
+        let mut tracker = PriceTracker::new(1);
+        for t in 0..20 {
+            tracker.add_price(100.0, 0.1 * volume, t);
+        }
+        for t in 20..30 {
+            tracker.add_price(101.5, 10.0 * volume, t);
+        }
+        tracker.add_price(103.0, 0.01 * volume, 30);
+        tracker
+    }
+
+    #[test]
+    fn test_vwap_ignores_low_volume_spike() {
+        let tracker = tracker_with_thin_spike(1.0);
+
+        // 103 is 2.4% over the SMA (100.6): the SMA path chases it
+        let sma = MomentumStrategy::new(1_000, 0.02, 1);
+        assert!(matches!(sma.generate_signal(&tracker), Some(TradeSignal::Buy { .. })));
+
+        // ...but only 1.5% over the volume-weighted 101.47
+        let vwap = MomentumStrategy::new(1_000, 0.02, 1).with_vwap(true);
+        assert!(matches!(vwap.generate_signal(&tracker), Some(TradeSignal::Hold)));
+    }
+
+    #[test]
+    fn test_vwap_falls_back_to_sma_without_volume() {
+        let tracker = tracker_with_thin_spike(0.0);
+        let vwap = MomentumStrategy::new(1_000, 0.02, 1).with_vwap(true);
+        assert!(matches!(vwap.generate_signal(&tracker), Some(TradeSignal::Buy { .. })));
+    }
+
+    #[test]
+    fn test_rsi_filter_skips_overbought_buy() {
+        let tracker = steady_rise();
+        assert_eq!(tracker.rsi(14), Some(100.0));
+
+        let unfiltered = MomentumStrategy::new(1_000, 0.01, 1);
+        assert!(matches!(unfiltered.generate_signal(&tracker), Some(TradeSignal::Buy { .. })));
+
+        let filtered = MomentumStrategy::new(1_000, 0.01, 1).with_rsi_filter(14);
+        assert!(matches!(filtered.generate_signal(&tracker), Some(TradeSignal::Hold)));
+    }
+
+    #[test]
+    fn test_rsi_filter_lets_choppy_breakout_through() {
+        // Chop between 99 and 100, then one jump to 103
+        let mut tracker = PriceTracker::new(1);
+        for t in 0..29 {
+            tracker.add_price(if t % 2 == 0 { 100.0 } else { 99.0 }, 1.0, t);
+        }
+        tracker.add_price(103.0, 1.0, 29);
+        assert!(tracker.rsi(14).unwrap() < RSI_OVERBOUGHT);
+
+        let filtered = MomentumStrategy::new(1_000, 0.02, 1).with_rsi_filter(14);
+        assert!(matches!(filtered.generate_signal(&tracker), Some(TradeSignal::Buy { .. })));
+    }
+
+    #[test]
+    fn test_rsi_filter_skips_oversold_sell() {
+        let mut tracker = PriceTracker::new(1);
+        for t in 0..30 {
+            tracker.add_price(103.0 - t as f64 * 0.1, 1.0, t);
+        }
+
+        let unfiltered = MomentumStrategy::new(1_000, 0.01, 1);
+        assert!(matches!(unfiltered.generate_signal(&tracker), Some(TradeSignal::Sell { .. })));
+
+        let filtered = MomentumStrategy::new(1_000, 0.01, 1).with_rsi_filter(14);
+        assert!(matches!(filtered.generate_signal(&tracker), Some(TradeSignal::Hold)));
+    }
+}
@@ -0,0 +1,291 @@
+use super::{Strategy, TradeSignal};
+use crate::price_tracker::PriceTracker;
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Position opened by the wrapped strategy's buys
+#[derive(Debug, Clone, Copy)]
+struct OpenPosition {
+    /// Quote spent over base bought: the average of the prices the buys were emitted at
+    entry_price: f64,
+    /// Raw base units the buys bought, at the prices they were emitted at
+    base_amount: u64,
+}
+
+/// The last Buy or Sell this strategy emitted, waiting on `on_trade_result`
+#[derive(Debug, Clone, Copy)]
+struct Emitted {
+    /// Price when the signal was emitted, which an executed Buy is booked at
+    price: f64,
+    /// The signal is this guard's own stop-loss / take-profit exit
+    exit: bool,
+}
+
+/// Stop-loss / take-profit guard around any strategy: once price is
+/// `stop_loss_bps` below or `take_profit_bps` above the entry, the wrapped
+/// signal is replaced by a Sell of the whole position. The position only
+/// changes when `on_trade_result` reports a trade executed, so a dropped or
+/// failed Buy books nothing and a failed exit is retried on the next tick.
+/// Buys spend quote, Sells spend base, so each buy is converted to the base
+/// it buys at the price it was emitted at.
+pub struct RiskManagedStrategy {
+    inner: Box<dyn Strategy>,
+    stop_loss_bps: Option<u16>,
+    take_profit_bps: Option<u16>,
+    base_decimals: u8,
+    quote_decimals: u8,
+    position: Mutex<Option<OpenPosition>>,
+    emitted: Mutex<Option<Emitted>>,
+}
+
+impl RiskManagedStrategy {
+    pub fn new(
+        inner: Box<dyn Strategy>,
+        stop_loss_bps: Option<u16>,
+        take_profit_bps: Option<u16>,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> Self {
+        Self {
+            inner,
+            stop_loss_bps,
+            take_profit_bps,
+            base_decimals,
+            quote_decimals,
+            position: Mutex::new(None),
+            emitted: Mutex::new(None),
+        }
+    }
+
+    /// Raw base units `quote_amount` raw quote units buy at `price`
+    fn base_for_quote(&self, quote_amount: u64, price: f64) -> u64 {
+        let quote = quote_amount as f64 / 10f64.powi(self.quote_decimals as i32);
+        (quote / price * 10f64.powi(self.base_decimals as i32)).round() as u64
+    }
+
+    /// Exit reason when `price` has crossed a trigger relative to `entry_price`
+    fn triggered(&self, entry_price: f64, price: f64) -> Option<String> {
+        let move_bps = (price - entry_price) / entry_price * 10_000.0;
+        if let Some(stop) = self.stop_loss_bps.filter(|&bps| move_bps <= -(bps as f64)) {
+            return Some(format!(
+                "Stop loss: ${:.4} is {:.1}bps below entry ${:.4} (limit {}bps)",
+                price, -move_bps, entry_price, stop
+            ));
+        }
+        if let Some(target) = self.take_profit_bps.filter(|&bps| move_bps >= bps as f64) {
+            return Some(format!(
+                "Take profit: ${:.4} is {:.1}bps above entry ${:.4} (target {}bps)",
+                price, move_bps, entry_price, target
+            ));
+        }
+        None
+    }
+}
+
+impl Strategy for RiskManagedStrategy {
+    fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal> {
+        self.generate_signal_for("", tracker)
+    }
+
+    fn generate_signal_for(&self, pair: &str, tracker: &PriceTracker) -> Option<TradeSignal> {
+        let current_price = tracker.current_price()?;
+        let signal = self.inner.generate_signal_for(pair, tracker);
+        let position = *self.position.lock().unwrap();
+        let mut emitted = self.emitted.lock().unwrap();
+
+        if let Some(open) = position {
+            if let Some(reason) = self.triggered(open.entry_price, current_price) {
+                warn!("🛡️  {}", reason);
+                *emitted = Some(Emitted { price: current_price, exit: true });
+                return Some(TradeSignal::Sell { amount: open.base_amount, reason, limit_price: None });
+            }
+        }
+
+        if matches!(signal, Some(TradeSignal::Buy { .. } | TradeSignal::Sell { .. })) {
+            *emitted = Some(Emitted { price: current_price, exit: false });
+        }
+        signal
+    }
+
+    fn on_trade_result(&self, pair: &str, signal: &TradeSignal, executed: bool) {
+        let Some(emitted) = self.emitted.lock().unwrap().take() else {
+            self.inner.on_trade_result(pair, signal, executed);
+            return;
+        };
+        // The wrapped strategy never emitted this guard's exits
+        if !emitted.exit {
+            self.inner.on_trade_result(pair, signal, executed);
+        }
+        if !executed {
+            return;
+        }
+
+        let mut position = self.position.lock().unwrap();
+        match signal {
+            TradeSignal::Buy { amount, .. } => {
+                let bought = self.base_for_quote(*amount, emitted.price);
+                let open = position.get_or_insert(OpenPosition { entry_price: emitted.price, base_amount: 0 });
+                let total = open.base_amount + bought;
+                if total > 0 {
+                    open.entry_price = (open.entry_price * open.base_amount as f64 + emitted.price * bought as f64)
+                        / total as f64;
+                }
+                open.base_amount = total;
+                info!("🛡️  Position {} base units at avg entry ${:.4}", open.base_amount, open.entry_price);
+            }
+            // Sells spend base: a partial one (a TWAP or scale-out slice) keeps
+            // the rest guarded, and only selling all of it closes the position
+            TradeSignal::Sell { amount, .. } => {
+                if let Some(open) = position.as_mut() {
+                    open.base_amount = open.base_amount.saturating_sub(*amount);
+                    if open.base_amount == 0 {
+                        *position = None;
+                    } else {
+                        info!("🛡️  Position {} base units left at entry ${:.4}", open.base_amount, open.entry_price);
+                    }
+                }
+            }
+            TradeSignal::Hold => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Buys on the first tick only, then holds
+    struct BuyOnce(Mutex<bool>);
+
+    impl Strategy for BuyOnce {
+        fn generate_signal(&self, _tracker: &PriceTracker) -> Option<TradeSignal> {
+            let mut bought = self.0.lock().unwrap();
+            if *bought {
+                return Some(TradeSignal::Hold);
+            }
+            *bought = true;
+            Some(TradeSignal::Buy { amount: 100_000_000, reason: "test".to_string(), limit_price: None })
+        }
+
+        fn name(&self) -> &str {
+            "BuyOnce"
+        }
+    }
+
+    /// Feed `prices` one tick apart and collect each signal, reporting every
+    /// Buy or Sell as executed unless `executed` says otherwise for its tick
+    fn run_with(
+        strategy: &RiskManagedStrategy,
+        prices: &[f64],
+        executed: impl Fn(usize) -> bool,
+    ) -> Vec<Option<TradeSignal>> {
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                tracker.add_price(*price, 1.0, now + i as i64);
+                let signal = strategy.generate_signal(&tracker);
+                if let Some(s @ (TradeSignal::Buy { .. } | TradeSignal::Sell { .. })) = &signal {
+                    strategy.on_trade_result("", s, executed(i));
+                }
+                signal
+            })
+            .collect()
+    }
+
+    fn run(strategy: &RiskManagedStrategy, prices: &[f64]) -> Vec<Option<TradeSignal>> {
+        run_with(strategy, prices, |_| true)
+    }
+
+    #[test]
+    fn test_price_crash_triggers_stop_loss() {
+        let strategy = RiskManagedStrategy::new(Box::new(BuyOnce(Mutex::new(false))), Some(500), Some(1_000), 9, 6);
+        // 100 USDC buys 1 SOL at 100, then a slide to -4% (inside the stop) and a crash to -8%
+        let signals = run(&strategy, &[100.0, 98.0, 96.0, 92.0, 90.0]);
+
+        assert!(matches!(signals[0], Some(TradeSignal::Buy { .. })));
+        assert!(signals[1..3].iter().all(|s| matches!(s, Some(TradeSignal::Hold))));
+        match &signals[3] {
+            Some(TradeSignal::Sell { amount, reason, .. }) => {
+                // The whole SOL bought, not the USDC spent
+                assert_eq!(*amount, 1_000_000_000);
+                assert!(reason.starts_with("Stop loss"));
+            }
+            other => panic!("expected stop-loss Sell, got {:?}", other),
+        }
+        // Position closed: no second exit
+        assert!(matches!(signals[4], Some(TradeSignal::Hold)));
+    }
+
+    #[test]
+    fn test_rally_triggers_take_profit() {
+        let strategy = RiskManagedStrategy::new(Box::new(BuyOnce(Mutex::new(false))), Some(500), Some(1_000), 9, 6);
+        let signals = run(&strategy, &[100.0, 105.0, 110.0]);
+
+        assert!(matches!(signals[1], Some(TradeSignal::Hold)));
+        assert!(matches!(
+            &signals[2],
+            Some(TradeSignal::Sell { amount: 1_000_000_000, reason, .. }) if reason.starts_with("Take profit")
+        ));
+    }
+
+    #[test]
+    fn test_failed_buy_opens_no_position() {
+        let strategy = RiskManagedStrategy::new(Box::new(BuyOnce(Mutex::new(false))), Some(500), Some(1_000), 9, 6);
+        let signals = run_with(&strategy, &[100.0, 92.0, 110.0], |i| i != 0);
+
+        // Nothing was bought, so there is nothing to stop out of or take profit on
+        assert!(signals[1..].iter().all(|s| matches!(s, Some(TradeSignal::Hold))));
+    }
+
+    #[test]
+    fn test_failed_stop_loss_is_retried() {
+        let strategy = RiskManagedStrategy::new(Box::new(BuyOnce(Mutex::new(false))), Some(500), Some(1_000), 9, 6);
+        let signals = run_with(&strategy, &[100.0, 92.0, 91.0, 90.0], |i| i != 1);
+
+        let is_stop = |s: &Option<TradeSignal>| {
+            matches!(s, Some(TradeSignal::Sell { amount: 1_000_000_000, reason, .. }) if reason.starts_with("Stop loss"))
+        };
+        // The first exit failed, so the position stays protected until one executes
+        assert!(is_stop(&signals[1]));
+        assert!(is_stop(&signals[2]));
+        assert!(matches!(signals[3], Some(TradeSignal::Hold)));
+    }
+
+    #[test]
+    fn test_partial_inner_sell_keeps_rest_guarded() {
+        /// Buys on the first tick, sells a quarter of it on the second, then holds
+        struct BuyThenTrim(Mutex<usize>);
+
+        impl Strategy for BuyThenTrim {
+            fn generate_signal(&self, _tracker: &PriceTracker) -> Option<TradeSignal> {
+                let mut tick = self.0.lock().unwrap();
+                *tick += 1;
+                Some(match *tick {
+                    1 => TradeSignal::Buy { amount: 100_000_000, reason: "test".to_string(), limit_price: None },
+                    2 => TradeSignal::Sell { amount: 250_000_000, reason: "trim".to_string(), limit_price: None },
+                    _ => TradeSignal::Hold,
+                })
+            }
+
+            fn name(&self) -> &str {
+                "BuyThenTrim"
+            }
+        }
+
+        let strategy = RiskManagedStrategy::new(Box::new(BuyThenTrim(Mutex::new(0))), Some(500), Some(1_000), 9, 6);
+        let signals = run(&strategy, &[100.0, 100.0, 92.0]);
+
+        // The stop still covers the 0.75 SOL the trim left
+        assert!(matches!(
+            &signals[2],
+            Some(TradeSignal::Sell { amount: 750_000_000, reason, .. }) if reason.starts_with("Stop loss")
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use super::{Strategy, TradeSignal};
+use crate::price_tracker::PriceTracker;
+use std::sync::Mutex;
+use tracing::info;
+
+/// An entry being built up over several buys
+#[derive(Debug, Clone)]
+struct ScaleInPlan {
+    /// Price of the first level; later levels trigger `step_pct` apart below it
+    anchor_price: f64,
+    level_amount: u64,
+    /// Levels reported executed; a dropped or failed level is offered again
+    filled_levels: usize,
+}
+
+/// Splits the wrapped strategy's `Buy` into `levels` equal buys: the first at
+/// the signal, each later one once price is another `step_pct` percent below
+/// the first. A level only counts once `on_trade_result` reports it executed.
+pub struct ScaleInStrategy {
+    inner: Box<dyn Strategy>,
+    levels: usize,
+    step_pct: f64,
+    plan: Mutex<Option<ScaleInPlan>>,
+}
+
+impl ScaleInStrategy {
+    pub fn new(inner: Box<dyn Strategy>, levels: usize, step_pct: f64) -> Self {
+        Self {
+            inner,
+            levels: levels.max(1),
+            step_pct,
+            plan: Mutex::new(None),
+        }
+    }
+
+    fn level_price(&self, plan: &ScaleInPlan, level: usize) -> f64 {
+        plan.anchor_price * (1.0 - self.step_pct / 100.0 * level as f64)
+    }
+}
+
+impl Strategy for ScaleInStrategy {
+    fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal> {
+        self.generate_signal_for("", tracker)
+    }
+
+    fn generate_signal_for(&self, pair: &str, tracker: &PriceTracker) -> Option<TradeSignal> {
+        let current_price = tracker.current_price()?;
+        let signal = self.inner.generate_signal_for(pair, tracker);
+        let mut plan_slot = self.plan.lock().unwrap();
+
+        if let Some(plan) = plan_slot.as_mut() {
+            // A sell from the inner strategy abandons the remaining levels
+            if let Some(TradeSignal::Sell { .. }) = signal {
+                info!("Scale-in cancelled after {}/{} levels", plan.filled_levels, self.levels);
+                *plan_slot = None;
+                return signal;
+            }
+
+            let level = plan.filled_levels;
+            let threshold = self.level_price(plan, level);
+            if current_price > threshold {
+                return Some(TradeSignal::Hold);
+            }
+
+            return Some(TradeSignal::Buy {
+                amount: plan.level_amount,
+                reason: format!(
+                    "Scale-in level {}/{}: ${:.4} <= ${:.4}",
+                    level + 1,
+                    self.levels,
+                    current_price,
+                    threshold
+                ),
+                limit_price: None,
+            });
+        }
+
+        match signal {
+            Some(TradeSignal::Buy { amount, reason, limit_price }) if self.levels > 1 => {
+                let level_amount = amount / self.levels as u64;
+                *plan_slot = Some(ScaleInPlan {
+                    anchor_price: current_price,
+                    level_amount,
+                    filled_levels: 0,
+                });
+                Some(TradeSignal::Buy {
+                    amount: level_amount,
+                    reason: format!("{} (scale-in level 1/{})", reason, self.levels),
+                    limit_price,
+                })
+            }
+            other => other,
+        }
+    }
+
+    fn on_trade_result(&self, pair: &str, signal: &TradeSignal, executed: bool) {
+        if executed && matches!(signal, TradeSignal::Buy { .. }) {
+            let mut plan_slot = self.plan.lock().unwrap();
+            if let Some(plan) = plan_slot.as_mut() {
+                plan.filled_levels += 1;
+                if plan.filled_levels >= self.levels {
+                    info!("Scale-in complete: {}/{} levels filled", plan.filled_levels, self.levels);
+                    *plan_slot = None;
+                }
+            }
+        }
+        self.inner.on_trade_result(pair, signal, executed);
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wants to buy on every tick
+    struct AlwaysBuy;
+
+    impl Strategy for AlwaysBuy {
+        fn generate_signal(&self, _tracker: &PriceTracker) -> Option<TradeSignal> {
+            Some(TradeSignal::Buy { amount: 300_000_000, reason: "test".to_string(), limit_price: None })
+        }
+
+        fn name(&self) -> &str {
+            "AlwaysBuy"
+        }
+    }
+
+    #[test]
+    fn test_buys_fire_at_each_level_on_decline() {
+        let strategy = ScaleInStrategy::new(Box::new(AlwaysBuy), 3, 2.0);
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+
+        let mut buys = Vec::new();
+        for (i, price) in [100.0, 99.0, 98.5, 98.0, 97.0, 96.0].iter().enumerate() {
+            tracker.add_price(*price, 1.0, now + i as i64);
+            if let Some(signal @ TradeSignal::Buy { amount, .. }) = strategy.generate_signal(&tracker) {
+                strategy.on_trade_result("", &signal, true);
+                buys.push((*price, amount));
+            }
+        }
+
+        // Levels at 100, 98 (-2%) and 96 (-4%), each a third of the original buy
+        assert_eq!(
+            buys,
+            vec![(100.0, 100_000_000), (98.0, 100_000_000), (96.0, 100_000_000)]
+        );
+    }
+
+    #[test]
+    fn test_single_level_passes_through() {
+        let strategy = ScaleInStrategy::new(Box::new(AlwaysBuy), 1, 2.0);
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(100.0, 1.0, chrono::Utc::now().timestamp());
+
+        match strategy.generate_signal(&tracker) {
+            Some(TradeSignal::Buy { amount, .. }) => assert_eq!(amount, 300_000_000),
+            other => panic!("expected full buy, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_failed_level_is_offered_again() {
+        let strategy = ScaleInStrategy::new(Box::new(AlwaysBuy), 2, 2.0);
+        let mut tracker = PriceTracker::new(60);
+        let now = chrono::Utc::now().timestamp();
+
+        let mut levels = Vec::new();
+        for (i, (price, executed)) in [(100.0, true), (98.0, false), (97.5, true), (97.0, true)].iter().enumerate() {
+            tracker.add_price(*price, 1.0, now + i as i64);
+            if let Some(signal @ TradeSignal::Buy { .. }) = strategy.generate_signal(&tracker) {
+                strategy.on_trade_result("", &signal, *executed);
+                let TradeSignal::Buy { reason, .. } = signal else { unreachable!() };
+                levels.push(reason);
+            }
+        }
+
+        // Level 2 failed at 98 and filled at 97.5; the plan then completed, so the
+        // inner strategy's next buy starts a new ladder
+        assert!(levels[1].starts_with("Scale-in level 2/2"));
+        assert!(levels[2].starts_with("Scale-in level 2/2"));
+        assert!(levels[3].ends_with("(scale-in level 1/2)"));
+    }
+}
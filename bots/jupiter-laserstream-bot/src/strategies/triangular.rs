@@ -0,0 +1,313 @@
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use crate::config::BotConfig;
+use jupiter_client::JupiterQuoteResponse;
+use crate::swap_provider::SwapProvider;
+
+const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// One swap of a cycle, sized from the previous leg's quote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleLeg {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    pub expected_out: u64,
+    /// The quote's minimum out after slippage: what the next leg can count on
+    pub min_out: u64,
+}
+
+impl CycleLeg {
+    fn from_quote(quote: &JupiterQuoteResponse, amount_in: u64) -> Result<Self> {
+        Ok(Self {
+            input_mint: quote.input_mint.clone(),
+            output_mint: quote.output_mint.clone(),
+            amount_in,
+            expected_out: quote.out_amount.parse().context("Invalid outAmount")?,
+            min_out: quote.other_amount_threshold.parse().context("Invalid otherAmountThreshold")?,
+        })
+    }
+}
+
+/// A quoted A→B→C→A round trip
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbCycle {
+    pub legs: Vec<CycleLeg>,
+    /// What the last leg returns over what the first spends, in bps, after
+    /// `fee_cost`. Quotes are net of route fees, so this is the edge after all fees.
+    pub net_bps: f64,
+    /// Network fees for every leg's transaction, in raw units of the first mint
+    pub fee_cost: u64,
+}
+
+impl ArbCycle {
+    /// Mints along the cycle, shortened, e.g. `So11…→EPjF…→JUPy…→So11…`
+    pub fn path(&self) -> String {
+        let short = |mint: &str| format!("{}…", &mint[..mint.len().min(4)]);
+        let mut path: Vec<String> = self.legs.iter().map(|leg| short(&leg.input_mint)).collect();
+        if let Some(last) = self.legs.last() {
+            path.push(short(&last.output_mint));
+        }
+        path.join("→")
+    }
+}
+
+/// `TRIANGULAR_MINTS`: three distinct comma-separated mints, the first being
+/// the token the cycle starts and ends in
+pub fn parse_cycle_mints(value: &str) -> Result<[String; 3]> {
+    let mints: Vec<String> = value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let mints: [String; 3] = mints
+        .try_into()
+        .map_err(|mints: Vec<String>| anyhow::anyhow!("Expected 3 mints, got {}", mints.len()))?;
+    if mints[0] == mints[1] || mints[1] == mints[2] || mints[0] == mints[2] {
+        anyhow::bail!("Cycle mints must be distinct");
+    }
+    Ok(mints)
+}
+
+/// Triangular arbitrage through the swap provider: quotes A→B→C→A and the
+/// reverse A→C→B→A concurrently and trades the better cycle once its return,
+/// net of the legs' network fees, beats `min_net_profit_bps`. Each direction is
+/// quoted leg by leg so every leg is sized by the previous leg's output.
+pub struct TriangularArb {
+    mints: [String; 3],
+    /// Raw amount of the first mint each cycle starts with
+    amount: u64,
+    min_net_profit_bps: u16,
+    slippage_bps: u16,
+    /// Network fee budgeted per leg; each leg is its own transaction
+    leg_fee_lamports: u64,
+}
+
+impl TriangularArb {
+    pub fn new(mints: [String; 3], amount: u64, min_net_profit_bps: u16, slippage_bps: u16) -> Self {
+        Self {
+            mints,
+            amount,
+            min_net_profit_bps,
+            slippage_bps,
+            leg_fee_lamports: 0,
+        }
+    }
+
+    /// Count `lamports` of network fees against every leg
+    pub fn with_leg_fee_lamports(mut self, lamports: u64) -> Self {
+        self.leg_fee_lamports = lamports;
+        self
+    }
+
+    /// Start/end mint of the cycle
+    pub fn start_mint(&self) -> &str {
+        &self.mints[0]
+    }
+
+    /// None unless `TRIANGULAR_MINTS` is set
+    pub fn from_config(config: &BotConfig) -> Option<Self> {
+        let mints = config.triangular_mints.clone()?;
+        Some(
+            Self::new(
+                mints,
+                config.triangular_amount,
+                config.min_net_profit_bps,
+                config.max_slippage_bps,
+            )
+            .with_leg_fee_lamports(config.triangular_leg_fee_lamports),
+        )
+    }
+
+    /// The more profitable direction around the triangle, if it clears `min_net_profit_bps`
+    pub async fn find_cycle(&self, provider: &dyn SwapProvider) -> Result<Option<ArbCycle>> {
+        let [a, b, c] = &self.mints;
+        let fee_cost = self.fee_cost(provider).await?;
+        let (forward, reverse) = tokio::join!(
+            self.quote_cycle(provider, [a, b, c], fee_cost),
+            self.quote_cycle(provider, [a, c, b], fee_cost),
+        );
+
+        let best = match (forward, reverse) {
+            (Ok(forward), Ok(reverse)) => {
+                if forward.net_bps >= reverse.net_bps {
+                    forward
+                } else {
+                    reverse
+                }
+            }
+            (Ok(cycle), Err(e)) | (Err(e), Ok(cycle)) => {
+                warn!("⚠️  One direction of the cycle could not be quoted: {}", e);
+                cycle
+            }
+            (Err(e), Err(_)) => return Err(e),
+        };
+
+        info!(
+            "🔺 Best cycle {}: {:+.1}bps (need {}bps)",
+            best.path(),
+            best.net_bps,
+            self.min_net_profit_bps
+        );
+        Ok((best.net_bps > self.min_net_profit_bps as f64).then_some(best))
+    }
+
+    /// Network fees for a whole cycle in raw units of the first mint, priced
+    /// through the provider unless the cycle starts in SOL
+    async fn fee_cost(&self, provider: &dyn SwapProvider) -> Result<u64> {
+        let lamports = self.leg_fee_lamports * self.mints.len() as u64;
+        if lamports == 0 || self.mints[0] == NATIVE_MINT {
+            return Ok(lamports);
+        }
+        let quote = provider
+            .get_quote(NATIVE_MINT, &self.mints[0], lamports, self.slippage_bps)
+            .await
+            .context("Failed to price the cycle's network fees")?;
+        quote.out_amount.parse().context("Invalid outAmount")
+    }
+
+    async fn quote_cycle(&self, provider: &dyn SwapProvider, path: [&String; 3], fee_cost: u64) -> Result<ArbCycle> {
+        let mut legs = Vec::with_capacity(path.len());
+        let mut amount = self.amount;
+        for i in 0..path.len() {
+            let (input, output) = (path[i], path[(i + 1) % path.len()]);
+            let quote = provider
+                .get_quote(input, output, amount, self.slippage_bps)
+                .await
+                .with_context(|| format!("Failed to quote leg {} ({} -> {})", i + 1, input, output))?;
+            let leg = CycleLeg::from_quote(&quote, amount)?;
+            amount = leg.expected_out;
+            legs.push(leg);
+        }
+
+        Ok(ArbCycle {
+            legs,
+            net_bps: ((amount as f64 - fee_cost as f64) / self.amount as f64 - 1.0) * 10_000.0,
+            fee_cost,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jupiter_client::JupiterSwapResponse;
+    use futures::future::BoxFuture;
+    use std::collections::HashMap;
+
+    /// Quotes each pair at a fixed rate, with 1% slippage on the minimum out
+    struct RateProvider(HashMap<(&'static str, &'static str), f64>);
+
+    impl SwapProvider for RateProvider {
+        fn name(&self) -> &str {
+            "rates"
+        }
+
+        fn get_quote<'a>(
+            &'a self,
+            input_mint: &'a str,
+            output_mint: &'a str,
+            amount: u64,
+            slippage_bps: u16,
+        ) -> BoxFuture<'a, Result<JupiterQuoteResponse>> {
+            Box::pin(async move {
+                let rate = self
+                    .0
+                    .get(&(input_mint, output_mint))
+                    .with_context(|| format!("no route {} -> {}", input_mint, output_mint))?;
+                let out = (amount as f64 * rate) as u64;
+                Ok(JupiterQuoteResponse {
+                    input_mint: input_mint.to_string(),
+                    in_amount: amount.to_string(),
+                    output_mint: output_mint.to_string(),
+                    out_amount: out.to_string(),
+                    other_amount_threshold: (out * 99 / 100).to_string(),
+                    swap_mode: "ExactIn".to_string(),
+                    slippage_bps,
+                    platform_fee: None,
+                    price_impact_pct: "0".to_string(),
+                    route_plan: Vec::new(),
+                })
+            })
+        }
+
+        fn get_swap_transaction<'a>(
+            &'a self,
+            _quote: &'a JupiterQuoteResponse,
+            _user_public_key: &'a str,
+            _wrap_unwrap_sol: bool,
+            _priority_fee_micro_lamports: Option<u64>,
+        ) -> BoxFuture<'a, Result<JupiterSwapResponse>> {
+            Box::pin(async { anyhow::bail!("quotes only") })
+        }
+
+        fn get_price<'a>(&'a self, _input_mint: &'a str, _output_mint: &'a str) -> BoxFuture<'a, Result<f64>> {
+            Box::pin(async { anyhow::bail!("quotes only") })
+        }
+    }
+
+    fn arb(min_net_profit_bps: u16) -> TriangularArb {
+        let mints = ["A".to_string(), "B".to_string(), "C".to_string()];
+        TriangularArb::new(mints, 1_000_000, min_net_profit_bps, 50)
+    }
+
+    /// A→B→C→A returns 1%; the reverse direction loses about as much
+    fn provider() -> RateProvider {
+        RateProvider(HashMap::from([
+            (("A", "B"), 2.0),
+            (("B", "C"), 0.5),
+            (("C", "A"), 1.01),
+            (("A", "C"), 0.99),
+            (("C", "B"), 2.0),
+            (("B", "A"), 0.5),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_profitable_cycle_emits_legs_in_order() {
+        let cycle = arb(50).find_cycle(&provider()).await.unwrap().unwrap();
+
+        let path: Vec<(&str, &str)> = cycle
+            .legs
+            .iter()
+            .map(|leg| (leg.input_mint.as_str(), leg.output_mint.as_str()))
+            .collect();
+        assert_eq!(path, vec![("A", "B"), ("B", "C"), ("C", "A")]);
+
+        // Each leg spends what the previous one is quoted to return
+        let amounts: Vec<(u64, u64)> = cycle.legs.iter().map(|leg| (leg.amount_in, leg.expected_out)).collect();
+        assert_eq!(amounts, vec![(1_000_000, 2_000_000), (2_000_000, 1_000_000), (1_000_000, 1_010_000)]);
+        assert_eq!(cycle.legs[2].min_out, 999_900);
+        assert!((cycle.net_bps - 100.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_below_min_profit_is_skipped() {
+        assert!(arb(150).find_cycle(&provider()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_network_fees_count_against_the_cycle() {
+        // 3 legs x 10_000 lamports at 100 A per lamport cost 3_000_000 A, more than the 1% edge
+        let mut provider = provider();
+        provider.0.insert((NATIVE_MINT, "A"), 100.0);
+        let expensive = arb(50).with_leg_fee_lamports(10_000);
+
+        assert!(expensive.find_cycle(&provider).await.unwrap().is_none());
+        // 3 legs x 10 lamports cost 3_000 A: 100bps gross, 70bps net
+        let cycle = arb(50).with_leg_fee_lamports(10).find_cycle(&provider).await.unwrap().unwrap();
+        assert_eq!(cycle.fee_cost, 3_000);
+        assert!((cycle.net_bps - 70.0).abs() < 1e-6);
+
+        // Fees that can't be priced block the cycle rather than being ignored
+        assert!(arb(50).with_leg_fee_lamports(100).find_cycle(&RateProvider(HashMap::new())).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_cycle_mints() {
+        assert_eq!(parse_cycle_mints(" A, B ,C").unwrap(), ["A", "B", "C"].map(String::from));
+        assert!(parse_cycle_mints("A,B").is_err());
+        assert!(parse_cycle_mints("A,B,A").is_err());
+    }
+}
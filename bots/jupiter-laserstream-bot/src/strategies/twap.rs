@@ -0,0 +1,256 @@
+use super::{Strategy, TradeSignal};
+use crate::price_tracker::PriceTracker;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::info;
+
+/// Side a TWAP order executes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TwapDirection {
+    Buy,
+    Sell,
+}
+
+impl FromStr for TwapDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "buy" => Ok(Self::Buy),
+            "sell" => Ok(Self::Sell),
+            other => anyhow::bail!("Unknown TWAP direction: {}. Options: buy, sell", other),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TwapProgress {
+    ticks: u64,
+    slices_sent: usize,
+    /// Executed part of the current slice, when a trade filled only some of it
+    slice_filled: u64,
+    /// The last slice failed or was cut short, and its remainder is offered
+    /// again on the next tick
+    retry: bool,
+}
+
+/// Splits `total_amount` (raw units of the input token) into `slices` equal
+/// trades, one every `ticks_per_slice` polls, then holds. The last slice takes
+/// the rounding remainder so the slices add up to the total exactly. Each pair
+/// runs its own schedule, and a slice only counts once it is reported executed
+/// in full: a failed slice, or the rest of one executed smaller, is offered
+/// again on the next tick, not the next slice boundary.
+pub struct TwapStrategy {
+    total_amount: u64,
+    slices: usize,
+    direction: TwapDirection,
+    ticks_per_slice: u64,
+    progress: Mutex<HashMap<String, TwapProgress>>,
+}
+
+impl TwapStrategy {
+    pub fn new(total_amount: u64, slices: usize, direction: TwapDirection, ticks_per_slice: u64) -> Self {
+        Self {
+            total_amount,
+            slices: slices.max(1),
+            direction,
+            ticks_per_slice: ticks_per_slice.max(1),
+            progress: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Polls between slices so `slices` trades spread over `window_minutes`
+    pub fn ticks_per_slice(window_minutes: u64, slices: usize, poll_interval_seconds: u64) -> u64 {
+        (window_minutes * 60 / (slices.max(1) as u64 * poll_interval_seconds.max(1))).max(1)
+    }
+
+    fn slice_amount(&self, slice: usize) -> u64 {
+        let base = self.total_amount / self.slices as u64;
+        if slice + 1 == self.slices {
+            self.total_amount - base * (self.slices as u64 - 1)
+        } else {
+            base
+        }
+    }
+}
+
+impl Strategy for TwapStrategy {
+    fn generate_signal(&self, tracker: &PriceTracker) -> Option<TradeSignal> {
+        self.generate_signal_for("", tracker)
+    }
+
+    fn generate_signal_for(&self, pair: &str, tracker: &PriceTracker) -> Option<TradeSignal> {
+        tracker.current_price()?;
+        let mut progress = self.progress.lock().unwrap();
+        let progress = progress.entry(pair.to_string()).or_default();
+
+        if progress.slices_sent >= self.slices {
+            return Some(TradeSignal::Hold);
+        }
+
+        let tick = progress.ticks;
+        progress.ticks += 1;
+        if !progress.retry && !tick.is_multiple_of(self.ticks_per_slice) {
+            return Some(TradeSignal::Hold);
+        }
+
+        let slice = progress.slices_sent;
+        let amount = self.slice_amount(slice) - progress.slice_filled;
+        let reason = format!("TWAP slice {}/{}", slice + 1, self.slices);
+
+        Some(match self.direction {
+            TwapDirection::Buy => TradeSignal::Buy { amount, reason, limit_price: None },
+            TwapDirection::Sell => TradeSignal::Sell { amount, reason, limit_price: None },
+        })
+    }
+
+    fn on_trade_result(&self, pair: &str, signal: &TradeSignal, executed: bool) {
+        let amount = match signal {
+            TradeSignal::Buy { amount, .. } | TradeSignal::Sell { amount, .. } => *amount,
+            TradeSignal::Hold => return,
+        };
+        let mut progress = self.progress.lock().unwrap();
+        let progress = progress.entry(pair.to_string()).or_default();
+        progress.retry = !executed;
+        if !executed || progress.slices_sent >= self.slices {
+            return;
+        }
+        let slice_amount = self.slice_amount(progress.slices_sent);
+        progress.slice_filled = (progress.slice_filled + amount).min(slice_amount);
+        if progress.slice_filled < slice_amount {
+            progress.retry = true;
+            return;
+        }
+        progress.slice_filled = 0;
+        progress.slices_sent += 1;
+        if progress.slices_sent == self.slices {
+            info!("TWAP complete{}: {} slices, {} total", pair_suffix(pair), self.slices, self.total_amount);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "TWAP (Time-Weighted Average Price)"
+    }
+}
+
+fn pair_suffix(pair: &str) -> String {
+    if pair.is_empty() {
+        String::new()
+    } else {
+        format!(" for {}", pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emits_exactly_n_slices_then_holds() {
+        let strategy = TwapStrategy::new(1_000_000, 3, TwapDirection::Sell, 2);
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(100.0, 1.0, chrono::Utc::now().timestamp());
+
+        let mut slices = Vec::new();
+        for tick in 0..10 {
+            match strategy.generate_signal(&tracker) {
+                Some(signal @ TradeSignal::Sell { .. }) => {
+                    strategy.on_trade_result("", &signal, true);
+                    if let TradeSignal::Sell { amount, .. } = signal {
+                        slices.push((tick, amount));
+                    }
+                }
+                Some(TradeSignal::Hold) => {}
+                other => panic!("unexpected signal {:?}", other),
+            }
+        }
+
+        // One slice every 2 ticks; the last one carries the remainder
+        assert_eq!(slices, vec![(0, 333_333), (2, 333_333), (4, 333_334)]);
+        assert!(matches!(strategy.generate_signal(&tracker), Some(TradeSignal::Hold)));
+    }
+
+    #[test]
+    fn test_failed_slice_is_retried_on_the_next_tick() {
+        let strategy = TwapStrategy::new(900, 3, TwapDirection::Buy, 3);
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(100.0, 1.0, chrono::Utc::now().timestamp());
+
+        let first = strategy.generate_signal(&tracker).unwrap();
+        assert!(matches!(&first, TradeSignal::Buy { reason, .. } if reason == "TWAP slice 1/3"));
+        strategy.on_trade_result("", &first, false);
+
+        // The failed slice is offered again at the very next tick, twice if need be,
+        // rather than skipped or held back to the next slice boundary
+        for _ in 0..2 {
+            let retry = strategy.generate_signal(&tracker).unwrap();
+            assert!(matches!(&retry, TradeSignal::Buy { reason, .. } if reason == "TWAP slice 1/3"));
+            strategy.on_trade_result("", &retry, false);
+        }
+        let retry = strategy.generate_signal(&tracker).unwrap();
+        assert!(matches!(&retry, TradeSignal::Buy { reason, .. } if reason == "TWAP slice 1/3"));
+        strategy.on_trade_result("", &retry, true);
+
+        // Then the schedule carries on where it was: slice 2 at tick 6
+        for _ in 4..6 {
+            assert!(matches!(strategy.generate_signal(&tracker), Some(TradeSignal::Hold)));
+        }
+        assert!(matches!(
+            strategy.generate_signal(&tracker),
+            Some(TradeSignal::Buy { reason, .. }) if reason == "TWAP slice 2/3"
+        ));
+    }
+
+    #[test]
+    fn test_shrunk_slice_remainder_is_sent_next_tick() {
+        let strategy = TwapStrategy::new(900, 3, TwapDirection::Sell, 3);
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(100.0, 1.0, chrono::Utc::now().timestamp());
+
+        // Only 200 of the first 300 slice executed (e.g. shrunk for price impact)
+        assert!(matches!(strategy.generate_signal(&tracker), Some(TradeSignal::Sell { amount: 300, .. })));
+        let shrunk = TradeSignal::Sell { amount: 200, reason: "TWAP slice 1/3".to_string(), limit_price: None };
+        strategy.on_trade_result("", &shrunk, true);
+
+        // The rest of the slice goes next tick, then the schedule carries on
+        let rest = strategy.generate_signal(&tracker).unwrap();
+        assert!(matches!(&rest, TradeSignal::Sell { amount: 100, reason, .. } if reason == "TWAP slice 1/3"));
+        strategy.on_trade_result("", &rest, true);
+        assert!(matches!(strategy.generate_signal(&tracker), Some(TradeSignal::Hold)));
+        assert!(matches!(
+            strategy.generate_signal(&tracker),
+            Some(TradeSignal::Sell { amount: 300, reason, .. }) if reason == "TWAP slice 2/3"
+        ));
+    }
+
+    #[test]
+    fn test_progress_is_kept_per_pair() {
+        let strategy = TwapStrategy::new(200, 2, TwapDirection::Sell, 1);
+        let mut tracker = PriceTracker::new(60);
+        tracker.add_price(100.0, 1.0, chrono::Utc::now().timestamp());
+
+        for _ in 0..2 {
+            let signal = strategy.generate_signal_for("SOL/USDC", &tracker).unwrap();
+            strategy.on_trade_result("SOL/USDC", &signal, true);
+        }
+        assert!(matches!(strategy.generate_signal_for("SOL/USDC", &tracker), Some(TradeSignal::Hold)));
+
+        // Another pair starts its own schedule from the first slice
+        assert!(matches!(
+            strategy.generate_signal_for("JUP/USDC", &tracker),
+            Some(TradeSignal::Sell { amount: 100, reason, .. }) if reason == "TWAP slice 1/2"
+        ));
+    }
+
+    #[test]
+    fn test_ticks_per_slice_spreads_over_window() {
+        // 60 minutes, 10 slices, 2s polls: one slice every 180 polls
+        assert_eq!(TwapStrategy::ticks_per_slice(60, 10, 2), 180);
+        // A window shorter than one poll per slice still trades every tick
+        assert_eq!(TwapStrategy::ticks_per_slice(1, 100, 2), 1);
+    }
+}
@@ -1,9 +1,23 @@
-use anyhow::Result;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    TransactionBinaryEncoding, UiInstruction,
+};
+use std::collections::HashMap;
 use tracing::debug;
 
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// SPL Token `Transfer { amount }`: accounts source, destination, authority
+const TRANSFER: u8 = 3;
+/// SPL Token `TransferChecked { amount, decimals }`: accounts source, mint, destination, authority
+const TRANSFER_CHECKED: u8 = 12;
+
 /// Parsed swap event from Jupiter transaction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SwapEvent {
     pub input_mint: String,
     pub output_mint: String,
@@ -57,6 +71,135 @@ pub mod jupiter_programs {
     }
 }
 
+/// An instruction run by CPI, as listed in a transaction's `innerInstructions`.
+/// Indexes point into the full account list (static keys, then loaded addresses).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl InnerInstruction {
+    /// A compiled inner instruction from RPC meta; its data is base58
+    fn from_ui(instruction: &UiInstruction) -> Option<Self> {
+        match instruction {
+            UiInstruction::Compiled(ix) => Some(Self {
+                program_id_index: ix.program_id_index,
+                accounts: ix.accounts.clone(),
+                data: bs58::decode(&ix.data).into_vec().ok()?,
+            }),
+            UiInstruction::Parsed(_) => None,
+        }
+    }
+}
+
+/// A confirmed transaction in the parts the parser reads: the serialized
+/// transaction plus its status meta
+#[derive(Debug, Clone)]
+pub struct Base64Transaction {
+    pub slot: u64,
+    pub timestamp: i64,
+    /// base64 of the bincode-serialized `VersionedTransaction`
+    pub transaction: String,
+    /// Addresses loaded from lookup tables (writable, then readonly)
+    pub loaded_addresses: Vec<String>,
+    /// Inner instructions, keyed by the index of the top-level instruction they ran under
+    pub inner_instructions: Vec<(u8, Vec<InnerInstruction>)>,
+    /// Mint of each token account by account index, from the pre/post token balances
+    pub token_account_mints: HashMap<u8, String>,
+}
+
+impl Base64Transaction {
+    /// From a `getTransaction` response fetched with base64 encoding; None when
+    /// it came back in another encoding or without status meta
+    pub fn from_rpc(response: &EncodedConfirmedTransactionWithStatusMeta) -> Option<Self> {
+        let EncodedTransaction::Binary(transaction, TransactionBinaryEncoding::Base64) =
+            &response.transaction.transaction
+        else {
+            return None;
+        };
+        let meta = response.transaction.meta.as_ref()?;
+
+        let loaded_addresses = match &meta.loaded_addresses {
+            OptionSerializer::Some(loaded) => loaded.writable.iter().chain(&loaded.readonly).cloned().collect(),
+            _ => Vec::new(),
+        };
+        let inner_instructions = match &meta.inner_instructions {
+            OptionSerializer::Some(inner) => inner
+                .iter()
+                .map(|ix| (ix.index, ix.instructions.iter().filter_map(InnerInstruction::from_ui).collect()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut token_account_mints = HashMap::new();
+        for balances in [&meta.pre_token_balances, &meta.post_token_balances] {
+            if let OptionSerializer::Some(balances) = balances {
+                token_account_mints.extend(balances.iter().map(|b| (b.account_index, b.mint.clone())));
+            }
+        }
+
+        Some(Self {
+            slot: response.slot,
+            timestamp: response.block_time.unwrap_or_default(),
+            transaction: transaction.clone(),
+            loaded_addresses,
+            inner_instructions,
+            token_account_mints,
+        })
+    }
+}
+
+/// An SPL token transfer, by account index
+#[derive(Debug)]
+struct TokenTransfer {
+    source: u8,
+    destination: u8,
+    authority: u8,
+    /// Only `TransferChecked` names the mint
+    mint: Option<u8>,
+    amount: u64,
+}
+
+impl TokenTransfer {
+    fn decode(instruction: &InnerInstruction, account_keys: &[String]) -> Option<Self> {
+        let program = account_keys.get(instruction.program_id_index as usize)?;
+        if program != TOKEN_PROGRAM_ID && program != TOKEN_2022_PROGRAM_ID {
+            return None;
+        }
+        let (tag, rest) = instruction.data.split_first()?;
+        let amount = u64::from_le_bytes(rest.get(..8)?.try_into().ok()?);
+        match (*tag, instruction.accounts.as_slice()) {
+            (TRANSFER, [source, destination, authority, ..]) => Some(Self {
+                source: *source,
+                destination: *destination,
+                authority: *authority,
+                mint: None,
+                amount,
+            }),
+            (TRANSFER_CHECKED, [source, mint, destination, authority, ..]) => Some(Self {
+                source: *source,
+                destination: *destination,
+                authority: *authority,
+                mint: Some(*mint),
+                amount,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Mint moved, from the instruction or else either side's token balance
+    fn mint(&self, account_keys: &[String], token_account_mints: &HashMap<u8, String>) -> Option<String> {
+        match self.mint {
+            Some(mint) => account_keys.get(mint as usize).cloned(),
+            None => token_account_mints
+                .get(&self.source)
+                .or_else(|| token_account_mints.get(&self.destination))
+                .cloned(),
+        }
+    }
+}
+
 /// Parse Jupiter swap events from transaction data
 pub struct SwapParser {
     // Token mint addresses for filtering
@@ -72,27 +215,65 @@ impl SwapParser {
         }
     }
 
-    /// Parse a transaction to extract swap events
-    /// This is a simplified parser - in production you'd need to parse the actual instruction data
-    pub fn parse_transaction(
-        &self,
-        _transaction_data: &[u8],
-        slot: u64,
-        signature: String,
-    ) -> Result<Option<SwapEvent>> {
-        // TODO: Implement full transaction parsing
-        // For now, this is a placeholder that shows the structure
-
-        // In a real implementation, you would:
-        // 1. Deserialize the transaction
-        // 2. Find Jupiter program instructions
-        // 3. Parse the instruction data to extract swap parameters
-        // 4. Extract pre/post token balances to calculate amounts
-
-        debug!("Parsing transaction {} at slot {}", signature, slot);
+    /// Swap `wallet` made in a transaction that calls Jupiter, read from the
+    /// SPL token transfers Jupiter made: the first one `wallet` signed is the
+    /// input, the last one it didn't (the pool or Jupiter paying out) the
+    /// output. `wallet` needn't be the fee payer, e.g. behind a relayer.
+    pub fn parse_encoded_transaction(&self, tx: &Base64Transaction, wallet: &str) -> Option<SwapEvent> {
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(&tx.transaction) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                debug!("Undecodable transaction at slot {}: {}", tx.slot, e);
+                return None;
+            }
+        };
+        let transaction: VersionedTransaction = match bincode::deserialize(&bytes) {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                debug!("Undecodable transaction at slot {}: {}", tx.slot, e);
+                return None;
+            }
+        };
+        let signature = transaction.signatures.first()?.to_string();
+        debug!("Parsing transaction {} at slot {}", signature, tx.slot);
+
+        // Invoked programs are always static keys; transfers may touch loaded ones
+        let account_keys: Vec<String> = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .map(|key| key.to_string())
+            .chain(tx.loaded_addresses.iter().cloned())
+            .collect();
+        let jupiter_index = transaction.message.instructions().iter().position(|ix| {
+            account_keys
+                .get(ix.program_id_index as usize)
+                .is_some_and(|program| jupiter_programs::is_jupiter_program(program))
+        })?;
+        let (_, inner) = tx
+            .inner_instructions
+            .iter()
+            .find(|(index, _)| *index as usize == jupiter_index)?;
+
+        // The wallet authorizes what it sends into the route
+        let wallet = account_keys.iter().position(|key| key == wallet)?;
+        let transfers: Vec<TokenTransfer> = inner
+            .iter()
+            .filter_map(|ix| TokenTransfer::decode(ix, &account_keys))
+            .collect();
+        let input = transfers.iter().find(|t| t.authority as usize == wallet)?;
+        let output = transfers.iter().rev().find(|t| t.authority as usize != wallet)?;
 
-        // Placeholder - return None for now
-        Ok(None)
+        let event = SwapEvent {
+            input_mint: input.mint(&account_keys, &tx.token_account_mints)?,
+            output_mint: output.mint(&account_keys, &tx.token_account_mints)?,
+            input_amount: input.amount,
+            output_amount: output.amount,
+            slot: tx.slot,
+            timestamp: tx.timestamp,
+            signature,
+        };
+        self.matches_target(&event).then_some(event)
     }
 
     /// Parse account update to detect swap (simplified approach)
@@ -156,9 +337,10 @@ impl SwapParser {
     }
 }
 
-/// Token decimals lookup (common tokens)
-pub fn get_token_decimals(mint: &str) -> u8 {
-    match mint {
+/// Decimals of the common tokens, None for any other mint;
+/// `token_decimals::fetch_decimals` reads the rest from chain
+pub fn known_token_decimals(mint: &str) -> Option<u8> {
+    let decimals = match mint {
         // SOL
         "So11111111111111111111111111111111111111112" => 9,
         // USDC
@@ -173,15 +355,17 @@ pub fn get_token_decimals(mint: &str) -> u8 {
         "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So" => 9,
         // jitoSOL
         "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn" => 9,
-        // Default
-        _ => 9,
-    }
+        _ => return None,
+    };
+    Some(decimals)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+
     #[test]
     fn test_swap_event_price_calculation() {
         let event = SwapEvent {
@@ -228,6 +412,134 @@ mod tests {
         assert!(parser.matches_target(&reverse_event));
     }
 
+    #[test]
+    fn test_parse_encoded_jupiter_route() {
+        use solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            message::{v0, VersionedMessage},
+            pubkey::Pubkey,
+            signature::{Keypair, Signer},
+        };
+        use std::str::FromStr;
+
+        const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let key = |s: &str| Pubkey::from_str(s).unwrap();
+        let user = Keypair::new();
+        let [user_wsol, user_usdc, pool_wsol, pool_usdc, pool_authority] =
+            std::array::from_fn(|_| Pubkey::new_unique());
+
+        // A one-hop Jupiter v6 route selling 2 SOL of wSOL for 300 USDC
+        let route = Instruction::new_with_bytes(
+            key(jupiter_programs::JUPITER_V6),
+            &[0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a],
+            vec![
+                AccountMeta::new_readonly(key(TOKEN_PROGRAM_ID), false),
+                AccountMeta::new_readonly(user.pubkey(), true),
+                AccountMeta::new(user_wsol, false),
+                AccountMeta::new(user_usdc, false),
+                AccountMeta::new(pool_wsol, false),
+                AccountMeta::new(pool_usdc, false),
+                AccountMeta::new_readonly(pool_authority, false),
+                AccountMeta::new_readonly(key(USDC), false),
+            ],
+        );
+        let message = v0::Message::try_compile(&user.pubkey(), &[route], &[], Hash::default()).unwrap();
+        let index = |key: &Pubkey| message.account_keys.iter().position(|k| k == key).unwrap() as u8;
+        let token_program = index(&key(TOKEN_PROGRAM_ID));
+
+        // What Jupiter's CPIs into the token program look like in the meta
+        let transfer = |source, destination, authority, amount: u64| {
+            let mut data = vec![TRANSFER];
+            data.extend_from_slice(&amount.to_le_bytes());
+            InnerInstruction {
+                program_id_index: token_program,
+                accounts: vec![source, destination, authority],
+                data,
+            }
+        };
+        let mut payout = vec![TRANSFER_CHECKED];
+        payout.extend_from_slice(&300_000_000u64.to_le_bytes());
+        payout.push(6);
+        let inner = vec![(
+            0,
+            vec![
+                transfer(index(&user_wsol), index(&pool_wsol), index(&user.pubkey()), 2_000_000_000),
+                InnerInstruction {
+                    program_id_index: token_program,
+                    accounts: vec![index(&pool_usdc), index(&key(USDC)), index(&user_usdc), index(&pool_authority)],
+                    data: payout,
+                },
+            ],
+        )];
+        let mints = HashMap::from([
+            (index(&user_wsol), NATIVE_MINT.to_string()),
+            (index(&pool_wsol), NATIVE_MINT.to_string()),
+        ]);
+
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message.clone()), &[&user]).unwrap();
+        let tx = Base64Transaction {
+            slot: 7,
+            timestamp: 1_700_000_000,
+            transaction: base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap()),
+            loaded_addresses: Vec::new(),
+            inner_instructions: inner,
+            token_account_mints: mints,
+        };
+        let wallet = user.pubkey().to_string();
+
+        let parser = SwapParser::new(Some(NATIVE_MINT.to_string()), Some(USDC.to_string()));
+        let event = parser.parse_encoded_transaction(&tx, &wallet).unwrap();
+        assert_eq!(event.signature, transaction.signatures[0].to_string());
+        assert_eq!((event.input_mint.as_str(), event.input_amount), (NATIVE_MINT, 2_000_000_000));
+        assert_eq!((event.output_mint.as_str(), event.output_amount), (USDC, 300_000_000));
+        assert_eq!(event.calculate_price(9, 6), 150.0);
+        assert_eq!(event.slot, 7);
+
+        // Another pair's swap is filtered out
+        let other = SwapParser::new(Some(NATIVE_MINT.to_string()), Some("BONK".to_string()));
+        assert!(other.parse_encoded_transaction(&tx, &wallet).is_none());
+
+        // Nor is it anyone else's swap
+        assert!(parser.parse_encoded_transaction(&tx, &Pubkey::new_unique().to_string()).is_none());
+
+        // Without Jupiter's inner instructions there is nothing to read
+        let bare = Base64Transaction { inner_instructions: Vec::new(), ..tx.clone() };
+        assert!(parser.parse_encoded_transaction(&bare, &wallet).is_none());
+        let garbled = Base64Transaction { transaction: "not base64".to_string(), ..tx };
+        assert!(parser.parse_encoded_transaction(&garbled, &wallet).is_none());
+    }
+
+    #[test]
+    fn test_parse_rpc_fixture() {
+        // getTransaction (base64) for a v0 Jupiter route paid for by a relayer,
+        // with the pool's accounts loaded from a lookup table
+        let response: EncodedConfirmedTransactionWithStatusMeta =
+            serde_json::from_str(include_str!("../tests/fixtures/jupiter_v6_swap.json")).unwrap();
+        let tx = Base64Transaction::from_rpc(&response).unwrap();
+        assert_eq!(tx.loaded_addresses.len(), 4);
+        assert_eq!(tx.timestamp, 1_730_000_000);
+
+        const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        const WALLET: &str = "6TcyBfPdBt1kjsvDZLzmBFnuMaLWiTaAt4RjUr9VA5YD";
+        let parser = SwapParser::new(Some(NATIVE_MINT.to_string()), Some(USDC.to_string()));
+        let event = parser.parse_encoded_transaction(&tx, WALLET).unwrap();
+        assert_eq!((event.input_mint.as_str(), event.input_amount), (NATIVE_MINT, 2_000_000_000));
+        assert_eq!((event.output_mint.as_str(), event.output_amount), (USDC, 301_234_567));
+        assert_eq!(event.slot, 301_554_210);
+
+        // The relayer paid the fee but made no swap
+        let relayer = tx_fee_payer(&tx);
+        assert_ne!(relayer, WALLET);
+        assert!(parser.parse_encoded_transaction(&tx, &relayer).is_none());
+    }
+
+    fn tx_fee_payer(tx: &Base64Transaction) -> String {
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&tx.transaction).unwrap();
+        let transaction: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+        transaction.message.static_account_keys()[0].to_string()
+    }
+
     #[test]
     fn test_jupiter_program_detection() {
         use jupiter_programs::*;
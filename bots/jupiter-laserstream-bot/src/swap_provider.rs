@@ -0,0 +1,107 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::config::BotConfig;
+use jupiter_client::{JupiterClient, JupiterQuoteResponse, JupiterSwapResponse, RateLimiter};
+
+/// Aggregator the executor routes swaps through. Quotes and swap transactions
+/// use Jupiter's v6 wire format, which self-hosted routers also speak.
+pub trait SwapProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn get_quote<'a>(
+        &'a self,
+        input_mint: &'a str,
+        output_mint: &'a str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> BoxFuture<'a, Result<JupiterQuoteResponse>>;
+
+    /// Unsigned swap transaction for `quote`, paid for by `user_public_key`,
+    /// at a fixed compute unit price or the provider's automatic one
+    fn get_swap_transaction<'a>(
+        &'a self,
+        quote: &'a JupiterQuoteResponse,
+        user_public_key: &'a str,
+        wrap_unwrap_sol: bool,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> BoxFuture<'a, Result<JupiterSwapResponse>>;
+
+    /// Price as output_mint per input_mint
+    fn get_price<'a>(&'a self, input_mint: &'a str, output_mint: &'a str) -> BoxFuture<'a, Result<f64>>;
+
+    /// Largest amount up to `up_to` whose quoted price impact stays within
+    /// `max_impact_pct`; None when no size does. Providers that don't report
+    /// price impact leave the amount alone.
+    fn max_size_for_impact<'a>(
+        &'a self,
+        _input_mint: &'a str,
+        _output_mint: &'a str,
+        _max_impact_pct: f64,
+        up_to: u64,
+    ) -> BoxFuture<'a, Result<Option<u64>>> {
+        Box::pin(async move { Ok(Some(up_to)) })
+    }
+}
+
+impl SwapProvider for JupiterClient {
+    fn name(&self) -> &str {
+        "jupiter"
+    }
+
+    fn get_quote<'a>(
+        &'a self,
+        input_mint: &'a str,
+        output_mint: &'a str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> BoxFuture<'a, Result<JupiterQuoteResponse>> {
+        Box::pin(async move { Ok(JupiterClient::get_quote(self, input_mint, output_mint, amount, slippage_bps).await?) })
+    }
+
+    fn get_swap_transaction<'a>(
+        &'a self,
+        quote: &'a JupiterQuoteResponse,
+        user_public_key: &'a str,
+        wrap_unwrap_sol: bool,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> BoxFuture<'a, Result<JupiterSwapResponse>> {
+        Box::pin(async move {
+            Ok(JupiterClient::get_swap_transaction(
+                self,
+                quote,
+                user_public_key,
+                wrap_unwrap_sol,
+                priority_fee_micro_lamports,
+            )
+            .await?)
+        })
+    }
+
+    fn get_price<'a>(&'a self, input_mint: &'a str, output_mint: &'a str) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(async move { Ok(JupiterClient::get_price(self, input_mint, output_mint).await?) })
+    }
+
+    fn max_size_for_impact<'a>(
+        &'a self,
+        input_mint: &'a str,
+        output_mint: &'a str,
+        max_impact_pct: f64,
+        up_to: u64,
+    ) -> BoxFuture<'a, Result<Option<u64>>> {
+        Box::pin(async move {
+            Ok(JupiterClient::max_size_for_impact(self, input_mint, output_mint, max_impact_pct, up_to).await?)
+        })
+    }
+}
+
+/// Jupiter (public or `JUPITER_QUOTE_URL`), or a Jupiter-compatible router at
+/// `SWAP_API_URL`, sending its requests through `rate_limiter`
+pub fn create_swap_provider(config: &BotConfig, rate_limiter: Arc<RateLimiter>) -> Box<dyn SwapProvider> {
+    if let Some(url) = config.quote_api_url() {
+        info!("Routing swaps through {}", url);
+    }
+    Box::new(config.jupiter_client_builder().rate_limiter(rate_limiter).build())
+}
@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::swap_parser::known_token_decimals;
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// SPL mint layout: mint authority (COption<Pubkey>, 36 bytes), supply (u64), then decimals
+const MINT_DECIMALS_OFFSET: usize = 44;
+/// Size of a base mint account; Token-2022 mints with extensions are longer
+const MINT_LEN: usize = 82;
+
+/// Decimals read from chain, by mint. A mint's decimals never change, so
+/// entries live for the whole process.
+fn cache() -> &'static Mutex<HashMap<Pubkey, u8>> {
+    static CACHE: OnceLock<Mutex<HashMap<Pubkey, u8>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Decimals of `mint`: the known-token table first, then the cache, then the
+/// mint account itself
+pub async fn fetch_decimals(rpc: &RpcClient, mint: &Pubkey) -> Result<u8> {
+    if let Some(decimals) = known_token_decimals(&mint.to_string()) {
+        return Ok(decimals);
+    }
+    if let Some(decimals) = cache().lock().unwrap().get(mint) {
+        return Ok(*decimals);
+    }
+
+    let account = rpc
+        .get_account(mint)
+        .await
+        .with_context(|| format!("Failed to fetch mint account {}", mint))?;
+    let decimals = decode_mint_decimals(&account.owner, &account.data)
+        .with_context(|| format!("{} is not a token mint", mint))?;
+    cache().lock().unwrap().insert(*mint, decimals);
+    Ok(decimals)
+}
+
+/// `decimals` field of an SPL Token or Token-2022 mint account
+fn decode_mint_decimals(owner: &Pubkey, data: &[u8]) -> Result<u8> {
+    let owner = owner.to_string();
+    if owner != TOKEN_PROGRAM_ID && owner != TOKEN_2022_PROGRAM_ID {
+        anyhow::bail!("Account is owned by {}, not a token program", owner);
+    }
+    if data.len() < MINT_LEN {
+        anyhow::bail!("Account data is {} bytes, a mint is at least {}", data.len(), MINT_LEN);
+    }
+    Ok(data[MINT_DECIMALS_OFFSET])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use solana_client::rpc_client::Mocks;
+    use solana_client::rpc_request::RpcRequest;
+    use std::str::FromStr;
+
+    /// Initialized mint account data with `decimals`
+    fn mint_data(decimals: u8) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_LEN];
+        data[MINT_DECIMALS_OFFSET] = decimals;
+        data[MINT_DECIMALS_OFFSET + 1] = 1; // is_initialized
+        data
+    }
+
+    #[tokio::test]
+    async fn test_unknown_mint_decimals_come_from_chain() {
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            serde_json::json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "data": [base64::engine::general_purpose::STANDARD.encode(mint_data(8)), "base64"],
+                    "executable": false,
+                    "lamports": 1_461_600,
+                    "owner": TOKEN_PROGRAM_ID,
+                    "rentEpoch": 0,
+                    "space": MINT_LEN,
+                },
+            }),
+        );
+        let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+        let mint = Pubkey::new_unique();
+        assert_eq!(fetch_decimals(&rpc, &mint).await.unwrap(), 8);
+
+        // Cached: an RPC that fails every request still answers for it
+        let offline = RpcClient::new_mock("fails".to_string());
+        assert_eq!(fetch_decimals(&offline, &mint).await.unwrap(), 8);
+        assert!(fetch_decimals(&offline, &Pubkey::new_unique()).await.is_err());
+
+        // Known mints never reach the RPC
+        let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        assert_eq!(fetch_decimals(&offline, &usdc).await.unwrap(), 6);
+    }
+
+    #[test]
+    fn test_only_token_program_mints_decode() {
+        let token = Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap();
+        let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+        assert_eq!(decode_mint_decimals(&token, &mint_data(8)).unwrap(), 8);
+        assert_eq!(decode_mint_decimals(&token_2022, &mint_data(2)).unwrap(), 2);
+
+        assert!(decode_mint_decimals(&Pubkey::new_unique(), &mint_data(8)).is_err());
+        assert!(decode_mint_decimals(&token, &[0u8; 40]).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use jupiter_client::JupiterQuoteResponse;
+
+use crate::swap_parser::SwapEvent;
+
+/// An SPL token balance from a transaction's pre/post token balances
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub owner: String,
+    pub mint: String,
+    pub amount: u64,
+}
+
+/// Change in `owner`'s total `mint` balance across the transaction
+pub fn token_delta(pre: &[TokenBalance], post: &[TokenBalance], owner: &str, mint: &str) -> i128 {
+    let total = |balances: &[TokenBalance]| -> i128 {
+        balances
+            .iter()
+            .filter(|b| b.owner == owner && b.mint == mint)
+            .map(|b| b.amount as i128)
+            .sum()
+    };
+    total(post) - total(pre)
+}
+
+/// Fee payer's lamport change with the transaction fee added back, so only
+/// the swap itself (and any account rent) remains
+pub fn native_delta(pre_lamports: u64, post_lamports: u64, fee_lamports: u64) -> i128 {
+    post_lamports as i128 - pre_lamports as i128 + fee_lamports as i128
+}
+
+/// Where the on-chain result disagrees with the quote it was built from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The wallet spent a different input amount than quoted
+    InputMismatch { quoted: u64, spent: i128 },
+    /// The wallet received less than the quote's minimum out
+    OutputBelowMinimum { min_out: u64, received: i128 },
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputMismatch { quoted, spent } => {
+                write!(f, "spent {} input, quote was for {}", spent, quoted)
+            }
+            Self::OutputBelowMinimum { min_out, received } => {
+                write!(f, "received {} output, below the quote's minimum {}", received, min_out)
+            }
+        }
+    }
+}
+
+/// What a confirmed swap actually did, per `getTransaction`, checked against its quote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeVerification {
+    pub fee_lamports: u64,
+    /// Wallet's input-mint change (negative when spent)
+    pub input_delta: i128,
+    /// Wallet's output-mint change (positive when received)
+    pub output_delta: i128,
+    pub discrepancies: Vec<Discrepancy>,
+    /// The swap as Jupiter's inner token transfers record it, when they could be read
+    pub route: Option<SwapEvent>,
+}
+
+impl TradeVerification {
+    pub fn reconcile(fee_lamports: u64, input_delta: i128, output_delta: i128, quote: &JupiterQuoteResponse) -> Self {
+        let mut discrepancies = Vec::new();
+
+        let quoted_in: u64 = quote.in_amount.parse().unwrap_or(0);
+        if -input_delta != quoted_in as i128 {
+            discrepancies.push(Discrepancy::InputMismatch {
+                quoted: quoted_in,
+                spent: -input_delta,
+            });
+        }
+
+        let min_out: u64 = quote.other_amount_threshold.parse().unwrap_or(0);
+        if output_delta < min_out as i128 {
+            discrepancies.push(Discrepancy::OutputBelowMinimum {
+                min_out,
+                received: output_delta,
+            });
+        }
+
+        Self {
+            fee_lamports,
+            input_delta,
+            output_delta,
+            discrepancies,
+            route: None,
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(in_amount: u64, min_out: u64) -> JupiterQuoteResponse {
+        JupiterQuoteResponse {
+            input_mint: "USDC".to_string(),
+            in_amount: in_amount.to_string(),
+            output_mint: "BONK".to_string(),
+            out_amount: (min_out + min_out / 100).to_string(),
+            other_amount_threshold: min_out.to_string(),
+            swap_mode: "ExactIn".to_string(),
+            slippage_bps: 100,
+            platform_fee: None,
+            price_impact_pct: "0".to_string(),
+            route_plan: Vec::new(),
+        }
+    }
+
+    fn balance(owner: &str, mint: &str, amount: u64) -> TokenBalance {
+        TokenBalance { owner: owner.to_string(), mint: mint.to_string(), amount }
+    }
+
+    #[test]
+    fn test_reconcile_flags_shortfalls() {
+        let pre = [balance("me", "USDC", 10_000_000), balance("pool", "USDC", 99_000_000)];
+        let post = [
+            balance("me", "USDC", 2_500_000),
+            balance("pool", "USDC", 106_500_000),
+            balance("me", "BONK", 1_000_000),
+        ];
+        assert_eq!(token_delta(&pre, &post, "me", "USDC"), -7_500_000);
+        assert_eq!(token_delta(&pre, &post, "me", "BONK"), 1_000_000);
+        assert_eq!(native_delta(1_000_000, 995_000, 5_000), 0);
+
+        // Matches the quote
+        let clean = TradeVerification::reconcile(5_000, -7_500_000, 1_000_000, &quote(7_500_000, 990_000));
+        assert!(clean.is_clean());
+
+        // Spent more than quoted and got less than the minimum
+        let short = TradeVerification::reconcile(5_000, -8_000_000, 900_000, &quote(7_500_000, 990_000));
+        assert_eq!(
+            short.discrepancies,
+            vec![
+                Discrepancy::InputMismatch { quoted: 7_500_000, spent: 8_000_000 },
+                Discrepancy::OutputBelowMinimum { min_out: 990_000, received: 900_000 },
+            ]
+        );
+    }
+}
@@ -1,9 +1,9 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dotenv::dotenv;
 use tracing::{info, error};
 
-use jupiter_laserstream_bot::jupiter_client::JupiterClient;
-use jupiter_laserstream_bot::swap_parser::get_token_decimals;
+use jupiter_client::JupiterClient;
+use jupiter_laserstream_bot::swap_parser::known_token_decimals;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -37,8 +37,8 @@ async fn main() -> Result<()> {
     let amount = 100_000_000; // 0.1 SOL
     match jupiter_client.get_quote(sol_mint, usdc_mint, amount, 50).await {
         Ok(quote) => {
-            let sol_decimals = get_token_decimals(sol_mint);
-            let usdc_decimals = get_token_decimals(usdc_mint);
+            let sol_decimals = known_token_decimals(sol_mint).context("SOL decimals")?;
+            let usdc_decimals = known_token_decimals(usdc_mint).context("USDC decimals")?;
             
             let in_amount: u64 = quote.in_amount.parse().unwrap_or(0);
             let out_amount: u64 = quote.out_amount.parse().unwrap_or(0);
@@ -47,7 +47,7 @@ async fn main() -> Result<()> {
             let out_human = out_amount as f64 / 10_f64.powi(usdc_decimals as i32);
             
             info!("✅ Quote: {:.4} SOL → {:.4} USDC", in_human, out_human);
-            info!("   Price Impact: {}%", quote.price_impact_pct);
+            info!("   Price Impact: {:.4}%", quote.price_impact_percent().unwrap_or(f64::NAN));
             info!("   Route: {} swaps", quote.route_plan.len());
         }
         Err(e) => {
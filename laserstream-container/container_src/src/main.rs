@@ -16,14 +16,22 @@ use axum::{
 use serde::Serialize;
 use tokio::sync::RwLock;
 use tracing::{error, info};
-use tracing_subscriber::EnvFilter;
 
+mod network;
 mod stream;
+mod transactions;
+
+use transactions::{TransactionBuffer, TransactionsResponse};
 
 #[derive(Clone)]
 struct AppState {
     started: Arc<AtomicBool>,
     latest: Arc<RwLock<Option<LatestSlot>>>,
+    /// Program ids whose transactions are streamed; empty means slots only
+    program_ids: Arc<Vec<String>>,
+    transactions: Arc<RwLock<TransactionBuffer>>,
+    /// Cluster the stream reads from (devnet/mainnet), reported at `/status`
+    network: Arc<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -34,28 +42,43 @@ struct LatestSlot {
     created_at_rfc3339: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+struct StatusResponse {
+    network: String,
+    started: bool,
+    latest_slot: Option<u64>,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+    let _telemetry = bot_common::telemetry::init("laserstream-container")?;
 
     let port: u16 = std::env::var("PORT")
         .unwrap_or_else(|_| "8080".to_string())
         .parse()?;
 
+    let buffer_size: usize = std::env::var("TRANSACTION_BUFFER_SIZE")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()?;
+
     let state = AppState {
         started: Arc::new(AtomicBool::new(false)),
         latest: Arc::new(RwLock::new(None)),
+        program_ids: Arc::new(transactions::program_ids_from_env()),
+        transactions: Arc::new(RwLock::new(TransactionBuffer::new(buffer_size))),
+        network: Arc::new(network::network_from_env()),
     };
+    info!("network={}", state.network);
 
     // Start on boot (so the first request already has stream warming up)
     ensure_stream_started(state.clone()).await;
 
     let app = Router::new()
         .route("/health", get(health))
+        .route("/status", get(status))
         .route("/start", post(start))
         .route("/latest", get(latest))
+        .route("/transactions", get(recent_transactions))
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
@@ -71,6 +94,15 @@ async fn health() -> impl IntoResponse {
     (StatusCode::OK, "ok\n")
 }
 
+async fn status(State(state): State<AppState>) -> impl IntoResponse {
+    let latest_slot = state.latest.read().await.as_ref().map(|l| l.slot);
+    Json(StatusResponse {
+        network: state.network.as_ref().clone(),
+        started: state.started.load(Ordering::SeqCst),
+        latest_slot,
+    })
+}
+
 async fn start(State(state): State<AppState>) -> impl IntoResponse {
     ensure_stream_started(state).await;
     (StatusCode::OK, "started\n")
@@ -85,6 +117,16 @@ async fn latest(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
+async fn recent_transactions(State(state): State<AppState>) -> impl IntoResponse {
+    let transactions = state.transactions.read().await.recent();
+    Json(TransactionsResponse {
+        enabled: !state.program_ids.is_empty(),
+        program_ids: state.program_ids.as_ref().clone(),
+        count: transactions.len(),
+        transactions,
+    })
+}
+
 async fn ensure_stream_started(state: AppState) {
     let was_started = state.started.swap(true, Ordering::SeqCst);
     if was_started {
@@ -0,0 +1,44 @@
+/// Cluster the stream reads from: `LASERSTREAM_NETWORK` if set, otherwise
+/// inferred from `LASERSTREAM_ENDPOINT`
+pub fn network_from_env() -> String {
+    if let Ok(network) = std::env::var("LASERSTREAM_NETWORK") {
+        if !network.trim().is_empty() {
+            return network.trim().to_lowercase();
+        }
+    }
+    let endpoint = std::env::var("LASERSTREAM_ENDPOINT").unwrap_or_default();
+    network_from_endpoint(&endpoint).to_string()
+}
+
+/// Helius endpoints name the cluster in the host, e.g.
+/// `laserstream-devnet-ewr.helius-rpc.com` vs `laserstream-mainnet-ewr.helius-rpc.com`
+pub fn network_from_endpoint(endpoint: &str) -> &'static str {
+    let endpoint = endpoint.to_lowercase();
+    if endpoint.contains("devnet") {
+        "devnet"
+    } else if endpoint.contains("testnet") {
+        "testnet"
+    } else if endpoint.contains("mainnet") {
+        "mainnet"
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_from_endpoint() {
+        assert_eq!(
+            network_from_endpoint("https://laserstream-devnet-ewr.helius-rpc.com"),
+            "devnet"
+        );
+        assert_eq!(
+            network_from_endpoint("https://laserstream-mainnet-ewr.helius-rpc.com"),
+            "mainnet"
+        );
+        assert_eq!(network_from_endpoint("http://localhost:10000"), "unknown");
+    }
+}
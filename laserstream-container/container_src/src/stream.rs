@@ -2,14 +2,19 @@ use anyhow::{anyhow, Context};
 use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
 use tokio::pin;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
+use bot_common::swap::{native_delta, trader_index, TokenBalanceEntry};
+use crate::transactions::{account_keys, parse_swap, TransactionView};
 use crate::{AppState, LatestSlot};
 
 // LaserStream SDK (crate name uses hyphen; module uses underscore)
 use helius_laserstream::{
     config::LaserstreamConfig,
-    grpc::{subscribe_update::UpdateOneof, SubscribeRequest},
+    grpc::{
+        subscribe_update::UpdateOneof, SubscribeRequest, SubscribeRequestFilterTransactions,
+        SubscribeUpdateTransaction,
+    },
 };
 
 // In docs.rs, subscribe is listed under client::subscribe
@@ -32,11 +37,23 @@ pub async fn run_slot_stream(state: AppState) -> anyhow::Result<()> {
         ..Default::default()
     };
 
-    // Minimal subscription: slots only
-    let request = SubscribeRequest {
+    // Slots always; successful non-vote transactions only when program ids are configured
+    let mut request = SubscribeRequest {
         slots: [("slots".to_string(), Default::default())].into(),
         ..Default::default()
     };
+    if !state.program_ids.is_empty() {
+        info!("subscribing to transactions for programs: {:?}", state.program_ids);
+        request.transactions.insert(
+            "swaps".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: state.program_ids.as_ref().clone(),
+                ..Default::default()
+            },
+        );
+    }
 
     info!("connecting to LaserStream endpoint: {}", endpoint);
 
@@ -63,26 +80,185 @@ pub async fn run_slot_stream(state: AppState) -> anyhow::Result<()> {
             Some(dt.to_rfc3339())
         });
 
-        if let Some(UpdateOneof::Slot(slot)) = update.update_oneof {
-            let latest = LatestSlot {
-                slot: slot.slot,
-                parent: slot.parent,
-                status: format!("{:?}", slot.status()),
-                created_at_rfc3339,
-            };
-
-            {
-                let mut guard = state.latest.write().await;
-                *guard = Some(latest.clone());
-            }
+        match update.update_oneof {
+            Some(UpdateOneof::Slot(slot)) => {
+                let latest = LatestSlot {
+                    slot: slot.slot,
+                    parent: slot.parent,
+                    status: format!("{:?}", slot.status()),
+                    created_at_rfc3339,
+                };
+
+                {
+                    let mut guard = state.latest.write().await;
+                    *guard = Some(latest.clone());
+                }
 
-            // Keep logs sparse but visible
-            info!(
-                "slot={} parent={:?} status={}",
-                latest.slot, latest.parent, latest.status
-            );
+                // Keep logs sparse but visible
+                info!(
+                    "slot={} parent={:?} status={}",
+                    latest.slot, latest.parent, latest.status
+                );
+            }
+            Some(UpdateOneof::Transaction(tx)) => {
+                if let Some(swap) = parse_transaction_update(tx, &state.program_ids) {
+                    debug!(
+                        "swap sig={} {} {} -> {} {}",
+                        swap.signature,
+                        swap.input_amount,
+                        swap.input_mint,
+                        swap.output_amount,
+                        swap.output_mint
+                    );
+                    state.transactions.write().await.push(swap);
+                }
+            }
+            _ => {}
         }
     }
 
     Err(anyhow!("LaserStream stream ended unexpectedly"))
 }
+
+/// Map a streamed transaction onto the proto-independent `TransactionView` and parse it
+#[tracing::instrument(skip_all, fields(slot = update.slot))]
+fn parse_transaction_update(
+    update: SubscribeUpdateTransaction,
+    program_ids: &[String],
+) -> Option<crate::transactions::ParsedSwap> {
+    let info = update.transaction?;
+    let meta = info.meta?;
+    let message = info.transaction?.message?;
+
+    // v0 messages carry only their static keys; the rest were loaded from
+    // address lookup tables and come back in the meta
+    let account_keys = account_keys(
+        &message.account_keys,
+        &meta.loaded_writable_addresses,
+        &meta.loaded_readonly_addresses,
+    );
+    let program_id = program_ids
+        .iter()
+        .find(|id| account_keys.contains(id))
+        .or(program_ids.first())?
+        .clone();
+
+    let pre: Vec<TokenBalanceEntry> = meta
+        .pre_token_balances
+        .iter()
+        .map(|b| TokenBalanceEntry {
+            mint: b.mint.clone(),
+            owner: b.owner.clone(),
+            amount: b
+                .ui_token_amount
+                .as_ref()
+                .and_then(|a| a.amount.parse().ok())
+                .unwrap_or(0),
+            decimals: b.ui_token_amount.as_ref().map_or(0, |a| a.decimals),
+        })
+        .collect();
+    let post: Vec<TokenBalanceEntry> = meta
+        .post_token_balances
+        .iter()
+        .map(|b| TokenBalanceEntry {
+            mint: b.mint.clone(),
+            owner: b.owner.clone(),
+            amount: b
+                .ui_token_amount
+                .as_ref()
+                .and_then(|a| a.amount.parse().ok())
+                .unwrap_or(0),
+            decimals: b.ui_token_amount.as_ref().map_or(0, |a| a.decimals),
+        })
+        .collect();
+
+    // The fee payer comes first, but may be a relayer paying for a co-signer
+    let num_signers = message.header.map_or(1, |h| h.num_required_signatures as usize);
+    let trader = trader_index(&account_keys, num_signers, &pre, &post);
+    let signer = account_keys.get(trader)?.clone();
+    // Only the fee payer's lamports paid the fee
+    let fee = if trader == 0 { meta.fee } else { 0 };
+    let native_delta = match (meta.pre_balances.get(trader), meta.post_balances.get(trader)) {
+        (Some(pre), Some(post)) => native_delta(*pre, *post, fee),
+        _ => 0,
+    };
+
+    parse_swap(&TransactionView {
+        signature: bs58::encode(&info.signature).into_string(),
+        slot: update.slot,
+        signer,
+        program_id,
+        fee_lamports: meta.fee,
+        native_delta,
+        pre_token_balances: &pre,
+        post_token_balances: &post,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helius_laserstream::grpc::SubscribeUpdateTransactionInfo;
+    use helius_laserstream::solana::storage::confirmed_block::{
+        Message, MessageHeader, TokenBalance, Transaction, TransactionStatusMeta, UiTokenAmount,
+    };
+
+    const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const RELAYER: &str = "7qbRF6YsyGuLUVs6Y1q64bdVrfe4ZcUUz1JRdoVNUJnm";
+    const TRADER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    fn token_balance(owner: &str, mint: &str, amount: u64, decimals: u32) -> TokenBalance {
+        TokenBalance {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            ui_token_amount: Some(UiTokenAmount {
+                amount: amount.to_string(),
+                decimals,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_relayer_paid_swap_is_the_traders() {
+        // The relayer pays the fee; the co-signing trader swaps 150 USDC for 10M BONK
+        let key = |k: &str| bs58::decode(k).into_vec().unwrap();
+        let update = SubscribeUpdateTransaction {
+            slot: 250_000_000,
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                transaction: Some(Transaction {
+                    message: Some(Message {
+                        header: Some(MessageHeader {
+                            num_required_signatures: 2,
+                            ..Default::default()
+                        }),
+                        account_keys: vec![key(RELAYER), key(TRADER), key(JUPITER)],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                meta: Some(TransactionStatusMeta {
+                    fee: 10_000,
+                    pre_balances: vec![1_000_000_000, 50_000_000, 1],
+                    post_balances: vec![999_990_000, 50_000_000, 1],
+                    pre_token_balances: vec![token_balance(TRADER, USDC, 200_000_000, 6)],
+                    post_token_balances: vec![
+                        token_balance(TRADER, USDC, 50_000_000, 6),
+                        token_balance(TRADER, BONK, 1_000_000_000_000, 5),
+                    ],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        };
+
+        let swap = parse_transaction_update(update, &[JUPITER.to_string()]).unwrap();
+        assert_eq!(swap.signer, TRADER);
+        assert_eq!((swap.input_mint.as_str(), swap.input_amount), (USDC, 150_000_000));
+        assert_eq!((swap.output_mint.as_str(), swap.output_amount), (BONK, 1_000_000_000_000));
+        assert_eq!(swap.fee_lamports, 10_000);
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::VecDeque;
+
+use bot_common::swap::{swap_legs, TokenBalanceEntry};
+use serde::Serialize;
+
+/// A swap reduced to what the signer paid and received
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedSwap {
+    pub signature: String,
+    pub slot: u64,
+    pub signer: String,
+    pub program_id: String,
+    pub input_mint: String,
+    pub input_amount: u64,
+    pub output_mint: String,
+    pub output_amount: u64,
+    pub fee_lamports: u64,
+}
+
+/// A transaction's full account list: the message's static keys, then the
+/// addresses a v0 message loads from address lookup tables (writable, then
+/// readonly), the order balances and instructions index into
+pub fn account_keys(
+    static_keys: &[Vec<u8>],
+    loaded_writable: &[Vec<u8>],
+    loaded_readonly: &[Vec<u8>],
+) -> Vec<String> {
+    static_keys
+        .iter()
+        .chain(loaded_writable)
+        .chain(loaded_readonly)
+        .map(|k| bs58::encode(k).into_string())
+        .collect()
+}
+
+/// Everything needed to turn a streamed transaction into a `ParsedSwap`
+pub struct TransactionView<'a> {
+    pub signature: String,
+    pub slot: u64,
+    pub signer: String,
+    pub program_id: String,
+    pub fee_lamports: u64,
+    /// From `bot_common::swap::native_delta`
+    pub native_delta: i128,
+    pub pre_token_balances: &'a [TokenBalanceEntry],
+    pub post_token_balances: &'a [TokenBalanceEntry],
+}
+
+/// What the signer paid and received, per `bot_common::swap::swap_legs`
+pub fn parse_swap(tx: &TransactionView) -> Option<ParsedSwap> {
+    let legs = swap_legs(&tx.signer, tx.pre_token_balances, tx.post_token_balances, tx.native_delta)?;
+    Some(ParsedSwap {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        signer: tx.signer.clone(),
+        program_id: tx.program_id.clone(),
+        input_mint: legs.input.mint,
+        input_amount: legs.input.amount,
+        output_mint: legs.output.mint,
+        output_amount: legs.output.amount,
+        fee_lamports: tx.fee_lamports,
+    })
+}
+
+/// Fixed-size ring buffer of the most recent swaps
+#[derive(Debug)]
+pub struct TransactionBuffer {
+    capacity: usize,
+    swaps: VecDeque<ParsedSwap>,
+}
+
+impl TransactionBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            swaps: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, swap: ParsedSwap) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.swaps.len() >= self.capacity {
+            self.swaps.pop_front();
+        }
+        self.swaps.push_back(swap);
+    }
+
+    /// Newest first
+    pub fn recent(&self) -> Vec<ParsedSwap> {
+        self.swaps.iter().rev().cloned().collect()
+    }
+}
+
+/// Body of `GET /transactions`
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionsResponse {
+    pub enabled: bool,
+    pub program_ids: Vec<String>,
+    pub count: usize,
+    pub transactions: Vec<ParsedSwap>,
+}
+
+/// Program ids to stream transactions for (`TRANSACTION_PROGRAM_IDS`, comma-separated).
+/// Empty means slots only.
+pub fn program_ids_from_env() -> Vec<String> {
+    std::env::var("TRANSACTION_PROGRAM_IDS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bot_common::swap::NATIVE_MINT;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn balance(mint: &str, owner: &str, amount: u64) -> TokenBalanceEntry {
+        TokenBalanceEntry {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            amount,
+            decimals: 6,
+        }
+    }
+
+    fn swap(slot: u64) -> ParsedSwap {
+        ParsedSwap {
+            signature: format!("sig{}", slot),
+            slot,
+            signer: "trader".to_string(),
+            program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+            input_mint: USDC.to_string(),
+            input_amount: 100_000_000,
+            output_mint: NATIVE_MINT.to_string(),
+            output_amount: 650_000_000,
+            fee_lamports: 5_000,
+        }
+    }
+
+    #[test]
+    fn test_parse_usdc_to_unwrapped_sol() {
+        let pre = [balance(USDC, "trader", 250_000_000), balance(USDC, "pool", 9_000_000_000)];
+        let post = [balance(USDC, "trader", 150_000_000), balance(USDC, "pool", 9_100_000_000)];
+
+        let parsed = parse_swap(&TransactionView {
+            signature: "sig".to_string(),
+            slot: 42,
+            signer: "trader".to_string(),
+            program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+            fee_lamports: 5_000,
+            native_delta: 650_000_000,
+            pre_token_balances: &pre,
+            post_token_balances: &post,
+        })
+        .unwrap();
+
+        assert_eq!(parsed.input_mint, USDC);
+        assert_eq!(parsed.input_amount, 100_000_000);
+        assert_eq!(parsed.output_mint, NATIVE_MINT);
+        assert_eq!(parsed.output_amount, 650_000_000);
+    }
+
+    #[test]
+    fn test_ata_rent_is_not_a_leg() {
+        // 1.5 USDC into a token the signer had no account for: the new
+        // account's rent outweighs the USDC in base units but isn't a leg
+        let bonk = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+        let pre = [balance(USDC, "trader", 1_500_000)];
+        let post = [
+            balance(USDC, "trader", 0),
+            TokenBalanceEntry { decimals: 5, ..balance(bonk, "trader", 10_000_000_000) },
+        ];
+
+        let parsed = parse_swap(&TransactionView {
+            signature: "sig".to_string(),
+            slot: 42,
+            signer: "trader".to_string(),
+            program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+            fee_lamports: 5_000,
+            native_delta: -2_039_280,
+            pre_token_balances: &pre,
+            post_token_balances: &post,
+        })
+        .unwrap();
+
+        assert_eq!((parsed.input_mint.as_str(), parsed.input_amount), (USDC, 1_500_000));
+        assert_eq!((parsed.output_mint.as_str(), parsed.output_amount), (bonk, 10_000_000_000));
+    }
+
+    #[test]
+    fn test_non_swap_is_ignored() {
+        let pre = [balance(USDC, "trader", 10)];
+        let post = [balance(USDC, "trader", 10)];
+
+        assert!(parse_swap(&TransactionView {
+            signature: "sig".to_string(),
+            slot: 1,
+            signer: "trader".to_string(),
+            program_id: "prog".to_string(),
+            fee_lamports: 5_000,
+            native_delta: 0,
+            pre_token_balances: &pre,
+            post_token_balances: &post,
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn test_account_keys_include_lookup_table_addresses() {
+        let key = |b: u8| vec![b; 32];
+        let keys = account_keys(&[key(1), key(2)], &[key(3)], &[key(4), key(5)]);
+
+        let expected: Vec<String> = (1..=5).map(|b| bs58::encode(key(b)).into_string()).collect();
+        assert_eq!(keys, expected);
+        // Legacy messages load nothing
+        assert_eq!(account_keys(&[key(1)], &[], &[]), expected[..1]);
+    }
+
+    #[test]
+    fn test_buffer_keeps_most_recent() {
+        let mut buffer = TransactionBuffer::new(2);
+        buffer.push(swap(1));
+        buffer.push(swap(2));
+        buffer.push(swap(3));
+
+        let slots: Vec<u64> = buffer.recent().iter().map(|s| s.slot).collect();
+        assert_eq!(slots, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_transactions_response_shape() {
+        let response = TransactionsResponse {
+            enabled: true,
+            program_ids: vec!["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string()],
+            count: 1,
+            transactions: vec![swap(7)],
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["enabled"], true);
+        assert_eq!(json["count"], 1);
+        assert!(json["program_ids"].is_array());
+
+        let tx = &json["transactions"][0];
+        for field in [
+            "signature",
+            "slot",
+            "signer",
+            "program_id",
+            "input_mint",
+            "input_amount",
+            "output_mint",
+            "output_amount",
+            "fee_lamports",
+        ] {
+            assert!(tx.get(field).is_some(), "missing field {}", field);
+        }
+        assert_eq!(tx["slot"], 7);
+    }
+}
@@ -11,7 +11,14 @@ export class LaserStreamContainer extends Container<Env> {
     return {
       HELIUS_API_KEY: this.env.HELIUS_API_KEY || "",
       LASERSTREAM_ENDPOINT: "https://laserstream-devnet-ewr.helius-rpc.com",
+      // Reported at /status; inferred from LASERSTREAM_ENDPOINT when empty
+      LASERSTREAM_NETWORK: this.env.LASERSTREAM_NETWORK || "",
       RUST_LOG: "debug",
+      // Comma-separated program ids to stream transactions for (empty = slots only)
+      TRANSACTION_PROGRAM_IDS: this.env.TRANSACTION_PROGRAM_IDS || "",
+      TRANSACTION_BUFFER_SIZE: this.env.TRANSACTION_BUFFER_SIZE || "100",
+      // OTLP/HTTP collector base URL for trace export (empty = disabled)
+      OTEL_EXPORTER_OTLP_ENDPOINT: this.env.OTEL_EXPORTER_OTLP_ENDPOINT || "",
     };
   }
 
@@ -36,8 +43,10 @@ app.get("/", (c) => {
     "LaserStream on Cloudflare Containers\n\n" +
       "Endpoints:\n" +
       "GET  /health - Health check\n" +
+      "GET  /status - Streamed network (devnet/mainnet) and latest slot\n" +
       "POST /start - Start LaserStream subscription\n" +
-      "GET  /latest - Get latest slot update\n"
+      "GET  /latest - Get latest slot update\n" +
+      "GET  /transactions - Recent parsed swaps for configured programs\n"
   );
 });
 
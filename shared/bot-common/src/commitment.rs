@@ -0,0 +1,30 @@
+use anyhow::Result;
+use solana_commitment_config::CommitmentConfig;
+
+/// `COMMITMENT` value: processed (fastest), confirmed or finalized (safest)
+pub fn parse_commitment(value: &str) -> Result<CommitmentConfig> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        other => anyhow::bail!(
+            "Invalid COMMITMENT '{}': expected processed, confirmed or finalized",
+            other
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commitment() {
+        assert_eq!(parse_commitment("processed").unwrap(), CommitmentConfig::processed());
+        assert_eq!(parse_commitment(" Confirmed ").unwrap(), CommitmentConfig::confirmed());
+        assert_eq!(parse_commitment("finalized").unwrap(), CommitmentConfig::finalized());
+
+        assert!(parse_commitment("recent").is_err());
+        assert!(parse_commitment("").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Indicators over a price series, oldest first, shared by the bots' price trackers
+
+/// Arithmetic mean; None for an empty series
+pub fn mean(prices: &[f64]) -> Option<f64> {
+    if prices.is_empty() {
+        return None;
+    }
+    Some(prices.iter().sum::<f64>() / prices.len() as f64)
+}
+
+/// Population standard deviation; None below two prices
+pub fn std_dev(prices: &[f64]) -> Option<f64> {
+    if prices.len() < 2 {
+        return None;
+    }
+    let mean = mean(prices)?;
+    let variance = prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / prices.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// (lower, middle, upper) Bollinger Bands: the mean plus/minus `std_devs`
+/// standard deviations. None below two prices.
+pub fn bollinger_bands(prices: &[f64], std_devs: f64) -> Option<(f64, f64, f64)> {
+    let std_dev = std_dev(prices)?;
+    let middle = mean(prices)?;
+    Some((middle - std_devs * std_dev, middle, middle + std_devs * std_dev))
+}
+
+/// EMA with smoothing factor `2 / (periods + 1)`, seeded with the first price
+pub fn ema(prices: &[f64], periods: usize) -> Option<f64> {
+    ema_series(prices, periods).last().copied()
+}
+
+/// Running EMA after each price, seeded with the first
+fn ema_series(prices: &[f64], periods: usize) -> Vec<f64> {
+    let alpha = 2.0 / (periods as f64 + 1.0);
+    let mut series: Vec<f64> = Vec::with_capacity(prices.len());
+    for price in prices {
+        let ema = match series.last() {
+            Some(prev) => alpha * price + (1.0 - alpha) * prev,
+            None => *price,
+        };
+        series.push(ema);
+    }
+    series
+}
+
+/// (MACD line, signal line, histogram). The signal EMA starts once the slow EMA
+/// has `slow` points behind it. None when `fast >= slow` (a misconfiguration,
+/// not a market state) or there are fewer than `slow` prices.
+pub fn macd(prices: &[f64], fast: usize, slow: usize, signal: usize) -> Option<(f64, f64, f64)> {
+    if fast == 0 || fast >= slow || signal == 0 || prices.len() < slow {
+        return None;
+    }
+
+    let fast_ema = ema_series(prices, fast);
+    let slow_ema = ema_series(prices, slow);
+    let macd: Vec<f64> = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .skip(slow - 1)
+        .map(|(f, s)| f - s)
+        .collect();
+
+    let macd_line = *macd.last()?;
+    let signal_line = ema(&macd, signal)?;
+    Some((macd_line, signal_line, macd_line - signal_line))
+}
+
+/// Relative Strength Index, 0-100: simple averages of the first `periods`
+/// gains/losses, then Wilder's smoothing for every later change. None until
+/// there are `periods + 1` prices.
+pub fn rsi(prices: &[f64], periods: usize) -> Option<f64> {
+    if periods == 0 || prices.len() < periods + 1 {
+        return None;
+    }
+
+    let changes: Vec<f64> = prices.windows(2).map(|w| w[1] - w[0]).collect();
+    let (initial, rest) = changes.split_at(periods);
+    let n = periods as f64;
+
+    let mut avg_gain = initial.iter().map(|c| c.max(0.0)).sum::<f64>() / n;
+    let mut avg_loss = initial.iter().map(|c| (-c).max(0.0)).sum::<f64>() / n;
+    for change in rest {
+        avg_gain = (avg_gain * (n - 1.0) + change.max(0.0)) / n;
+        avg_loss = (avg_loss * (n - 1.0) + (-change).max(0.0)) / n;
+    }
+
+    let rsi = if avg_loss == 0.0 {
+        // Flat series is neutral; only gains is maximally overbought
+        if avg_gain == 0.0 { 50.0 } else { 100.0 }
+    } else {
+        100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+    };
+    Some(rsi.clamp(0.0, 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_std_dev_and_bands_need_two_prices() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(std_dev(&[20.0]), None);
+        assert_eq!(bollinger_bands(&[20.0], 2.0), None);
+        assert_eq!(bollinger_bands(&[20.0, 20.0], 2.0), Some((20.0, 20.0, 20.0)));
+
+        // 18, 20, 22, 20: mean 20, population std dev sqrt(2)
+        let (lower, middle, upper) = bollinger_bands(&[18.0, 20.0, 22.0, 20.0], 1.5).unwrap();
+        let band = 1.5 * 2f64.sqrt();
+        assert!((middle - 20.0).abs() < 1e-9);
+        assert!((lower - (20.0 - band)).abs() < 1e-9);
+        assert!((upper - (20.0 + band)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ema_and_macd() {
+        assert_eq!(ema(&[], 5), None);
+        assert_eq!(ema(&[100.0], 5), Some(100.0));
+        // alpha = 1/3: 100 -> 103 -> 104
+        assert!((ema(&[100.0, 109.0, 106.0], 5).unwrap() - 104.0).abs() < 1e-9);
+
+        let rising: Vec<f64> = (0..40).map(|i| 150.0 + i as f64).collect();
+        let (line, signal, histogram) = macd(&rising, 12, 26, 9).unwrap();
+        assert_eq!(histogram, line - signal);
+        assert!(line > 0.0);
+        assert!(macd(&rising, 26, 12, 9).is_none());
+        assert!(macd(&rising[..25], 12, 26, 9).is_none());
+    }
+
+    #[test]
+    fn test_rsi_hand_calculated() {
+        let prices = [44.0, 44.5, 43.5, 44.5, 45.5];
+        assert!(rsi(&prices, 5).is_none());
+
+        // Changes +0.5, -1, +1, +1: avg gain 2.5/4, avg loss 1/4, RS 2.5 => 100 - 100/3.5
+        assert!((rsi(&prices, 4).unwrap() - 71.428_571).abs() < 1e-4);
+
+        // -1.5: avg gain (2.5/4*3)/4 = 0.46875, avg loss (0.25*3 + 1.5)/4 = 0.5625
+        let prices = [44.0, 44.5, 43.5, 44.5, 45.5, 44.0];
+        assert!((rsi(&prices, 4).unwrap() - 45.454_545).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rsi_bounds() {
+        let rising: Vec<f64> = (0..15).map(|i| 100.0 + i as f64).collect();
+        let falling: Vec<f64> = (0..15).map(|i| 100.0 - i as f64).collect();
+        assert_eq!(rsi(&rising, 14), Some(100.0));
+        assert_eq!(rsi(&falling, 14), Some(0.0));
+        assert_eq!(rsi(&[100.0; 15], 14), Some(50.0));
+    }
+}
@@ -0,0 +1,11 @@
+pub mod commitment;
+pub mod indicators;
+pub mod shutdown;
+pub mod state;
+pub mod swap;
+pub mod telemetry;
+pub mod uptime;
+
+pub use commitment::parse_commitment;
+pub use shutdown::{shutdown_signal, sleep_or_shutdown, ShutdownWatchdog};
+pub use uptime::Uptime;
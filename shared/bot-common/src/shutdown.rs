@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Resolves on Ctrl-C, or on SIGTERM (what container runtimes send on stop)
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Could not listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Could not listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Sleep out the poll interval between ticks. Returns false, straight away,
+/// once `shutdown` resolves instead: the loop should exit rather than continue.
+pub async fn sleep_or_shutdown<F: Future>(interval: Duration, shutdown: F) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => true,
+        _ = shutdown => false,
+    }
+}
+
+/// Forces an exit if draining outlives the shutdown timeout. Runs on its own
+/// OS thread so a blocking RPC call on the runtime can't hold it up.
+pub struct ShutdownWatchdog {
+    disarmed: Arc<AtomicBool>,
+}
+
+impl ShutdownWatchdog {
+    /// After `timeout`, unless disarmed first, call `on_expire` from the
+    /// watchdog thread: record what's left, then exit (`std::process::exit`
+    /// in the bots)
+    pub fn arm<F>(timeout: Duration, on_expire: F) -> Self
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let disarmed = Arc::new(AtomicBool::new(false));
+        let flag = disarmed.clone();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            if flag.load(Ordering::SeqCst) {
+                return;
+            }
+            error!("🛑 Shutdown drain exceeded {:?}, forcing exit", timeout);
+            on_expire();
+        });
+
+        Self { disarmed }
+    }
+
+    /// Drain finished in time; the watchdog will do nothing
+    pub fn disarm(&self) {
+        self.disarmed.store(true, Ordering::SeqCst);
+        info!("✅ Shutdown drain completed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_shutdown_ends_the_wait_between_ticks() {
+        let started = std::time::Instant::now();
+        assert!(!sleep_or_shutdown(Duration::from_secs(60), async {}).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // No signal: the loop carries on after the interval
+        let pending = std::future::pending::<()>();
+        assert!(sleep_or_shutdown(Duration::from_millis(10), pending).await);
+    }
+
+    #[test]
+    fn test_watchdog_fires_past_a_blocking_call() {
+        let (expired_tx, expired_rx) = mpsc::channel();
+        let _watchdog = ShutdownWatchdog::arm(Duration::from_millis(50), move || expired_tx.send(()).unwrap());
+
+        // A blocking RPC call holding the thread doesn't hold up the watchdog
+        std::thread::sleep(Duration::from_millis(100));
+        expired_rx.recv_timeout(Duration::from_secs(2)).expect("watchdog did not fire");
+    }
+
+    #[test]
+    fn test_disarmed_watchdog_does_nothing() {
+        let (expired_tx, expired_rx) = mpsc::channel();
+        let watchdog = ShutdownWatchdog::arm(Duration::from_millis(20), move || expired_tx.send(()).unwrap());
+        watchdog.disarm();
+
+        assert!(expired_rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}
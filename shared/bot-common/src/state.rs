@@ -0,0 +1,130 @@
+//! The part of a bot's state that survives a restart, so one can't reset the
+//! daily trade limit or cooldown
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The last slot moves every tick and only needs to be roughly current on
+/// disk, so a change to it alone is saved at most this often
+pub const SLOT_SAVE_INTERVAL_SECS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedState {
+    pub cooldown_until: Option<DateTime<Utc>>,
+    pub daily_trade_count: usize,
+    pub day_start: DateTime<Utc>,
+    pub last_slot: Option<u64>,
+}
+
+impl PersistedState {
+    /// Whether this state needs writing over `saved`, written at `saved_at`:
+    /// when the cooldown, daily count or day changed, or when only the last
+    /// slot did and `SLOT_SAVE_INTERVAL_SECS` has passed
+    pub fn save_due(&self, saved: &PersistedState, saved_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        if self == saved {
+            return false;
+        }
+        if (PersistedState { last_slot: self.last_slot, ..saved.clone() }) == *self {
+            return now.signed_duration_since(saved_at) >= Duration::seconds(SLOT_SAVE_INTERVAL_SECS);
+        }
+        true
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        // Write then rename, so a crash mid-write leaves the previous state intact
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// State saved by `save`, or None if `path` doesn't exist yet. A day that
+    /// ended while the bot was down is rolled over.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut state: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid bot state file {}", path.display()))?;
+        state.roll_day(Utc::now());
+        Ok(Some(state))
+    }
+
+    /// Start a new day, with no trades counted, once 24h have passed since `day_start`
+    pub fn roll_day(&mut self, now: DateTime<Utc>) {
+        if now.signed_duration_since(self.day_start).num_hours() >= 24 {
+            self.daily_trade_count = 0;
+            self.day_start = now;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_state_survives_a_restart() {
+        let path = state_path("persisted-state");
+        assert_eq!(PersistedState::load(&path).unwrap(), None);
+
+        let state = PersistedState {
+            cooldown_until: Some(Utc::now() + Duration::minutes(5)),
+            daily_trade_count: 7,
+            day_start: Utc::now() - Duration::hours(3),
+            last_slot: Some(250_000_000),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(PersistedState::load(&path).unwrap(), Some(state));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(PersistedState::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_day_rolls_over_on_load_after_downtime() {
+        let path = state_path("persisted-state-rollover");
+        let state = PersistedState {
+            cooldown_until: None,
+            daily_trade_count: 20,
+            day_start: Utc::now() - Duration::hours(30),
+            last_slot: Some(100),
+        };
+        state.save(&path).unwrap();
+        let loaded = PersistedState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.daily_trade_count, 0);
+        assert!(Utc::now().signed_duration_since(loaded.day_start).num_minutes() < 1);
+        assert_eq!(loaded.last_slot, Some(100));
+
+        // Still inside the day: the count is kept
+        let state = PersistedState { day_start: Utc::now() - Duration::hours(23), ..state };
+        state.save(&path).unwrap();
+        assert_eq!(PersistedState::load(&path).unwrap(), Some(state));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_slot_only_change_saved_at_interval() {
+        let now = Utc::now();
+        let saved = PersistedState { cooldown_until: None, daily_trade_count: 2, day_start: now, last_slot: Some(100) };
+        assert!(!saved.save_due(&saved, now, now));
+
+        // A new slot alone waits out the interval
+        let moved = PersistedState { last_slot: Some(101), ..saved.clone() };
+        assert!(!moved.save_due(&saved, now, now + Duration::seconds(29)));
+        assert!(moved.save_due(&saved, now, now + Duration::seconds(30)));
+
+        // Anything else is saved right away
+        let traded = PersistedState { daily_trade_count: 3, ..moved };
+        assert!(traded.save_due(&saved, now, now));
+    }
+}
@@ -0,0 +1,210 @@
+//! A swap read back from a transaction's balance changes, shared by the
+//! LaserStream adapter and container
+
+use std::collections::HashMap;
+
+pub const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+const NATIVE_DECIMALS: u32 = 9;
+
+/// Token balance row from a transaction's pre/post balances
+#[derive(Debug, Clone)]
+pub struct TokenBalanceEntry {
+    pub mint: String,
+    pub owner: String,
+    /// Base units
+    pub amount: u64,
+    pub decimals: u32,
+}
+
+/// One side of a swap, in the mint's base units
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapLeg {
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: u32,
+}
+
+impl SwapLeg {
+    pub fn ui_amount(&self) -> f64 {
+        self.amount as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// What the signer paid and received
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapLegs {
+    pub input: SwapLeg,
+    pub output: SwapLeg,
+}
+
+impl SwapLegs {
+    /// Output per input, in UI units
+    pub fn price(&self) -> f64 {
+        self.output.ui_amount() / self.input.ui_amount()
+    }
+}
+
+/// Signer lamports after minus before, with the fee added back
+pub fn native_delta(pre_lamports: u64, post_lamports: u64, fee: u64) -> i128 {
+    post_lamports as i128 - pre_lamports as i128 + fee as i128
+}
+
+/// Index of the signer who traded among the first `num_signers` account keys:
+/// the first one after the fee payer whose token balances changed, since a
+/// relayer may pay the fee for a trader who co-signs; else the fee payer
+pub fn trader_index(
+    account_keys: &[String],
+    num_signers: usize,
+    pre_token_balances: &[TokenBalanceEntry],
+    post_token_balances: &[TokenBalanceEntry],
+) -> usize {
+    let moved = |owner: &str| {
+        let mut deltas: HashMap<&str, i128> = HashMap::new();
+        let balances = pre_token_balances
+            .iter()
+            .map(|b| (b, -1))
+            .chain(post_token_balances.iter().map(|b| (b, 1)));
+        for (balance, sign) in balances.filter(|(b, _)| b.owner == owner) {
+            *deltas.entry(balance.mint.as_str()).or_default() += sign * balance.amount as i128;
+        }
+        deltas.values().any(|delta| *delta != 0)
+    };
+    account_keys
+        .iter()
+        .take(num_signers)
+        .skip(1)
+        .position(|key| moved(key))
+        .map_or(0, |i| i + 1)
+}
+
+/// Net per-mint balance change for `signer`. The mint that decreased most is
+/// the input, the one that increased most the output, compared in UI units.
+///
+/// Native SOL stands in for wSOL when no wSOL token balance moved (unwrapped
+/// swaps), but only on a side no token covers: lamports also pay account rent
+/// (e.g. creating the output ATA), priority fees and tips, none of which is a leg.
+pub fn swap_legs(
+    signer: &str,
+    pre_token_balances: &[TokenBalanceEntry],
+    post_token_balances: &[TokenBalanceEntry],
+    native_delta: i128,
+) -> Option<SwapLegs> {
+    let mut deltas: HashMap<&str, (i128, u32)> = HashMap::new();
+    let balances = pre_token_balances
+        .iter()
+        .map(|b| (b, -1))
+        .chain(post_token_balances.iter().map(|b| (b, 1)));
+    for (balance, sign) in balances.filter(|(b, _)| b.owner == signer) {
+        let entry = deltas.entry(balance.mint.as_str()).or_default();
+        entry.0 += sign * balance.amount as i128;
+        entry.1 = balance.decimals;
+    }
+    deltas.retain(|_, (delta, _)| *delta != 0);
+    if !deltas.contains_key(NATIVE_MINT)
+        && native_delta != 0
+        && !deltas.values().any(|(delta, _)| delta.signum() == native_delta.signum())
+    {
+        deltas.insert(NATIVE_MINT, (native_delta, NATIVE_DECIMALS));
+    }
+
+    let ui = |(delta, decimals): &(i128, u32)| *delta as f64 / 10f64.powi(*decimals as i32);
+    let (input_mint, input) = deltas.iter().min_by(|a, b| ui(a.1).total_cmp(&ui(b.1)))?;
+    let (output_mint, output) = deltas.iter().max_by(|a, b| ui(a.1).total_cmp(&ui(b.1)))?;
+    if input.0 >= 0 || output.0 <= 0 {
+        return None;
+    }
+
+    Some(SwapLegs {
+        input: SwapLeg {
+            mint: input_mint.to_string(),
+            amount: input.0.unsigned_abs() as u64,
+            decimals: input.1,
+        },
+        output: SwapLeg {
+            mint: output_mint.to_string(),
+            amount: output.0 as u64,
+            decimals: output.1,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    /// Rent-exempt minimum of a token account
+    const ATA_RENT: i128 = 2_039_280;
+
+    fn balance(owner: &str, mint: &str, amount: u64, decimals: u32) -> TokenBalanceEntry {
+        TokenBalanceEntry {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            amount,
+            decimals,
+        }
+    }
+
+    #[test]
+    fn test_small_swap_creating_output_ata_ignores_rent() {
+        // 1.5 USDC for 100k BONK; the signer also funds the new BONK account
+        let pre = [balance("trader", USDC, 1_500_000, 6)];
+        let post = [balance("trader", USDC, 0, 6), balance("trader", BONK, 10_000_000_000, 5)];
+
+        let legs = swap_legs("trader", &pre, &post, -ATA_RENT).unwrap();
+        assert_eq!(legs.input, SwapLeg { mint: USDC.to_string(), amount: 1_500_000, decimals: 6 });
+        assert_eq!(legs.output.mint, BONK);
+        assert!((legs.price() - 100_000.0 / 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unwrapped_sol_is_a_leg() {
+        // USDC into unwrapped SOL: lamports are the output
+        let pre = [balance("trader", USDC, 250_000_000, 6), balance("pool", USDC, 9_000_000_000, 6)];
+        let post = [balance("trader", USDC, 150_000_000, 6), balance("pool", USDC, 9_100_000_000, 6)];
+        let legs = swap_legs("trader", &pre, &post, 650_000_000).unwrap();
+        assert_eq!(legs.input.mint, USDC);
+        assert_eq!((legs.output.mint.as_str(), legs.output.amount), (NATIVE_MINT, 650_000_000));
+
+        // Unwrapped SOL into USDC: lamports are the input
+        let legs = swap_legs("trader", &[], &[balance("trader", USDC, 15_000_000, 6)], -100_000_000).unwrap();
+        assert_eq!((legs.input.mint.as_str(), legs.input.amount), (NATIVE_MINT, 100_000_000));
+        assert!((legs.price() - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_legs_compare_in_ui_units() {
+        // 0.003 of a 9-decimal token moves more base units than 2 USDC, but is the smaller leg
+        let dust = "DustMint1111111111111111111111111111111111";
+        let pre = [balance("trader", USDC, 2_000_000, 6), balance("trader", dust, 3_000_000, 9)];
+        let post = [balance("trader", USDC, 0, 6), balance("trader", BONK, 4_000_000_000, 5)];
+
+        let legs = swap_legs("trader", &pre, &post, 0).unwrap();
+        assert_eq!(legs.input.mint, USDC);
+        assert_eq!(legs.output.mint, BONK);
+    }
+
+    #[test]
+    fn test_non_swap_is_ignored() {
+        let unchanged = [balance("trader", USDC, 10, 6)];
+        assert!(swap_legs("trader", &unchanged, &unchanged, 0).is_none());
+        // Rent alone isn't a swap
+        assert!(swap_legs("trader", &unchanged, &unchanged, -ATA_RENT).is_none());
+    }
+
+    #[test]
+    fn test_trader_behind_relayer() {
+        let keys = ["relayer", "trader", "pool"].map(String::from);
+        let pre = [balance("trader", USDC, 100_000_000, 6), balance("pool", USDC, 0, 6)];
+        let post = [balance("trader", USDC, 0, 6), balance("pool", USDC, 100_000_000, 6)];
+
+        assert_eq!(trader_index(&keys, 2, &pre, &post), 1);
+        // The pool moved too, but isn't a signer
+        assert_eq!(trader_index(&keys, 1, &pre, &post), 0);
+        // Self-paid: only the fee payer's balances move
+        let pre = [balance("relayer", USDC, 100_000_000, 6)];
+        let post = [balance("relayer", USDC, 0, 6)];
+        assert_eq!(trader_index(&keys, 2, &pre, &post), 0);
+    }
+}
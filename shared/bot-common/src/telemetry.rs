@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Flushes and shuts down the OTLP exporter when dropped; hold it for the life of `main`
+pub struct TelemetryGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!("OpenTelemetry shutdown failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Tracer provider exporting spans over OTLP/HTTP to `endpoint` (the collector's base URL)
+fn otlp_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .context("Failed to build OTLP span exporter")?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build())
+}
+
+/// OpenTelemetry layer for `endpoint`, or nothing when no endpoint is configured
+pub fn otel_layer<S>(
+    endpoint: Option<&str>,
+    service_name: &str,
+) -> Result<(Option<impl Layer<S>>, TelemetryGuard)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let Some(endpoint) = endpoint.filter(|e| !e.trim().is_empty()) else {
+        return Ok((None, TelemetryGuard { provider: None }));
+    };
+
+    let provider = otlp_provider(endpoint, service_name)?;
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name.to_string()));
+    Ok((Some(layer), TelemetryGuard { provider: Some(provider) }))
+}
+
+/// Install the global subscriber: console logs, plus OTLP span export when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` is set
+pub fn init(service_name: &str) -> Result<TelemetryGuard> {
+    let endpoint = std::env::var(OTLP_ENDPOINT_ENV).ok();
+    let (otel, guard) = otel_layer(endpoint.as_deref(), service_name)?;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::builder()
+            .with_default_directive(LevelFilter::INFO.into())
+            .from_env_lossy())
+        .with(tracing_subscriber::fmt::layer())
+        .with(otel)
+        .try_init()
+        .context("Failed to install tracing subscriber")?;
+
+    if let Some(endpoint) = endpoint.filter(|e| !e.trim().is_empty()) {
+        tracing::info!("📡 Exporting traces to {}", endpoint);
+    }
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_endpoint_is_noop() {
+        let (layer, guard) = otel_layer::<tracing_subscriber::Registry>(None, "test").unwrap();
+        assert!(layer.is_none());
+        assert!(guard.provider.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bogus_endpoint_does_not_crash() {
+        let (layer, guard) =
+            otel_layer::<tracing_subscriber::Registry>(Some("http://127.0.0.1:1"), "test").unwrap();
+        assert!(layer.is_some());
+
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("trade", pair = "SOL/USDC");
+            let _entered = span.enter();
+            tracing::info!("span with nowhere to go");
+        });
+
+        // Export fails against the dead collector; shutdown just reports it
+        drop(guard);
+    }
+}
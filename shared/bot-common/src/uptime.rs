@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// When the bot started. Serializes as `started_at` and `uptime_secs`, the
+/// latter worked out at serialization time, so a status snapshot flattening
+/// it in is always current.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uptime {
+    started_at: DateTime<Utc>,
+}
+
+impl Uptime {
+    pub fn since(started_at: DateTime<Utc>) -> Self {
+        Self { started_at }
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn uptime_secs(&self) -> u64 {
+        Utc::now().signed_duration_since(self.started_at).num_seconds().max(0) as u64
+    }
+}
+
+/// Starting now
+impl Default for Uptime {
+    fn default() -> Self {
+        Self::since(Utc::now())
+    }
+}
+
+impl Serialize for Uptime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Uptime", 2)?;
+        state.serialize_field("started_at", &self.started_at)?;
+        state.serialize_field("uptime_secs", &self.uptime_secs())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Status {
+        last_slot: u64,
+        #[serde(flatten)]
+        uptime: Uptime,
+    }
+
+    #[test]
+    fn test_flattens_into_status() {
+        let started_at = Utc::now() - chrono::Duration::seconds(90);
+        let status = Status { last_slot: 7, uptime: Uptime::since(started_at) };
+
+        let body = serde_json::to_value(&status).unwrap();
+        assert_eq!(body["last_slot"], 7);
+        assert_eq!(body["started_at"], serde_json::to_value(started_at).unwrap());
+        assert!(body["uptime_secs"].as_u64().unwrap() >= 90);
+    }
+}
@@ -0,0 +1,1116 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+pub mod rate_limiter;
+
+pub use rate_limiter::RateLimiter;
+
+/// `max_size_for_impact` starts at `up_to` halved this many times
+const IMPACT_PROBE_DOUBLINGS: u32 = 5;
+/// Quotes spent narrowing down between the last size within the cap and the first over it
+const IMPACT_PROBE_BISECTIONS: u32 = 3;
+/// Price impact doesn't depend on the slippage tolerance; probes just need a valid one
+const IMPACT_PROBE_SLIPPAGE_BPS: u16 = 50;
+
+/// Why a Jupiter request failed, so callers can tell a rate limit from a bad
+/// response. Converts into `anyhow::Error` for call sites that don't care.
+#[derive(Debug, thiserror::Error)]
+pub enum JupiterError {
+    /// Still 429 after the retry policy ran out
+    #[error("Jupiter rate limited the request: {0}")]
+    RateLimited(String),
+    /// Any other non-success status, with the response body
+    #[error("Jupiter API error: {0} - {1}")]
+    HttpStatus(u16, String),
+    /// The response body wasn't what the endpoint documents
+    #[error("Failed to parse Jupiter {0}")]
+    Parse(String),
+    /// The request never got a response (connection, DNS, timeout)
+    #[error("Jupiter request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    /// 404, or a price missing from the response
+    #[error("Not found on Jupiter: {0}")]
+    NotFound(String),
+}
+
+impl JupiterError {
+    /// Whether the same request may succeed later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::RateLimited(_) | Self::Network(_) => true,
+            Self::HttpStatus(status, _) => *status >= 500,
+            Self::Parse(_) | Self::NotFound(_) => false,
+        }
+    }
+
+    /// Error for a non-success `response`, by status
+    async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(text),
+            StatusCode::NOT_FOUND => Self::NotFound(text),
+            _ => Self::HttpStatus(status.as_u16(), text),
+        }
+    }
+}
+
+pub type JupiterResult<T> = std::result::Result<T, JupiterError>;
+
+/// Which Jupiter Price API `get_price` talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceApiVersion {
+    /// Legacy `price.jup.ag/v4`
+    V4,
+    /// `api.jup.ag/price/v2`
+    V2,
+}
+
+impl FromStr for PriceApiVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "v4" => Ok(Self::V4),
+            "v2" => Ok(Self::V2),
+            other => anyhow::bail!("Unknown Jupiter price API version: {}. Options: v4, v2", other),
+        }
+    }
+}
+
+impl PriceApiVersion {
+    fn default_url(&self) -> &'static str {
+        match self {
+            Self::V4 => "https://price.jup.ag/v4",
+            Self::V2 => "https://api.jup.ag/price/v2",
+        }
+    }
+}
+
+/// Jupiter Price API v4 response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterPriceResponse {
+    pub data: HashMap<String, TokenPrice>,
+    #[serde(rename = "timeTaken")]
+    pub time_taken: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenPrice {
+    pub id: String,
+    #[serde(rename = "mintSymbol")]
+    pub mint_symbol: Option<String>,
+    #[serde(rename = "vsToken")]
+    pub vs_token: String,
+    #[serde(rename = "vsTokenSymbol")]
+    pub vs_token_symbol: String,
+    pub price: f64,
+}
+
+/// Jupiter Price API v2 response. Mints without a price map to `null`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterPriceV2Response {
+    pub data: HashMap<String, Option<TokenPriceV2>>,
+    #[serde(rename = "timeTaken")]
+    pub time_taken: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenPriceV2 {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub price_type: Option<String>,
+    /// Decimal string, e.g. `"132.280970000"`
+    pub price: String,
+}
+
+impl JupiterPriceV2Response {
+    /// Mint → price; unpriced (`null`) or unparseable entries are left out
+    pub fn prices(&self) -> HashMap<String, f64> {
+        self.data
+            .iter()
+            .filter_map(|(mint, token)| Some((mint.clone(), token.as_ref()?.price.parse().ok()?)))
+            .collect()
+    }
+}
+
+/// Jupiter Quote API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterQuoteResponse {
+    #[serde(rename = "inputMint")]
+    pub input_mint: String,
+    #[serde(rename = "inAmount")]
+    pub in_amount: String,
+    #[serde(rename = "outputMint")]
+    pub output_mint: String,
+    #[serde(rename = "outAmount")]
+    pub out_amount: String,
+    #[serde(rename = "otherAmountThreshold")]
+    pub other_amount_threshold: String,
+    #[serde(rename = "swapMode")]
+    pub swap_mode: String,
+    #[serde(rename = "slippageBps")]
+    pub slippage_bps: u16,
+    #[serde(rename = "platformFee")]
+    pub platform_fee: Option<PlatformFee>,
+    /// A fraction despite the name: `"0.0125"` is 1.25%
+    #[serde(rename = "priceImpactPct")]
+    pub price_impact_pct: String,
+    #[serde(rename = "routePlan")]
+    pub route_plan: Vec<RoutePlan>,
+}
+
+impl JupiterQuoteResponse {
+    /// `price_impact_pct` in percent
+    pub fn price_impact_percent(&self) -> Result<f64, std::num::ParseFloatError> {
+        Ok(self.price_impact_pct.parse::<f64>()? * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformFee {
+    pub amount: String,
+    #[serde(rename = "feeBps")]
+    pub fee_bps: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutePlan {
+    #[serde(rename = "swapInfo")]
+    pub swap_info: SwapInfo,
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapInfo {
+    #[serde(rename = "ammKey")]
+    pub amm_key: String,
+    pub label: Option<String>,
+    #[serde(rename = "inputMint")]
+    pub input_mint: String,
+    #[serde(rename = "outputMint")]
+    pub output_mint: String,
+    #[serde(rename = "inAmount")]
+    pub in_amount: String,
+    #[serde(rename = "outAmount")]
+    pub out_amount: String,
+    #[serde(rename = "feeAmount")]
+    pub fee_amount: String,
+    #[serde(rename = "feeMint")]
+    pub fee_mint: String,
+}
+
+/// Jupiter Swap API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterSwapResponse {
+    #[serde(rename = "swapTransaction")]
+    pub swap_transaction: String,
+    #[serde(rename = "lastValidBlockHeight")]
+    pub last_valid_block_height: u64,
+}
+
+pub struct JupiterClient {
+    client: Client,
+    base_url: String,
+    price_api_url: String,
+    price_api_version: PriceApiVersion,
+    retry_policy: RetryPolicy,
+    route_options: RouteOptions,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+/// Route constraints sent with every quote. The defaults leave routing to Jupiter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteOptions {
+    /// Single-hop routes only (`onlyDirectRoutes`)
+    pub only_direct_routes: bool,
+    /// Most accounts a route may touch, keeping the transaction small (`maxAccounts`)
+    pub max_accounts: Option<u32>,
+    /// AMM labels to route around, e.g. `Raydium CLMM` (`excludeDexes`)
+    pub excluded_dexes: Vec<String>,
+}
+
+impl RouteOptions {
+    /// Quote query params for the constraints that are set
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if self.only_direct_routes {
+            params.push(("onlyDirectRoutes", "true".to_string()));
+        }
+        if let Some(max_accounts) = self.max_accounts {
+            params.push(("maxAccounts", max_accounts.to_string()));
+        }
+        if !self.excluded_dexes.is_empty() {
+            params.push(("excludeDexes", self.excluded_dexes.join(",")));
+        }
+        params
+    }
+}
+
+/// Retries for rate limits (429), server errors (5xx) and timeouts. Other
+/// failures, including 4xx client errors, are returned straight away.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total tries per request, including the first
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles on each further retry
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay_before_retry(&self, attempt: u32) -> Duration {
+        self.base_delay * 2u32.saturating_pow(attempt - 1)
+    }
+}
+
+/// Builds a `JupiterClient` against custom endpoints (self-hosted or paid
+/// Jupiter, or a local mock); unset values use the public API defaults
+#[derive(Debug, Clone)]
+pub struct JupiterClientBuilder {
+    quote_url: String,
+    price_url: Option<String>,
+    price_api_version: PriceApiVersion,
+    timeout: Duration,
+    retry_policy: RetryPolicy,
+    route_options: RouteOptions,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl Default for JupiterClientBuilder {
+    fn default() -> Self {
+        Self {
+            quote_url: "https://quote-api.jup.ag/v6".to_string(),
+            price_url: None,
+            price_api_version: PriceApiVersion::V4,
+            timeout: Duration::from_secs(30),
+            retry_policy: RetryPolicy::default(),
+            route_options: RouteOptions::default(),
+            rate_limiter: None,
+        }
+    }
+}
+
+impl JupiterClientBuilder {
+    /// Base URL of the quote/swap API (`/quote` and `/swap` are appended)
+    pub fn quote_url(mut self, url: &str) -> Self {
+        self.quote_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Base URL of the price API; defaults to the public URL for the version
+    pub fn price_url(mut self, url: &str) -> Self {
+        self.price_url = Some(url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn price_api_version(mut self, version: PriceApiVersion) -> Self {
+        self.price_api_version = version;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Up to `max_attempts` tries per request (1 disables retries)
+    pub fn retry_policy(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.retry_policy = RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+        };
+        self
+    }
+
+    /// Hop, account and DEX constraints for every quote
+    pub fn route_options(mut self, options: RouteOptions) -> Self {
+        self.route_options = options;
+        self
+    }
+
+    /// Space every request, retries included, through `limiter`; clients
+    /// sharing one limiter share its budget
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    pub fn build(self) -> JupiterClient {
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        JupiterClient {
+            client,
+            base_url: self.quote_url,
+            price_api_url: self
+                .price_url
+                .unwrap_or_else(|| self.price_api_version.default_url().to_string()),
+            price_api_version: self.price_api_version,
+            retry_policy: self.retry_policy,
+            route_options: self.route_options,
+            rate_limiter: self.rate_limiter,
+        }
+    }
+}
+
+impl JupiterClient {
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> JupiterClientBuilder {
+        JupiterClientBuilder::default()
+    }
+
+    /// Client for a Jupiter-compatible quote/swap API (e.g. a self-hosted router)
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self::builder().quote_url(base_url).build()
+    }
+
+    /// Public Jupiter client reading prices from the given Price API version
+    pub fn with_price_api_version(version: PriceApiVersion) -> Self {
+        Self::builder().price_api_version(version).build()
+    }
+
+    /// Public Jupiter client retrying 429/5xx/timeouts up to `max_attempts` times
+    pub fn with_retry_policy(max_attempts: u32, base_delay: Duration) -> Self {
+        Self::builder().retry_policy(max_attempts, base_delay).build()
+    }
+
+    /// Send the request built by `request`, retrying transient failures per the
+    /// retry policy. The final response is returned whatever its status.
+    async fn send_with_retry(&self, request: impl Fn() -> RequestBuilder) -> reqwest::Result<Response> {
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire().await;
+            }
+            let retryable = match request().send().await {
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error() =>
+                {
+                    if attempt >= self.retry_policy.max_attempts {
+                        return Ok(response);
+                    }
+                    response.status().to_string()
+                }
+                Err(e) if e.is_timeout() && attempt < self.retry_policy.max_attempts => e.to_string(),
+                result => return result,
+            };
+
+            let delay = self.retry_policy.delay_before_retry(attempt);
+            warn!(
+                "Jupiter request failed ({}), retrying in {:?} (attempt {}/{})",
+                retryable,
+                delay,
+                attempt + 1,
+                self.retry_policy.max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Get current price for a token pair using Jupiter Price API
+    /// Returns price as output_token per input_token
+    pub async fn get_price(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+    ) -> JupiterResult<f64> {
+        let price = self
+            .get_prices(&[input_mint], output_mint)
+            .await?
+            .remove(input_mint)
+            .ok_or_else(|| JupiterError::NotFound(format!("no price for {} in response", input_mint)))?;
+
+        debug!("Jupiter price: {} {} per {}", price, output_mint, input_mint);
+
+        Ok(price)
+    }
+
+    /// Prices of several mints in `vs_token` from a single request. Mints the
+    /// API has no price for are missing from the map.
+    pub async fn get_prices(&self, mints: &[&str], vs_token: &str) -> JupiterResult<HashMap<String, f64>> {
+        let ids = mints.join(",");
+        let url = match self.price_api_version {
+            PriceApiVersion::V4 => format!(
+                "{}/price?ids={}&vsToken={}",
+                self.price_api_url, ids, vs_token
+            ),
+            PriceApiVersion::V2 => format!(
+                "{}?ids={}&vsToken={}",
+                self.price_api_url, ids, vs_token
+            ),
+        };
+
+        debug!("Fetching price from Jupiter: {}", url);
+
+        let response = self.send_with_retry(|| self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(JupiterError::from_response(response).await);
+        }
+
+        let prices = match self.price_api_version {
+            PriceApiVersion::V4 => {
+                let price_response: JupiterPriceResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| JupiterError::Parse(format!("price response: {}", e)))?;
+                price_response
+                    .data
+                    .into_iter()
+                    .map(|(mint, token)| (mint, token.price))
+                    .collect()
+            }
+            PriceApiVersion::V2 => {
+                let price_response: JupiterPriceV2Response = response
+                    .json()
+                    .await
+                    .map_err(|e| JupiterError::Parse(format!("price v2 response: {}", e)))?;
+                price_response.prices()
+            }
+        };
+
+        Ok(prices)
+    }
+
+    /// Get a quote for swapping tokens, within the client's route constraints
+    pub async fn get_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> JupiterResult<JupiterQuoteResponse> {
+        let url = format!(
+            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
+            self.base_url, input_mint, output_mint, amount, slippage_bps
+        );
+
+        let route_params = self.route_options.query_params();
+        debug!("Fetching quote from Jupiter: {} {:?}", url, route_params);
+
+        let response = self.send_with_retry(|| self.client.get(&url).query(&route_params)).await?;
+        if !response.status().is_success() {
+            return Err(JupiterError::from_response(response).await);
+        }
+
+        let quote: JupiterQuoteResponse = response
+            .json()
+            .await
+            .map_err(|e| JupiterError::Parse(format!("quote response: {}", e)))?;
+
+        info!(
+            "Jupiter quote: {} {} -> {} {} (impact: {:.4}%)",
+            amount,
+            input_mint.chars().take(8).collect::<String>(),
+            quote.out_amount,
+            output_mint.chars().take(8).collect::<String>(),
+            quote.price_impact_percent().unwrap_or(f64::NAN)
+        );
+
+        Ok(quote)
+    }
+
+    /// Largest amount of `input_mint`, up to `up_to`, whose quote's price impact
+    /// stays within `max_impact_pct` percent.
+    /// Probes from `up_to / 32`, doubling until the cap is crossed, then bisects
+    /// the last step, up to 9 quotes in all, each through the client's rate
+    /// limiter. None when even the smallest probe is over the cap.
+    pub async fn max_size_for_impact(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        max_impact_pct: f64,
+        up_to: u64,
+    ) -> JupiterResult<Option<u64>> {
+        let within_cap = |amount| async move {
+            let impact = self.price_impact_pct(input_mint, output_mint, amount).await?;
+            Ok::<_, JupiterError>(impact <= max_impact_pct)
+        };
+
+        // Largest size known to be within the cap, smallest known to be over it
+        let mut within = None;
+        let mut size = (up_to >> IMPACT_PROBE_DOUBLINGS).max(1);
+        let beyond = loop {
+            if !within_cap(size).await? {
+                break size;
+            }
+            within = Some(size);
+            if size >= up_to {
+                return Ok(within);
+            }
+            size = size.saturating_mul(2).min(up_to);
+        };
+        let Some(mut low) = within else {
+            return Ok(None);
+        };
+
+        let mut high = beyond;
+        for _ in 0..IMPACT_PROBE_BISECTIONS {
+            let mid = low + (high - low) / 2;
+            if mid == low {
+                break;
+            }
+            if within_cap(mid).await? {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        debug!("Largest size within {}% price impact: {}", max_impact_pct, low);
+        Ok(Some(low))
+    }
+
+    /// Price impact, in percent, of a quote for `amount`
+    async fn price_impact_pct(&self, input_mint: &str, output_mint: &str, amount: u64) -> JupiterResult<f64> {
+        let quote = self.get_quote(input_mint, output_mint, amount, IMPACT_PROBE_SLIPPAGE_BPS).await?;
+        quote
+            .price_impact_percent()
+            .map_err(|e| JupiterError::Parse(format!("price impact {:?}: {}", quote.price_impact_pct, e)))
+    }
+
+    /// Get swap transaction for a quote. The compute unit price is fixed at
+    /// `priority_fee_micro_lamports` when set, otherwise left to Jupiter ("auto").
+    pub async fn get_swap_transaction(
+        &self,
+        quote: &JupiterQuoteResponse,
+        user_public_key: &str,
+        wrap_unwrap_sol: bool,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> JupiterResult<JupiterSwapResponse> {
+        let url = format!("{}/swap", self.base_url);
+
+        let compute_unit_price = match priority_fee_micro_lamports {
+            Some(micro_lamports) => serde_json::json!(micro_lamports),
+            None => serde_json::json!("auto"),
+        };
+        let payload = serde_json::json!({
+            "quoteResponse": quote,
+            "userPublicKey": user_public_key,
+            "wrapAndUnwrapSol": wrap_unwrap_sol,
+            "computeUnitPriceMicroLamports": compute_unit_price,
+        });
+
+        debug!("Requesting swap transaction from Jupiter");
+
+        let response = self.send_with_retry(|| self.client.post(&url).json(&payload)).await?;
+        if !response.status().is_success() {
+            return Err(JupiterError::from_response(response).await);
+        }
+
+        let swap_response: JupiterSwapResponse = response
+            .json()
+            .await
+            .map_err(|e| JupiterError::Parse(format!("swap response: {}", e)))?;
+
+        Ok(swap_response)
+    }
+
+    /// Calculate price from a quote (output amount / input amount)
+    pub fn calculate_price_from_quote(
+        quote: &JupiterQuoteResponse,
+        input_decimals: u8,
+        output_decimals: u8,
+    ) -> f64 {
+        let in_amount: u64 = quote.in_amount.parse().unwrap_or(0);
+        let out_amount: u64 = quote.out_amount.parse().unwrap_or(0);
+
+        if in_amount == 0 {
+            return 0.0;
+        }
+
+        // Normalize to human-readable amounts
+        let in_amount_normalized = in_amount as f64 / 10_f64.powi(input_decimals as i32);
+        let out_amount_normalized = out_amount as f64 / 10_f64.powi(output_decimals as i32);
+
+        // Price = output / input
+        out_amount_normalized / in_amount_normalized
+    }
+}
+
+/// Errors on a quote that shouldn't be executed: one with no route, or whose
+/// price impact is above `max_price_impact_pct` percent
+pub fn check_quote_route(quote: &JupiterQuoteResponse, max_price_impact_pct: Option<f64>) -> Result<()> {
+    if quote.route_plan.is_empty() {
+        anyhow::bail!("Quote has an empty route plan");
+    }
+    if let Some(max_impact_pct) = max_price_impact_pct {
+        let impact = quote
+            .price_impact_percent()
+            .with_context(|| format!("Quote has an unreadable price impact {:?}", quote.price_impact_pct))?;
+        if impact > max_impact_pct {
+            anyhow::bail!(
+                "Quote price impact {}% is above MAX_PRICE_IMPACT_PCT {}% (thin liquidity on {})",
+                impact,
+                max_impact_pct,
+                route_labels(quote)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// AMMs a quote routes through, for logs and errors
+fn route_labels(quote: &JupiterQuoteResponse) -> String {
+    quote
+        .route_plan
+        .iter()
+        .map(|step| step.swap_info.label.as_deref().unwrap_or(&step.swap_info.amm_key))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Worst price a swap may fill at, in quote per base: the most a buy pays or
+/// the least a sell receives
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceLimit {
+    pub is_buy: bool,
+    pub price: f64,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+}
+
+impl PriceLimit {
+    /// Quote per base that `quote` fills at: a buy swaps quote in, a sell base in
+    pub fn quote_price(&self, quote: &JupiterQuoteResponse) -> f64 {
+        if self.is_buy {
+            // 1/0 is infinitely expensive, which no buy limit accepts
+            1.0 / JupiterClient::calculate_price_from_quote(quote, self.quote_decimals, self.base_decimals)
+        } else {
+            JupiterClient::calculate_price_from_quote(quote, self.base_decimals, self.quote_decimals)
+        }
+    }
+
+    /// Errors instead of letting a quote worse than the limit be executed
+    pub fn check(&self, quote: &JupiterQuoteResponse) -> Result<()> {
+        let price = self.quote_price(quote);
+        if self.is_buy && price > self.price {
+            anyhow::bail!("Quote price {:.6} is above the buy limit {:.6}", price, self.price);
+        }
+        if !self.is_buy && price < self.price {
+            anyhow::bail!("Quote price {:.6} is below the sell limit {:.6}", price, self.price);
+        }
+        Ok(())
+    }
+}
+
+impl Default for JupiterClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    /// Requests a mock server received: (path and query, body)
+    type Recorded = Arc<Mutex<Vec<(String, String)>>>;
+
+    /// Local HTTP server answering requests with `responses` in order (the last
+    /// one repeats) and recording each request
+    async fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, Recorded) {
+        let requests: Recorded = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = axum::Router::new().fallback(move |uri: axum::http::Uri, request_body: String| {
+            let recorded = recorded.clone();
+            let responses = responses.clone();
+            async move {
+                let mut recorded = recorded.lock().unwrap();
+                let (status, body) = responses[recorded.len().min(responses.len() - 1)];
+                recorded.push((uri.to_string(), request_body));
+                (axum::http::StatusCode::from_u16(status).unwrap(), body)
+            }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn test_get_price() {
+        let client = JupiterClient::new();
+        
+        // SOL/USDC price
+        let sol_mint = "So11111111111111111111111111111111111111112";
+        let usdc_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        
+        let result = client.get_price(sol_mint, usdc_mint).await;
+        assert!(result.is_ok());
+        
+        let price = result.unwrap();
+        assert!(price > 0.0);
+        println!("SOL/USDC price: ${}", price);
+    }
+
+    #[tokio::test]
+    async fn test_get_quote() {
+        let client = JupiterClient::new();
+        
+        // Quote for 0.1 SOL -> USDC
+        let sol_mint = "So11111111111111111111111111111111111111112";
+        let usdc_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let amount = 100_000_000; // 0.1 SOL (9 decimals)
+        
+        let result = client.get_quote(sol_mint, usdc_mint, amount, 50).await;
+        assert!(result.is_ok());
+        
+        let quote = result.unwrap();
+        println!("Quote: {} SOL -> {} USDC", amount, quote.out_amount);
+    }
+
+    #[test]
+    fn test_parse_price_v2_response() {
+        // Captured from https://api.jup.ag/price/v2?ids=So11...112,Unknown111&vsToken=EPjF...Dt1v
+        let body = r#"{
+            "data": {
+                "So11111111111111111111111111111111111111112": {
+                    "id": "So11111111111111111111111111111111111111112",
+                    "type": "derivedPrice",
+                    "price": "132.280970000"
+                },
+                "Unknown11111111111111111111111111111111111": null
+            },
+            "timeTaken": 0.003186454
+        }"#;
+
+        let prices = serde_json::from_str::<JupiterPriceV2Response>(body).unwrap().prices();
+        let price = prices["So11111111111111111111111111111111111111112"];
+        assert!((price - 132.28097).abs() < 1e-9);
+        assert!(!prices.contains_key("Unknown11111111111111111111111111111111111"));
+        assert!(!prices.contains_key("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"));
+    }
+
+    #[test]
+    fn test_parse_multi_token_price_response() {
+        // Recorded from https://api.jup.ag/price/v2?ids=So11...112,JUPy...vCN,DezX...B263,Missing111&vsToken=EPjF...Dt1v
+        let body = r#"{
+            "data": {
+                "So11111111111111111111111111111111111111112": {
+                    "id": "So11111111111111111111111111111111111111112",
+                    "type": "derivedPrice",
+                    "price": "146.512380000"
+                },
+                "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN": {
+                    "id": "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+                    "type": "derivedPrice",
+                    "price": "0.8123450000"
+                },
+                "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263": {
+                    "id": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+                    "type": "derivedPrice",
+                    "price": "0.0000231200"
+                },
+                "Missing1111111111111111111111111111111111111": null
+            },
+            "timeTaken": 0.004123
+        }"#;
+
+        let prices = serde_json::from_str::<JupiterPriceV2Response>(body).unwrap().prices();
+        assert_eq!(prices.len(), 3);
+        assert!((prices["JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN"] - 0.812345).abs() < 1e-12);
+        assert!((prices["DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"] - 0.00002312).abs() < 1e-12);
+        assert!(!prices.contains_key("Missing1111111111111111111111111111111111111"));
+    }
+
+    #[tokio::test]
+    async fn test_builder_routes_to_custom_urls() {
+        let body = r#"{"data":{"So11111111111111111111111111111111111111112":{"id":"So11111111111111111111111111111111111111112","type":"derivedPrice","price":"150.5"}},"timeTaken":0.001}"#;
+        let (url, requests) = mock_server(vec![(200, body)]).await;
+        let client = JupiterClient::builder()
+            .quote_url(&format!("{}/quote-api/", url))
+            .price_url(&format!("{}/price/v2", url))
+            .price_api_version(PriceApiVersion::V2)
+            .timeout(Duration::from_secs(5))
+            .build();
+
+        assert_eq!(client.get_price(SOL_MINT, USDC_MINT).await.unwrap(), 150.5);
+        // The mock answers every path with the price body; only the URL matters here
+        assert!(client.get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.is_err());
+
+        let paths: Vec<String> = requests.lock().unwrap().iter().map(|(uri, _)| uri.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                format!("/price/v2?ids={}&vsToken={}", SOL_MINT, USDC_MINT),
+                format!(
+                    "/quote-api/quote?inputMint={}&outputMint={}&amount=1000&slippageBps=50",
+                    SOL_MINT, USDC_MINT
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_then_succeeds() {
+        let body = r#"{"data":{"So11111111111111111111111111111111111111112":{"id":"So11111111111111111111111111111111111111112","mintSymbol":"SOL","vsToken":"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v","vsTokenSymbol":"USDC","price":150.5}},"timeTaken":0.001}"#;
+        let (url, requests) = mock_server(vec![(429, "rate limited"), (429, "rate limited"), (200, body)]).await;
+        let client = JupiterClient::builder()
+            .price_url(&url)
+            .retry_policy(3, Duration::from_millis(10))
+            .build();
+
+        assert_eq!(client.get_price(SOL_MINT, USDC_MINT).await.unwrap(), 150.5);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // Client errors are not retried
+        let (url, requests) = mock_server(vec![(400, "bad request"), (200, body)]).await;
+        let client = JupiterClient::builder()
+            .price_url(&url)
+            .retry_policy(3, Duration::from_millis(10))
+            .build();
+
+        assert!(client.get_price(SOL_MINT, USDC_MINT).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_errors_map_to_variants() {
+        let client = |url: &str| {
+            JupiterClient::builder()
+                .quote_url(url)
+                .price_url(url)
+                .retry_policy(2, Duration::from_millis(10))
+                .build()
+        };
+
+        let (url, _) = mock_server(vec![(429, "slow down")]).await;
+        let err = client(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap_err();
+        assert!(matches!(&err, JupiterError::RateLimited(body) if body == "slow down"));
+        assert!(err.is_transient());
+
+        let (url, _) = mock_server(vec![(503, "maintenance")]).await;
+        let err = client(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap_err();
+        assert!(matches!(err, JupiterError::HttpStatus(503, _)));
+        assert!(err.is_transient());
+
+        let (url, _) = mock_server(vec![(400, "invalid mint")]).await;
+        let err = client(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap_err();
+        assert!(matches!(err, JupiterError::HttpStatus(400, _)));
+        assert!(!err.is_transient());
+
+        let (url, _) = mock_server(vec![(404, "")]).await;
+        let err = client(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap_err();
+        assert!(matches!(err, JupiterError::NotFound(_)));
+
+        let (url, _) = mock_server(vec![(200, "<html>not json</html>")]).await;
+        let err = client(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap_err();
+        assert!(matches!(err, JupiterError::Parse(_)));
+
+        // A well-formed price response without the requested mint
+        let (url, _) = mock_server(vec![(200, r#"{"data":{},"timeTaken":0.001}"#)]).await;
+        let err = client(&url).get_price(SOL_MINT, USDC_MINT).await.unwrap_err();
+        assert!(matches!(err, JupiterError::NotFound(_)));
+
+        // Nothing listening
+        let err = client("http://127.0.0.1:1").get_price(SOL_MINT, USDC_MINT).await.unwrap_err();
+        assert!(matches!(err, JupiterError::Network(_)));
+
+        // Existing anyhow call sites keep the message
+        let err: anyhow::Error = JupiterError::HttpStatus(400, "invalid mint".to_string()).into();
+        assert_eq!(err.to_string(), "Jupiter API error: 400 - invalid mint");
+    }
+
+    #[tokio::test]
+    async fn test_swap_request_carries_priority_fee() {
+        let body = r#"{"swapTransaction":"AA==","lastValidBlockHeight":1}"#;
+        let (url, requests) = mock_server(vec![(200, body)]).await;
+        let client = JupiterClient::with_base_url(&url);
+        let quote = JupiterQuoteResponse {
+            input_mint: SOL_MINT.to_string(),
+            in_amount: "1000".to_string(),
+            output_mint: USDC_MINT.to_string(),
+            out_amount: "150".to_string(),
+            other_amount_threshold: "149".to_string(),
+            swap_mode: "ExactIn".to_string(),
+            slippage_bps: 50,
+            platform_fee: None,
+            price_impact_pct: "0".to_string(),
+            route_plan: Vec::new(),
+        };
+
+        client.get_swap_transaction(&quote, "user", true, Some(50_000)).await.unwrap();
+        client.get_swap_transaction(&quote, "user", true, None).await.unwrap();
+
+        let payloads: Vec<serde_json::Value> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, body)| serde_json::from_str(body).unwrap())
+            .collect();
+        assert_eq!(payloads[0]["computeUnitPriceMicroLamports"], serde_json::json!(50_000));
+        assert_eq!(payloads[1]["computeUnitPriceMicroLamports"], serde_json::json!("auto"));
+    }
+
+    #[tokio::test]
+    async fn test_quote_carries_route_constraints() {
+        let body = r#"{"inputMint":"So11111111111111111111111111111111111111112","inAmount":"1000","outputMint":"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v","outAmount":"150","otherAmountThreshold":"149","swapMode":"ExactIn","slippageBps":50,"platformFee":null,"priceImpactPct":"0","routePlan":[]}"#;
+        let (url, requests) = mock_server(vec![(200, body)]).await;
+
+        let constrained = JupiterClient::builder()
+            .quote_url(&url)
+            .route_options(RouteOptions {
+                only_direct_routes: true,
+                max_accounts: Some(20),
+                excluded_dexes: vec!["Raydium CLMM".to_string(), "Whirlpool".to_string()],
+            })
+            .build();
+        constrained.get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap();
+        JupiterClient::with_base_url(&url).get_quote(SOL_MINT, USDC_MINT, 1_000, 50).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let query = &requests[0].0;
+        assert!(query.contains("&amount=1000&slippageBps=50"), "{}", query);
+        assert!(query.contains("onlyDirectRoutes=true"), "{}", query);
+        assert!(query.contains("maxAccounts=20"), "{}", query);
+        assert!(query.contains("excludeDexes=Raydium+CLMM%2CWhirlpool"), "{}", query);
+
+        // Unconstrained quotes are unchanged
+        assert!(requests[1].0.ends_with("&amount=1000&slippageBps=50"), "{}", requests[1].0);
+    }
+
+    fn quote(input_mint: &str, in_amount: u64, output_mint: &str, out_amount: u64) -> JupiterQuoteResponse {
+        JupiterQuoteResponse {
+            input_mint: input_mint.to_string(),
+            in_amount: in_amount.to_string(),
+            output_mint: output_mint.to_string(),
+            out_amount: out_amount.to_string(),
+            other_amount_threshold: out_amount.to_string(),
+            swap_mode: "ExactIn".to_string(),
+            slippage_bps: 50,
+            platform_fee: None,
+            price_impact_pct: "0".to_string(),
+            route_plan: vec![RoutePlan {
+                swap_info: SwapInfo {
+                    amm_key: "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2".to_string(),
+                    label: Some("Raydium".to_string()),
+                    input_mint: input_mint.to_string(),
+                    output_mint: output_mint.to_string(),
+                    in_amount: in_amount.to_string(),
+                    out_amount: out_amount.to_string(),
+                    fee_amount: "0".to_string(),
+                    fee_mint: input_mint.to_string(),
+                },
+                percent: 100,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_quote_route_sanity() {
+        let mut normal = quote(SOL_MINT, 1_000_000_000, USDC_MINT, 150_000_000);
+        // Jupiter reports a fraction: 0.12%
+        normal.price_impact_pct = "0.0012".to_string();
+        assert!(check_quote_route(&normal, Some(1.0)).is_ok());
+        assert!(check_quote_route(&normal, None).is_ok());
+
+        let mut thin = normal.clone();
+        thin.price_impact_pct = "0.375".to_string();
+        let err = check_quote_route(&thin, Some(1.0)).unwrap_err();
+        assert!(err.to_string().contains("37.5% is above MAX_PRICE_IMPACT_PCT 1%"), "{}", err);
+        assert!(err.to_string().contains("Raydium"), "{}", err);
+
+        let mut unrouted = normal.clone();
+        unrouted.route_plan.clear();
+        let err = check_quote_route(&unrouted, None).unwrap_err();
+        assert!(err.to_string().contains("empty route plan"), "{}", err);
+    }
+
+    #[test]
+    fn test_buy_limit_rejects_quotes_above_it() {
+        let limit = PriceLimit { is_buy: true, price: 150.0, base_decimals: 9, quote_decimals: 6 };
+
+        // $150 for 1.01 SOL is ~$148.5 per SOL
+        let cheap = quote(USDC_MINT, 150_000_000, SOL_MINT, 1_010_000_000);
+        assert!((limit.quote_price(&cheap) - 148.514851).abs() < 1e-6);
+        assert!(limit.check(&cheap).is_ok());
+
+        // ...and 0.99 SOL is ~$151.5
+        let dear = quote(USDC_MINT, 150_000_000, SOL_MINT, 990_000_000);
+        let err = limit.check(&dear).unwrap_err();
+        assert!(err.to_string().contains("above the buy limit"), "{}", err);
+
+        // An empty quote never satisfies a buy
+        assert!(limit.check(&quote(USDC_MINT, 150_000_000, SOL_MINT, 0)).is_err());
+    }
+
+    #[test]
+    fn test_sell_limit_rejects_quotes_below_it() {
+        let limit = PriceLimit { is_buy: false, price: 150.0, base_decimals: 9, quote_decimals: 6 };
+
+        // 1 SOL for $151, then for $149
+        assert!(limit.check(&quote(SOL_MINT, 1_000_000_000, USDC_MINT, 151_000_000)).is_ok());
+        let err = limit.check(&quote(SOL_MINT, 1_000_000_000, USDC_MINT, 149_000_000)).unwrap_err();
+        assert!(err.to_string().contains("below the sell limit"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_max_size_for_impact() {
+        // Every SOL quoted adds 0.1% price impact
+        let requests: Recorded = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = axum::Router::new().route(
+            "/quote",
+            axum::routing::get(move |axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>| {
+                let recorded = recorded.clone();
+                async move {
+                    let amount: u64 = params["amount"].parse().unwrap();
+                    recorded.lock().unwrap().push((amount.to_string(), String::new()));
+                    let mut quote = serde_json::to_value(quote(SOL_MINT, amount, USDC_MINT, amount / 10)).unwrap();
+                    quote["priceImpactPct"] = (amount as f64 / 1e12).to_string().into();
+                    axum::Json(quote)
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let client = JupiterClient::with_base_url(&format!("http://{}", addr));
+
+        // A 0.5% cap allows 5 SOL out of the 8 requested: doubling from 0.25
+        // SOL crosses it at 8, then bisection lands on 5
+        let size = client.max_size_for_impact(SOL_MINT, USDC_MINT, 0.5, 8_000_000_000).await.unwrap();
+        assert_eq!(size, Some(5_000_000_000));
+        let probed: Vec<String> = requests.lock().unwrap().drain(..).map(|(amount, _)| amount).collect();
+        assert_eq!(
+            probed,
+            [250_000_000u64, 500_000_000, 1_000_000_000, 2_000_000_000, 4_000_000_000, 8_000_000_000,
+                6_000_000_000, 5_000_000_000, 5_500_000_000]
+                .map(|a| a.to_string())
+        );
+
+        // Within the cap, the requested size comes back as is
+        let size = client.max_size_for_impact(SOL_MINT, USDC_MINT, 0.5, 3_000_000_000).await.unwrap();
+        assert_eq!(size, Some(3_000_000_000));
+
+        // Nothing fits under a cap the smallest probe already breaks
+        let size = client.max_size_for_impact(SOL_MINT, USDC_MINT, 0.01, 8_000_000_000).await.unwrap();
+        assert_eq!(size, None);
+    }
+}
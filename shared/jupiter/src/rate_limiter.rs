@@ -0,0 +1,55 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Spaces outgoing API calls to at most `max_per_second`. Concurrent callers
+/// each reserve the next free slot, so parallel fetches stay within the limit.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_second: u32) -> Self {
+        let interval = if max_per_second == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / max_per_second
+        };
+
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until this caller's slot comes up
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next_slot.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spaces_calls() {
+        let limiter = RateLimiter::new(20); // 50ms apart
+        let started = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        // First call is immediate, the next two wait 50ms each
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -1,21 +1,27 @@
 use std::{
+    convert::Infallible,
     net::SocketAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     routing::{get, post},
     Json, Router,
 };
-use serde::Serialize;
-use tokio::sync::RwLock;
-use tracing::{error, info};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 mod stream;
@@ -24,6 +30,10 @@ mod stream;
 struct AppState {
     started: Arc<AtomicBool>,
     latest: Arc<RwLock<Option<LatestSlot>>>,
+    /// Every new slot, fanned out to `/stream` subscribers
+    slots: broadcast::Sender<LatestSlot>,
+    /// Latest transaction or account update touching `WATCH_PROGRAM_ID`
+    latest_tx: Arc<RwLock<Option<LatestTx>>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -34,6 +44,43 @@ struct LatestSlot {
     created_at_rfc3339: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum LatestTx {
+    Transaction {
+        slot: u64,
+        signature: String,
+        pre_token_balances: Vec<TokenBalance>,
+        post_token_balances: Vec<TokenBalance>,
+        created_at_rfc3339: Option<String>,
+    },
+    Account {
+        slot: u64,
+        pubkey: String,
+        owner: String,
+        lamports: u64,
+        /// Base64-encoded account data
+        data: String,
+        created_at_rfc3339: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TokenBalance {
+    account_index: u32,
+    mint: String,
+    owner: String,
+    /// Raw amount, as a string to keep u64 precision
+    amount: String,
+    decimals: u32,
+}
+
+/// Cap on how long a `/latest?since=` long poll may hold the connection
+const MAX_LONG_POLL: Duration = Duration::from_secs(30);
+
+/// Slots a slow `/stream` client may fall behind before it skips ahead
+const SLOT_CHANNEL_CAPACITY: usize = 64;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
@@ -47,16 +94,14 @@ async fn main() -> anyhow::Result<()> {
     let state = AppState {
         started: Arc::new(AtomicBool::new(false)),
         latest: Arc::new(RwLock::new(None)),
+        slots: broadcast::channel(SLOT_CHANNEL_CAPACITY).0,
+        latest_tx: Arc::new(RwLock::new(None)),
     };
 
     // Start on boot (so the first request already has stream warming up)
     ensure_stream_started(state.clone()).await;
 
-    let app = Router::new()
-        .route("/health", get(health))
-        .route("/start", post(start))
-        .route("/latest", get(latest))
-        .with_state(state);
+    let app = router(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     info!("listening on {}", addr);
@@ -67,6 +112,16 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/start", post(start))
+        .route("/latest", get(latest))
+        .route("/latest-tx", get(latest_tx))
+        .route("/stream", get(slot_stream))
+        .with_state(state)
+}
+
 async fn health() -> impl IntoResponse {
     (StatusCode::OK, "ok\n")
 }
@@ -76,8 +131,51 @@ async fn start(State(state): State<AppState>) -> impl IntoResponse {
     (StatusCode::OK, "started\n")
 }
 
-async fn latest(State(state): State<AppState>) -> impl IntoResponse {
-    let guard = state.latest.read().await;
+#[derive(Debug, Deserialize)]
+struct LatestQuery {
+    /// Long-poll: wait for a slot newer than this
+    since: Option<u64>,
+    /// Long-poll timeout, capped at MAX_LONG_POLL
+    wait_ms: Option<u64>,
+}
+
+async fn latest(State(state): State<AppState>, Query(query): Query<LatestQuery>) -> impl IntoResponse {
+    let Some(since) = query.since else {
+        let guard = state.latest.read().await;
+        return if let Some(v) = guard.clone() {
+            (StatusCode::OK, Json(v)).into_response()
+        } else {
+            (StatusCode::NOT_FOUND, "no data yet\n").into_response()
+        };
+    };
+
+    // Subscribe before reading `latest` so no slot slips in between
+    let mut rx = state.slots.subscribe();
+    if let Some(v) = state.latest.read().await.clone().filter(|v| v.slot > since) {
+        return (StatusCode::OK, Json(v)).into_response();
+    }
+
+    let wait = Duration::from_millis(query.wait_ms.unwrap_or(0)).min(MAX_LONG_POLL);
+    let newer = tokio::time::timeout(wait, async {
+        loop {
+            match rx.recv().await {
+                Ok(slot) if slot.slot > since => return Some(slot),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+    .await;
+
+    match newer {
+        Ok(Some(v)) => (StatusCode::OK, Json(v)).into_response(),
+        // Nothing newer in time: 204 rather than 404, which means "no long-poll support"
+        _ => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+async fn latest_tx(State(state): State<AppState>) -> impl IntoResponse {
+    let guard = state.latest_tx.read().await;
     if let Some(v) = guard.clone() {
         (StatusCode::OK, Json(v)).into_response()
     } else {
@@ -85,6 +183,31 @@ async fn latest(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
+/// Server-Sent Events: one `slot` event per new LatestSlot
+async fn slot_stream(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = futures_util::stream::unfold(state.slots.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(slot) => {
+                    let event = Event::default()
+                        .event("slot")
+                        .json_data(&slot)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable slot"));
+                    return Some((Ok(event), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("/stream client lagged, skipped {} slots", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
 async fn ensure_stream_started(state: AppState) {
     let was_started = state.started.swap(true, Ordering::SeqCst);
     if was_started {
@@ -99,3 +222,119 @@ async fn ensure_stream_started(state: AppState) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn test_state() -> AppState {
+        AppState {
+            started: Arc::new(AtomicBool::new(true)),
+            latest: Arc::new(RwLock::new(None)),
+            slots: broadcast::channel(SLOT_CHANNEL_CAPACITY).0,
+            latest_tx: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    async fn get_latest_tx(state: AppState) -> (StatusCode, String) {
+        let response = router(state)
+            .oneshot(Request::get("/latest-tx").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_latest_tx_endpoint() {
+        let state = test_state();
+
+        assert_eq!(
+            get_latest_tx(state.clone()).await,
+            (StatusCode::NOT_FOUND, "no data yet\n".to_string())
+        );
+
+        *state.latest_tx.write().await = Some(LatestTx::Transaction {
+            slot: 42,
+            signature: "5sig".to_string(),
+            pre_token_balances: vec![],
+            post_token_balances: vec![TokenBalance {
+                account_index: 1,
+                mint: "So11111111111111111111111111111111111111112".to_string(),
+                owner: "owner".to_string(),
+                amount: "1500000000".to_string(),
+                decimals: 9,
+            }],
+            created_at_rfc3339: None,
+        });
+
+        let (status, body) = get_latest_tx(state).await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["kind"], "transaction");
+        assert_eq!(json["slot"], 42);
+        assert_eq!(json["signature"], "5sig");
+        assert_eq!(json["post_token_balances"][0]["amount"], "1500000000");
+    }
+
+    #[tokio::test]
+    async fn test_stream_pushes_slot_events() {
+        let state = test_state();
+        let response = router(state.clone())
+            .oneshot(Request::get("/stream").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        state
+            .slots
+            .send(LatestSlot {
+                slot: 7,
+                parent: Some(6),
+                status: "SlotProcessed".to_string(),
+                created_at_rfc3339: None,
+            })
+            .unwrap();
+
+        let mut body = response.into_body();
+        let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        let event = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(event.starts_with("event: slot\n"), "{event}");
+        assert!(event.contains(r#"data: {"slot":7,"parent":6"#), "{event}");
+    }
+
+    #[tokio::test]
+    async fn test_latest_long_polls_for_newer_slot() {
+        let state = test_state();
+        let slot = |slot| LatestSlot {
+            slot,
+            parent: None,
+            status: "SlotProcessed".to_string(),
+            created_at_rfc3339: None,
+        };
+        *state.latest.write().await = Some(slot(10));
+        let get = |uri: &'static str| {
+            router(state.clone()).oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        };
+
+        // Already newer: answers at once
+        assert_eq!(get("/latest?since=9&wait_ms=5000").await.unwrap().status(), StatusCode::OK);
+        // Nothing newer within the wait
+        assert_eq!(get("/latest?since=10&wait_ms=10").await.unwrap().status(), StatusCode::NO_CONTENT);
+
+        let pending = tokio::spawn(get("/latest?since=10&wait_ms=5000"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        state.slots.send(slot(11)).unwrap();
+        let response = pending.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["slot"], 11);
+    }
+}
@@ -1,14 +1,18 @@
 use anyhow::{anyhow, Context};
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
 use tracing::{info, warn};
 
-use crate::{AppState, LatestSlot};
+use crate::{AppState, LatestSlot, LatestTx, TokenBalance};
 
 // LaserStream SDK (crate name uses hyphen; module uses underscore)
 use helius_laserstream::{
     config::LaserstreamConfig,
-    grpc::{subscribe_update::UpdateOneof, SubscribeRequest},
+    grpc::{
+        subscribe_update::UpdateOneof, SubscribeRequest, SubscribeRequestFilterAccounts,
+        SubscribeRequestFilterTransactions,
+    },
 };
 
 // In docs.rs, subscribe is listed under client::subscribe
@@ -31,13 +35,13 @@ pub async fn run_slot_stream(state: AppState) -> anyhow::Result<()> {
         ..Default::default()
     };
 
-    // Minimal subscription: slots only
-    let request = SubscribeRequest {
-        slots: [("slots".to_string(), Default::default())].into(),
-        ..Default::default()
-    };
+    let watch_program_id = watch_program_id()?;
+    let request = subscribe_request(watch_program_id.as_deref());
 
     info!("connecting to LaserStream endpoint: {}", endpoint);
+    if let Some(program_id) = &watch_program_id {
+        info!("watching transactions and accounts of program {}", program_id);
+    }
 
     let (mut stream, _handle) = subscribe(config, request)
         .map_err(|e| anyhow!("subscribe() failed: {e:?}"))?;
@@ -59,26 +63,140 @@ pub async fn run_slot_stream(state: AppState) -> anyhow::Result<()> {
             Some(dt.to_rfc3339())
         });
 
-        if let Some(UpdateOneof::Slot(slot)) = update.update_oneof {
-            let latest = LatestSlot {
-                slot: slot.slot,
-                parent: slot.parent,
-                status: format!("{:?}", slot.status()),
-                created_at_rfc3339,
-            };
-
-            {
-                let mut guard = state.latest.write().await;
-                *guard = Some(latest.clone());
+        match update.update_oneof {
+            Some(UpdateOneof::Slot(slot)) => {
+                let latest = LatestSlot {
+                    slot: slot.slot,
+                    parent: slot.parent,
+                    status: format!("{:?}", slot.status()),
+                    created_at_rfc3339,
+                };
+
+                {
+                    let mut guard = state.latest.write().await;
+                    *guard = Some(latest.clone());
+                }
+                // No receivers just means no /stream clients are connected
+                let _ = state.slots.send(latest.clone());
+
+                // Keep logs sparse but visible
+                info!(
+                    "slot={} parent={:?} status={}",
+                    latest.slot, latest.parent, latest.status
+                );
             }
+            Some(UpdateOneof::Transaction(tx)) => {
+                let Some(info) = tx.transaction else {
+                    continue;
+                };
+                let (pre_token_balances, post_token_balances) = info
+                    .meta
+                    .map(|meta| {
+                        let pre = meta.pre_token_balances.into_iter().map(|b| {
+                            let amount = b.ui_token_amount.unwrap_or_default();
+                            token_balance(b.account_index, b.mint, b.owner, amount.amount, amount.decimals)
+                        });
+                        let post = meta.post_token_balances.into_iter().map(|b| {
+                            let amount = b.ui_token_amount.unwrap_or_default();
+                            token_balance(b.account_index, b.mint, b.owner, amount.amount, amount.decimals)
+                        });
+                        (pre.collect(), post.collect())
+                    })
+                    .unwrap_or_default();
 
-            // Keep logs sparse but visible
-            info!(
-                "slot={} parent={:?} status={}",
-                latest.slot, latest.parent, latest.status
-            );
+                *state.latest_tx.write().await = Some(LatestTx::Transaction {
+                    slot: tx.slot,
+                    signature: bs58::encode(&info.signature).into_string(),
+                    pre_token_balances,
+                    post_token_balances,
+                    created_at_rfc3339,
+                });
+            }
+            Some(UpdateOneof::Account(account)) => {
+                let Some(info) = account.account else {
+                    continue;
+                };
+
+                *state.latest_tx.write().await = Some(LatestTx::Account {
+                    slot: account.slot,
+                    pubkey: bs58::encode(&info.pubkey).into_string(),
+                    owner: bs58::encode(&info.owner).into_string(),
+                    lamports: info.lamports,
+                    data: base64::engine::general_purpose::STANDARD.encode(&info.data),
+                    created_at_rfc3339,
+                });
+            }
+            _ => {}
         }
     }
 
     Err(anyhow!("LaserStream stream ended unexpectedly"))
 }
+
+/// Optional `WATCH_PROGRAM_ID`: a base58 program id whose transactions and
+/// owned accounts are streamed to `/latest-tx`
+fn watch_program_id() -> anyhow::Result<Option<String>> {
+    let Some(program_id) = std::env::var("WATCH_PROGRAM_ID")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let bytes = bs58::decode(&program_id)
+        .into_vec()
+        .with_context(|| format!("WATCH_PROGRAM_ID is not base58: {program_id}"))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("WATCH_PROGRAM_ID is not a 32-byte pubkey: {program_id}"));
+    }
+    Ok(Some(program_id))
+}
+
+/// Slots always; transactions mentioning and accounts owned by the watched
+/// program when one is set
+fn subscribe_request(watch_program_id: Option<&str>) -> SubscribeRequest {
+    let mut request = SubscribeRequest {
+        slots: [("slots".to_string(), Default::default())].into(),
+        ..Default::default()
+    };
+
+    if let Some(program_id) = watch_program_id {
+        request.transactions = [(
+            "watch".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: vec![program_id.to_string()],
+                ..Default::default()
+            },
+        )]
+        .into();
+        request.accounts = [(
+            "watch".to_string(),
+            SubscribeRequestFilterAccounts {
+                owner: vec![program_id.to_string()],
+                ..Default::default()
+            },
+        )]
+        .into();
+    }
+
+    request
+}
+
+fn token_balance(
+    account_index: u32,
+    mint: String,
+    owner: String,
+    amount: String,
+    decimals: u32,
+) -> TokenBalance {
+    TokenBalance {
+        account_index,
+        mint,
+        owner,
+        amount,
+        decimals,
+    }
+}
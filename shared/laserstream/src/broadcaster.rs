@@ -1,15 +1,66 @@
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::RwLock;
 use tokio_tungstenite::{accept_async, tungstenite::Message};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 
 type ClientId = usize;
-type Clients = Arc<RwLock<HashMap<ClientId, tokio::sync::mpsc::UnboundedSender<Message>>>>;
+type Clients = Arc<RwLock<HashMap<ClientId, Client>>>;
+
+struct Client {
+    tx: tokio::sync::mpsc::UnboundedSender<Message>,
+    /// None until the client first subscribes; until then it receives everything
+    channels: Option<HashSet<String>>,
+}
+
+impl Client {
+    fn new(tx: tokio::sync::mpsc::UnboundedSender<Message>) -> Self {
+        Self { tx, channels: None }
+    }
+
+    /// Messages without a channel (pings) go to everyone
+    fn wants(&self, channel: Option<&str>) -> bool {
+        match (&self.channels, channel) {
+            (Some(channels), Some(channel)) => channels.contains(channel),
+            _ => true,
+        }
+    }
+}
+
+/// Control frames a client sends, e.g. `{"type":"Subscribe","channels":["jupiter:SOL-USDC"]}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ClientRequest {
+    Subscribe { channels: Vec<String> },
+    Unsubscribe { channels: Vec<String> },
+}
+
+/// Short name for well-known mints in channel names; other mints use their address
+fn mint_symbol(mint: &str) -> &str {
+    match mint {
+        "So11111111111111111111111111111111111111112" => "SOL",
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v" => "USDC",
+        "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB" => "USDT",
+        _ => mint,
+    }
+}
+
+/// Pair channels name their legs in a fixed order, so `jupiter:USDC-SOL` and
+/// `jupiter:SOL-USDC` are one channel whichever way a price was quoted.
+/// Other channels are returned unchanged.
+fn normalize_channel(channel: &str) -> String {
+    let Some((source, pair)) = channel.split_once(':') else {
+        return channel.to_string();
+    };
+    match pair.split_once('-') {
+        Some((a, b)) if a > b => format!("{}:{}-{}", source, b, a),
+        _ => channel.to_string(),
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceUpdate {
@@ -39,6 +90,25 @@ pub enum StreamMessage {
     Ping,
 }
 
+impl StreamMessage {
+    /// Channel clients subscribe to for this message: `<source>:<a>-<b>` for
+    /// prices in either direction (e.g. `jupiter:SOL-USDC`), `account:<pubkey>`,
+    /// or `slots`
+    pub fn channel(&self) -> Option<String> {
+        match self {
+            Self::PriceUpdate(update) => Some(normalize_channel(&format!(
+                "{}:{}-{}",
+                update.source,
+                mint_symbol(&update.input_mint),
+                mint_symbol(&update.output_mint)
+            ))),
+            Self::AccountUpdate(update) => Some(format!("account:{}", update.pubkey)),
+            Self::SlotUpdate { .. } => Some("slots".to_string()),
+            Self::Ping => None,
+        }
+    }
+}
+
 pub struct WebSocketBroadcaster {
     clients: Clients,
     next_client_id: Arc<RwLock<ClientId>>,
@@ -101,7 +171,7 @@ impl WebSocketBroadcaster {
         };
         
         // Register client
-        clients.write().await.insert(client_id, tx);
+        clients.write().await.insert(client_id, Client::new(tx));
         info!("Client {} connected", client_id);
         
         // Send messages to client
@@ -113,12 +183,13 @@ impl WebSocketBroadcaster {
             }
         });
         
-        // Receive messages from client (mostly for health checks)
+        // Receive subscription changes and health checks from client
         while let Some(msg) = ws_receiver.next().await {
             match msg {
+                Ok(Message::Text(text)) => Self::handle_request(&clients, client_id, &text).await,
                 Ok(Message::Ping(data)) => {
-                    if let Some(tx) = clients.read().await.get(&client_id) {
-                        let _ = tx.send(Message::Pong(data));
+                    if let Some(client) = clients.read().await.get(&client_id) {
+                        let _ = client.tx.send(Message::Pong(data));
                     }
                 }
                 Ok(Message::Close(_)) => break,
@@ -135,15 +206,45 @@ impl WebSocketBroadcaster {
         Ok(())
     }
     
+    /// Apply a Subscribe/Unsubscribe frame to the client's channels
+    async fn handle_request(clients: &Clients, client_id: ClientId, text: &str) {
+        let request = match serde_json::from_str::<ClientRequest>(text) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("Ignoring message from client {}: {}", client_id, e);
+                return;
+            }
+        };
+
+        let mut clients = clients.write().await;
+        let Some(client) = clients.get_mut(&client_id) else {
+            return;
+        };
+        let subscribed = client.channels.get_or_insert_with(HashSet::new);
+        match request {
+            ClientRequest::Subscribe { channels } => {
+                subscribed.extend(channels.iter().map(|channel| normalize_channel(channel)))
+            }
+            ClientRequest::Unsubscribe { channels } => {
+                for channel in &channels {
+                    subscribed.remove(&normalize_channel(channel));
+                }
+            }
+        }
+        debug!("Client {} subscribed to {:?}", client_id, subscribed);
+    }
+
+    /// Send `message` to every client subscribed to its channel
     pub async fn broadcast(&self, message: StreamMessage) -> Result<()> {
+        let channel = message.channel();
         let json = serde_json::to_string(&message)?;
         let msg = Message::Text(json);
         
         let clients = self.clients.read().await;
         let mut disconnected = Vec::new();
         
-        for (id, tx) in clients.iter() {
-            if tx.send(msg.clone()).is_err() {
+        for (id, client) in clients.iter().filter(|(_, c)| c.wants(channel.as_deref())) {
+            if client.tx.send(msg.clone()).is_err() {
                 disconnected.push(*id);
             }
         }
@@ -165,3 +266,101 @@ impl WebSocketBroadcaster {
         self.clients.read().await.len()
     }
 }
+
+#[cfg(test)]
+impl WebSocketBroadcaster {
+    /// Broadcaster without a WebSocket listener
+    pub fn detached() -> Arc<Self> {
+        Arc::new(Self {
+            clients: Arc::new(RwLock::new(HashMap::new())),
+            next_client_id: Arc::new(RwLock::new(0)),
+        })
+    }
+
+    /// Register an in-process client, as `handle_connection` does for a WebSocket
+    pub async fn add_client(&self) -> (ClientId, tokio::sync::mpsc::UnboundedReceiver<Message>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut next_id = self.next_client_id.write().await;
+        let id = *next_id;
+        *next_id += 1;
+        self.clients.write().await.insert(id, Client::new(tx));
+        (id, rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const USDT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+    fn price(input_mint: &str, output_mint: &str) -> StreamMessage {
+        StreamMessage::PriceUpdate(PriceUpdate {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            price: 1.0,
+            volume: 1,
+            timestamp: 0,
+            source: "jupiter".to_string(),
+        })
+    }
+
+    /// Channels of the messages waiting for a client
+    fn received(rx: &mut tokio::sync::mpsc::UnboundedReceiver<Message>) -> Vec<String> {
+        let mut channels = Vec::new();
+        while let Ok(Message::Text(json)) = rx.try_recv() {
+            let message: StreamMessage = serde_json::from_str(&json).unwrap();
+            channels.push(message.channel().unwrap_or_else(|| "ping".to_string()));
+        }
+        channels
+    }
+
+    #[tokio::test]
+    async fn test_clients_only_receive_subscribed_channels() {
+        let broadcaster = WebSocketBroadcaster::detached();
+        let (sol_id, mut sol_client) = broadcaster.add_client().await;
+        let (usdt_id, mut usdt_client) = broadcaster.add_client().await;
+        let (_, mut unfiltered) = broadcaster.add_client().await;
+
+        let clients = &broadcaster.clients;
+        WebSocketBroadcaster::handle_request(clients, sol_id, r#"{"type":"Subscribe","channels":["jupiter:SOL-USDC"]}"#).await;
+        WebSocketBroadcaster::handle_request(clients, usdt_id, r#"{"type":"Subscribe","channels":["jupiter:USDC-USDT"]}"#).await;
+
+        broadcaster.broadcast(price(SOL, USDC)).await.unwrap();
+        broadcaster.broadcast(price(USDC, USDT)).await.unwrap();
+        broadcaster.broadcast(StreamMessage::SlotUpdate { slot: 1, timestamp: 0 }).await.unwrap();
+        broadcaster.broadcast(StreamMessage::Ping).await.unwrap();
+
+        assert_eq!(received(&mut sol_client), vec!["jupiter:SOL-USDC", "ping"]);
+        assert_eq!(received(&mut usdt_client), vec!["jupiter:USDC-USDT", "ping"]);
+        // A client that never subscribed still gets everything
+        assert_eq!(received(&mut unfiltered).len(), 4);
+
+        WebSocketBroadcaster::handle_request(clients, sol_id, r#"{"type":"Unsubscribe","channels":["jupiter:SOL-USDC"]}"#).await;
+        broadcaster.broadcast(price(SOL, USDC)).await.unwrap();
+        assert!(received(&mut sol_client).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pair_channels_ignore_direction() {
+        let broadcaster = WebSocketBroadcaster::detached();
+        let (id, mut client) = broadcaster.add_client().await;
+        let clients = &broadcaster.clients;
+        WebSocketBroadcaster::handle_request(clients, id, r#"{"type":"Subscribe","channels":["jupiter:USDC-SOL"]}"#).await;
+
+        // Quoted either way round, it's the one SOL/USDC channel
+        broadcaster.broadcast(price(SOL, USDC)).await.unwrap();
+        broadcaster.broadcast(price(USDC, SOL)).await.unwrap();
+        broadcaster.broadcast(price(USDT, USDC)).await.unwrap();
+        assert_eq!(received(&mut client), vec!["jupiter:SOL-USDC", "jupiter:SOL-USDC"]);
+
+        WebSocketBroadcaster::handle_request(clients, id, r#"{"type":"Unsubscribe","channels":["jupiter:SOL-USDC"]}"#).await;
+        broadcaster.broadcast(price(USDC, SOL)).await.unwrap();
+        assert!(received(&mut client).is_empty());
+
+        assert_eq!(normalize_channel("account:9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"), "account:9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+        assert_eq!(normalize_channel("slots"), "slots");
+    }
+}
@@ -2,6 +2,12 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::env;
 
+/// Jupiter aggregator programs whose transactions are turned into price updates
+pub const JUPITER_PROGRAM_IDS: &[&str] = &[
+    "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", // V6
+    "JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB", // V4
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LaserStreamConfig {
     pub api_key: String,
@@ -13,6 +19,82 @@ pub struct LaserStreamConfig {
     pub start_slot: Option<u64>,
     pub auto_reconnect: bool,
     pub max_reconnect_attempts: Option<usize>,
+    pub subscriptions: SubscriptionConfig,
+}
+
+/// What the gRPC subscription asks LaserStream for. Defaults to Jupiter swaps
+/// and slots; set `SUBSCRIPTIONS_FILE` (JSON) or the `SUBSCRIBE_*` variables to
+/// watch other programs (DeFiTuna, Orca, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionConfig {
+    /// Accounts whose updates are streamed
+    pub accounts: Vec<String>,
+    /// Programs whose owned accounts' updates are streamed
+    pub owners: Vec<String>,
+    /// Transactions touching any of these accounts or programs are streamed
+    pub transaction_includes: Vec<String>,
+    pub slots: bool,
+    /// Overrides `COMMITMENT_LEVEL` for the subscription
+    pub commitment: Option<CommitmentLevel>,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            accounts: vec![JUPITER_PROGRAM_IDS[0].to_string()],
+            owners: Vec::new(),
+            transaction_includes: JUPITER_PROGRAM_IDS.iter().map(|id| id.to_string()).collect(),
+            slots: true,
+            commitment: None,
+        }
+    }
+}
+
+impl SubscriptionConfig {
+    /// From the JSON file at `SUBSCRIPTIONS_FILE` if set, otherwise the
+    /// defaults overridden by any `SUBSCRIBE_*` variables
+    pub fn from_env() -> Result<Self> {
+        let subscriptions = match env::var("SUBSCRIPTIONS_FILE") {
+            Ok(path) => {
+                let json = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read SUBSCRIPTIONS_FILE {}", path))?;
+                serde_json::from_str(&json).with_context(|| format!("Invalid SUBSCRIPTIONS_FILE {}", path))?
+            }
+            Err(_) => {
+                let list = |name: &str| {
+                    env::var(name).ok().map(|value| {
+                        value
+                            .split(',')
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect::<Vec<_>>()
+                    })
+                };
+                let defaults = Self::default();
+                Self {
+                    accounts: list("SUBSCRIBE_ACCOUNTS").unwrap_or(defaults.accounts),
+                    owners: list("SUBSCRIBE_OWNERS").unwrap_or(defaults.owners),
+                    transaction_includes: list("SUBSCRIBE_TRANSACTIONS").unwrap_or(defaults.transaction_includes),
+                    slots: env::var("SUBSCRIBE_SLOTS")
+                        .map(|s| s.parse())
+                        .unwrap_or(Ok(defaults.slots))
+                        .context("Invalid SUBSCRIBE_SLOTS")?,
+                    commitment: None,
+                }
+            }
+        };
+        subscriptions.validate()?;
+        Ok(subscriptions)
+    }
+
+    /// A subscription with no filters would stream nothing
+    pub fn validate(&self) -> Result<()> {
+        if self.accounts.is_empty() && self.owners.is_empty() && self.transaction_includes.is_empty() && !self.slots {
+            anyhow::bail!("Subscription has no filters: set accounts, owners, transaction includes or slots");
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,10 +103,13 @@ pub enum Network {
     Devnet,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CommitmentLevel {
+    #[serde(alias = "processed")]
     Processed,
+    #[serde(alias = "confirmed")]
     Confirmed,
+    #[serde(alias = "finalized")]
     Finalized,
 }
 
@@ -81,6 +166,7 @@ impl LaserStreamConfig {
             start_slot,
             auto_reconnect,
             max_reconnect_attempts,
+            subscriptions: SubscriptionConfig::from_env()?,
         })
     }
 
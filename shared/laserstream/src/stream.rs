@@ -1,19 +1,26 @@
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{info, warn, error};
+use tracing::{debug, error, info, warn};
 use yellowstone_grpc_client::GeyserGrpcClient;
 use yellowstone_grpc_proto::prelude::*;
 
-use crate::config::{LaserStreamConfig, CommitmentLevel};
-use crate::broadcaster::{WebSocketBroadcaster, StreamMessage, PriceUpdate, AccountUpdate};
+use crate::broadcaster::{AccountUpdate, PriceUpdate, StreamMessage, WebSocketBroadcaster};
+use crate::config::{CommitmentLevel, LaserStreamConfig, SubscriptionConfig, JUPITER_PROGRAM_IDS};
 use crate::metrics::Metrics;
+use bot_common::swap::{native_delta, swap_legs, trader_index, TokenBalanceEntry};
+
+type ReconnectHook = Box<dyn Fn(usize) + Send + Sync>;
+type ConnectedHook = Box<dyn Fn() + Send + Sync>;
 
 pub struct StreamManager {
     config: LaserStreamConfig,
     broadcaster: Arc<WebSocketBroadcaster>,
     metrics: Arc<Metrics>,
     reconnect_attempts: usize,
+    on_reconnect: Option<ReconnectHook>,
+    on_connected: Option<ConnectedHook>,
 }
 
 impl StreamManager {
@@ -27,9 +34,47 @@ impl StreamManager {
             broadcaster,
             metrics,
             reconnect_attempts: 0,
+            on_reconnect: None,
+            on_connected: None,
+        }
+    }
+
+    /// Called with the attempt number each time the stream is about to reconnect,
+    /// e.g. to alert
+    pub fn on_reconnect(&mut self, cb: impl Fn(usize) + Send + Sync + 'static) {
+        self.on_reconnect = Some(Box::new(cb));
+    }
+
+    /// Called each time a subscription is established, including after reconnects
+    pub fn on_connected(&mut self, cb: impl Fn() + Send + Sync + 'static) {
+        self.on_connected = Some(Box::new(cb));
+    }
+
+    /// Count a reconnect after `error` and return the backoff before it, or
+    /// hand the error back when reconnecting is disabled or exhausted
+    fn schedule_reconnect(&mut self, error: anyhow::Error) -> Result<Duration> {
+        if !self.config.auto_reconnect {
+            return Err(error);
+        }
+
+        if let Some(max) = self.config.max_reconnect_attempts {
+            if self.reconnect_attempts >= max {
+                error!("Max reconnect attempts ({}) reached", max);
+                return Err(error);
+            }
         }
+
+        self.reconnect_attempts += 1;
+        self.metrics.record_reconnection();
+        if let Some(cb) = &self.on_reconnect {
+            cb(self.reconnect_attempts);
+        }
+
+        Ok(Duration::from_secs(
+            2u64.pow(self.reconnect_attempts.min(5) as u32),
+        ))
     }
-    
+
     pub async fn start(&mut self) -> Result<()> {
         loop {
             match self.run_stream().await {
@@ -39,90 +84,49 @@ impl StreamManager {
                 }
                 Err(e) => {
                     error!("Stream error: {}", e);
-                    
-                    if !self.config.auto_reconnect {
-                        return Err(e);
-                    }
-                    
-                    if let Some(max) = self.config.max_reconnect_attempts {
-                        if self.reconnect_attempts >= max {
-                            error!("Max reconnect attempts ({}) reached", max);
-                            return Err(e);
-                        }
-                    }
-                    
-                    self.reconnect_attempts += 1;
-                    self.metrics.record_reconnection();
-                    
-                    let backoff = Duration::from_secs(2u64.pow(self.reconnect_attempts.min(5) as u32));
-                    warn!("Reconnecting in {:?} (attempt {})", backoff, self.reconnect_attempts);
+
+                    let backoff = self.schedule_reconnect(e)?;
+                    warn!(
+                        "Reconnecting in {:?} (attempt {})",
+                        backoff, self.reconnect_attempts
+                    );
                     tokio::time::sleep(backoff).await;
                 }
             }
         }
-        
+
         Ok(())
     }
-    
+
     async fn run_stream(&mut self) -> Result<()> {
         // Connect to LaserStream
-        let mut client = GeyserGrpcClient::connect(
-            &self.config.endpoint,
-            Some(&self.config.api_key),
-            None,
-        )
-        .await
-        .context("Failed to connect to LaserStream")?;
-        
+        let mut client =
+            GeyserGrpcClient::connect(&self.config.endpoint, Some(&self.config.api_key), None)
+                .await
+                .context("Failed to connect to LaserStream")?;
+
         info!("Connected to LaserStream");
         self.reconnect_attempts = 0; // Reset on successful connection
-        
-        // Build subscription request
-        let mut accounts = HashMap::new();
-        
-        // Example: Subscribe to Jupiter V6 swaps
-        accounts.insert(
-            "jupiter".to_string(),
-            SubscribeRequestFilterAccounts {
-                account: vec!["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string()],
-                owner: vec![],
-                filters: vec![],
-            },
-        );
-        
-        let commitment = self.config.commitment_level.to_grpc() as i32;
-        
-        let request = SubscribeRequest {
-            accounts,
-            slots: HashMap::from([(
-                "slots".to_string(),
-                SubscribeRequestFilterSlots {
-                    filter_by_commitment: Some(true),
-                },
-            )]),
-            transactions: HashMap::new(),
-            blocks: HashMap::new(),
-            blocks_meta: HashMap::new(),
-            entry: HashMap::new(),
-            commitment: Some(commitment),
-            accounts_data_slice: vec![],
-            ping: None,
-        };
-        
+
+        let request = subscribe_request(&self.config.subscriptions, &self.config.commitment_level);
+
         // Subscribe
         let (mut subscribe_tx, mut stream) = client
             .subscribe_with_request(Some(request))
             .await
             .context("Failed to subscribe")?;
-        
+
         info!("Subscribed to LaserStream");
-        
+        if let Some(cb) = &self.on_connected {
+            cb();
+        }
+
         // Process stream
         while let Some(message) = stream.next().await {
             match message {
                 Ok(msg) => {
                     self.metrics.record_message();
-                    
+
                     if let Err(e) = self.process_message(msg).await {
                         error!("Error processing message: {}", e);
                         self.metrics.record_error();
@@ -134,13 +138,13 @@ impl StreamManager {
                 }
             }
         }
-        
+
         Ok(())
     }
-    
+
     async fn process_message(&self, msg: SubscribeUpdate) -> Result<()> {
         use subscribe_update::UpdateOneof;
-        
+
         match msg.update_oneof {
             Some(UpdateOneof::Account(account_update)) => {
                 self.handle_account_update(account_update).await?;
@@ -156,10 +160,10 @@ impl StreamManager {
             }
             _ => {}
         }
-        
+
         Ok(())
     }
-    
+
     async fn handle_account_update(&self, update: SubscribeUpdateAccount) -> Result<()> {
         if let Some(account_info) = update.account {
             let update_msg = AccountUpdate {
@@ -169,15 +173,15 @@ impl StreamManager {
                 slot: update.slot,
                 timestamp: chrono::Utc::now().timestamp(),
             };
-            
+
             self.broadcaster
                 .broadcast(StreamMessage::AccountUpdate(update_msg))
                 .await?;
         }
-        
+
         Ok(())
     }
-    
+
     async fn handle_slot_update(&self, update: SubscribeUpdateSlot) -> Result<()> {
         self.broadcaster
             .broadcast(StreamMessage::SlotUpdate {
@@ -185,19 +189,503 @@ impl StreamManager {
                 timestamp: chrono::Utc::now().timestamp(),
             })
             .await?;
-        
+
         Ok(())
     }
-    
+
     async fn handle_transaction_update(&self, update: SubscribeUpdateTransaction) -> Result<()> {
-        // Parse transaction for price updates (simplified example)
-        // In production, you'd parse the actual instruction data
-        
-        info!("Received transaction at slot {}", update.slot);
-        
-        // Example: Extract swap data from Jupiter transaction
-        // This is a placeholder - actual implementation would parse instruction data
-        
+        let Some(price_update) =
+            price_update_from_transaction(&update, chrono::Utc::now().timestamp())
+        else {
+            debug!("No Jupiter swap in transaction at slot {}", update.slot);
+            return Ok(());
+        };
+
+        debug!(
+            "Swap at slot {}: {} -> {} @ {:.6}",
+            update.slot, price_update.input_mint, price_update.output_mint, price_update.price
+        );
+        self.broadcaster
+            .broadcast(StreamMessage::PriceUpdate(price_update))
+            .await?;
+
         Ok(())
     }
 }
+
+/// The gRPC subscription for `subscriptions`, at its commitment or `default_commitment`
+fn subscribe_request(
+    subscriptions: &SubscriptionConfig,
+    default_commitment: &CommitmentLevel,
+) -> SubscribeRequest {
+    let mut accounts = HashMap::new();
+    if !subscriptions.accounts.is_empty() {
+        accounts.insert(
+            "accounts".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: subscriptions.accounts.clone(),
+                owner: vec![],
+                filters: vec![],
+            },
+        );
+    }
+    // A separate filter: account and owner lists in one filter must both match
+    if !subscriptions.owners.is_empty() {
+        accounts.insert(
+            "owners".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: vec![],
+                owner: subscriptions.owners.clone(),
+                filters: vec![],
+            },
+        );
+    }
+
+    let mut slots = HashMap::new();
+    if subscriptions.slots {
+        slots.insert(
+            "slots".to_string(),
+            SubscribeRequestFilterSlots {
+                filter_by_commitment: Some(true),
+            },
+        );
+    }
+
+    let mut transactions = HashMap::new();
+    if !subscriptions.transaction_includes.is_empty() {
+        transactions.insert(
+            "transactions".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                signature: None,
+                account_include: subscriptions.transaction_includes.clone(),
+                account_exclude: vec![],
+                account_required: vec![],
+            },
+        );
+    }
+
+    let commitment = subscriptions
+        .commitment
+        .as_ref()
+        .unwrap_or(default_commitment);
+    SubscribeRequest {
+        accounts,
+        slots,
+        transactions,
+        blocks: HashMap::new(),
+        blocks_meta: HashMap::new(),
+        entry: HashMap::new(),
+        commitment: Some(commitment.to_grpc() as i32),
+        accounts_data_slice: vec![],
+        ping: None,
+    }
+}
+
+/// Price implied by a Jupiter swap: the trader's net per-mint balance change
+/// from the pre/post token balances, read by `bot_common::swap::swap_legs`.
+/// None for failed transactions, transactions that don't invoke Jupiter, or
+/// anything that isn't a swap.
+fn price_update_from_transaction(
+    update: &SubscribeUpdateTransaction,
+    timestamp: i64,
+) -> Option<PriceUpdate> {
+    let info = update.transaction.as_ref()?;
+    let meta = info.meta.as_ref()?;
+    let message = info.transaction.as_ref()?.message.as_ref()?;
+    if meta.err.is_some() {
+        return None;
+    }
+
+    let account_keys: Vec<String> = message
+        .account_keys
+        .iter()
+        .map(|k| bs58::encode(k).into_string())
+        .collect();
+    if !account_keys
+        .iter()
+        .any(|k| JUPITER_PROGRAM_IDS.contains(&k.as_str()))
+    {
+        return None;
+    }
+    let entries = |balances: &[TokenBalance]| -> Vec<TokenBalanceEntry> {
+        balances
+            .iter()
+            .filter_map(|b| {
+                let amount = b.ui_token_amount.as_ref()?;
+                Some(TokenBalanceEntry {
+                    mint: b.mint.clone(),
+                    owner: b.owner.clone(),
+                    amount: amount.amount.parse().unwrap_or(0),
+                    decimals: amount.decimals,
+                })
+            })
+            .collect()
+    };
+    let pre = entries(&meta.pre_token_balances);
+    let post = entries(&meta.post_token_balances);
+
+    // The fee payer comes first, but may be a relayer paying for a co-signer
+    let num_signers = message
+        .header
+        .as_ref()
+        .map_or(1, |h| h.num_required_signatures as usize);
+    let trader = trader_index(&account_keys, num_signers, &pre, &post);
+    // Only the fee payer's lamports paid the fee
+    let fee = if trader == 0 { meta.fee } else { 0 };
+    let native_delta = match (meta.pre_balances.get(trader), meta.post_balances.get(trader)) {
+        (Some(pre), Some(post)) => native_delta(*pre, *post, fee),
+        _ => 0,
+    };
+    let legs = swap_legs(account_keys.get(trader)?, &pre, &post, native_delta)?;
+
+    Some(PriceUpdate {
+        price: legs.price(),
+        volume: legs.input.amount,
+        input_mint: legs.input.mint,
+        output_mint: legs.output.mint,
+        timestamp,
+        source: "jupiter".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Network;
+    use bot_common::swap::NATIVE_MINT;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn token_balance(owner: &str, mint: &str, amount: u64, decimals: u32) -> TokenBalance {
+        TokenBalance {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            ui_token_amount: Some(UiTokenAmount {
+                amount: amount.to_string(),
+                decimals,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Transaction signed by `signer` with the given program in its account keys
+    fn transaction(
+        signer: &str,
+        program: &str,
+        pre_token_balances: Vec<TokenBalance>,
+        post_token_balances: Vec<TokenBalance>,
+    ) -> SubscribeUpdateTransaction {
+        let key = |k: &str| bs58::decode(k).into_vec().unwrap();
+        SubscribeUpdateTransaction {
+            slot: 250_000_000,
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                transaction: Some(Transaction {
+                    message: Some(Message {
+                        account_keys: vec![key(signer), key(program)],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                meta: Some(TransactionStatusMeta {
+                    fee: 5_000,
+                    pre_balances: vec![1_000_000_000, 1],
+                    post_balances: vec![999_995_000, 1],
+                    pre_token_balances,
+                    post_token_balances,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_price_update_from_balance_deltas() {
+        let signer = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        let pool = "7qbRF6YsyGuLUVs6Y1q64bdVrfe4ZcUUz1JRdoVNUJnm";
+        // Signer pays 150 USDC for 10M BONK; the pool's side of the trade is ignored
+        let pre = vec![
+            token_balance(signer, USDC, 200_000_000, 6),
+            token_balance(pool, USDC, 5_000_000_000, 6),
+        ];
+        let post = vec![
+            token_balance(signer, USDC, 50_000_000, 6),
+            token_balance(pool, USDC, 5_150_000_000, 6),
+            token_balance(signer, BONK, 1_000_000_000_000, 5),
+        ];
+
+        let update = price_update_from_transaction(
+            &transaction(signer, JUPITER_PROGRAM_IDS[0], pre.clone(), post.clone()),
+            42,
+        )
+        .unwrap();
+        assert_eq!(update.input_mint, USDC);
+        assert_eq!(update.output_mint, BONK);
+        assert_eq!(update.volume, 150_000_000);
+        assert!((update.price - 10_000_000.0 / 150.0).abs() < 1e-6);
+        assert_eq!(update.timestamp, 42);
+
+        // Same balances without Jupiter in the account keys
+        let other = transaction(signer, "11111111111111111111111111111111", pre, post);
+        assert!(price_update_from_transaction(&other, 42).is_none());
+    }
+
+    #[test]
+    fn test_unwrapped_sol_counts_as_input() {
+        let signer = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        let mut tx = transaction(
+            signer,
+            JUPITER_PROGRAM_IDS[0],
+            vec![],
+            vec![token_balance(signer, USDC, 15_000_000, 6)],
+        );
+        // 0.1 SOL spent on top of the 5000 lamport fee
+        tx.transaction
+            .as_mut()
+            .unwrap()
+            .meta
+            .as_mut()
+            .unwrap()
+            .post_balances[0] = 899_995_000;
+
+        let update = price_update_from_transaction(&tx, 0).unwrap();
+        assert_eq!(update.input_mint, NATIVE_MINT);
+        assert_eq!(update.volume, 100_000_000);
+        assert!((update.price - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ata_rent_is_not_a_swap_leg() {
+        let signer = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        // 1.5 USDC for 100k BONK, creating the signer's BONK account: its
+        // 2_039_280 lamport rent outweighs 1.5 USDC in base units
+        let mut tx = transaction(
+            signer,
+            JUPITER_PROGRAM_IDS[0],
+            vec![token_balance(signer, USDC, 1_500_000, 6)],
+            vec![
+                token_balance(signer, USDC, 0, 6),
+                token_balance(signer, BONK, 10_000_000_000, 5),
+            ],
+        );
+        tx.transaction
+            .as_mut()
+            .unwrap()
+            .meta
+            .as_mut()
+            .unwrap()
+            .post_balances[0] = 999_995_000 - 2_039_280;
+
+        let update = price_update_from_transaction(&tx, 0).unwrap();
+        assert_eq!(update.input_mint, USDC);
+        assert_eq!(update.output_mint, BONK);
+        assert_eq!(update.volume, 1_500_000);
+        assert!((update.price - 100_000.0 / 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_relayer_paid_swap_prices_the_traders_legs() {
+        let relayer = "7qbRF6YsyGuLUVs6Y1q64bdVrfe4ZcUUz1JRdoVNUJnm";
+        let trader = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        // The relayer pays the fee; the co-signing trader swaps 150 USDC for 10M BONK
+        let mut tx = transaction(
+            relayer,
+            JUPITER_PROGRAM_IDS[0],
+            vec![token_balance(trader, USDC, 200_000_000, 6)],
+            vec![
+                token_balance(trader, USDC, 50_000_000, 6),
+                token_balance(trader, BONK, 1_000_000_000_000, 5),
+            ],
+        );
+        let info = tx.transaction.as_mut().unwrap();
+        let message = info.transaction.as_mut().unwrap().message.as_mut().unwrap();
+        message.header = Some(MessageHeader {
+            num_required_signatures: 2,
+            ..Default::default()
+        });
+        message
+            .account_keys
+            .insert(1, bs58::decode(trader).into_vec().unwrap());
+        let meta = info.meta.as_mut().unwrap();
+        meta.pre_balances.insert(1, 50_000_000);
+        meta.post_balances.insert(1, 50_000_000);
+
+        let update = price_update_from_transaction(&tx, 0).unwrap();
+        assert_eq!(update.input_mint, USDC);
+        assert_eq!(update.output_mint, BONK);
+        assert_eq!(update.volume, 150_000_000);
+        assert!((update.price - 10_000_000.0 / 150.0).abs() < 1e-6);
+    }
+
+    fn config() -> LaserStreamConfig {
+        LaserStreamConfig {
+            api_key: "test".to_string(),
+            endpoint: "http://localhost".to_string(),
+            region: "ewr".to_string(),
+            network: Network::Mainnet,
+            broadcast_port: 0,
+            commitment_level: CommitmentLevel::Confirmed,
+            start_slot: None,
+            auto_reconnect: false,
+            max_reconnect_attempts: None,
+            subscriptions: SubscriptionConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_subscription_config_maps_to_request() {
+        // Defaults keep the Jupiter account, Jupiter transactions and slots
+        let request =
+            subscribe_request(&SubscriptionConfig::default(), &CommitmentLevel::Confirmed);
+        assert_eq!(
+            request.accounts["accounts"].account,
+            vec![JUPITER_PROGRAM_IDS[0].to_string()]
+        );
+        assert!(!request.accounts.contains_key("owners"));
+        assert_eq!(
+            request.transactions["transactions"].account_include.len(),
+            2
+        );
+        assert_eq!(request.transactions["transactions"].vote, Some(false));
+        assert_eq!(request.slots["slots"].filter_by_commitment, Some(true));
+        assert_eq!(
+            request.commitment,
+            Some(CommitmentLevel::Confirmed.to_grpc() as i32)
+        );
+
+        // DeFiTuna program accounts only, finalized
+        let json = r#"{
+            "owners": ["tuna4uSQZncNeeiAMKbstuxA9CUkHH6HmC64wgmnogD"],
+            "accounts": [],
+            "transaction_includes": [],
+            "slots": false,
+            "commitment": "finalized"
+        }"#;
+        let subscriptions: SubscriptionConfig = serde_json::from_str(json).unwrap();
+        subscriptions.validate().unwrap();
+        let request = subscribe_request(&subscriptions, &CommitmentLevel::Confirmed);
+        assert_eq!(request.accounts.len(), 1);
+        assert!(request.accounts["owners"].account.is_empty());
+        assert_eq!(
+            request.accounts["owners"].owner,
+            vec!["tuna4uSQZncNeeiAMKbstuxA9CUkHH6HmC64wgmnogD".to_string()]
+        );
+        assert!(request.transactions.is_empty());
+        assert!(request.slots.is_empty());
+        assert_eq!(
+            request.commitment,
+            Some(CommitmentLevel::Finalized.to_grpc() as i32)
+        );
+
+        // Omitted fields take the defaults, but disabling everything is rejected
+        let partial: SubscriptionConfig = serde_json::from_str(r#"{"slots": false}"#).unwrap();
+        assert_eq!(
+            partial.transaction_includes,
+            SubscriptionConfig::default().transaction_includes
+        );
+        let empty = SubscriptionConfig {
+            accounts: vec![],
+            owners: vec![],
+            transaction_includes: vec![],
+            slots: false,
+            commitment: None,
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_reconnect_hook_fires_with_attempt_count() {
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = attempts.clone();
+        let mut manager = StreamManager::new(
+            LaserStreamConfig {
+                auto_reconnect: true,
+                max_reconnect_attempts: Some(2),
+                ..config()
+            },
+            WebSocketBroadcaster::detached(),
+            Metrics::new(),
+        );
+        manager.on_reconnect(move |attempt| recorded.lock().unwrap().push(attempt));
+
+        assert_eq!(
+            manager
+                .schedule_reconnect(anyhow::anyhow!("stream reset"))
+                .unwrap(),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            manager
+                .schedule_reconnect(anyhow::anyhow!("stream reset"))
+                .unwrap(),
+            Duration::from_secs(4)
+        );
+        // Out of attempts: the error surfaces and the hook stays quiet
+        assert!(manager
+            .schedule_reconnect(anyhow::anyhow!("stream reset"))
+            .is_err());
+        assert_eq!(*attempts.lock().unwrap(), vec![1, 2]);
+
+        // No hooks set: reconnects still work as before
+        let mut manager = StreamManager::new(
+            LaserStreamConfig {
+                auto_reconnect: true,
+                ..config()
+            },
+            WebSocketBroadcaster::detached(),
+            Metrics::new(),
+        );
+        assert!(manager
+            .schedule_reconnect(anyhow::anyhow!("stream reset"))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_parsed_swap_broadcasts_one_price_update() {
+        let broadcaster = WebSocketBroadcaster::detached();
+        let (_, mut client) = broadcaster.add_client().await;
+        let manager = StreamManager::new(config(), broadcaster, Metrics::new());
+
+        // Signer sells 1 SOL for 150 USDC
+        let signer = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        let mut tx = transaction(
+            signer,
+            JUPITER_PROGRAM_IDS[0],
+            vec![],
+            vec![token_balance(signer, USDC, 150_000_000, 6)],
+        );
+        tx.transaction
+            .as_mut()
+            .unwrap()
+            .meta
+            .as_mut()
+            .unwrap()
+            .pre_balances[0] = 2_000_000_000;
+        manager.handle_transaction_update(tx).await.unwrap();
+
+        let Ok(WsMessage::Text(json)) = client.try_recv() else {
+            panic!("expected a broadcast message");
+        };
+        match serde_json::from_str::<StreamMessage>(&json).unwrap() {
+            StreamMessage::PriceUpdate(update) => {
+                assert_eq!(update.input_mint, NATIVE_MINT);
+                assert_eq!(update.output_mint, USDC);
+                assert!((update.price - 150.0).abs() < 1e-9);
+                assert_eq!(update.volume, 1_000_000_000);
+                assert_eq!(update.source, "jupiter");
+            }
+            other => panic!("expected PriceUpdate, got {:?}", other),
+        }
+        assert!(client.try_recv().is_err());
+
+        // A Jupiter transaction that moved no balances broadcasts nothing
+        let idle = transaction(signer, JUPITER_PROGRAM_IDS[0], vec![], vec![]);
+        manager.handle_transaction_update(idle).await.unwrap();
+        assert!(client.try_recv().is_err());
+    }
+}
@@ -1,22 +1,51 @@
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::VecDeque;
 use std::time::Duration;
 use tokio::net::TcpStream;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio::time::Instant;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, tungstenite::Message, Connector, MaybeTlsStream, WebSocketStream,
+};
 use tracing::{info, warn, error};
 
+/// What woke `receive` up
+enum Event {
+    Frame(Option<Result<Message, tokio_tungstenite::tungstenite::Error>>),
+    /// Time to ping, or the outstanding ping went unanswered
+    Heartbeat,
+}
+
 pub struct WebSocketClient {
     ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
     url: String,
+    /// TLS settings for `wss://`; None uses the system roots
+    connector: Option<Connector>,
     auto_reconnect: bool,
     reconnect_attempts: usize,
     max_reconnect_attempts: Option<usize>,
+    /// When the reconnect in progress may dial again. Kept on `self` so a
+    /// reconnect cancelled mid-backoff (e.g. by `receive_timeout`) resumes
+    /// where it left off instead of starting a new, longer backoff.
+    reconnect_at: Option<Instant>,
+    /// Ping this often while receiving; None disables the heartbeat
+    heartbeat_interval: Option<Duration>,
+    /// Treat the connection as dead when a ping goes unanswered this long
+    pong_timeout: Duration,
+    last_pong: Instant,
+    ping_sent_at: Option<Instant>,
+    /// Messages that arrived while `request` waited for its reply, served first by `receive`
+    buffered: VecDeque<String>,
 }
 
 impl WebSocketClient {
     pub async fn connect(url: &str) -> Result<Self> {
-        let (ws_stream, _) = connect_async(url)
+        Self::connect_with(url, None).await
+    }
+    
+    async fn connect_with(url: &str, connector: Option<Connector>) -> Result<Self> {
+        let (ws_stream, _) = connect_async_tls_with_config(url, None, false, connector.clone())
             .await
             .context("Failed to connect to WebSocket")?;
         
@@ -25,9 +54,16 @@ impl WebSocketClient {
         Ok(Self {
             ws_stream,
             url: url.to_string(),
+            connector,
             auto_reconnect: false,
             reconnect_attempts: 0,
             max_reconnect_attempts: None,
+            reconnect_at: None,
+            heartbeat_interval: None,
+            pong_timeout: DEFAULT_PONG_TIMEOUT,
+            last_pong: Instant::now(),
+            ping_sent_at: None,
+            buffered: VecDeque::new(),
         })
     }
     
@@ -44,13 +80,60 @@ impl WebSocketClient {
         Ok(())
     }
     
+    /// Send `req` tagged with `"id": id` and wait for the reply carrying the same
+    /// id. `req` must serialize to a JSON object. Other messages arriving in the
+    /// meantime are kept for `receive`.
+    pub async fn request<Req: Serialize, Res: DeserializeOwned>(&mut self, req: Req, id: u64) -> Result<Res> {
+        let mut tagged = serde_json::to_value(req)?;
+        tagged
+            .as_object_mut()
+            .context("Request must serialize to a JSON object")?
+            .insert("id".to_string(), id.into());
+        self.send(&tagged).await?;
+        
+        loop {
+            let text = self
+                .next_text()
+                .await?
+                .with_context(|| format!("Connection closed before the reply to request {}", id))?;
+            let reply: serde_json::Value = serde_json::from_str(&text)?;
+            if reply.get("id").and_then(|v| v.as_u64()) == Some(id) {
+                return serde_json::from_value(reply).context("Failed to parse reply");
+            }
+            self.buffered.push_back(text);
+        }
+    }
+    
+    /// Next text message. With a heartbeat configured, pings the server while
+    /// waiting and treats a missing pong as a dead connection: it reconnects
+    /// when auto-reconnect is on and errors otherwise.
     pub async fn receive<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        let text = match self.buffered.pop_front() {
+            Some(text) => Some(text),
+            None => self.next_text().await?,
+        };
+        text.map(|text| serde_json::from_str(&text)).transpose().map_err(Into::into)
+    }
+    
+    /// Raw text of the next message off the socket
+    async fn next_text(&mut self) -> Result<Option<String>> {
+        if self.reconnect_at.is_some() {
+            self.reconnect().await?;
+        }
         loop {
-            match self.ws_stream.next().await {
-                Some(Ok(Message::Text(text))) => {
-                    let message = serde_json::from_str(&text)?;
-                    return Ok(Some(message));
+            let frame = match self.next_event().await {
+                Event::Frame(frame) => frame,
+                Event::Heartbeat => {
+                    self.heartbeat().await?;
+                    continue;
+                }
+            };
+            match frame {
+                Some(Ok(Message::Pong(_))) => {
+                    self.last_pong = Instant::now();
+                    self.ping_sent_at = None;
                 }
+                Some(Ok(Message::Text(text))) => return Ok(Some(text)),
                 Some(Ok(Message::Ping(data))) => {
                     self.ws_stream.send(Message::Pong(data)).await?;
                 }
@@ -84,25 +167,82 @@ impl WebSocketClient {
         }
     }
     
-    async fn reconnect(&mut self) -> Result<()> {
-        if let Some(max) = self.max_reconnect_attempts {
-            if self.reconnect_attempts >= max {
-                return Err(anyhow::anyhow!("Max reconnect attempts reached"));
+    /// `receive`, giving up after `timeout` with Ok(None) so a caller's loop can
+    /// do periodic work while the stream is quiet. A message still in flight at
+    /// the deadline is not lost: it is returned by the next call.
+    pub async fn receive_timeout<T: DeserializeOwned>(&mut self, timeout: Duration) -> Result<Option<T>> {
+        match tokio::time::timeout(timeout, self.receive()).await {
+            Ok(result) => result,
+            Err(_) => Ok(None),
+        }
+    }
+    
+    /// The next frame, or a heartbeat deadline if one comes first
+    async fn next_event(&mut self) -> Event {
+        let Some(interval) = self.heartbeat_interval else {
+            return Event::Frame(self.ws_stream.next().await);
+        };
+        let deadline = match self.ping_sent_at {
+            Some(sent_at) => sent_at + self.pong_timeout,
+            None => self.last_pong + interval,
+        };
+        tokio::select! {
+            frame = self.ws_stream.next() => Event::Frame(frame),
+            _ = tokio::time::sleep_until(deadline) => Event::Heartbeat,
+        }
+    }
+    
+    /// Send the next ping, or give up on a connection that never answered the last one
+    async fn heartbeat(&mut self) -> Result<()> {
+        if self.ping_sent_at.is_none() {
+            match self.ws_stream.send(Message::Ping(Vec::new())).await {
+                Ok(()) => {
+                    self.ping_sent_at = Some(Instant::now());
+                    return Ok(());
+                }
+                Err(e) => warn!("Failed to send heartbeat ping: {}", e),
             }
+        } else {
+            warn!("No pong within {:?}, connection presumed dead", self.pong_timeout);
         }
         
-        self.reconnect_attempts += 1;
-        let backoff = Duration::from_secs(2u64.pow(self.reconnect_attempts.min(5) as u32));
-        
-        warn!("Reconnecting in {:?} (attempt {})", backoff, self.reconnect_attempts);
-        tokio::time::sleep(backoff).await;
+        if self.auto_reconnect {
+            self.reconnect().await
+        } else {
+            Err(anyhow::anyhow!("WebSocket heartbeat timed out after {:?}", self.pong_timeout))
+        }
+    }
+    
+    /// Back off, then replace the dead stream. Safe to cancel: the next call
+    /// waits out the same deadline rather than counting a new attempt.
+    async fn reconnect(&mut self) -> Result<()> {
+        let deadline = match self.reconnect_at {
+            Some(deadline) => deadline,
+            None => {
+                if let Some(max) = self.max_reconnect_attempts {
+                    if self.reconnect_attempts >= max {
+                        return Err(anyhow::anyhow!("Max reconnect attempts reached"));
+                    }
+                }
+                
+                self.reconnect_attempts += 1;
+                let backoff = Duration::from_secs(2u64.pow(self.reconnect_attempts.min(5) as u32));
+                
+                warn!("Reconnecting in {:?} (attempt {})", backoff, self.reconnect_attempts);
+                *self.reconnect_at.insert(Instant::now() + backoff)
+            }
+        };
+        tokio::time::sleep_until(deadline).await;
         
-        let (ws_stream, _) = connect_async(&self.url)
-            .await
-            .context("Failed to reconnect")?;
+        let connected = connect_async_tls_with_config(&self.url, None, false, self.connector.clone()).await;
+        // Success or failure, this attempt is over; a failure backs off afresh next time
+        self.reconnect_at = None;
+        let (ws_stream, _) = connected.context("Failed to reconnect")?;
         
         self.ws_stream = ws_stream;
         self.reconnect_attempts = 0; // Reset on successful connection
+        self.last_pong = Instant::now();
+        self.ping_sent_at = None;
         
         info!("Reconnected successfully");
         
@@ -110,11 +250,17 @@ impl WebSocketClient {
     }
 }
 
+const DEFAULT_PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Default)]
 pub struct WebSocketClientBuilder {
     url: Option<String>,
     auto_reconnect: bool,
     max_reconnect_attempts: Option<usize>,
+    heartbeat_interval: Option<Duration>,
+    pong_timeout: Option<Duration>,
+    connector: Option<Connector>,
+    root_certificates: Vec<String>,
 }
 
 impl WebSocketClientBuilder {
@@ -133,11 +279,283 @@ impl WebSocketClientBuilder {
         self
     }
     
+    /// Ping the server after this long without a pong, to catch connections
+    /// that died silently (e.g. behind a proxy)
+    pub fn heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = Some(interval);
+        self
+    }
+    
+    /// How long a heartbeat ping may go unanswered (default 10s)
+    pub fn pong_timeout(mut self, timeout: Duration) -> Self {
+        self.pong_timeout = Some(timeout);
+        self
+    }
+    
+    /// Use this TLS connector for `wss://` connections (and reconnections)
+    pub fn tls_connector(mut self, connector: Connector) -> Self {
+        self.connector = Some(connector);
+        self
+    }
+    
+    /// Trust the PEM root certificate at `path` in addition to the system roots,
+    /// e.g. a corporate proxy's CA. Ignored when `tls_connector` is set.
+    pub fn add_root_certificate(mut self, path: &str) -> Self {
+        self.root_certificates.push(path.to_string());
+        self
+    }
+    
+    /// Connector for the configured roots, if any were added
+    fn root_connector(&self) -> Result<Option<Connector>> {
+        if self.root_certificates.is_empty() {
+            return Ok(None);
+        }
+        let mut tls = native_tls::TlsConnector::builder();
+        for path in &self.root_certificates {
+            let pem = std::fs::read(path)
+                .with_context(|| format!("Failed to read root certificate {}", path))?;
+            let certificate = native_tls::Certificate::from_pem(&pem)
+                .with_context(|| format!("Invalid PEM root certificate {}", path))?;
+            tls.add_root_certificate(certificate);
+        }
+        let tls = tls.build().context("Failed to build TLS connector")?;
+        Ok(Some(Connector::NativeTls(tls)))
+    }
+    
     pub async fn build(self) -> Result<WebSocketClient> {
+        let connector = match self.connector.clone() {
+            Some(connector) => Some(connector),
+            None => self.root_connector()?,
+        };
         let url = self.url.context("URL is required")?;
-        let mut client = WebSocketClient::connect(&url).await?;
+        let mut client = WebSocketClient::connect_with(&url, connector).await?;
         client.auto_reconnect = self.auto_reconnect;
         client.max_reconnect_attempts = self.max_reconnect_attempts;
+        client.heartbeat_interval = self.heartbeat_interval;
+        client.pong_timeout = self.pong_timeout.unwrap_or(DEFAULT_PONG_TIMEOUT);
         Ok(client)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+
+    /// Server whose first connection goes silent (never reads, so never pongs)
+    /// and whose later connections send one message. Returns its URL and a
+    /// count of accepted connections.
+    async fn stalling_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                if accepted.fetch_add(1, Ordering::SeqCst) == 0 {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        drop(ws);
+                    });
+                } else {
+                    ws.send(Message::Text(r#"{"slot":1}"#.to_string())).await.unwrap();
+                    // Keep answering pings until the client goes away
+                    while ws.next().await.is_some() {}
+                }
+            }
+        });
+        (url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_receive_timeout_returns_none_on_silence() {
+        let (url, _) = stalling_server().await;
+        let mut client = WebSocketClient::connect(&url).await.unwrap();
+
+        let started = Instant::now();
+        let message = client
+            .receive_timeout::<serde_json::Value>(Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert!(message.is_none());
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_receive_timeout_survives_reconnect_backoff() {
+        // Closes the first connection straight away; later ones send one message
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                if accepted.fetch_add(1, Ordering::SeqCst) == 0 {
+                    ws.close(None).await.unwrap();
+                } else {
+                    ws.send(Message::Text(r#"{"slot":1}"#.to_string())).await.unwrap();
+                    while ws.next().await.is_some() {}
+                }
+            }
+        });
+        let mut client = WebSocketClient::builder().url(&url).auto_reconnect(true).build().await.unwrap();
+
+        // Each call times out long before the 2s backoff ends
+        let started = Instant::now();
+        let message = loop {
+            if let Some(message) = client
+                .receive_timeout::<serde_json::Value>(Duration::from_millis(200))
+                .await
+                .unwrap()
+            {
+                break message;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "never reconnected");
+        };
+        assert_eq!(message["slot"], 1);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+        assert_eq!(client.reconnect_attempts, 0);
+    }
+
+    #[derive(Debug, serde::Serialize)]
+    struct SnapshotRequest {
+        method: &'static str,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct SnapshotReply {
+        id: u64,
+        price: f64,
+    }
+
+    #[tokio::test]
+    async fn test_request_resolves_matching_reply() {
+        // Pushes an unrelated update and a reply to another id before echoing the request's id
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                assert_eq!(request["method"], "snapshot");
+                for reply in [
+                    serde_json::json!({ "type": "price_update", "price": 1.0 }),
+                    serde_json::json!({ "id": 99, "price": 2.0 }),
+                    serde_json::json!({ "id": request["id"], "price": 150.5 }),
+                ] {
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let mut client = WebSocketClient::connect(&url).await.unwrap();
+        let reply: SnapshotReply = client.request(SnapshotRequest { method: "snapshot" }, 7).await.unwrap();
+        assert_eq!(reply.id, 7);
+        assert_eq!(reply.price, 150.5);
+
+        // The messages that arrived first are still delivered, in order
+        let first: serde_json::Value = client.receive().await.unwrap().unwrap();
+        assert_eq!(first["type"], "price_update");
+        let second: serde_json::Value = client.receive().await.unwrap().unwrap();
+        assert_eq!(second["id"], 99);
+
+        assert!(client.request::<_, SnapshotReply>(42u64, 8).await.is_err());
+    }
+
+    /// `wss://` server presenting a certificate issued by the test CA in
+    /// `testdata/`; sends one message per connection
+    async fn tls_server() -> String {
+        let identity = native_tls::Identity::from_pkcs8(
+            include_bytes!("../testdata/server.pem"),
+            include_bytes!("../testdata/server.key"),
+        )
+        .unwrap();
+        let acceptor = tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("wss://localhost:{}", listener.local_addr().unwrap().port());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let Ok(stream) = acceptor.accept(stream).await else { continue };
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                ws.send(Message::Text(r#"{"slot":1}"#.to_string())).await.unwrap();
+                while ws.next().await.is_some() {}
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_wss_with_custom_root_certificate() {
+        let url = tls_server().await;
+
+        // The system roots don't know the test CA
+        assert!(WebSocketClient::connect(&url).await.is_err());
+
+        let mut client = WebSocketClient::builder()
+            .url(&url)
+            .add_root_certificate(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/ca.pem"))
+            .build()
+            .await
+            .unwrap();
+        let message: serde_json::Value = client.receive().await.unwrap().unwrap();
+        assert_eq!(message["slot"], 1);
+
+        // Plain ws:// is unaffected by TLS settings
+        let (url, _) = stalling_server().await;
+        assert!(WebSocketClient::builder()
+            .url(&url)
+            .add_root_certificate(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/ca.pem"))
+            .build()
+            .await
+            .is_ok());
+
+        assert!(WebSocketClient::builder()
+            .url(&url)
+            .add_root_certificate("/nonexistent/ca.pem")
+            .build()
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_pong_forces_reconnect() {
+        let (url, connections) = stalling_server().await;
+        let mut client = WebSocketClient::builder()
+            .url(&url)
+            .auto_reconnect(true)
+            .heartbeat_interval(Duration::from_millis(100))
+            .pong_timeout(Duration::from_millis(200))
+            .build()
+            .await
+            .unwrap();
+
+        let message: serde_json::Value = tokio::time::timeout(Duration::from_secs(10), client.receive())
+            .await
+            .expect("heartbeat never detected the dead connection")
+            .unwrap()
+            .unwrap();
+        assert_eq!(message["slot"], 1);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_pong_errors_without_auto_reconnect() {
+        let (url, _) = stalling_server().await;
+        let mut client = WebSocketClient::builder()
+            .url(&url)
+            .heartbeat_interval(Duration::from_millis(100))
+            .pong_timeout(Duration::from_millis(200))
+            .build()
+            .await
+            .unwrap();
+
+        let err = client.receive::<serde_json::Value>().await.unwrap_err();
+        assert!(err.to_string().contains("heartbeat timed out"));
+    }
+}
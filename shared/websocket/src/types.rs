@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -38,4 +39,42 @@ pub enum WsMessage {
     // Health
     Ping,
     Pong,
+
+    /// A `type` this version doesn't know, e.g. one added by a newer adapter.
+    /// Its fields are dropped; `from_json` logs the kind.
+    #[serde(other)]
+    Unknown,
+}
+
+impl WsMessage {
+    /// Parse a text frame, warning about message kinds this version doesn't know
+    pub fn from_json(text: &str) -> serde_json::Result<Self> {
+        let message: Self = serde_json::from_str(text)?;
+        if matches!(message, Self::Unknown) {
+            let kind = serde_json::from_str::<serde_json::Value>(text)?
+                .get("type")
+                .and_then(|kind| kind.as_str().map(str::to_string));
+            warn!("Unsupported WebSocket message type: {}", kind.as_deref().unwrap_or("?"));
+        }
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_type_falls_back_to_unknown() {
+        let message = WsMessage::from_json(r#"{"type":"OrderBookUpdate","bids":[[150.1,2]],"slot":7}"#).unwrap();
+        assert!(matches!(message, WsMessage::Unknown));
+
+        // Known kinds are unchanged
+        let message = WsMessage::from_json(r#"{"type":"SlotUpdate","slot":7,"timestamp":1}"#).unwrap();
+        assert!(matches!(message, WsMessage::SlotUpdate { slot: 7, timestamp: 1 }));
+        assert!(matches!(WsMessage::from_json(r#"{"type":"Ping"}"#).unwrap(), WsMessage::Ping));
+
+        // A known kind with bad fields is still an error, not Unknown
+        assert!(WsMessage::from_json(r#"{"type":"SlotUpdate","slot":"seven"}"#).is_err());
+    }
 }